@@ -0,0 +1,5 @@
+// sqlx::migrate! migration dosyalarını derleme anında gömer; dosyalar
+// değiştiğinde (veya yenisi eklendiğinde) crate yeniden derlensin.
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,34 @@
+-- logs tablosunun ilk sürümlü şeması. Sürüm takibinden önce oluşturulmuş
+-- veritabanları açılışta bu şemaya yükseltilir; bu yüzden her ifade IF NOT EXISTS'tir.
+-- Yayınlanmış bir migration dosyası değiştirilmez, değişiklikler yeni dosyaya yazılır.
+
+CREATE TABLE IF NOT EXISTS logs (
+    id BIGSERIAL PRIMARY KEY,
+    tenant_id TEXT NOT NULL DEFAULT 'default',
+    level TEXT NOT NULL,
+    message TEXT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    details TEXT,
+    remote_addr TEXT,
+    user_agent TEXT,
+    api_key TEXT,
+    received_at TEXT,
+    severity INTEGER,
+    service TEXT,
+    host TEXT,
+    env TEXT,
+    trace_id TEXT,
+    span_id TEXT
+);
+
+-- Zaman aralığı sorguları ve seviye + zaman filtreleri tabloyu baştan sona taramasın.
+-- (timestamp, id) /logs sıralaması ve imleçleri içindir; yalnız timestamp'li eski dizinin yerini alır.
+CREATE INDEX IF NOT EXISTS idx_logs_timestamp_id ON logs(timestamp, id);
+DROP INDEX IF EXISTS idx_logs_timestamp;
+CREATE INDEX IF NOT EXISTS idx_logs_level_timestamp ON logs(level, timestamp);
+
+-- Öne çıkarılan sütunlar: servis / makine / ortam + zaman ve trace_id
+CREATE INDEX IF NOT EXISTS idx_logs_service ON logs(service, timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_host ON logs(host, timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_env ON logs(env, timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_trace_id ON logs(trace_id);
@@ -0,0 +1,13 @@
+-- Yönetim ve silme işlemlerinin denetim kaydı (GET /admin/audit). Zaman epoch
+-- mikro saniye; params işlemin parametreleri ve sonucudur (JSON metni).
+CREATE TABLE IF NOT EXISTS audit (
+    id BIGSERIAL PRIMARY KEY,
+    timestamp BIGINT NOT NULL,
+    actor TEXT NOT NULL,
+    remote_addr TEXT,
+    action TEXT NOT NULL,
+    params TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit(timestamp);
+CREATE INDEX IF NOT EXISTS idx_audit_action ON audit(action, id);
@@ -0,0 +1,51 @@
+-- logs tablosunun ilk sürümlü şeması. Sürüm takibinden önce oluşturulmuş
+-- veritabanları açılışta bu şemaya yükseltilir; bu yüzden her ifade IF NOT EXISTS'tir.
+-- Yayınlanmış bir migration dosyası değiştirilmez, değişiklikler yeni dosyaya yazılır.
+
+CREATE TABLE IF NOT EXISTS logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id TEXT NOT NULL DEFAULT 'default',
+    level TEXT NOT NULL,
+    message TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    details TEXT,
+    remote_addr TEXT,
+    user_agent TEXT,
+    api_key TEXT,
+    received_at TEXT,
+    severity INTEGER,
+    service TEXT,
+    host TEXT,
+    env TEXT,
+    trace_id TEXT,
+    span_id TEXT
+);
+
+-- Zaman aralığı sorguları ve seviye + zaman filtreleri tabloyu baştan sona taramasın.
+-- SQLite dizinleri rowid'yi (id) de içerir: (timestamp, id) sıralaması da bu dizini kullanır.
+CREATE INDEX IF NOT EXISTS idx_logs_timestamp ON logs(timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_level_timestamp ON logs(level, timestamp);
+
+-- Öne çıkarılan sütunlar: servis / makine / ortam + zaman ve trace_id
+CREATE INDEX IF NOT EXISTS idx_logs_service ON logs(service, timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_host ON logs(host, timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_env ON logs(env, timestamp);
+CREATE INDEX IF NOT EXISTS idx_logs_trace_id ON logs(trace_id);
+
+-- Tam metin dizini (FTS5): message ve details üzerinde harici içerikli (content='logs')
+-- dizin; metin iki kez saklanmaz. Tetikleyiciler dizini logs tablosuyla eşit tutar
+-- (silme ve eviction dahil).
+CREATE VIRTUAL TABLE IF NOT EXISTS logs_fts USING fts5(message, details, content='logs', content_rowid='id');
+
+CREATE TRIGGER IF NOT EXISTS logs_fts_ai AFTER INSERT ON logs BEGIN
+    INSERT INTO logs_fts(rowid, message, details) VALUES (new.id, new.message, new.details);
+END;
+
+CREATE TRIGGER IF NOT EXISTS logs_fts_ad AFTER DELETE ON logs BEGIN
+    INSERT INTO logs_fts(logs_fts, rowid, message, details) VALUES ('delete', old.id, old.message, old.details);
+END;
+
+CREATE TRIGGER IF NOT EXISTS logs_fts_au AFTER UPDATE ON logs BEGIN
+    INSERT INTO logs_fts(logs_fts, rowid, message, details) VALUES ('delete', old.id, old.message, old.details);
+    INSERT INTO logs_fts(rowid, message, details) VALUES (new.id, new.message, new.details);
+END;
@@ -0,0 +1,28 @@
+-- details sütunu zstd ile sıkıştırılmış (BLOB) olabilir (storage.sqlite.compress_details).
+-- Sözlükler burada saklanır; kimlik zstd'nin sözlük kimliğidir ve sıkıştırılmış her
+-- değerin çerçeve başlığında da yazar.
+CREATE TABLE IF NOT EXISTS details_dictionaries (
+    id INTEGER PRIMARY KEY,
+    dictionary BLOB NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+-- Tam metin dizini sıkıştırılmış baytları değil JSON metnini görmeli: tetikleyiciler
+-- details_json() ile yeniden oluşturulur. Fonksiyon uygulama tarafından her bağlantıya
+-- eklenir; sqlite3 kabuğundan logs tablosuna yazmak bu yüzden hata verir.
+DROP TRIGGER IF EXISTS logs_fts_ai;
+DROP TRIGGER IF EXISTS logs_fts_ad;
+DROP TRIGGER IF EXISTS logs_fts_au;
+
+CREATE TRIGGER logs_fts_ai AFTER INSERT ON logs BEGIN
+    INSERT INTO logs_fts(rowid, message, details) VALUES (new.id, new.message, details_json(new.details));
+END;
+
+CREATE TRIGGER logs_fts_ad AFTER DELETE ON logs BEGIN
+    INSERT INTO logs_fts(logs_fts, rowid, message, details) VALUES ('delete', old.id, old.message, details_json(old.details));
+END;
+
+CREATE TRIGGER logs_fts_au AFTER UPDATE ON logs BEGIN
+    INSERT INTO logs_fts(logs_fts, rowid, message, details) VALUES ('delete', old.id, old.message, details_json(old.details));
+    INSERT INTO logs_fts(rowid, message, details) VALUES (new.id, new.message, details_json(new.details));
+END;
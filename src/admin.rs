@@ -0,0 +1,284 @@
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::auth::{self, Caller};
+use crate::metrics::WriteFailure;
+use crate::AppState;
+
+// --- Yönetim Uçları ---
+// Durum, duraklatma ve boşaltma. Tüm kiracıları kapsadıkları için sadece
+// varsayılan kiracının anahtarlarıyla kullanılabilir; diğerleri 403 alır.
+
+// --- Yönetim Durumu ---
+// GET /admin/status: orkestrasyon (otomatik ölçekleme, alarm betikleri) için
+// Prometheus gerektirmeyen tek JSON özeti. Sayaçlar süreç başından beri
+// toplamdır; veritabanı boyutu her istekte ölçülür.
+#[derive(Debug, Serialize)]
+pub struct Status {
+    // POST /admin/pause ile alım durduruldu mu
+    pub paused: bool,
+    pub started_at: String,
+    pub uptime_secs: u64,
+    pub queue: QueueStatus,
+    pub counts: Counts,
+    // Yeniden denemelere rağmen başarısız olan son yazma (hiç olmadıysa null)
+    pub last_write_error: Option<WriteFailure>,
+    pub database: DatabaseStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueStatus {
+    pub depth: usize,
+    pub capacity: usize,
+    // depth / capacity (0.0 - 1.0)
+    pub fill: f64,
+    // Yazıcı başına bekleyen log sayısı
+    pub stripes: Vec<usize>,
+    // Kanala girip henüz sonuçlanmamış loglar (kanaldakiler + yazıcı tamponları)
+    pub in_flight: usize,
+    // Son yazılan partideki en eski logun bekleme süresi
+    pub write_lag_ms: u64,
+    // WAL kapalıysa null
+    pub wal_pending: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Counts {
+    pub received: u64,
+    pub accepted: u64,
+    pub written: u64,
+    pub filtered: u64,
+    pub sampled_out: u64,
+    // Kanala gönderilemeyenler (kapanış sırasında)
+    pub dropped: u64,
+    // Kanal dolu olduğu için atılan veya reddedilenler
+    pub overloaded: u64,
+    pub rate_limited: u64,
+    pub write_errors: u64,
+    pub dead_lettered: u64,
+    pub forward_dropped: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseStatus {
+    pub reachable: bool,
+    // Ölçülemediyse null
+    pub size_bytes: Option<u64>,
+    pub rows: Option<u64>,
+}
+
+pub async fn status_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<Status>, StatusCode> {
+    auth::require_default_tenant(caller.as_deref())?;
+
+    let metrics = &state.metrics;
+    let count = |counter: &std::sync::atomic::AtomicU64| counter.load(Ordering::Relaxed);
+    let (depth, capacity) = (state.tx.depth(), state.tx.capacity());
+    let queue = QueueStatus {
+        depth,
+        capacity,
+        fill: depth as f64 / capacity.max(1) as f64,
+        stripes: state.tx.depths(),
+        in_flight: state.tx.in_flight().load(Ordering::Relaxed),
+        write_lag_ms: count(&metrics.write_lag_last_ms),
+        wal_pending: state.tx.wal_pending(),
+    };
+    let counts = Counts {
+        received: count(&metrics.received),
+        accepted: count(&metrics.accepted),
+        written: count(&metrics.written),
+        filtered: count(&metrics.filtered),
+        sampled_out: count(&metrics.sampled_out),
+        dropped: count(&metrics.dropped),
+        overloaded: count(&metrics.overloaded),
+        rate_limited: count(&metrics.rate_limited),
+        write_errors: count(&metrics.write_errors),
+        dead_lettered: count(&metrics.dead_lettered),
+        forward_dropped: count(&metrics.forward_dropped),
+    };
+    let database = match state.store.usage().await {
+        Ok(usage) => DatabaseStatus {
+            reachable: true,
+            size_bytes: Some(usage.bytes),
+            rows: Some(usage.rows),
+        },
+        Err(e) => {
+            warn!("⚠️ Durum için veritabanı boyutu ölçülemedi: {}", e);
+            DatabaseStatus {
+                reachable: state.store.ping().await.is_ok(),
+                size_bytes: None,
+                rows: None,
+            }
+        }
+    };
+
+    let uptime = chrono::Utc::now() - state.started_at;
+    Ok(Json(Status {
+        paused: state.pause.is_paused(),
+        started_at: state.started_at.to_rfc3339(),
+        uptime_secs: uptime.num_seconds().max(0) as u64,
+        queue,
+        counts,
+        last_write_error: metrics.last_write_error.lock().unwrap().clone(),
+        database,
+    }))
+}
+
+// --- Duraklatma ---
+// Bakım (veritabanı yedeği, taşıma) öncesi yeni logların kanala girmesi durdurulur:
+// HTTP ve OTLP gRPC istekleri 503 / UNAVAILABLE alır (Retry-After ile; ajanlar
+// tekrar dener), akış kaynakları (dosya, journald, Docker, Kafka, syslog, Fluentd)
+// sürdürülene kadar bekler. Kanaldakiler yazılmaya devam eder. Durum süreç
+// belleğindedir; yeniden başlatınca alım açık başlar.
+pub struct Pause(watch::Sender<bool>);
+
+impl Default for Pause {
+    fn default() -> Self {
+        Pause(watch::Sender::new(false))
+    }
+}
+
+impl Pause {
+    pub fn is_paused(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    // Durumu değiştirir; önceki durumu döner.
+    fn set(&self, paused: bool) -> bool {
+        self.0.send_replace(paused)
+    }
+
+    // Duraklatılmışsa sürdürülene kadar bekler.
+    pub async fn wait(&self) {
+        if self.is_paused() {
+            let _ = self.0.subscribe().wait_for(|paused| !paused).await;
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PauseReport {
+    pub paused: bool,
+    // false: zaten bu durumdaydı
+    pub changed: bool,
+}
+
+// POST /admin/pause
+pub async fn pause_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<PauseReport>, StatusCode> {
+    auth::require_default_tenant(caller.as_deref())?;
+    let changed = !state.pause.set(true);
+    if changed {
+        info!("⏸️ Alım duraklatıldı; yeni istekler 503 alacak ({} log kanalda).", state.tx.depth());
+    }
+    Ok(Json(PauseReport { paused: true, changed }))
+}
+
+// POST /admin/resume
+pub async fn resume_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<PauseReport>, StatusCode> {
+    auth::require_default_tenant(caller.as_deref())?;
+    let changed = state.pause.set(false);
+    if changed {
+        info!("▶️ Alım sürdürülüyor.");
+    }
+    Ok(Json(PauseReport { paused: false, changed }))
+}
+
+// --- Boşaltma ---
+// POST /admin/drain?timeout_secs=30: kanaldaki ve yazıcı tamponlarındaki loglar
+// yazılana kadar bekler, sonra WAL dosyasını ve veritabanlarını (SQLite WAL
+// checkpoint) diske aktarır. Alım açıkken yeni loglar gelmeye devam eder; tutarlı
+// bir yedek için önce /admin/pause çağrılmalıdır.
+#[derive(Debug, Deserialize)]
+pub struct DrainQuery {
+    // Verilmezse pipeline.shutdown_timeout_secs (0 = süresiz)
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DrainReport {
+    // Kanal ve tamponlar boşaldı mı (süre dolduysa false)
+    pub drained: bool,
+    pub paused: bool,
+    // Süre dolduğunda hâlâ yazılmayı bekleyen loglar
+    pub pending: usize,
+    pub wal_pending: Option<usize>,
+    pub checkpointed: bool,
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub async fn drain_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(params): Query<DrainQuery>,
+) -> Result<(StatusCode, Json<DrainReport>), StatusCode> {
+    auth::require_default_tenant(caller.as_deref())?;
+    let started = Instant::now();
+    let timeout = params.timeout_secs.unwrap_or(state.drain_timeout_secs);
+    let deadline = (timeout > 0).then(|| started + Duration::from_secs(timeout));
+
+    let in_flight = state.tx.in_flight();
+    info!("🚰 Kuyruk boşaltılıyor ({} log bekliyor).", in_flight.load(Ordering::Relaxed));
+    let mut poll = tokio::time::interval(Duration::from_millis(20));
+    let drained = loop {
+        poll.tick().await;
+        if in_flight.load(Ordering::Relaxed) == 0 {
+            break true;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break false;
+        }
+    };
+
+    let mut report = DrainReport {
+        drained,
+        paused: state.pause.is_paused(),
+        pending: in_flight.load(Ordering::Relaxed),
+        wal_pending: state.tx.wal_pending(),
+        checkpointed: false,
+        elapsed_ms: 0,
+        error: None,
+    };
+    if !drained {
+        warn!("⚠️ Kuyruk {} sn içinde boşalmadı ({} log bekliyor).", timeout, report.pending);
+        report.elapsed_ms = started.elapsed().as_millis() as u64;
+        return Ok((StatusCode::GATEWAY_TIMEOUT, Json(report)));
+    }
+
+    if let Err(e) = checkpoint(&state).await {
+        error!("❌ Boşaltma sonrası checkpoint yapılamadı: {}", e);
+        report.error = Some(e);
+        report.elapsed_ms = started.elapsed().as_millis() as u64;
+        return Ok((StatusCode::INTERNAL_SERVER_ERROR, Json(report)));
+    }
+    report.checkpointed = true;
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    info!("✅ Kuyruk boşaltıldı ve diske aktarıldı ({} ms).", report.elapsed_ms);
+    Ok((StatusCode::OK, Json(report)))
+}
+
+// WAL dosyası, ana veritabanı ve yönlendirme kurallarının ayrı depoları
+async fn checkpoint(state: &AppState) -> Result<(), String> {
+    state.tx.checkpoint_wal().map_err(|e| format!("WAL: {}", e))?;
+    state.store.checkpoint().await.map_err(|e| e.to_string())?;
+    for (name, store) in state.routes.targets() {
+        store.checkpoint().await.map_err(|e| format!("{}: {}", name, e))?;
+    }
+    Ok(())
+}
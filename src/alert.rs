@@ -0,0 +1,392 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+use url::Url;
+
+use crate::config::{AlertFormat, AlertRuleConfig, AlertsConfig};
+use crate::metrics::Metrics;
+use crate::LogEntry;
+
+// Özete eklenen en fazla örnek log
+const MAX_SAMPLES: usize = 5;
+// Örnek mesajlar bu uzunlukta kesilir (karakter)
+const SAMPLE_MESSAGE_CHARS: usize = 500;
+// Webhook isteği bu sürede bitmezse başarısız sayılır
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// --- Uyarı Kuralları ---
+// Kanala giren her log kurallarla karşılaştırılır (veritabanı sorgusu yok);
+// birleştirilen, atılan veya reddedilen loglar sayılmaz. Her kural eşleşmeleri saniyelik kovalarda sayar; son
+// 'window_secs' içindeki sayı 'threshold'u aşınca özet webhook'a POST edilir.
+// Gönderim arka planda yapılır, alımı bekletmez. Tetiklenen kural
+// 'cooldown_secs' boyunca susar.
+pub struct Alerts {
+    rules: Vec<AlertRule>,
+    client: reqwest::Client,
+    metrics: Arc<Metrics>,
+    started: Instant,
+}
+
+struct AlertRule {
+    name: String,
+    level: Option<String>,
+    contains: Option<String>,
+    // 'contains'in küçük harfli hali (karşılaştırma için)
+    needle: Option<String>,
+    tenant: Option<String>,
+    threshold: u64,
+    window_secs: u64,
+    cooldown_secs: u64,
+    webhook: Url,
+    format: AlertFormat,
+    state: Mutex<RuleState>,
+}
+
+#[derive(Default)]
+struct RuleState {
+    // (saniye, o saniyedeki seviye -> eşleşme sayısı), eskiden yeniye
+    buckets: VecDeque<(u64, BTreeMap<String, u64>)>,
+    // Kovalardaki toplamlar
+    count: u64,
+    levels: BTreeMap<String, u64>,
+    samples: VecDeque<AlertSample>,
+    last_fired: Option<u64>,
+}
+
+// Webhook'a gönderilen özet. Adres sır içerebileceği için loglara sadece host yazılır.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertSummary {
+    pub rule: String,
+    pub count: u64,
+    // Penceredeki eşleşmelerin seviyelere göre dağılımı (küçük harf)
+    pub levels: BTreeMap<String, u64>,
+    pub threshold: u64,
+    pub window_secs: u64,
+    pub level: Option<String>,
+    pub contains: Option<String>,
+    pub tenant: Option<String>,
+    pub fired_at: String,
+    // Son eşleşen loglar (en yenisi sonda)
+    pub samples: Vec<AlertSample>,
+}
+
+// Logun uyduğu kurallar (Alerts::matching) ve özete eklenecek örneği
+pub struct Matched {
+    rules: Vec<usize>,
+    sample: AlertSample,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertSample {
+    pub tenant_id: String,
+    pub level: String,
+    pub message: String,
+    pub timestamp: String,
+}
+
+impl Alerts {
+    pub fn from_config(config: &AlertsConfig, metrics: Arc<Metrics>) -> Result<Self, String> {
+        let rules = config.rules.iter().map(AlertRule::new).collect::<Result<Vec<_>, _>>()?;
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| format!("webhook istemcisi kurulamadı: {}", e))?;
+        Ok(Alerts {
+            rules,
+            client,
+            metrics,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    // Logun uyduğu kuralları bulur (hiçbiri yoksa None). Log kanala girerken
+    // taşındığı için önceden bakılır; kanala girince 'record' ile sayılır.
+    pub fn matching(&self, log: &LogEntry) -> Option<Matched> {
+        let rules: Vec<usize> = (0..self.rules.len()).filter(|&i| self.rules[i].matches(log)).collect();
+        if rules.is_empty() {
+            return None;
+        }
+        let sample = AlertSample {
+            tenant_id: log.tenant_id.clone(),
+            level: log.level.clone(),
+            message: log.message.chars().take(SAMPLE_MESSAGE_CHARS).collect(),
+            timestamp: log.extra.get("timestamp").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        };
+        Some(Matched { rules, sample })
+    }
+
+    // Kanala giren logun eşleşmelerini sayar; eşiği aşan kural için webhook gönderir.
+    pub fn record(&self, matched: Matched) {
+        let now = self.started.elapsed().as_secs();
+        for i in matched.rules {
+            let rule = &self.rules[i];
+            if let Some(summary) = rule.record(&matched.sample, now) {
+                self.metrics.alerts_fired.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "🚨 Uyarı '{}': son {} sn içinde {} eşleşme (eşik {}).",
+                    summary.rule, summary.window_secs, summary.count, summary.threshold
+                );
+                tokio::spawn(deliver(
+                    self.client.clone(),
+                    rule.webhook.clone(),
+                    rule.format,
+                    summary,
+                    self.metrics.clone(),
+                ));
+            }
+        }
+    }
+}
+
+impl AlertRule {
+    fn new(config: &AlertRuleConfig) -> Result<Self, String> {
+        let webhook = Url::parse(&config.webhook)
+            .map_err(|e| format!("'{}' uyarısının webhook adresi geçersiz: {}", config.name, e))?;
+        if config.window_secs == 0 {
+            return Err(format!("'{}' uyarısının window_secs değeri 0 olamaz", config.name));
+        }
+        Ok(AlertRule {
+            name: config.name.clone(),
+            level: config.level.clone(),
+            contains: config.contains.clone(),
+            needle: config.contains.as_ref().map(|c| c.to_lowercase()).filter(|c| !c.is_empty()),
+            tenant: config.tenant.clone(),
+            threshold: config.threshold,
+            window_secs: config.window_secs,
+            cooldown_secs: config.cooldown_secs.unwrap_or(config.window_secs),
+            webhook,
+            format: config.format,
+            state: Mutex::new(RuleState::default()),
+        })
+    }
+
+    fn matches(&self, log: &LogEntry) -> bool {
+        self.level.as_ref().is_none_or(|level| log.level.eq_ignore_ascii_case(level))
+            && self.tenant.as_ref().is_none_or(|tenant| &log.tenant_id == tenant)
+            && self.needle.as_ref().is_none_or(|c| log.message.to_lowercase().contains(c))
+    }
+
+    // Eşleşmeyi sayar; kural tetiklenmeliyse özeti döner.
+    fn record(&self, sample: &AlertSample, now: u64) -> Option<AlertSummary> {
+        let mut state = self.state.lock().unwrap();
+
+        let level = sample.level.to_lowercase();
+        if state.buckets.back().is_none_or(|(second, _)| *second != now) {
+            state.buckets.push_back((now, BTreeMap::new()));
+        }
+        if let Some((_, bucket)) = state.buckets.back_mut() {
+            *bucket.entry(level.clone()).or_default() += 1;
+        }
+        state.count += 1;
+        *state.levels.entry(level).or_default() += 1;
+
+        while state.buckets.front().is_some_and(|(second, _)| second + self.window_secs <= now) {
+            let Some((_, expired)) = state.buckets.pop_front() else {
+                break;
+            };
+            for (level, count) in expired {
+                state.count -= count;
+                if let Some(total) = state.levels.get_mut(&level) {
+                    *total -= count;
+                    if *total == 0 {
+                        state.levels.remove(&level);
+                    }
+                }
+            }
+        }
+
+        if state.samples.len() == MAX_SAMPLES {
+            state.samples.pop_front();
+        }
+        state.samples.push_back(sample.clone());
+
+        if state.count <= self.threshold {
+            return None;
+        }
+        if state
+            .last_fired
+            .is_some_and(|fired| now < fired + self.cooldown_secs)
+        {
+            return None;
+        }
+        state.last_fired = Some(now);
+
+        Some(AlertSummary {
+            rule: self.name.clone(),
+            count: state.count,
+            levels: state.levels.clone(),
+            threshold: self.threshold,
+            window_secs: self.window_secs,
+            level: self.level.clone(),
+            contains: self.contains.clone(),
+            tenant: self.tenant.clone(),
+            fired_at: chrono::Utc::now().to_rfc3339(),
+            samples: state.samples.iter().cloned().collect(),
+        })
+    }
+}
+
+async fn deliver(
+    client: reqwest::Client,
+    webhook: Url,
+    format: AlertFormat,
+    summary: AlertSummary,
+    metrics: Arc<Metrics>,
+) {
+    let body = match format {
+        AlertFormat::Json => serde_json::to_value(&summary).unwrap_or(Value::Null),
+        AlertFormat::Slack => slack_message(&summary),
+        AlertFormat::Discord => discord_message(&summary),
+    };
+    let result = client.post(webhook.clone()).json(&body).send().await;
+    match result {
+        Ok(response) if response.status().is_success() => {
+            info!("🚨 '{}' uyarısı gönderildi ({}).", summary.rule, webhook.host_str().unwrap_or(""));
+        }
+        Ok(response) => {
+            metrics.alert_errors.fetch_add(1, Ordering::Relaxed);
+            error!(
+                "❌ '{}' uyarısı gönderilemedi ({}): {}",
+                summary.rule,
+                webhook.host_str().unwrap_or(""),
+                response.status()
+            );
+        }
+        Err(e) => {
+            metrics.alert_errors.fetch_add(1, Ordering::Relaxed);
+            error!("❌ '{}' uyarısı gönderilemedi: {}", summary.rule, e);
+        }
+    }
+}
+
+// --- Sohbet Biçimleri ---
+// Slack ve Discord gelen webhook'ları için hazır mesajlar: başlık, eşik
+// bilgisi, seviyelere göre sayılar ve örnek mesajlar. Ara bir servise gerek kalmaz.
+
+// Slack/Discord alan sınırlarının altında kalmak için örnek bloğunun en fazla uzunluğu (karakter)
+const SLACK_SAMPLES_CHARS: usize = 2800;
+const DISCORD_SAMPLES_CHARS: usize = 1000;
+// Discord embed rengi (kırmızı)
+const DISCORD_COLOR: u32 = 0xE74C3C;
+
+fn headline(summary: &AlertSummary) -> String {
+    format!(
+        "🚨 {}: son {} sn içinde {} eşleşme (eşik {})",
+        summary.rule, summary.window_secs, summary.count, summary.threshold
+    )
+}
+
+// Kuralın filtresi, örn. "seviye: error, içerik: OOM"
+fn filter_text(summary: &AlertSummary) -> String {
+    let parts: Vec<String> = [
+        summary.level.as_ref().map(|l| format!("seviye: {}", l)),
+        summary.contains.as_ref().map(|c| format!("içerik: {}", c)),
+        summary.tenant.as_ref().map(|t| format!("kiracı: {}", t)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if parts.is_empty() {
+        "tüm loglar".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+// Örnek mesajlar (en yenisi önce), kod bloğu içeriği olarak. Bloğu erken
+// kapatmasın diye mesajlardaki ``` değiştirilir.
+fn samples_text(summary: &AlertSummary, max_chars: usize) -> String {
+    let mut text = String::new();
+    let mut chars = 0;
+    for sample in summary.samples.iter().rev() {
+        let line = format!("[{}] {}\n", sample.level, sample.message.replace("```", "'''"));
+        chars += line.chars().count();
+        if chars > max_chars {
+            break;
+        }
+        text.push_str(&line);
+    }
+    text
+}
+
+fn slack_message(summary: &AlertSummary) -> Value {
+    let level_fields: Vec<Value> = summary
+        .levels
+        .iter()
+        .take(10)
+        .map(|(level, count)| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", level, count) }))
+        .collect();
+
+    let mut blocks = vec![
+        json!({ "type": "header", "text": { "type": "plain_text", "text": format!("🚨 {}", summary.rule) } }),
+        json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!(
+                    "Son *{} sn* içinde *{}* eşleşme (eşik {})\nFiltre: {}",
+                    summary.window_secs,
+                    summary.count,
+                    summary.threshold,
+                    filter_text(summary)
+                ),
+            },
+        }),
+    ];
+    if !level_fields.is_empty() {
+        blocks.push(json!({ "type": "section", "fields": level_fields }));
+    }
+    let samples = samples_text(summary, SLACK_SAMPLES_CHARS);
+    if !samples.is_empty() {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("*Son örnekler*\n```{}```", samples) },
+        }));
+    }
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{ "type": "mrkdwn", "text": format!("log-ingestor · {}", summary.fired_at) }],
+    }));
+
+    json!({ "text": headline(summary), "blocks": blocks })
+}
+
+fn discord_message(summary: &AlertSummary) -> Value {
+    let mut fields: Vec<Value> = summary
+        .levels
+        .iter()
+        .take(20)
+        .map(|(level, count)| json!({ "name": level, "value": count.to_string(), "inline": true }))
+        .collect();
+    let samples = samples_text(summary, DISCORD_SAMPLES_CHARS);
+    if !samples.is_empty() {
+        fields.push(json!({ "name": "Son örnekler", "value": format!("```{}```", samples), "inline": false }));
+    }
+
+    json!({
+        "content": headline(summary),
+        "embeds": [{
+            "title": format!("🚨 {}", summary.rule),
+            "description": format!(
+                "Son **{} sn** içinde **{}** eşleşme (eşik {})\nFiltre: {}",
+                summary.window_secs,
+                summary.count,
+                summary.threshold,
+                filter_text(summary)
+            ),
+            "color": DISCORD_COLOR,
+            "fields": fields,
+            "timestamp": summary.fired_at,
+            "footer": { "text": "log-ingestor" },
+        }],
+    })
+}
@@ -0,0 +1,237 @@
+use std::io::{BufRead, BufReader, Write};
+
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+use url::Url;
+
+use crate::config::ArchiveConfig;
+use crate::query::StoredLog;
+use crate::storage::SharedStorage;
+use crate::LogEntry;
+
+// --- Nesne Deposu Arşivi ---
+// Saklama görevi kayıtları silmeden önce gzip'li JSONL olarak S3 uyumlu bir
+// kovaya (AWS S3, MinIO, R2, ...) yükler. Yükleme başarısız olursa o parça
+// silinmez; bir sonraki çalıştırmada yeniden denenir.
+//
+// Nesne adı: <prefix>dt=YYYY-MM-DD/<HHMMSS>-<ilk id>-<son id>.jsonl.gz
+// Her satır bir StoredLog'dur (GET /logs ile aynı biçim); 'log-ingestor import'
+// ile yeniden içeri alınabilir.
+pub struct Archiver {
+    client: reqwest::Client,
+    endpoint: Url,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key: String,
+    secret_key: String,
+    path_style: bool,
+    chunk_rows: i64,
+}
+
+impl Archiver {
+    pub fn from_config(config: &ArchiveConfig) -> Result<Self, String> {
+        let endpoint = Url::parse(&config.endpoint).map_err(|e| format!("archive.endpoint geçersiz: {}", e))?;
+        let access_key = config
+            .access_key
+            .clone()
+            .or_else(|| std::env::var("AWS_ACCESS_KEY_ID").ok())
+            .ok_or("archive.access_key (veya AWS_ACCESS_KEY_ID) gerekli")?;
+        let secret_key = config
+            .secret_key
+            .clone()
+            .or_else(|| std::env::var("AWS_SECRET_ACCESS_KEY").ok())
+            .ok_or("archive.secret_key (veya AWS_SECRET_ACCESS_KEY) gerekli")?;
+        Ok(Archiver {
+            client: reqwest::Client::new(),
+            endpoint,
+            bucket: config.bucket.clone(),
+            region: config.region.clone(),
+            prefix: config.prefix.clone(),
+            access_key,
+            secret_key,
+            path_style: config.path_style,
+            chunk_rows: config.chunk_rows.max(1),
+        })
+    }
+
+    pub fn chunk_rows(&self) -> i64 {
+        self.chunk_rows
+    }
+
+    // Kayıtları tek bir nesne olarak yükler ve nesne adını döner.
+    pub async fn upload(&self, rows: &[StoredLog]) -> Result<String, String> {
+        let (Some(first), Some(last)) = (rows.first(), rows.last()) else {
+            return Err("boş arşiv parçası".to_string());
+        };
+        let now = Utc::now();
+        let key = format!(
+            "{}dt={}/{}-{}-{}.jsonl.gz",
+            self.prefix,
+            now.format("%Y-%m-%d"),
+            now.format("%H%M%S"),
+            first.id,
+            last.id
+        );
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for row in rows {
+            let line = serde_json::to_string(row).map_err(|e| e.to_string())?;
+            writeln!(encoder, "{}", line).map_err(|e| e.to_string())?;
+        }
+        let body = encoder.finish().map_err(|e| e.to_string())?;
+
+        self.put_object(&key, body).await?;
+        debug!("📦 {} kayıt arşivlendi: {}", rows.len(), key);
+        Ok(key)
+    }
+
+    // PUT Object, AWS Signature V4 ile imzalanır.
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+        let host = self.endpoint.host_str().ok_or("archive.endpoint'te host yok")?;
+        let (host, path) = if self.path_style {
+            (host.to_string(), format!("/{}/{}", self.bucket, key))
+        } else {
+            (format!("{}.{}", self.bucket, host), format!("/{}", key))
+        };
+        // reqwest varsayılan olmayan portu Host başlığına ekler; imza da aynısını içermeli
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        };
+        let path = uri_encode_path(&path);
+        let url = format!("{}://{}{}", self.endpoint.scheme(), host, path);
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let canonical_request = format!(
+            "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            path, host, payload_hash, amz_date, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = [self.region.as_bytes(), b"s3", b"aws4_request"]
+            .iter()
+            .fold(hmac_sha256(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes()), |key, part| {
+                hmac_sha256(&key, part)
+            });
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.access_key, scope, signature
+        );
+
+        let response = self
+            .client
+            .put(url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .header("content-type", "application/gzip")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("arşiv yüklenemedi: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("arşiv yüklenemedi ({}): {}", status, text.trim()));
+        }
+        Ok(())
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC her anahtar uzunluğunu kabul eder");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+// S3 kurallarıyla yol kodlaması: ayrılmamış karakterler ve '/' aynen kalır.
+fn uri_encode_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+// --- Arşivden Geri Yükleme ---
+// Arşiv dosyasını (.jsonl veya .jsonl.gz) okuyup kayıtları yeniden yazar.
+// Kayıtlar yeni id'ler alır; kiracı, seviye, mesaj ve ayrıntılar korunur.
+pub async fn import_file(store: &SharedStorage, path: &str, batch_size: usize) -> Result<u64, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("{} açılamadı: {}", path, e))?;
+    let reader: Box<dyn BufRead> = if path.ends_with(".gz") {
+        Box::new(BufReader::new(GzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    let batch_size = batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut imported = 0;
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("{} okunamadı: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: StoredLog =
+            serde_json::from_str(&line).map_err(|e| format!("{}:{} geçersiz kayıt: {}", path, n + 1, e))?;
+        batch.push(LogEntry::from(row));
+        if batch.len() >= batch_size {
+            store.insert_batch(&batch).await.map_err(|e| e.to_string())?;
+            imported += batch.len() as u64;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        store.insert_batch(&batch).await.map_err(|e| e.to_string())?;
+        imported += batch.len() as u64;
+    }
+    Ok(imported)
+}
+
+impl From<StoredLog> for LogEntry {
+    fn from(row: StoredLog) -> Self {
+        let mut extra = match row.details {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        // Sütundaki zaman esastır (silme ve sorgular onu kullanır)
+        extra.insert("timestamp".to_string(), serde_json::Value::String(row.timestamp));
+        // Kendi sütunlarına taşınmış alanlar geri konur (yeniden yazılırken tekrar taşınır)
+        for (key, value) in [("service", row.service), ("host", row.host), ("env", row.env), ("trace_id", row.trace_id), ("span_id", row.span_id)] {
+            if let Some(value) = value {
+                extra.insert(key.to_string(), serde_json::Value::String(value));
+            }
+        }
+        LogEntry {
+            level: row.level,
+            message: row.message,
+            extra: serde_json::Value::Object(extra),
+            tenant_id: row.tenant_id,
+            source: crate::Source {
+                remote_addr: row.remote_addr.and_then(|addr| addr.parse().ok()),
+                user_agent: row.user_agent,
+                api_key: row.api_key,
+                received_at: row.received_at,
+            },
+        }
+    }
+}
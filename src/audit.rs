@@ -0,0 +1,132 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, error};
+
+use crate::auth::{self, Caller};
+use crate::storage::{AuditEvent, AuditQuery, AuditRecord, SharedStorage};
+use crate::timestamp;
+use crate::AppState;
+
+const DEFAULT_LIMIT: i64 = 100;
+
+// --- Denetim Kaydı ---
+// Silmeler (DELETE /logs, saklama politikası, `log-ingestor purge`), yapılandırma
+// yeniden yüklemeleri ve anahtar değişiklikleri ana veritabanındaki 'audit'
+// tablosuna kim yaptı, ne zaman ve hangi parametrelerle bilgisiyle yazılır;
+// GET /admin/audit ile okunur. Kayıt işlemden sonra yazılır: yazılamazsa işlem
+// geri alınmaz, hata süreç loguna düşer.
+//
+// İşlemler:
+//   logs.delete        silme talebi (DELETE /logs)
+//   retention.purge    saklama süresi dolan kayıtlar
+//   retention.evict    boyut sınırı için silinen en eski kayıtlar
+//   cli.purge          komut satırından silme
+//   config.reload      POST /reload veya SIGHUP (başarısız olanlar da)
+//   token.create       POST /admin/tokens
+//   token.revoke       DELETE /admin/tokens/{ad}
+
+// Sunucunun kendi başlattığı işlemler için (istemci yok)
+pub async fn record(store: &SharedStorage, actor: &str, action: &str, params: Value) {
+    write(
+        store,
+        AuditEvent {
+            timestamp: Utc::now().timestamp_micros(),
+            actor: actor.to_string(),
+            remote_addr: None,
+            action: action.to_string(),
+            params,
+        },
+    )
+    .await
+}
+
+// Bir isteğin yaptığı işlemler için; doğrulama kapalıyken "anonymous"
+pub async fn record_caller(store: &SharedStorage, caller: Option<&Caller>, action: &str, params: Value) {
+    write(
+        store,
+        AuditEvent {
+            timestamp: Utc::now().timestamp_micros(),
+            actor: caller.map_or("anonymous", |c| &c.id).to_string(),
+            remote_addr: caller.and_then(|c| c.remote_addr).map(|ip| ip.to_string()),
+            action: action.to_string(),
+            params,
+        },
+    )
+    .await
+}
+
+async fn write(store: &SharedStorage, event: AuditEvent) {
+    debug!("📝 Denetim kaydı: {} {} {}", event.actor, event.action, event.params);
+    if let Err(e) = store.record_audit(&event).await {
+        error!("❌ Denetim kaydı yazılamadı: {} ({:?})", e, event);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditParams {
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_micros")]
+    from: Option<i64>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_micros")]
+    to: Option<i64>,
+    action: Option<String>,
+    actor: Option<String>,
+    before_id: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditEntry {
+    id: i64,
+    // RFC 3339, UTC
+    timestamp: String,
+    actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote_addr: Option<String>,
+    action: String,
+    params: Value,
+}
+
+impl From<AuditRecord> for AuditEntry {
+    fn from(record: AuditRecord) -> Self {
+        AuditEntry {
+            id: record.id,
+            timestamp: timestamp::format_micros(record.timestamp),
+            actor: record.actor,
+            remote_addr: record.remote_addr,
+            action: record.action,
+            params: serde_json::from_str(&record.params).unwrap_or(Value::String(record.params)),
+        }
+    }
+}
+
+// GET /admin/audit?action=logs.delete&from=...&to=...&limit=100: en yeniden
+// eskiye; sonraki sayfa için son kaydın id'si before_id olarak verilir.
+pub async fn list_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(params): Query<AuditParams>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, String)> {
+    auth::require_default_tenant(caller.as_deref()).map_err(|status| (status, String::new()))?;
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err((StatusCode::BAD_REQUEST, "'from', 'to'dan sonra olamaz".to_string()));
+        }
+    }
+    let query = AuditQuery {
+        from: params.from,
+        to: params.to,
+        action: params.action.filter(|action| !action.is_empty()),
+        actor: params.actor.filter(|actor| !actor.is_empty()),
+        before_id: params.before_id,
+        limit: params.limit.unwrap_or(DEFAULT_LIMIT),
+    };
+    let records = state.store.audit_log(&query).await.map_err(|e| {
+        error!("❌ Denetim kayıtları okunamadı: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+    })?;
+    Ok(Json(records.into_iter().map(AuditEntry::from).collect()))
+}
@@ -0,0 +1,155 @@
+use clap::{Args, Parser, Subcommand};
+
+use log_ingestor::config::Backpressure;
+use log_ingestor::Config;
+
+// --- Komut Satırı Arayüzü ---
+// Alt komut verilmezse 'serve' çalışır (eski davranış).
+#[derive(Debug, Parser)]
+#[command(name = "log-ingestor", version, about = "Yüksek performanslı log toplama sunucusu")]
+pub struct Cli {
+    /// Yapılandırma dosyası yolu
+    #[arg(long, global = true, env = "LOG_INGESTOR_CONFIG", default_value = "config.toml")]
+    pub config: String,
+
+    /// SQLite veritabanı dosyası (storage.db_path ayarını ezer)
+    #[arg(long, global = true)]
+    pub db: Option<String>,
+
+    /// Sunucunun kendi log seviyesi (örn. "info", "debug"); RUST_LOG'dan önceliklidir
+    #[arg(long, global = true)]
+    pub log_level: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// HTTP sunucusunu başlatır
+    Serve {
+        /// Dinlenecek port (server.port ayarını ezer)
+        #[arg(long)]
+        port: Option<u16>,
+        /// Windows hizmeti olarak çalış (hizmet denetim yöneticisi başlatır)
+        #[cfg(windows)]
+        #[arg(long)]
+        service: bool,
+    },
+    /// Belirli bir tarihten eski logları siler
+    Purge(PurgeArgs),
+    /// Kayıtlı logları sorgular ve JSON satırları olarak basar
+    Query(Box<QueryArgs>),
+    /// Arşiv dosyalarını (.jsonl / .jsonl.gz) veritabanına geri yükler
+    Import(ImportArgs),
+    /// Standart girdiden NDJSON veya düz metin satırları okuyup veritabanına yazar
+    Pipe(PipeArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PurgeArgs {
+    /// Bu RFC 3339 zamanından eski kayıtları sil
+    #[arg(long, conflicts_with = "older_than_days", required_unless_present = "older_than_days", value_parser = crate::timestamp::parse_micros)]
+    pub before: Option<i64>,
+
+    /// Bu kadar günden eski kayıtları sil
+    #[arg(long)]
+    pub older_than_days: Option<i64>,
+
+    /// Sadece bu seviyedeki kayıtları sil
+    #[arg(long)]
+    pub level: Option<String>,
+
+    /// Sadece bu kiracının kayıtlarını sil (verilmezse tüm kiracılar)
+    #[arg(long)]
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// Sadece bu kiracının kayıtları (verilmezse tüm kiracılar)
+    #[arg(long)]
+    pub tenant: Option<String>,
+    #[arg(long)]
+    pub level: Option<String>,
+    /// Bu seviye ve üstü (örn. "warn": warn, error, fatal)
+    #[arg(long, value_parser = crate::query::parse_min_level)]
+    pub min_level: Option<u8>,
+    /// Sadece bu servisin kayıtları
+    #[arg(long)]
+    pub service: Option<String>,
+    /// Sadece bu makinenin kayıtları
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Sadece bu ortamın kayıtları (örn. "prod")
+    #[arg(long)]
+    pub env: Option<String>,
+    /// Sadece bu izin (trace) kayıtları
+    #[arg(long)]
+    pub trace_id: Option<String>,
+    /// Sadece bu span'in kayıtları
+    #[arg(long)]
+    pub span_id: Option<String>,
+    /// RFC 3339 başlangıç zamanı (dahil)
+    #[arg(long, value_parser = crate::timestamp::parse_micros)]
+    pub from: Option<i64>,
+    /// RFC 3339 bitiş zamanı (dahil)
+    #[arg(long, value_parser = crate::timestamp::parse_micros)]
+    pub to: Option<i64>,
+    /// Mesaj içinde aranacak metin
+    #[arg(long)]
+    pub q: Option<String>,
+    /// Filtre ifadesi, örn. 'level=error AND extra.user_id=123'
+    #[arg(long, value_parser = crate::expr::parse)]
+    pub filter: Option<crate::expr::Expr>,
+    #[arg(long, default_value_t = 100)]
+    pub limit: i64,
+    #[arg(long, default_value_t = 0)]
+    pub offset: i64,
+    /// Bu id'li kayıttan eski kayıtlar (sonraki sayfa)
+    #[arg(long)]
+    pub before_id: Option<i64>,
+    /// Bu id'li kayıttan yeni kayıtlar (önceki sayfa)
+    #[arg(long)]
+    pub after_id: Option<i64>,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// Arşivden indirilmiş dosyalar
+    #[arg(required = true)]
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct PipeArgs {
+    /// JSON olmayan satırların [raw] ayrıştırıcısı (verilmezse raw.default_parser)
+    #[arg(long)]
+    pub parser: Option<String>,
+    /// Bu seviye ve üstünü yaz (filter ayarlarını ezer), örn. "debug"
+    #[arg(long)]
+    pub min_level: Option<String>,
+    /// Okunan satırları standart çıktıya da bas
+    #[arg(long)]
+    pub tee: bool,
+}
+
+impl Cli {
+    // Komut satırı bayraklarını yapılandırmanın üzerine uygular.
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(db) = &self.db {
+            config.storage.db_path = db.clone();
+        }
+        if let Some(Command::Serve { port: Some(port), .. }) = &self.command {
+            config.server.port = *port;
+        }
+        if let Some(Command::Pipe(args)) = &self.command {
+            if let Some(level) = &args.min_level {
+                config.filter.levels = None;
+                config.filter.min_level = Some(level.clone());
+            }
+            // Girdi okuma hızı kanala göre ayarlanır; satır atılmaz
+            config.pipeline.backpressure = Backpressure::Block;
+        }
+    }
+}
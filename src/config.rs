@@ -0,0 +1,1815 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+// --- Yapılandırma ---
+// Ayarlar önce config.toml dosyasından okunur, sonra ortam değişkenleriyle ezilir.
+// Dosya yoksa varsayılan değerler kullanılır (eski sabit kodlanmış değerlerle aynı).
+//
+// Dosya yolu --config bayrağı veya LOG_INGESTOR_CONFIG ile değiştirilebilir.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub storage: StorageConfig,
+    pub pipeline: PipelineConfig,
+    pub filter: FilterConfig,
+    pub syslog: SyslogConfig,
+    pub gelf: GelfConfig,
+    pub hec: HecConfig,
+    pub otlp: OtlpConfig,
+    pub fluentd: FluentdConfig,
+    pub kafka: KafkaConfig,
+    pub raw: RawConfig,
+    pub files: FilesConfig,
+    pub journald: JournaldConfig,
+    pub docker: DockerConfig,
+    pub auth: AuthConfig,
+    pub tls: Option<TlsConfig>,
+    pub rate_limit: RateLimitConfig,
+    pub limits: LimitsConfig,
+    pub retention: RetentionConfig,
+    pub archive: Option<ArchiveConfig>,
+    pub export: ExportConfig,
+    pub tail: TailConfig,
+    pub alerts: AlertsConfig,
+    pub redact: RedactConfig,
+    pub transform: Vec<TransformStepConfig>,
+    pub wasm: Vec<WasmHookConfig>,
+    pub sampling: SamplingConfig,
+    pub dedup: DedupConfig,
+    pub geoip: GeoIpConfig,
+    pub levels: LevelsConfig,
+    pub dead_letter: DeadLetterConfig,
+    pub wal: WalConfig,
+    pub erasure: ErasureConfig,
+    pub loki: Option<LokiConfig>,
+    pub elasticsearch: Option<ElasticsearchConfig>,
+    pub upstream: Option<UpstreamConfig>,
+    pub self_log: SelfLogConfig,
+    pub ui: UiConfig,
+    pub cors: CorsConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind_address: String,
+    pub port: u16,
+    // Yazma uçlarında kabul edilen en büyük istek gövdesi (açılmış hali dahil)
+    pub max_body_bytes: usize,
+    // Verilirse TCP yerine bu yoldaki unix soketinde dinlenir (sadece Unix)
+    pub unix_socket: Option<String>,
+    // Soket dosyasının izinleri (TOML'da sekizlik: 0o660)
+    pub unix_socket_mode: u32,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind_address: "0.0.0.0".to_string(),
+            port: 3002,
+            max_body_bytes: 2 * 1024 * 1024,
+            unix_socket: None,
+            unix_socket_mode: 0o660,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub db_path: String,
+    // Bağlantı havuzunun en fazla bağlantı sayısı (SQLite ve PostgreSQL;
+    // bellek içi SQLite her zaman tek bağlantı kullanır)
+    pub max_connections: u32,
+    // SQLite bağlantı ayarları (PRAGMA'lar); diğer arka uçlarda yok sayılır
+    pub sqlite: SqliteConfig,
+    // Eşleşen logları ana veritabanı yerine başka bir depoya yazan kurallar
+    // (yazıldıkları sırayla; ilk eşleşen kazanır)
+    pub routes: Vec<RouteConfig>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            db_path: "logs.db".to_string(),
+            max_connections: 10,
+            sqlite: SqliteConfig::default(),
+            routes: Vec::new(),
+        }
+    }
+}
+
+// Her SQLite bağlantısı açılırken uygulanan PRAGMA'lar. Varsayılanlar SQLite'ınkilerle
+// aynıdır (busy_timeout hariç); yazma yoğun kurulumlarda genellikle
+// synchronous = "normal" (WAL ile güvenli, yalnızca işletim sistemi çökmesinde son
+// işlemler kaybolabilir) ve daha büyük cache_size / mmap_size tercih edilir.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SqliteConfig {
+    pub synchronous: SqliteSynchronous,
+    // Sayfa önbelleği: pozitif değer sayfa sayısı, negatif değer KiB (-2000 = ~2 MB)
+    pub cache_size: i64,
+    // Kilitli veritabanında hata vermeden önce beklenecek süre
+    pub busy_timeout_ms: u64,
+    // Bellek eşlemeli G/Ç için bayt sınırı (0 = kapalı)
+    pub mmap_size: u64,
+    // Okuma API'sine ayrılan salt okunur bağlantı sayısı (0 = okumalar yazma
+    // havuzunu paylaşır)
+    pub read_connections: u32,
+    // details sütununu zstd ile sıkıştırarak yaz
+    pub compress_details: bool,
+    // zstd seviyesi (1 - 22)
+    pub compression_level: i32,
+    // Sözlük bu kadar kayıttan eğitilir; o kadar kayıt birikene kadar sözlüksüz
+    // sıkıştırılır (0 = sözlük kullanılmaz)
+    pub dictionary_samples: usize,
+    // "day": loglar UTC gününe göre ayrı dosyalara yazılır (logs-2024-06-01.db)
+    pub partition: SqlitePartition,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlitePartition {
+    // Tek dosya (db_path)
+    #[default]
+    None,
+    Day,
+}
+
+impl std::str::FromStr for SqlitePartition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(SqlitePartition::None),
+            "day" => Ok(SqlitePartition::Day),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        SqliteConfig {
+            synchronous: SqliteSynchronous::Full,
+            cache_size: -2000,
+            busy_timeout_ms: 5000,
+            mmap_size: 0,
+            read_connections: 4,
+            compress_details: false,
+            compression_level: 3,
+            dictionary_samples: 1000,
+            partition: SqlitePartition::None,
+        }
+    }
+}
+
+// PRAGMA synchronous değeri
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqliteSynchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl std::str::FromStr for SqliteSynchronous {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(SqliteSynchronous::Off),
+            "normal" => Ok(SqliteSynchronous::Normal),
+            "full" => Ok(SqliteSynchronous::Full),
+            "extra" => Ok(SqliteSynchronous::Extra),
+            _ => Err(()),
+        }
+    }
+}
+
+// Yönlendirme kuralı: 'field' değeri 'pattern'e uyarsa log 'db_path'e yazılır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteConfig {
+    pub name: String,
+    // "message", "level" veya extra içindeki alan (iç içe alanlar noktayla: "k8s.namespace")
+    #[serde(default = "default_route_field")]
+    pub field: String,
+    pub pattern: String,
+    // storage.db_path ile aynı biçim: SQLite dosyası, postgres:// veya clickhouse:// adresi
+    pub db_path: String,
+}
+
+fn default_route_field() -> String {
+    "service".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipelineConfig {
+    // MPSC kanalının kapasitesi (dolunca 'backpressure' uygulanır)
+    pub channel_capacity: usize,
+    // Kanal doluyken ne yapılacağı
+    pub backpressure: Backpressure,
+    // backpressure = "reject" iken 503 yanıtlarındaki Retry-After (saniye)
+    pub retry_after_secs: u64,
+    // Kanal doluluk oranı bu değeri geçerse /readyz 503 döner (0.0 - 1.0)
+    pub ready_max_fill: f64,
+    // Yazıcı bu kadar log biriktiğinde toplu yazar
+    pub batch_size: usize,
+    // ... ya da ilk log tampona girdikten bu kadar milisaniye sonra
+    pub flush_interval_ms: u64,
+    // Paralel yazıcı görevi sayısı (her birinin kendi kanalı olur)
+    pub writers: usize,
+    // Geçici yazma hatalarında (kilitli veritabanı, dolu havuz) parti en fazla bu kadar
+    // kez yeniden denenir; sonra ölü mektup dosyasına eklenir (0 = denenmez)
+    pub write_retries: u32,
+    // İlk yeniden deneme beklemesi; her denemede ikiye katlanır, üst sınırı 'retry_backoff_max_ms'
+    pub retry_backoff_ms: u64,
+    pub retry_backoff_max_ms: u64,
+    // Kapanışta yazıcıların kalan logları yazması için en fazla beklenecek süre (0 = süresiz)
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        PipelineConfig {
+            channel_capacity: 10_000,
+            backpressure: Backpressure::Block,
+            retry_after_secs: 1,
+            ready_max_fill: 0.9,
+            batch_size: 500,
+            flush_interval_ms: 200,
+            writers: 1,
+            write_retries: 3,
+            retry_backoff_ms: 100,
+            retry_backoff_max_ms: 5000,
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+// Kanal dolu olduğunda:
+//   block   handler yer açılana kadar bekler (varsayılan)
+//   drop    log atılır ve sayılır; istemci yine 202 alır
+//   reject  HTTP istekleri 503 + Retry-After ile reddedilir (UDP/TCP girişlerinde log atılır)
+// Yazma onayı bekleyen kaynaklar (Kafka) her zaman bekler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backpressure {
+    Block,
+    Drop,
+    Reject,
+}
+
+impl std::str::FromStr for Backpressure {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "block" => Ok(Backpressure::Block),
+            "drop" => Ok(Backpressure::Drop),
+            "reject" => Ok(Backpressure::Reject),
+            _ => Err(()),
+        }
+    }
+}
+
+// Seviye filtresi. 'levels' verilirse 'min_level'dan önceliklidir.
+// 'rules' seviye filtresinden önce, sırayla değerlendirilir.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    pub levels: Option<Vec<String>>,
+    pub min_level: Option<String>,
+    pub rules: Vec<FilterRuleConfig>,
+}
+
+// Regex kuralı: 'field' değeri 'pattern'e uyarsa 'action' uygulanır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterRuleConfig {
+    pub name: String,
+    // "message", "level" veya extra içindeki alan (iç içe alanlar noktayla: "http.path")
+    #[serde(default = "default_rule_field")]
+    pub field: String,
+    pub pattern: String,
+    pub action: FilterAction,
+    // action = "tag" için extra.tags listesine eklenecek etiket
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+fn default_rule_field() -> String {
+    "message".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    // Logu at (seviye filtresine bakılmaz)
+    Drop,
+    // Logu kabul et (seviye filtresine bakılmaz)
+    Keep,
+    // Etiketle ve sonraki kurallarla devam et
+    Tag,
+}
+
+// Syslog girişleri. Adres verilmezse ilgili dinleyici açılmaz.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SyslogConfig {
+    // Örn. "0.0.0.0:5514"
+    pub udp_bind: Option<String>,
+    // RFC 6587 çerçeveli TCP, örn. "0.0.0.0:6514"
+    pub tcp_bind: Option<String>,
+}
+
+// GELF UDP girişi. HTTP girişi (/gelf) her zaman açıktır.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GelfConfig {
+    // Örn. "0.0.0.0:12201"
+    pub udp_bind: Option<String>,
+}
+
+// Splunk HTTP Event Collector uyumlu giriş (/services/collector). Uçlar her zaman
+// açıktır; buradaki ayarlar kanal onaylarıyla (indexer acknowledgement) ilgilidir.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HecConfig {
+    // Açıksa her istek bir kanal (X-Splunk-Request-Channel) ister ve ackId döner
+    pub ack: bool,
+    // Bu süre kullanılmayan kanalın onayları silinir
+    pub ack_idle_timeout_secs: u64,
+    // Kanal başına sorgulanmamış en fazla onay; aşılırsa 503 (Server is busy)
+    pub max_pending_acks: usize,
+}
+
+impl Default for HecConfig {
+    fn default() -> Self {
+        HecConfig {
+            ack: false,
+            ack_idle_timeout_secs: 600,
+            max_pending_acks: 10_000,
+        }
+    }
+}
+
+// OpenTelemetry log girişi
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OtlpConfig {
+    // OTLP/gRPC LogsService, örn. "0.0.0.0:4317"
+    pub grpc_bind: Option<String>,
+    // Kaynak (resource) ve kapsam (scope) özniteliklerinin saklanma biçimi
+    pub resource_attributes: AttributeLayout,
+    pub scope_attributes: AttributeLayout,
+    // 'flatten' biçiminde alan adlarının önüne eklenir
+    pub resource_prefix: String,
+    pub scope_prefix: String,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        OtlpConfig {
+            grpc_bind: None,
+            resource_attributes: AttributeLayout::Nested,
+            scope_attributes: AttributeLayout::Nested,
+            resource_prefix: "resource_".to_string(),
+            scope_prefix: "scope_".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeLayout {
+    // details.resource / details.scope nesnesi
+    #[default]
+    Nested,
+    // Üst seviye alanlar, önekli ve noktalar '_' ile: resource_k8s_pod_name
+    Flatten,
+    // Üst seviye alanlar, öneksiz: k8s_pod_name (kayıt öznitelikleri önceliklidir)
+    Promote,
+}
+
+impl std::str::FromStr for AttributeLayout {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "nested" => Ok(AttributeLayout::Nested),
+            "flatten" => Ok(AttributeLayout::Flatten),
+            "promote" => Ok(AttributeLayout::Promote),
+            _ => Err(()),
+        }
+    }
+}
+
+// Yerleşik web arayüzü (/ui). Sayfa okuma uçlarını kullanır; anahtar gerekiyorsa
+// tarayıcıda girilir.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    pub enabled: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        UiConfig { enabled: true }
+    }
+}
+
+// Tarayıcıdan okuma uçlarına (GET /logs, /traces, /stats, /export, /tail)
+// doğrudan erişim. 'allowed_origins' boşsa CORS başlıkları gönderilmez; "*" her
+// kaynağa izin verir (allow_credentials ile birlikte kullanılamaz).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    // "https://dashboard.example.com" (şema ve port dahil, sonda '/' yok)
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    // İstekte gönderilebilecek başlıklar; "*" hepsi
+    pub allowed_headers: Vec<String>,
+    // Çerez / Basic auth bilgileriyle istek (fetch credentials: "include")
+    pub allow_credentials: bool,
+    // Tarayıcının ön kontrol (OPTIONS) yanıtını önbellekte tutma süresi
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["GET".to_string()],
+            allowed_headers: vec!["authorization".to_string(), "content-type".to_string(), "x-api-key".to_string()],
+            allow_credentials: false,
+            max_age_secs: 600,
+        }
+    }
+}
+
+// Sunucunun kendi loglarını (uyarı, hata) kendi deposuna yazması. Kayıtlar
+// source = "self", service = "log-ingestor" ile işaretlenir ve diğer loglar gibi
+// filtre, kural ve dönüştürmelerden geçer.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SelfLogConfig {
+    pub enabled: bool,
+    // Bu seviye ve üstü alınır: info, warn, error
+    pub min_level: SelfLogLevel,
+    // Saniyede en fazla kaç kayıt alınır; fazlası atılır (yazma hatası döngülerine karşı)
+    pub max_per_sec: u32,
+}
+
+impl Default for SelfLogConfig {
+    fn default() -> Self {
+        SelfLogConfig {
+            enabled: false,
+            min_level: SelfLogLevel::Warn,
+            max_per_sec: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfLogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl std::str::FromStr for SelfLogLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(SelfLogLevel::Info),
+            "warn" | "warning" => Ok(SelfLogLevel::Warn),
+            "error" => Ok(SelfLogLevel::Error),
+            _ => Err(()),
+        }
+    }
+}
+
+// Fluentd forward protokolü girişi (fluent-bit / fluentd 'forward' çıkışı)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FluentdConfig {
+    // Örn. "0.0.0.0:24224"
+    pub forward_bind: Option<String>,
+}
+
+// Kafka tüketicisi. İkili 'kafka' özelliğiyle derlenmiş olmalıdır;
+// 'brokers' verilmezse tüketici başlatılmaz.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KafkaConfig {
+    // Örn. "kafka1:9092,kafka2:9092"
+    pub brokers: Option<String>,
+    pub topics: Vec<String>,
+    pub group_id: String,
+}
+
+impl Default for KafkaConfig {
+    fn default() -> Self {
+        KafkaConfig {
+            brokers: None,
+            topics: vec!["logs".to_string()],
+            group_id: "log-ingestor".to_string(),
+        }
+    }
+}
+
+// Düz metin girişi (POST /ingest/raw). Ayrıştırıcı tanımlanmazsa yerleşik
+// "default" ayrıştırıcı kullanılır.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RawConfig {
+    // ?parser= verilmezse kullanılacak ayrıştırıcı (yoksa listedeki ilki)
+    pub default_parser: Option<String>,
+    pub parsers: Vec<LineParserConfig>,
+}
+
+// Adlandırılmış yakalama gruplu (named capture) regex ile tek satır ayrıştırıcı.
+// 'level', 'message' ve 'timestamp' grupları ilgili alanlara, diğer gruplar extra'ya gider.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LineParserConfig {
+    pub name: String,
+    pub pattern: String,
+    // 'timestamp' grubunun chrono biçimi (örn. "%d/%b/%Y:%H:%M:%S %z"); verilmezse RFC 3339
+    #[serde(default)]
+    pub timestamp_format: Option<String>,
+    // Satırda seviye yoksa (veya satır desene uymazsa) kullanılacak seviye
+    #[serde(default = "default_line_level")]
+    pub default_level: String,
+}
+
+fn default_line_level() -> String {
+    "info".to_string()
+}
+
+// Yerel dosyaları izleme (tail). 'paths' boşsa kapalıdır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilesConfig {
+    // Glob desenleri (bileşenlerde * ve ?), örn. "/var/log/nginx/*.log"
+    pub paths: Vec<String>,
+    // Satırları ayrıştıracak [raw] ayrıştırıcısı (yoksa raw.default_parser)
+    pub parser: Option<String>,
+    // Okunan konumların (checkpoint) saklandığı dosya
+    pub state_path: String,
+    // Desenlerin ve dosyaların yoklanma aralığı
+    pub poll_interval_ms: u64,
+    // Konumu kayıtlı olmayan ve başlangıçta zaten var olan dosyalar nereden okunur
+    pub start_at: FileStart,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        FilesConfig {
+            paths: Vec::new(),
+            parser: None,
+            state_path: "file-offsets.json".to_string(),
+            poll_interval_ms: 1000,
+            start_at: FileStart::End,
+        }
+    }
+}
+
+// systemd günlüğü (journald) girişi. Sadece Linux; kayıtlar journalctl ile okunur.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JournaldConfig {
+    pub enabled: bool,
+    // Sadece bu birimlerin kayıtları (boşsa hepsi), örn. "nginx.service"
+    pub units: Vec<String>,
+    // En düşük önem ("err", "warning") veya aralık ("0..3"); journalctl -p
+    pub priority: Option<String>,
+    // Sistem günlüğü yerine okunacak dizin (journalctl -D), örn. bağlanan /var/log/journal
+    pub directory: Option<String>,
+    // Son yazılan kaydın imlecinin (cursor) saklandığı dosya
+    pub state_path: String,
+    // Kayıtlı imleç yoksa nereden başlanır
+    pub start_at: FileStart,
+    // journalctl'in yolu
+    pub journalctl: String,
+}
+
+impl Default for JournaldConfig {
+    fn default() -> Self {
+        JournaldConfig {
+            enabled: false,
+            units: Vec::new(),
+            priority: None,
+            directory: None,
+            state_path: "journald-cursor".to_string(),
+            start_at: FileStart::End,
+            journalctl: "journalctl".to_string(),
+        }
+    }
+}
+
+// Docker konteyner logları (sadece Unix). Docker soketinden etiketlere uyan
+// konteynerler bulunur ve stdout/stderr akışları okunur.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DockerConfig {
+    pub enabled: bool,
+    pub socket: String,
+    // Sadece bu etiketleri taşıyan konteynerler ("logs=true" veya sadece anahtar); boşsa hepsi
+    pub labels: Vec<String>,
+    // JSON olmayan satırların [raw] ayrıştırıcısı (yoksa raw.default_parser)
+    pub parser: Option<String>,
+    // Konteyner listesinin yenilenme aralığı
+    pub poll_interval_secs: u64,
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        DockerConfig {
+            enabled: false,
+            socket: "/var/run/docker.sock".to_string(),
+            labels: Vec::new(),
+            parser: None,
+            poll_interval_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStart {
+    Beginning,
+    // Sadece bundan sonra eklenen satırlar (tail -f gibi)
+    #[default]
+    End,
+}
+
+impl std::str::FromStr for FileStart {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "beginning" => Ok(FileStart::Beginning),
+            "end" => Ok(FileStart::End),
+            _ => Err(()),
+        }
+    }
+}
+
+// Yazma ve sorgu uçları için API anahtarları. Hiç anahtar yoksa doğrulama kapalıdır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    // Varsayılan kiracının anahtarları (tüm roller)
+    pub api_keys: Vec<String>,
+    // kiracı adı -> anahtarları, örn. tenants.payments = ["..."] (yazma ve okuma)
+    pub tenants: HashMap<String, Vec<String>>,
+    // Tek rollü anahtarlar: [[auth.tokens]] name, key, role, tenant
+    pub tokens: Vec<TokenConfig>,
+    // /admin/tokens ile oluşturulan anahtarların (SHA-256 özetleri) dosyası;
+    // boşsa uç kapalıdır
+    pub token_file: String,
+    // Okuma uçlarının (sorgu, dışa aktarma, canlı akış) ayrı kimlik bilgileri
+    pub read: ReadAuthConfig,
+    // SSO ile verilen JWT'ler (Bearer); kiracı ve yetkiler token'dan okunur
+    pub jwt: JwtConfig,
+    // İstemci sertifikalarının (mTLS) rolleri ve kiracıları, CN'e göre.
+    // Listede olmayan sertifikalar yalnızca ingest rolüyle varsayılan kiracıya yazar.
+    pub client_certs: Vec<ClientCertConfig>,
+    // Uç sunucuların ([upstream]) /ingest/relay için kullandığı anahtarlar; 'relay'
+    // rolüne sahiptir (role = "relay" olan bir [[auth.tokens]] girdisiyle aynı).
+    pub relay_keys: Vec<String>,
+}
+
+// JWT doğrulaması. 'secret', 'jwks_path' veya 'jwks_url'den biri verilince açılır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JwtConfig {
+    // HS256 / HS384 / HS512 için paylaşılan anahtar
+    pub secret: Option<String>,
+    // Açık anahtarlar (RS*, PS*, ES256, ES384, EdDSA): JWKS dosyası veya adresi
+    pub jwks_path: Option<String>,
+    pub jwks_url: Option<String>,
+    // jwks_url'nin yeniden okunma aralığı (saniye)
+    pub jwks_refresh_secs: u64,
+    // Verilirse 'iss' / 'aud' bununla eşleşmeli
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    // Kiracı kimliğinin okunduğu claim
+    pub tenant_claim: String,
+    // Claim yoksa kullanılacak kiracı; verilmezse token reddedilir
+    pub default_tenant: Option<String>,
+    // Yetkilerin okunduğu claim: boşlukla ayrılmış metin ("scope") veya dizi ("scp")
+    pub scope_claim: String,
+    // Okuma, yazma ve yönetim uçlarını açan yetki adları (bkz. Role)
+    pub read_scope: String,
+    pub write_scope: String,
+    pub admin_scope: String,
+    // exp / nbf için saat kayması toleransı (saniye)
+    pub leeway_secs: u64,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        JwtConfig {
+            secret: None,
+            jwks_path: None,
+            jwks_url: None,
+            jwks_refresh_secs: 300,
+            issuer: None,
+            audience: None,
+            tenant_claim: "tenant".to_string(),
+            default_tenant: None,
+            scope_claim: "scope".to_string(),
+            read_scope: "read".to_string(),
+            write_scope: "write".to_string(),
+            admin_scope: "admin".to_string(),
+            leeway_secs: 60,
+        }
+    }
+}
+
+impl JwtConfig {
+    pub fn enabled(&self) -> bool {
+        self.secret.is_some() || self.jwks_path.is_some() || self.jwks_url.is_some()
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            api_keys: Vec::new(),
+            tenants: HashMap::new(),
+            tokens: Vec::new(),
+            token_file: "tokens.json".to_string(),
+            read: ReadAuthConfig::default(),
+            jwt: JwtConfig::default(),
+            client_certs: Vec::new(),
+            relay_keys: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenConfig {
+    // Loglarda ve /admin/tokens listesinde görünen ad
+    pub name: String,
+    pub key: String,
+    pub role: Role,
+    // Verilmezse varsayılan kiracı
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientCertConfig {
+    // Sertifika konusunun CN'i
+    pub cn: String,
+    // Verilmezse ["ingest"]
+    #[serde(default = "default_cert_roles")]
+    pub roles: Vec<Role>,
+    // Verilmezse varsayılan kiracı
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+fn default_cert_roles() -> Vec<Role> {
+    vec![Role::Ingest]
+}
+
+// Anahtarın açtığı uç grubu. admin ilk ikisini de kapsar.
+//   ingest: yazma uçları
+//   read:   sorgu, istatistik, dışa aktarma, canlı akış, Grafana
+//   admin:  silme, ölü mektup, yeniden yükleme, /admin/*
+//   relay:  uç sunucuların /ingest/relay ucu (ingest'i de kapsar). Loglar
+//           gövdedeki kiracıya yazılır; admin bu rolü kapsamaz, ayrıca verilmelidir
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Ingest,
+    Read,
+    Admin,
+    Relay,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Ingest => "ingest",
+            Role::Read => "read",
+            Role::Admin => "admin",
+            Role::Relay => "relay",
+        }
+    }
+}
+
+// Okuma uçları için ayrı kimlik bilgileri. Hiçbiri tanımlı değilse okuma uçları
+// yazma anahtarlarıyla korunur (eski davranış); tanımlıysa yazma anahtarları
+// okuma uçlarında geçmez.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadAuthConfig {
+    // Varsayılan kiracının okuma anahtarları
+    pub api_keys: Vec<String>,
+    // kiracı adı -> okuma anahtarları
+    pub tenants: HashMap<String, Vec<String>>,
+    // Basic auth kullanıcıları: users.alice = { password = "...", tenant = "payments" }
+    pub users: HashMap<String, ReadUserConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReadUserConfig {
+    // Düz metin veya "sha256:<hex>" (echo -n parola | sha256sum)
+    pub password: String,
+    // Verilmezse varsayılan kiracı
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+// Yerleşik HTTPS. Bölüm yoksa sunucu düz HTTP dinler.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    // PEM sertifika zinciri (sunucu sertifikası önce)
+    pub cert_path: String,
+    // PEM özel anahtar (PKCS#8, PKCS#1 veya SEC1)
+    pub key_path: String,
+    // > 0 ise dosyalar bu kadar saniyede bir kontrol edilir, değişince yeniden yüklenir
+    #[serde(default)]
+    pub reload_interval_secs: u64,
+    // İstemci sertifikalarını doğrulayan PEM CA paketi (mTLS)
+    #[serde(default)]
+    pub client_ca_path: Option<String>,
+    // true ise geçerli istemci sertifikası olmayan bağlantılar el sıkışmada reddedilir
+    #[serde(default)]
+    pub require_client_cert: bool,
+}
+
+// İstemci (API anahtarı / sertifika) başına hız sınırı. 0 = sınırsız.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub entries_per_sec: f64,
+    pub bytes_per_sec: f64,
+    // Kova kapasitesi, saniye cinsinden (ani yüklenmelere ne kadar izin verileceği)
+    pub burst_secs: f64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            entries_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+            burst_secs: 1.0,
+        }
+    }
+}
+
+// Tek bir kaydın alan boyutu sınırları (bayt). 'details' mesaj ve seviye dışındaki
+// tüm alanların JSON hali. 0 = sınırsız.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    pub max_message_bytes: usize,
+    pub max_details_bytes: usize,
+    pub oversize: Oversize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        LimitsConfig {
+            max_message_bytes: 64 * 1024,
+            max_details_bytes: 256 * 1024,
+            oversize: Oversize::Truncate,
+        }
+    }
+}
+
+// Sınırı aşan kayıt:
+//   truncate  alanlar sınıra kadar kesilir, kayda truncated = true eklenir (varsayılan)
+//   reject    kayıt atılır; /ingest?verbose=true hatalı kayıtlarda nedenini döner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Oversize {
+    Truncate,
+    Reject,
+}
+
+impl std::str::FromStr for Oversize {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "truncate" => Ok(Oversize::Truncate),
+            "reject" => Ok(Oversize::Reject),
+            _ => Err(()),
+        }
+    }
+}
+
+// Saklama süresi ve boyut sınırı. Eski loglar arka planda düzenli olarak silinir.
+// 'max_age_days', 'levels' ve 'max_db_bytes' hepsi boşsa görev çalışmaz.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionConfig {
+    // Tüm seviyeler için saklama süresi (gün); 0 = süresiz
+    pub max_age_days: u64,
+    // Seviyeye özel süreler, örn. levels.error = 90 (0 = o seviye hiç silinmez)
+    pub levels: HashMap<String, u64>,
+    // Veritabanı bu boyutu (bayt) aşarsa en eski kayıtlar silinir; 0 = sınırsız
+    pub max_db_bytes: u64,
+    // Boyut sınırı aşıldığında tek DELETE ile silinecek en fazla satır
+    pub evict_chunk_rows: u64,
+    // Silme görevinin çalışma aralığı (saniye)
+    pub interval_secs: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        RetentionConfig {
+            max_age_days: 0,
+            levels: HashMap::new(),
+            max_db_bytes: 0,
+            evict_chunk_rows: 10_000,
+            interval_secs: 3600,
+        }
+    }
+}
+
+// Saklama görevi silmeden önce kayıtları S3 uyumlu bir kovaya yazar.
+// Bölüm yoksa arşivleme kapalıdır.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveConfig {
+    // Örn. "https://s3.eu-central-1.amazonaws.com" veya "http://minio:9000"
+    pub endpoint: String,
+    pub bucket: String,
+    #[serde(default = "default_archive_region")]
+    pub region: String,
+    // Nesne adlarının ön eki, örn. "log-ingestor/"
+    #[serde(default)]
+    pub prefix: String,
+    // Verilmezse AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY kullanılır
+    #[serde(default)]
+    pub access_key: Option<String>,
+    #[serde(default)]
+    pub secret_key: Option<String>,
+    // true: endpoint/kova/nesne (MinIO vb.), false: kova.endpoint/nesne
+    #[serde(default = "default_true")]
+    pub path_style: bool,
+    // Bir arşiv dosyasındaki en fazla satır
+    #[serde(default = "default_archive_chunk_rows")]
+    pub chunk_rows: i64,
+}
+
+// Gizli anahtar loglara (debug yapılandırma dökümü) düşmesin diye elle yazılır.
+impl std::fmt::Debug for ArchiveConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArchiveConfig")
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("prefix", &self.prefix)
+            .field("access_key", &self.access_key)
+            .field("secret_key", &self.secret_key.as_ref().map(|_| "***"))
+            .field("path_style", &self.path_style)
+            .field("chunk_rows", &self.chunk_rows)
+            .finish()
+    }
+}
+
+fn default_archive_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_archive_chunk_rows() -> i64 {
+    50_000
+}
+
+// Yazılan logların kopyalarını Grafana Loki'ye gönderir (push API).
+// Bölüm yoksa kapalıdır.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LokiConfig {
+    // Örn. "http://loki:3100/loki/api/v1/push"
+    pub url: String,
+    // Çok kiracılı Loki için X-Scope-OrgID başlığı
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    // Basic auth (Grafana Cloud: kullanıcı numarası ve API anahtarı)
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    // Loki etiketi -> log alanı ("level", "tenant_id" veya 'extra'daki
+    // anahtar; iç içe alanlar "resource.host" gibi). Alanı olmayan loglarda etiket eklenmez.
+    #[serde(default = "default_loki_labels")]
+    pub labels: HashMap<String, String>,
+    // Her akışa eklenen sabit etiketler, örn. { job = "log-ingestor" }
+    #[serde(default)]
+    pub static_labels: HashMap<String, String>,
+    // Hedef yavaşken veya çalışmıyorken bellekte bekleyen en fazla parti;
+    // dolunca yeni partiler Loki için atılır (yerel yazma etkilenmez)
+    #[serde(default = "default_queue_batches")]
+    pub queue_batches: usize,
+    // Geçici hatada (bağlantı, 429, 5xx) en fazla yeniden deneme (0 = başarılı olana kadar)
+    #[serde(default)]
+    pub max_retries: u32,
+    // Yeniden denemeler arası bekleme: her denemede ikiye katlanır
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    #[serde(default = "default_retry_backoff_max_ms")]
+    pub retry_backoff_max_ms: u64,
+    // Tek isteğin zaman aşımı
+    #[serde(default = "default_sink_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+// Gizli alanlar loglara (debug yapılandırma dökümü) düşmesin diye elle yazılır.
+impl std::fmt::Debug for LokiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LokiConfig")
+            .field("url", &self.url)
+            .field("tenant_id", &self.tenant_id)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("labels", &self.labels)
+            .field("static_labels", &self.static_labels)
+            .field("queue_batches", &self.queue_batches)
+            .field("max_retries", &self.max_retries)
+            .field("retry_backoff_ms", &self.retry_backoff_ms)
+            .field("retry_backoff_max_ms", &self.retry_backoff_max_ms)
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
+fn default_loki_labels() -> HashMap<String, String> {
+    [("level", "level"), ("service", "service")]
+        .into_iter()
+        .map(|(label, field)| (label.to_string(), field.to_string()))
+        .collect()
+}
+
+// Yazılan logların kopyalarını Elasticsearch / OpenSearch'e _bulk API ile
+// gönderir. Bölüm yoksa kapalıdır.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ElasticsearchConfig {
+    // Küme adresi, örn. "https://es.internal:9200"
+    pub url: String,
+    // Dizin adı; logun zamanına (UTC) göre strftime ile doldurulur
+    #[serde(default = "default_es_index")]
+    pub index: String,
+    // Basic auth veya API anahtarı ("Authorization: ApiKey ...")
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default = "default_queue_batches")]
+    pub queue_batches: usize,
+    #[serde(default)]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    #[serde(default = "default_retry_backoff_max_ms")]
+    pub retry_backoff_max_ms: u64,
+    #[serde(default = "default_sink_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for ElasticsearchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElasticsearchConfig")
+            .field("url", &self.url)
+            .field("index", &self.index)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .field("queue_batches", &self.queue_batches)
+            .field("max_retries", &self.max_retries)
+            .field("retry_backoff_ms", &self.retry_backoff_ms)
+            .field("retry_backoff_max_ms", &self.retry_backoff_max_ms)
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
+fn default_es_index() -> String {
+    "logs-%Y.%m.%d".to_string()
+}
+
+// Zincirleme aktarım: yazılan loglar başka bir log-ingestor'ın /ingest/relay
+// ucuna da gönderilir (uç sunucular -> merkez). Bölüm yoksa kapalıdır.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpstreamConfig {
+    // Üst sunucunun adresi, örn. "https://hub.internal:3002" (/ingest/relay eklenir)
+    pub url: String,
+    // Üst sunucudaki 'relay' rollü anahtar; loglar orada uçtaki kiracılarına yazılır
+    #[serde(default)]
+    pub api_key: Option<String>,
+    // Gönderilmeyi bekleyen partilerin (gzip) tutulduğu dizin; yeniden
+    // başlatmada kalan partiler gönderilmeye devam edilir
+    #[serde(default = "default_upstream_buffer_dir")]
+    pub buffer_dir: String,
+    // Dizinin en fazla boyutu; dolunca yeni partiler üst sunucu için atılır
+    #[serde(default = "default_upstream_buffer_max_bytes")]
+    pub buffer_max_bytes: u64,
+    #[serde(default = "default_queue_batches")]
+    pub queue_batches: usize,
+    #[serde(default)]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    #[serde(default = "default_retry_backoff_max_ms")]
+    pub retry_backoff_max_ms: u64,
+    #[serde(default = "default_sink_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for UpstreamConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpstreamConfig")
+            .field("url", &self.url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .field("buffer_dir", &self.buffer_dir)
+            .field("buffer_max_bytes", &self.buffer_max_bytes)
+            .field("queue_batches", &self.queue_batches)
+            .field("max_retries", &self.max_retries)
+            .field("retry_backoff_ms", &self.retry_backoff_ms)
+            .field("retry_backoff_max_ms", &self.retry_backoff_max_ms)
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
+fn default_upstream_buffer_dir() -> String {
+    "upstream-buffer".to_string()
+}
+
+fn default_upstream_buffer_max_bytes() -> u64 {
+    1 << 30
+}
+
+// Yönlendiricilerin (Loki, Elasticsearch, üst sunucu) ortak gönderim varsayılanları
+fn default_queue_batches() -> usize {
+    1000
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_retry_backoff_max_ms() -> u64 {
+    30_000
+}
+
+fn default_sink_timeout_secs() -> u64 {
+    10
+}
+
+// Parquet dışa aktarma: GET /export/parquet ve zamanlanmış dışa aktarıcı.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExportConfig {
+    // /export/parquet yanıtındaki en fazla satır
+    pub max_rows: u64,
+    // Verilirse yeni kayıtlar düzenli olarak bu dizine Parquet dosyaları olarak yazılır
+    pub dir: Option<String>,
+    // Zamanlanmış dışa aktarma aralığı (saniye; varsayılan saatlik)
+    pub interval_secs: u64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        ExportConfig {
+            max_rows: 1_000_000,
+            dir: None,
+            interval_secs: 3600,
+        }
+    }
+}
+
+// Canlı akış (/tail): kabul edilen loglar bağlı izleyicilere dağıtılır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TailConfig {
+    // İzleyici başına bekletilebilecek en fazla log; yetişemeyen izleyici kayıt kaçırır
+    pub buffer: usize,
+    // SSE bağlantısı başına gönderilmeyi bekleyen en fazla olay; dolarsa bağlantı kapatılır
+    pub sse_buffer: usize,
+}
+
+impl Default for TailConfig {
+    fn default() -> Self {
+        TailConfig {
+            buffer: 1024,
+            sse_buffer: 256,
+        }
+    }
+}
+
+// Uyarı kuralları. Kabul edilen loglar kurallarla karşılaştırılır; bir kural
+// penceresi içinde eşiği aşınca webhook'a özet gönderilir.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsConfig {
+    pub rules: Vec<AlertRuleConfig>,
+}
+
+// Örn. "5 dakikada 'OOM' içeren 50'den fazla error logu"
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRuleConfig {
+    pub name: String,
+    // Sadece bu seviye (büyük/küçük harf duyarsız); verilmezse tümü
+    #[serde(default)]
+    pub level: Option<String>,
+    // Mesajda geçmesi gereken metin (büyük/küçük harf duyarsız)
+    #[serde(default)]
+    pub contains: Option<String>,
+    // Sadece bu kiracının logları; verilmezse tüm kiracılar
+    #[serde(default)]
+    pub tenant: Option<String>,
+    // Pencere içindeki eşleşme sayısı bunu AŞARSA uyarı verilir
+    pub threshold: u64,
+    #[serde(default = "default_alert_window_secs")]
+    pub window_secs: u64,
+    // Uyarıdan sonra aynı kural bu süre boyunca tekrar tetiklenmez (verilmezse pencere kadar)
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+    // Özetin POST edileceği adres
+    pub webhook: String,
+    // Gövde biçimi: "json" (özetin kendisi), "slack" veya "discord" (gelen webhook mesajı)
+    #[serde(default)]
+    pub format: AlertFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertFormat {
+    #[default]
+    Json,
+    Slack,
+    Discord,
+}
+
+fn default_alert_window_secs() -> u64 {
+    300
+}
+
+// Örnekleme: seviye filtresinden geçen logların hangi oranının saklanacağı (0..1).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SamplingConfig {
+    // 'levels'ta olmayan seviyelerin oranı
+    pub default_rate: f64,
+    // seviye -> oran, örn. debug = 0.01
+    pub levels: HashMap<String, f64>,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        SamplingConfig {
+            default_rate: 1.0,
+            levels: HashMap::new(),
+        }
+    }
+}
+
+// Seviye normalleştirme: "WARNING", "err", 30 gibi seviyeler kanonik adlara çevrilir.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LevelsConfig {
+    pub normalize: bool,
+    // Sayısal seviyelerin yorumu
+    pub numeric: NumericLevels,
+    // Ek eşlemeler, örn. sev1 = "fatal" (yerleşik eşlemelerden önce bakılır)
+    pub aliases: HashMap<String, String>,
+}
+
+impl Default for LevelsConfig {
+    fn default() -> Self {
+        LevelsConfig {
+            normalize: true,
+            numeric: NumericLevels::Bunyan,
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumericLevels {
+    // pino / bunyan: 10 trace .. 60 fatal
+    Bunyan,
+    // Python logging: 10 debug .. 50 critical
+    Python,
+    // syslog önem derecesi: 0 emerg .. 7 debug
+    Syslog,
+}
+
+// Tekrar birleştirme: pencere içinde gelen aynı loglar tek satır (repeat_count) olur.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DedupConfig {
+    // 0 = kapalı. Açıkken loglar en fazla bu kadar gecikmeyle yazılır.
+    pub window_secs: u64,
+    // Aynı anda bekletilebilecek en fazla farklı log
+    pub max_pending: usize,
+    // Varsa anahtara eklenen extra alanı (aynı mesajın farklı kaynaklarını ayırmak için)
+    pub fingerprint_field: String,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            window_secs: 0,
+            max_pending: 10_000,
+            fingerprint_field: "fingerprint".to_string(),
+        }
+    }
+}
+
+// Veritabanına yazılamayan partilerin eklendiği NDJSON dosyası (boş yol = kapalı)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeadLetterConfig {
+    pub path: String,
+    // Dosya bu boyuta ulaşınca yeni partiler eklenmez (0 = sınırsız)
+    pub max_bytes: u64,
+}
+
+impl Default for DeadLetterConfig {
+    fn default() -> Self {
+        DeadLetterConfig {
+            path: "dead-letter.ndjson".to_string(),
+            max_bytes: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+// DELETE /logs ile yapılan silmelerin denetim kaydı (NDJSON, satır başına bir silme)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ErasureConfig {
+    // Boşsa silmeler sadece süreç loguna yazılır
+    pub audit_path: String,
+}
+
+impl Default for ErasureConfig {
+    fn default() -> Self {
+        ErasureConfig {
+            audit_path: "erasure-audit.ndjson".to_string(),
+        }
+    }
+}
+
+// Diskte kuyruk: kabul edilen loglar yazılana kadar parça dosyalarında da tutulur
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WalConfig {
+    // Verilirse açıktır
+    pub dir: Option<String>,
+    // Parça dosyası bu boyuta ulaşınca yenisine geçilir
+    pub segment_bytes: u64,
+    // Her kayıttan sonra fsync (elektrik kesintisine karşı; yavaştır)
+    pub fsync: bool,
+}
+
+impl Default for WalConfig {
+    fn default() -> Self {
+        WalConfig {
+            dir: None,
+            segment_bytes: 64 * 1024 * 1024,
+            fsync: false,
+        }
+    }
+}
+
+// GeoIP zenginleştirme: MaxMind (.mmdb) veritabanları. İkisi de verilmezse kapalı.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeoIpConfig {
+    // GeoLite2-City / GeoIP2-City (veya sadece ülke için Country) veritabanı
+    pub city_db: Option<String>,
+    // GeoLite2-ASN veritabanı
+    pub asn_db: Option<String>,
+    // IP adresinin okunduğu extra alanı (yoksa HTTP istemcisinin adresi kullanılır)
+    pub field: String,
+    // Sonucun yazıldığı extra alanı
+    pub target: String,
+}
+
+impl Default for GeoIpConfig {
+    fn default() -> Self {
+        GeoIpConfig {
+            city_db: None,
+            asn_db: None,
+            field: "ip".to_string(),
+            target: "geo".to_string(),
+        }
+    }
+}
+
+// Kişisel veri maskeleme: loglar yazılmadan önce 'message' ve 'extra'
+// içindeki eşleşmeler değiştirilir. Varsayılan olarak kapalıdır.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactConfig {
+    pub emails: bool,
+    pub email_replacement: String,
+    // Luhn kontrolünden geçen 13-19 haneli kart numaraları
+    pub credit_cards: bool,
+    pub card_replacement: String,
+    pub patterns: Vec<RedactPatternConfig>,
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        RedactConfig {
+            emails: false,
+            email_replacement: "[EMAIL]".to_string(),
+            credit_cards: false,
+            card_replacement: "[CARD]".to_string(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+// Özel desen. 'replacement' içinde $1, $ad gibi grup referansları kullanılabilir.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactPatternConfig {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default = "default_redact_replacement")]
+    pub replacement: String,
+}
+
+fn default_redact_replacement() -> String {
+    "[REDACTED]".to_string()
+}
+
+// WASM kancası ([[wasm]]): [[transform]] adımlarından sonra her logu alır,
+// değiştirebilir veya atabilir. İkili 'wasm' özelliğiyle derlenmiş olmalıdır;
+// değişiklik için yeniden başlatmak gerekir.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WasmHookConfig {
+    // .wasm veya .wat dosyası
+    pub path: String,
+    // Log başına en fazla yürütülecek komut (yaklaşık); aşılınca log değişmeden geçer
+    #[serde(default = "default_wasm_fuel")]
+    pub fuel: u64,
+    // Modülün doğrusal belleği için üst sınır
+    #[serde(default = "default_wasm_memory_mb")]
+    pub max_memory_mb: usize,
+}
+
+fn default_wasm_fuel() -> u64 {
+    10_000_000
+}
+
+fn default_wasm_memory_mb() -> usize {
+    64
+}
+
+// Dönüştürme adımı ([[transform]]): loglar filtrelerden önce, yazıldıkları
+// sırayla bu adımlardan geçer. Alanlar extra içindeki yollardır (iç içe
+// alanlar noktayla: "http.status").
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TransformStepConfig {
+    // Alanı taşır; 'to' "message" veya "level" de olabilir
+    Rename { from: String, to: String },
+    // Metin alanını (veya "message"ı) JSON olarak çözer: sonuç 'target'a (yoksa
+    // alanın yerine) yazılır ya da merge = true ise nesnenin anahtarları köke eklenir
+    ParseJson {
+        field: String,
+        #[serde(default)]
+        target: Option<String>,
+        #[serde(default)]
+        merge: bool,
+    },
+    // extra.tags listesine etiket ekler
+    AddTags { tags: Vec<String> },
+    // Sabit alanlar ekler; overwrite = false ise var olan alanlara dokunulmaz
+    AddFields {
+        fields: serde_json::Map<String, serde_json::Value>,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    // Alanın tipini değiştirir; çevrilemeyen değer olduğu gibi kalır
+    Coerce { field: String, to: CoerceType },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoerceType {
+    Integer,
+    Float,
+    Boolean,
+    String,
+}
+
+impl Config {
+    // Dosyayı (varsa) okur ve ortam değişkenlerini uygular.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut config = Self::from_file(Path::new(path))?;
+        config.apply_env();
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("{} okunamadı: {}", path.display(), e))?;
+        toml::from_str(&text).map_err(|e| format!("{} geçersiz: {}", path.display(), e))
+    }
+
+    // Ortam değişkenleri dosyadaki değerleri ezer.
+    fn apply_env(&mut self) {
+        if let Ok(v) = std::env::var("INGEST_BIND_ADDRESS") {
+            self.server.bind_address = v;
+        }
+        if let Some(v) = env_parse("INGEST_PORT") {
+            self.server.port = v;
+        }
+        if let Some(v) = env_parse("INGEST_MAX_BODY_BYTES") {
+            self.server.max_body_bytes = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_UNIX_SOCKET") {
+            self.server.unix_socket = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_DB_PATH") {
+            self.storage.db_path = v;
+        }
+        if let Some(v) = env_parse("INGEST_DB_MAX_CONNECTIONS") {
+            self.storage.max_connections = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_SYNCHRONOUS") {
+            self.storage.sqlite.synchronous = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_CACHE_SIZE") {
+            self.storage.sqlite.cache_size = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_BUSY_TIMEOUT_MS") {
+            self.storage.sqlite.busy_timeout_ms = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_MMAP_SIZE") {
+            self.storage.sqlite.mmap_size = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_READ_CONNECTIONS") {
+            self.storage.sqlite.read_connections = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_COMPRESS_DETAILS") {
+            self.storage.sqlite.compress_details = v;
+        }
+        if let Some(v) = env_parse("INGEST_SQLITE_PARTITION") {
+            self.storage.sqlite.partition = v;
+        }
+        if let Some(v) = env_parse("INGEST_CHANNEL_CAPACITY") {
+            self.pipeline.channel_capacity = v;
+        }
+        if let Some(v) = env_parse("INGEST_BACKPRESSURE") {
+            self.pipeline.backpressure = v;
+        }
+        if let Some(v) = env_parse("INGEST_MAX_MESSAGE_BYTES") {
+            self.limits.max_message_bytes = v;
+        }
+        if let Some(v) = env_parse("INGEST_MAX_DETAILS_BYTES") {
+            self.limits.max_details_bytes = v;
+        }
+        if let Some(v) = env_parse("INGEST_OVERSIZE") {
+            self.limits.oversize = v;
+        }
+        if let Some(v) = env_parse("INGEST_BATCH_SIZE") {
+            self.pipeline.batch_size = v;
+        }
+        if let Some(v) = env_parse("INGEST_FLUSH_INTERVAL_MS") {
+            self.pipeline.flush_interval_ms = v;
+        }
+        if let Some(v) = env_parse("INGEST_WRITERS") {
+            self.pipeline.writers = v;
+        }
+        if let Some(v) = env_parse("INGEST_WRITE_RETRIES") {
+            self.pipeline.write_retries = v;
+        }
+        if let Some(v) = env_parse("INGEST_SHUTDOWN_TIMEOUT_SECS") {
+            self.pipeline.shutdown_timeout_secs = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_LEVELS") {
+            self.filter.levels = Some(v.split(',').map(|l| l.trim().to_string()).collect());
+        }
+        if let Ok(v) = std::env::var("INGEST_MIN_LEVEL") {
+            self.filter.min_level = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_SYSLOG_UDP") {
+            self.syslog.udp_bind = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_SYSLOG_TCP") {
+            self.syslog.tcp_bind = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_GELF_UDP") {
+            self.gelf.udp_bind = Some(v);
+        }
+        if let Some(v) = env_parse("INGEST_HEC_ACK") {
+            self.hec.ack = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_OTLP_GRPC") {
+            self.otlp.grpc_bind = Some(v);
+        }
+        if let Some(v) = env_parse("INGEST_OTLP_RESOURCE_ATTRIBUTES") {
+            self.otlp.resource_attributes = v;
+        }
+        if let Some(v) = env_parse("INGEST_OTLP_SCOPE_ATTRIBUTES") {
+            self.otlp.scope_attributes = v;
+        }
+        if let Some(v) = env_parse("INGEST_UI") {
+            self.ui.enabled = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_CORS_ALLOWED_ORIGINS") {
+            self.cors.allowed_origins = v.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect();
+        }
+        if let Some(v) = env_parse("INGEST_SELF_LOG") {
+            self.self_log.enabled = v;
+        }
+        if let Some(v) = env_parse("INGEST_SELF_LOG_LEVEL") {
+            self.self_log.min_level = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_FLUENTD_FORWARD") {
+            self.fluentd.forward_bind = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_KAFKA_BROKERS") {
+            self.kafka.brokers = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_KAFKA_TOPICS") {
+            self.kafka.topics = v.split(',').map(|t| t.trim().to_string()).collect();
+        }
+        if let Ok(v) = std::env::var("INGEST_KAFKA_GROUP_ID") {
+            self.kafka.group_id = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_FILES_PATHS") {
+            self.files.paths = v.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+        }
+        if let Ok(v) = std::env::var("INGEST_FILES_PARSER") {
+            self.files.parser = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_FILES_STATE") {
+            self.files.state_path = v;
+        }
+        if let Some(v) = env_parse("INGEST_FILES_START_AT") {
+            self.files.start_at = v;
+        }
+        if let Some(v) = env_parse("INGEST_JOURNALD") {
+            self.journald.enabled = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_JOURNALD_UNITS") {
+            self.journald.units = v.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect();
+        }
+        if let Ok(v) = std::env::var("INGEST_JOURNALD_PRIORITY") {
+            self.journald.priority = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_JOURNALD_STATE") {
+            self.journald.state_path = v;
+        }
+        if let Some(v) = env_parse("INGEST_DOCKER") {
+            self.docker.enabled = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_DOCKER_SOCKET") {
+            self.docker.socket = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_DOCKER_LABELS") {
+            self.docker.labels = v.split(',').map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+        }
+        if let Ok(v) = std::env::var("INGEST_API_KEYS") {
+            self.auth.api_keys = v.split(',').map(|k| k.trim().to_string()).collect();
+        }
+        if let Ok(v) = std::env::var("INGEST_READ_API_KEYS") {
+            self.auth.read.api_keys = v.split(',').map(|k| k.trim().to_string()).collect();
+        }
+        if let Ok(v) = std::env::var("INGEST_JWT_SECRET") {
+            self.auth.jwt.secret = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_JWT_JWKS_URL") {
+            self.auth.jwt.jwks_url = Some(v);
+        }
+        match (std::env::var("INGEST_TLS_CERT"), std::env::var("INGEST_TLS_KEY")) {
+            (Ok(cert_path), Ok(key_path)) => {
+                let previous = self.tls.take();
+                self.tls = Some(TlsConfig {
+                    cert_path,
+                    key_path,
+                    reload_interval_secs: previous.as_ref().map_or(0, |t| t.reload_interval_secs),
+                    client_ca_path: previous.as_ref().and_then(|t| t.client_ca_path.clone()),
+                    require_client_cert: previous.is_some_and(|t| t.require_client_cert),
+                });
+            }
+            (Ok(_), Err(_)) | (Err(_), Ok(_)) => {
+                tracing::warn!("⚠️ INGEST_TLS_CERT ve INGEST_TLS_KEY birlikte verilmeli, yok sayılıyor.");
+            }
+            (Err(_), Err(_)) => {}
+        }
+        if let Some(v) = env_parse("INGEST_RATE_LIMIT_ENTRIES") {
+            self.rate_limit.entries_per_sec = v;
+        }
+        if let Some(v) = env_parse("INGEST_RATE_LIMIT_BYTES") {
+            self.rate_limit.bytes_per_sec = v;
+        }
+        if let Some(v) = env_parse("INGEST_RETENTION_DAYS") {
+            self.retention.max_age_days = v;
+        }
+        if let Some(v) = env_parse("INGEST_RETENTION_MAX_BYTES") {
+            self.retention.max_db_bytes = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_EXPORT_DIR") {
+            self.export.dir = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_WAL_DIR") {
+            self.wal.dir = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_DEAD_LETTER_PATH") {
+            self.dead_letter.path = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_ERASURE_AUDIT_PATH") {
+            self.erasure.audit_path = v;
+        }
+        if let Ok(v) = std::env::var("INGEST_GEOIP_CITY_DB") {
+            self.geoip.city_db = Some(v);
+        }
+        if let Ok(v) = std::env::var("INGEST_GEOIP_ASN_DB") {
+            self.geoip.asn_db = Some(v);
+        }
+        for (name, secret) in [("INGEST_ARCHIVE_ACCESS_KEY", false), ("INGEST_ARCHIVE_SECRET_KEY", true)] {
+            let Ok(v) = std::env::var(name) else {
+                continue;
+            };
+            match &mut self.archive {
+                Some(archive) if secret => archive.secret_key = Some(v),
+                Some(archive) => archive.access_key = Some(v),
+                None => tracing::warn!("⚠️ {} için önce [archive] yapılandırılmalı, yok sayılıyor.", name),
+            }
+        }
+        if let Ok(v) = std::env::var("INGEST_LOKI_PASSWORD") {
+            match &mut self.loki {
+                Some(loki) => loki.password = Some(v),
+                None => tracing::warn!("⚠️ INGEST_LOKI_PASSWORD için önce [loki] yapılandırılmalı, yok sayılıyor."),
+            }
+        }
+        for (name, api_key) in [("INGEST_ELASTICSEARCH_PASSWORD", false), ("INGEST_ELASTICSEARCH_API_KEY", true)] {
+            let Ok(v) = std::env::var(name) else {
+                continue;
+            };
+            match &mut self.elasticsearch {
+                Some(es) if api_key => es.api_key = Some(v),
+                Some(es) => es.password = Some(v),
+                None => tracing::warn!("⚠️ {} için önce [elasticsearch] yapılandırılmalı, yok sayılıyor.", name),
+            }
+        }
+        if let Ok(v) = std::env::var("INGEST_UPSTREAM_API_KEY") {
+            match &mut self.upstream {
+                Some(upstream) => upstream.api_key = Some(v),
+                None => tracing::warn!("⚠️ INGEST_UPSTREAM_API_KEY için önce [upstream] yapılandırılmalı, yok sayılıyor."),
+            }
+        }
+        if let Ok(v) = std::env::var("INGEST_TLS_CLIENT_CA") {
+            match &mut self.tls {
+                Some(tls) => tls.client_ca_path = Some(v),
+                None => tracing::warn!("⚠️ INGEST_TLS_CLIENT_CA için önce TLS yapılandırılmalı, yok sayılıyor."),
+            }
+        }
+    }
+}
+
+// Ortam değişkenini okur ve ayrıştırır; geçersizse uyarı verip yok sayar.
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let raw = std::env::var(name).ok()?;
+    match raw.parse() {
+        Ok(v) => Some(v),
+        Err(_) => {
+            tracing::warn!("⚠️ {}='{}' geçersiz, yok sayılıyor.", name, raw);
+            None
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+
+use crate::config::CorsConfig;
+
+// --- CORS ---
+// Tarayıcıda çalışan panoların (React vb.) okuma uçlarını vekil sunucu olmadan
+// çağırabilmesi için. Katman yalnızca okuma uçlarına eklenir; ön kontrol
+// (OPTIONS) istekleri doğrulamaya uğramadan burada yanıtlanır, asıl istek yine
+// anahtar ister. İzin verilmeyen kaynaklara CORS başlığı dönmez, tarayıcı
+// yanıtı sayfaya vermez.
+
+// Dışa aktarmanın yanıt başlıkları sayfadan okunabilsin
+const EXPOSED_HEADERS: [&str; 2] = ["x-export-rows", "x-export-truncated"];
+
+// Kaynak listesi boşsa None (CORS kapalı).
+pub fn layer(config: &CorsConfig) -> Result<Option<CorsLayer>, String> {
+    if config.allowed_origins.is_empty() {
+        return Ok(None);
+    }
+    let any_origin = config.allowed_origins.iter().any(|origin| origin == "*");
+    let any_header = config.allowed_headers.iter().any(|header| header == "*");
+    if config.allow_credentials && (any_origin || any_header) {
+        return Err("cors.allow_credentials açıkken kaynak ve başlıklarda \"*\" kullanılamaz".to_string());
+    }
+
+    let origins = if any_origin {
+        AllowOrigin::any()
+    } else {
+        let origins = config
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                let origin = origin.trim_end_matches('/');
+                if !origin.starts_with("http://") && !origin.starts_with("https://") {
+                    return Err(format!("geçersiz CORS kaynağı: {} (http:// veya https:// ile başlamalı)", origin));
+                }
+                HeaderValue::from_str(origin).map_err(|_| format!("geçersiz CORS kaynağı: {}", origin))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowOrigin::list(origins)
+    };
+    let methods = config
+        .allowed_methods
+        .iter()
+        .map(|method| {
+            Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes())
+                .map_err(|_| format!("geçersiz CORS yöntemi: {}", method))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let headers = if any_header {
+        AllowHeaders::any()
+    } else {
+        let headers = config
+            .allowed_headers
+            .iter()
+            .map(|header| HeaderName::from_bytes(header.trim().as_bytes()).map_err(|_| format!("geçersiz CORS başlığı: {}", header)))
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowHeaders::list(headers)
+    };
+
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .allow_credentials(config.allow_credentials)
+            .expose_headers(EXPOSED_HEADERS.map(HeaderName::from_static))
+            .max_age(Duration::from_secs(config.max_age_secs)),
+    ))
+}
@@ -0,0 +1,306 @@
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::auth::{self, Caller};
+use crate::config::DeadLetterConfig;
+use crate::metrics::Metrics;
+use crate::storage::Routes;
+use crate::{AppState, LogEntry, Source};
+
+// --- Ölü Mektup (Dead-Letter) Dosyası ---
+// Veritabanına yazılamayan partiler atılmaz; hata nedeniyle birlikte NDJSON
+// dosyasına eklenir (satır başına bir parti). Yönetim uçları:
+//   GET  /dead-letter              bekleyen partiler (loglar olmadan özet)
+//   POST /dead-letter/retry        tüm partileri yeniden yazmayı dener
+//   POST /dead-letter/{id}/retry   tek partiyi yeniden yazmayı dener
+// Yeniden yazılan loglar ardışık düzenden (filtre, maskeleme, ...) tekrar
+// geçmez; doğrudan veritabanına eklenir. Başarılı partiler dosyadan çıkarılır,
+// başarısızlar son hatayla birlikte kalır.
+pub struct DeadLetter {
+    path: PathBuf,
+    max_bytes: u64,
+    // Dosyaya erişimi sıralar; değer bir sonraki parti numarasıdır
+    next_id: Mutex<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DeadBatch {
+    id: u64,
+    // İlk başarısızlığın zamanı (RFC 3339, UTC)
+    failed_at: String,
+    // Son yazma hatası
+    error: String,
+    // Kaç kez yeniden denendiği
+    #[serde(default)]
+    attempts: u32,
+    logs: Vec<SavedLog>,
+}
+
+// LogEntry'nin gövdeden okunmayan alanları (kiracı, kaynak) dahil tam hali.
+// Diske yazılıp sonra aynen geri okunması gereken loglar için (ölü mektup, WAL).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedLog {
+    tenant_id: String,
+    level: String,
+    message: String,
+    #[serde(default)]
+    extra: Value,
+    remote_addr: Option<String>,
+    user_agent: Option<String>,
+    api_key: Option<String>,
+    received_at: Option<String>,
+}
+
+// GET /dead-letter yanıtındaki parti özeti
+#[derive(Debug, Serialize)]
+pub struct DeadBatchSummary {
+    id: u64,
+    failed_at: String,
+    error: String,
+    attempts: u32,
+    count: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RetryReport {
+    // Yazılıp dosyadan çıkarılan partiler ve log sayıları
+    retried: usize,
+    written: usize,
+    // Yine yazılamayıp dosyada kalan partiler
+    failed: usize,
+}
+
+impl DeadLetter {
+    // Dosya yolu boşsa kapalıdır (None). Var olan dosyadaki en büyük parti numarasından devam edilir.
+    pub fn from_config(config: &DeadLetterConfig) -> Result<Option<Self>, String> {
+        if config.path.trim().is_empty() {
+            return Ok(None);
+        }
+        let path = PathBuf::from(&config.path);
+        let last_id = match std::fs::read_to_string(&path) {
+            Ok(text) => parse(&text).iter().map(|batch| batch.id).max().unwrap_or(0),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(format!("{} okunamadı: {}", path.display(), e)),
+        };
+        Ok(Some(DeadLetter {
+            path,
+            max_bytes: config.max_bytes,
+            next_id: Mutex::new(last_id + 1),
+        }))
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    // Yazılamayan partiyi dosyanın sonuna ekler. Dosya 'max_bytes' sınırına ulaşmışsa reddeder.
+    pub async fn push(&self, logs: &[LogEntry], reason: &str) -> Result<u64, String> {
+        let mut next_id = self.next_id.lock().await;
+        let size = tokio::fs::metadata(&self.path).await.map(|m| m.len()).unwrap_or(0);
+        if self.max_bytes > 0 && size >= self.max_bytes {
+            return Err(format!("dosya boyut sınırına ulaştı ({} bayt)", self.max_bytes));
+        }
+        let batch = DeadBatch {
+            id: *next_id,
+            failed_at: Utc::now().to_rfc3339(),
+            error: reason.to_string(),
+            attempts: 0,
+            logs: logs.iter().map(SavedLog::from).collect(),
+        };
+        let mut line = serde_json::to_string(&batch).map_err(|e| e.to_string())?;
+        line.push('\n');
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| e.to_string())?;
+        file.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+        file.flush().await.map_err(|e| e.to_string())?;
+        *next_id += 1;
+        Ok(batch.id)
+    }
+
+    pub async fn list(&self) -> Result<Vec<DeadBatchSummary>, String> {
+        let _guard = self.next_id.lock().await;
+        let batches = self.load().await?;
+        Ok(batches
+            .into_iter()
+            .map(|batch| DeadBatchSummary {
+                id: batch.id,
+                failed_at: batch.failed_at,
+                error: batch.error,
+                attempts: batch.attempts,
+                count: batch.logs.len(),
+            })
+            .collect())
+    }
+
+    // Partileri (id verilmişse sadece o partiyi) yeniden yazar; loglar yönlendirme
+    // kurallarına göre dağıtılır. Parti bulunamazsa None döner.
+    pub async fn retry(&self, routes: &Routes, metrics: &Metrics, id: Option<u64>) -> Result<Option<RetryReport>, String> {
+        let _guard = self.next_id.lock().await;
+        let batches = self.load().await?;
+        if id.is_some_and(|id| !batches.iter().any(|batch| batch.id == id)) {
+            return Ok(None);
+        }
+
+        let mut report = RetryReport::default();
+        let mut remaining = Vec::with_capacity(batches.len());
+        for mut batch in batches {
+            if id.is_some_and(|id| id != batch.id) {
+                remaining.push(batch);
+                continue;
+            }
+            let logs: Vec<LogEntry> = batch.logs.iter().map(LogEntry::from).collect();
+            let count = logs.len();
+            match routes.insert_batch(logs).await {
+                Ok(()) => {
+                    report.retried += 1;
+                    report.written += count;
+                    metrics.written.fetch_add(count as u64, Ordering::Relaxed);
+                    metrics.dead_letter_retried.fetch_add(count as u64, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    warn!("⚠️ Ölü mektup partisi #{} yine yazılamadı: {}", batch.id, e);
+                    report.failed += 1;
+                    batch.attempts += 1;
+                    batch.error = e.to_string();
+                    remaining.push(batch);
+                }
+            }
+        }
+        self.save(&remaining).await?;
+        info!(
+            "♻️ Ölü mektup: {} parti ({} log) yeniden yazıldı, {} parti bekliyor.",
+            report.retried,
+            report.written,
+            remaining.len()
+        );
+        Ok(Some(report))
+    }
+
+    async fn load(&self) -> Result<Vec<DeadBatch>, String> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(text) => Ok(parse(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    // Dosyayı geçici dosya üzerinden yeniden yazar (yarıda kalırsa eski dosya bozulmaz).
+    async fn save(&self, batches: &[DeadBatch]) -> Result<(), String> {
+        let mut text = String::new();
+        for batch in batches {
+            text.push_str(&serde_json::to_string(batch).map_err(|e| e.to_string())?);
+            text.push('\n');
+        }
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        tokio::fs::write(&tmp, text).await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(&tmp, &self.path).await.map_err(|e| e.to_string())
+    }
+}
+
+// Bozuk satırlar atlanır (örn. yazılırken kesilmiş son satır).
+fn parse(text: &str) -> Vec<DeadBatch> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(batch) => Some(batch),
+            Err(e) => {
+                warn!("⚠️ Ölü mektup dosyasında okunamayan satır atlandı: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+impl From<&LogEntry> for SavedLog {
+    fn from(log: &LogEntry) -> Self {
+        SavedLog {
+            tenant_id: log.tenant_id.clone(),
+            level: log.level.clone(),
+            message: log.message.clone(),
+            extra: log.extra.clone(),
+            remote_addr: log.source.remote_addr.map(|addr| addr.to_string()),
+            user_agent: log.source.user_agent.clone(),
+            api_key: log.source.api_key.clone(),
+            received_at: log.source.received_at.clone(),
+        }
+    }
+}
+
+impl From<&SavedLog> for LogEntry {
+    fn from(log: &SavedLog) -> Self {
+        LogEntry {
+            level: log.level.clone(),
+            message: log.message.clone(),
+            extra: log.extra.clone(),
+            tenant_id: log.tenant_id.clone(),
+            source: Source {
+                remote_addr: log.remote_addr.as_deref().and_then(|addr| addr.parse().ok()),
+                user_agent: log.user_agent.clone(),
+                api_key: log.api_key.clone(),
+                received_at: log.received_at.clone(),
+            },
+        }
+    }
+}
+
+// --- Yönetim Handler'ları ---
+// Partiler tüm kiracıların loglarını içerebildiği için sadece varsayılan
+// kiracının anahtarları (auth.api_keys) kullanabilir; diğerleri 403 alır.
+fn admin<'a>(state: &'a AppState, caller: Option<&Caller>) -> Result<&'a DeadLetter, StatusCode> {
+    auth::require_default_tenant(caller)?;
+    state.dead_letter.as_deref().ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn list_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<Vec<DeadBatchSummary>>, StatusCode> {
+    let dead_letter = admin(&state, caller.as_deref())?;
+    let batches = dead_letter.list().await.map_err(|e| {
+        error!("❌ Ölü mektup dosyası okunamadı: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(batches))
+}
+
+pub async fn retry_all_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<RetryReport>, StatusCode> {
+    retry(&state, caller.as_deref(), None).await
+}
+
+pub async fn retry_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u64>,
+) -> Result<Json<RetryReport>, StatusCode> {
+    retry(&state, caller.as_deref(), Some(id)).await
+}
+
+async fn retry(state: &AppState, caller: Option<&Caller>, id: Option<u64>) -> Result<Json<RetryReport>, StatusCode> {
+    let dead_letter = admin(state, caller)?;
+    match dead_letter.retry(&state.routes, &state.metrics, id).await {
+        Ok(Some(report)) => Ok(Json(report)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("❌ Ölü mektup partileri yeniden yazılamadı: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
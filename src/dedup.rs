@@ -0,0 +1,179 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use crate::alert::Alerts;
+use crate::config::DedupConfig;
+use crate::ingest::Observed;
+use crate::metrics::Metrics;
+use crate::pipeline::{Ack, Dispatcher};
+use crate::tail::Tail;
+use crate::LogEntry;
+
+// --- Tekrar Birleştirme (Deduplication) ---
+// Aynı (kiracı, seviye, mesaj, parmak izi) logu pencere içinde tekrar gelirse
+// yeni satır yazılmaz; ilk log pencere boyunca bekletilir ve tekrarlar sayılır.
+// Pencere dolunca tek satır olarak kanala gider:
+//   extra.repeat_count  pencere içinde kaç kez geldiği (tekrar yoksa eklenmez)
+//   extra.last_seen     son tekrarın zamanı
+// Böylece sürekli çöküp yeniden başlayan bir pod milyonlarca aynı satır yazamaz.
+// Bekletilen anahtar sayısı sınırlıdır; sınır doluysa yeni loglar beklemeden geçer.
+pub struct Deduplicator {
+    window: Duration,
+    max_pending: usize,
+    fingerprint_field: String,
+    pending: Mutex<HashMap<u64, Pending>>,
+    closed: AtomicBool,
+    close: Notify,
+}
+
+struct Pending {
+    log: LogEntry,
+    ack: Option<Ack>,
+    count: u64,
+    first_seen: Instant,
+    last_seen: String,
+}
+
+// offer sonucu
+pub enum Offer {
+    // İlk geliş: pencere sonuna kadar bekletilecek
+    Held,
+    // Tekrar: bekleyen logun sayacına eklendi
+    Merged,
+    // Birleştirme yapılmadı; log hemen gönderilmeli (seyrek: kutulanır)
+    Pass(Box<LogEntry>, Option<Ack>),
+}
+
+impl Deduplicator {
+    pub fn from_config(config: &DedupConfig) -> Option<Self> {
+        (config.window_secs > 0).then(|| Deduplicator {
+            window: Duration::from_secs(config.window_secs),
+            max_pending: config.max_pending.max(1),
+            fingerprint_field: config.fingerprint_field.clone(),
+            pending: Mutex::new(HashMap::new()),
+            closed: AtomicBool::new(false),
+            close: Notify::new(),
+        })
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    // Logu birleştirmeye sunar. Tekrarın onayı (ack) hemen sonuçlandırılır;
+    // logu, bekleyen ilk satırın 'repeat_count'u temsil eder.
+    pub fn offer(&self, log: LogEntry, ack: Option<Ack>) -> Offer {
+        if self.closed.load(Ordering::Relaxed) {
+            return Offer::Pass(Box::new(log), ack);
+        }
+        let key = self.key(&log);
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(entry) = pending.get_mut(&key) {
+            entry.count += 1;
+            if let Some(timestamp) = log.extra.get("timestamp").and_then(|v| v.as_str()) {
+                entry.last_seen = timestamp.to_string();
+            }
+            if let Some(ack) = ack {
+                ack.complete(true);
+            }
+            return Offer::Merged;
+        }
+        if pending.len() >= self.max_pending {
+            return Offer::Pass(Box::new(log), ack);
+        }
+        let last_seen = log.extra.get("timestamp").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        pending.insert(
+            key,
+            Pending {
+                log,
+                ack,
+                count: 1,
+                first_seen: Instant::now(),
+                last_seen,
+            },
+        );
+        Offer::Held
+    }
+
+    // Bekleyen tüm logları bırakıp görevi bitirir (kapanışta).
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.close.notify_one();
+    }
+
+    fn key(&self, log: &LogEntry) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        log.tenant_id.hash(&mut hasher);
+        log.level.hash(&mut hasher);
+        log.message.hash(&mut hasher);
+        if let Some(fingerprint) = log.extra.get(&self.fingerprint_field) {
+            fingerprint.to_string().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    // Penceresi dolan (ya da 'all' ise tüm) logları çıkarır.
+    fn take_expired(&self, all: bool) -> Vec<Pending> {
+        let mut pending = self.pending.lock().unwrap();
+        let keys: Vec<u64> = pending
+            .iter()
+            .filter(|(_, entry)| all || entry.first_seen.elapsed() >= self.window)
+            .map(|(key, _)| *key)
+            .collect();
+        keys.into_iter().filter_map(|key| pending.remove(&key)).collect()
+    }
+}
+
+impl Pending {
+    fn into_log(mut self) -> (LogEntry, Option<Ack>) {
+        if self.count > 1 {
+            if let Value::Object(map) = &mut self.log.extra {
+                map.insert("repeat_count".to_string(), Value::from(self.count));
+                map.insert("last_seen".to_string(), Value::String(self.last_seen));
+            }
+        }
+        (self.log, self.ack)
+    }
+}
+
+// Penceresi dolan logları düzenli olarak kanala gönderir. Kapanışta
+// bekleyenlerin hepsini gönderip biter.
+pub async fn run(dedup: Arc<Deduplicator>, tx: Dispatcher, metrics: Arc<Metrics>, tail: Tail, alerts: Arc<Alerts>) {
+    let tick = (dedup.window / 4).clamp(Duration::from_millis(100), Duration::from_secs(1));
+    let mut ticker = tokio::time::interval(tick);
+    loop {
+        let closing = tokio::select! {
+            _ = ticker.tick() => false,
+            _ = dedup.close.notified() => true,
+        };
+        let expired = dedup.take_expired(closing);
+        if !expired.is_empty() {
+            debug!("🔁 {} birleştirilmiş log kanala gönderiliyor.", expired.len());
+        }
+        for entry in expired {
+            let (log, ack) = entry.into_log();
+            // Birleştirilmiş satır canlı akışa ve uyarılara kanala girince gider
+            let observed = Observed::of(&tail, &alerts, &log);
+            if tx.send(log, ack.clone()).await.is_ok() {
+                observed.report(&tail, &alerts);
+            } else {
+                metrics.dropped.fetch_add(1, Ordering::Relaxed);
+                if let Some(ack) = ack {
+                    ack.complete(false);
+                }
+                warn!("⚠️ Birleştirilmiş log kanala gönderilemedi (kanal kapalı).");
+            }
+        }
+        if closing {
+            break;
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{error, info};
+
+use crate::audit;
+use crate::auth::{self, Caller};
+use crate::config::ErasureConfig;
+use crate::storage::{FieldMatch, PurgeFilter};
+use crate::timestamp;
+use crate::AppState;
+
+// --- Silme Talepleri (KVKK / GDPR) ---
+// DELETE /logs?field=user_id&value=42&from=...&to=...: bir kişiye ait logları
+// siler ve silinen kayıt sayısını döner. 'field' öne çıkarılmış bir sütun
+// (service, host, ...) ya da details içindeki bir anahtardır ("user.id");
+// 'from' / 'to' isteğe bağlıdır (dahil). Silme istemcinin kiracısıyla sınırlıdır.
+// Yanlışlıkla tüm tablonun silinmemesi için alan ve değer zorunludur.
+// Her silme kimin yaptığı, filtresi ve silinen sayıyla denetim dosyasına
+// eklenir; dosya açılamıyorsa hiçbir şey silinmez. Arşivlerdeki, ölü mektup
+// dosyasındaki ve WAL'daki kopyalara dokunulmaz. Silmeler ayrıca 'audit'
+// tablosuna da yazılır (GET /admin/audit).
+pub struct ErasureAudit {
+    path: PathBuf,
+    // Kayıtların silme sırasıyla ve bölünmeden yazılması için
+    lock: Mutex<()>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErasureParams {
+    field: String,
+    value: String,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_micros")]
+    from: Option<i64>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_micros")]
+    to: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErasureReport {
+    deleted: u64,
+}
+
+// Denetim dosyasındaki bir satır
+#[derive(Debug, Serialize)]
+struct ErasureRecord<'a> {
+    // Silmenin yapıldığı an (RFC 3339, UTC)
+    deleted_at: String,
+    // İstemci kimliği ("key:<etiket>", "cn:<ad>" veya "anonymous")
+    caller: &'a str,
+    tenant: &'a str,
+    remote_addr: Option<String>,
+    field: &'a str,
+    value: &'a str,
+    from: Option<String>,
+    to: Option<String>,
+    deleted: u64,
+}
+
+impl ErasureAudit {
+    // Yol boşsa denetim dosyası kapalıdır (None).
+    pub fn from_config(config: &ErasureConfig) -> Option<Self> {
+        if config.audit_path.trim().is_empty() {
+            return None;
+        }
+        Some(ErasureAudit {
+            path: PathBuf::from(&config.audit_path),
+            lock: Mutex::new(()),
+        })
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(params): Query<ErasureParams>,
+) -> Result<Json<ErasureReport>, (StatusCode, String)> {
+    let field = FieldMatch::new(&params.field, &params.value).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err((StatusCode::BAD_REQUEST, "'from', 'to'dan sonra olamaz".to_string()));
+        }
+    }
+    let caller = caller.as_deref();
+    let tenant = auth::tenant_of(caller);
+    let filter = PurgeFilter {
+        tenant: Some(tenant.clone()),
+        from: params.from,
+        to: params.to,
+        field: Some(field),
+        ..Default::default()
+    };
+
+    // Dosya silmeden önce açılır: denetim kaydı yazılamayacaksa silme yapılmaz.
+    let audit = match &state.erasure_audit {
+        Some(audit) => {
+            let guard = audit.lock.lock().await;
+            let file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&audit.path)
+                .await
+                .map_err(|e| {
+                    error!("❌ Silme denetim dosyası açılamadı, silme yapılmadı: {}", e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+                })?;
+            Some((guard, file))
+        }
+        None => None,
+    };
+
+    // Yönlendirme kurallarının ayrı depolarındaki kayıtlar da silinir
+    let mut deleted = 0;
+    let routed = state.routes.targets().into_iter().map(|(_, store)| store);
+    for store in std::iter::once(&state.store).chain(routed) {
+        deleted += store.purge(&filter).await.map_err(|e| {
+            error!("❌ Silme talebi uygulanamadı: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+        })?;
+    }
+    state.metrics.erased.fetch_add(deleted, Ordering::Relaxed);
+
+    let record = ErasureRecord {
+        deleted_at: Utc::now().to_rfc3339(),
+        caller: caller.map_or("anonymous", |c| &c.id),
+        tenant: &tenant,
+        remote_addr: caller.and_then(|c| c.remote_addr).map(|ip| ip.to_string()),
+        field: &params.field,
+        value: &params.value,
+        from: params.from.map(timestamp::format_micros),
+        to: params.to.map(timestamp::format_micros),
+        deleted,
+    };
+    info!(
+        "🗑️ Silme talebi: {} ({}) '{}' alanına göre {} kayıt sildi.",
+        record.caller, record.tenant, record.field, deleted
+    );
+    audit::record_caller(
+        &state.store,
+        caller,
+        "logs.delete",
+        serde_json::json!({
+            "tenant": record.tenant,
+            "field": record.field,
+            "value": record.value,
+            "from": record.from,
+            "to": record.to,
+            "deleted": deleted,
+        }),
+    )
+    .await;
+    if let Some((_guard, mut file)) = audit {
+        let mut line = serde_json::to_string(&record).unwrap_or_default();
+        line.push('\n');
+        let written = match file.write_all(line.as_bytes()).await {
+            Ok(()) => file.flush().await,
+            Err(e) => Err(e),
+        };
+        // Kayıtlar silindi; yanıt yine başarılıdır, kayıt süreç logunda kalır.
+        if let Err(e) = written {
+            error!("❌ Silme denetim kaydı yazılamadı: {} ({:?})", e, record);
+        }
+    }
+    Ok(Json(ErasureReport { deleted }))
+}
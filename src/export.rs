@@ -0,0 +1,486 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use chrono::{DateTime, Utc};
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, Int32Type, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::parser::parse_message_type;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, error, info};
+
+use crate::auth::{self, Caller};
+use crate::metrics::Metrics;
+use crate::query::{LogQuery, StoredLog};
+use crate::storage::SharedStorage;
+use crate::AppState;
+
+// Veritabanından tek seferde okunan ve tek row group'a yazılan satır sayısı
+const CHUNK_ROWS: i64 = 10_000;
+
+// 'timestamp' ve 'received_at' RFC 3339 ise UTC mikro saniye olarak yazılır
+// (DuckDB/Spark'ta TIMESTAMP WITH TIME ZONE olarak görünür); çözülemezse NULL kalır.
+// 'details' JSON metnidir (DuckDB: details::JSON).
+const SCHEMA: &str = "
+message log {
+    required int64 id;
+    required binary tenant_id (STRING);
+    required binary level (STRING);
+    required binary message (STRING);
+    optional int64 timestamp (TIMESTAMP(MICROS,true));
+    optional binary details (STRING);
+    optional binary remote_addr (STRING);
+    optional binary user_agent (STRING);
+    optional binary api_key (STRING);
+    optional int64 received_at (TIMESTAMP(MICROS,true));
+    optional int32 severity;
+    optional binary service (STRING);
+    optional binary host (STRING);
+    optional binary env (STRING);
+    optional binary trace_id (STRING);
+    optional binary span_id (STRING);
+}";
+
+// --- Parquet Yazıcısı ---
+// Kayıtları parça parça (her parça bir row group) Snappy ile sıkıştırılmış
+// Parquet'e yazar. Bellekte sadece o anki parça tutulur.
+pub struct ParquetWriter<W: Write + Send> {
+    inner: SerializedFileWriter<W>,
+    rows: u64,
+    first_id: Option<i64>,
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    pub fn new(out: W) -> Result<Self, String> {
+        let schema = Arc::new(parse_message_type(SCHEMA).map_err(|e| e.to_string())?);
+        let props = WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::SNAPPY)
+            .build();
+        let inner = SerializedFileWriter::new(out, schema, Arc::new(props)).map_err(|e| e.to_string())?;
+        Ok(ParquetWriter {
+            inner,
+            rows: 0,
+            first_id: None,
+        })
+    }
+
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    pub fn write(&mut self, rows: &[StoredLog]) -> Result<(), String> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        self.write_row_group(rows).map_err(|e| e.to_string())?;
+        self.rows += rows.len() as u64;
+        self.first_id.get_or_insert(rows[0].id);
+        Ok(())
+    }
+
+    fn write_row_group(&mut self, rows: &[StoredLog]) -> Result<(), ParquetError> {
+        let ids: Vec<i64> = rows.iter().map(|r| r.id).collect();
+        let text = |f: fn(&StoredLog) -> &str| -> Vec<ByteArray> { rows.iter().map(|r| ByteArray::from(f(r))).collect() };
+        let tenants = text(|r| &r.tenant_id);
+        let levels = text(|r| &r.level);
+        let messages = text(|r| &r.message);
+
+        let micros = |text: &str| DateTime::parse_from_rfc3339(text).ok().map(|t| t.timestamp_micros());
+        let timestamps: Vec<Option<i64>> = rows.iter().map(|r| micros(&r.timestamp)).collect();
+        let details: Vec<Option<ByteArray>> = rows
+            .iter()
+            .map(|r| (!r.details.is_null()).then(|| ByteArray::from(r.details.to_string().as_str())))
+            .collect();
+        let optional_text = |f: fn(&StoredLog) -> &Option<String>| -> Vec<Option<ByteArray>> {
+            rows.iter().map(|r| f(r).as_deref().map(ByteArray::from)).collect()
+        };
+        let received: Vec<Option<i64>> = rows.iter().map(|r| r.received_at.as_deref().and_then(micros)).collect();
+        let severities: Vec<Option<i32>> = rows.iter().map(|r| r.severity).collect();
+
+        let mut group = self.inner.next_row_group()?;
+        column::<Int64Type, W>(&mut group, &ids, None)?;
+        column::<ByteArrayType, W>(&mut group, &tenants, None)?;
+        column::<ByteArrayType, W>(&mut group, &levels, None)?;
+        column::<ByteArrayType, W>(&mut group, &messages, None)?;
+        optional_column::<Int64Type, W>(&mut group, timestamps)?;
+        optional_column::<ByteArrayType, W>(&mut group, details)?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.remote_addr))?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.user_agent))?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.api_key))?;
+        optional_column::<Int64Type, W>(&mut group, received)?;
+        optional_column::<Int32Type, W>(&mut group, severities)?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.service))?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.host))?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.env))?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.trace_id))?;
+        optional_column::<ByteArrayType, W>(&mut group, optional_text(|r| &r.span_id))?;
+        group.close()?;
+        Ok(())
+    }
+
+    // Dosya sonunu (footer) yazar ve alttaki yazıcıyı geri verir.
+    pub fn finish(self) -> Result<W, String> {
+        self.inner.into_inner().map_err(|e| e.to_string())
+    }
+}
+
+fn column<T: DataType, W: Write + Send>(
+    group: &mut SerializedRowGroupWriter<'_, W>,
+    values: &[T::T],
+    def_levels: Option<&[i16]>,
+) -> Result<(), ParquetError> {
+    let mut writer = group
+        .next_column()?
+        .ok_or_else(|| ParquetError::General("şemada eksik sütun".to_string()))?;
+    writer.typed::<T>().write_batch(values, def_levels, None)?;
+    writer.close()
+}
+
+// NULL olabilen sütun: sadece dolu değerler yazılır, NULL'lar tanım seviyesiyle (0) işaretlenir.
+fn optional_column<T: DataType, W: Write + Send>(
+    group: &mut SerializedRowGroupWriter<'_, W>,
+    values: Vec<Option<T::T>>,
+) -> Result<(), ParquetError> {
+    let def_levels: Vec<i16> = values.iter().map(|v| v.is_some() as i16).collect();
+    let present: Vec<T::T> = values.into_iter().flatten().collect();
+    column::<T, W>(group, &present, Some(&def_levels))
+}
+
+// Filtreye uyan kayıtları (en fazla 'max_rows' tanesini) id sırasıyla yazar.
+// Sınıra takıldıysa son id'den sonra hâlâ kayıt olup olmadığını da döner.
+async fn write_matching<W: Write + Send>(
+    store: &SharedStorage,
+    params: &LogQuery,
+    after_id: i64,
+    max_rows: u64,
+    writer: &mut ParquetWriter<W>,
+) -> Result<(i64, bool), String> {
+    let mut after_id = after_id;
+    loop {
+        let want = (max_rows - writer.rows()).min(CHUNK_ROWS as u64) as i64;
+        if want == 0 {
+            let more = !store.export(params, after_id, 1).await.map_err(|e| e.to_string())?.is_empty();
+            return Ok((after_id, more));
+        }
+        let rows = store.export(params, after_id, want).await.map_err(|e| e.to_string())?;
+        let Some(last_id) = rows.last().map(|r| r.id) else {
+            return Ok((after_id, false));
+        };
+        writer.write(&rows)?;
+        after_id = last_id;
+        if (rows.len() as i64) < want {
+            return Ok((after_id, false));
+        }
+    }
+}
+
+// --- Parquet Dışa Aktarma Ucu ---
+// GET /export/parquet?from=...&to=...&level=...&q=...
+// /logs ile aynı filtreler ve aynı kiracı sınırı; sıralama id'ye göre artandır.
+// En fazla 'export.max_rows' satır döner; kesildiyse X-Export-Truncated: true.
+pub async fn parquet_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(mut params): Query<LogQuery>,
+) -> Response {
+    params.tenant = Some(auth::tenant_of(caller.as_deref()));
+    debug!("📤 Parquet dışa aktarma: {:?}", params);
+
+    let result = async {
+        let mut writer = ParquetWriter::new(Vec::new())?;
+        let (_, truncated) = write_matching(&state.store, &params, 0, state.export_max_rows, &mut writer).await?;
+        let rows = writer.rows();
+        Ok::<_, String>((writer.finish()?, rows, truncated))
+    }
+    .await;
+
+    match result {
+        Ok((body, rows, truncated)) => {
+            state.metrics.exported.fetch_add(rows, Ordering::Relaxed);
+            (
+                [
+                    (header::CONTENT_TYPE, "application/vnd.apache.parquet".to_string()),
+                    (header::CONTENT_DISPOSITION, "attachment; filename=\"logs.parquet\"".to_string()),
+                    (header::HeaderName::from_static("x-export-rows"), rows.to_string()),
+                    (header::HeaderName::from_static("x-export-truncated"), truncated.to_string()),
+                ],
+                body,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("❌ Parquet dışa aktarma başarısız: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+// --- CSV / JSONL Dışa Aktarma Ucu ---
+// GET /export?format=csv|jsonl&from=...&level=...: /logs ile aynı filtreler ve
+// kiracı sınırı; 'limit' ve 'offset' yok sayılır, eşleşen tüm satırlar id
+// sırasıyla döner. Yanıt parça parça (chunked) akar: veritabanından CHUNK_ROWS
+// satır okunur, yazılır, sonraki parça okunur; istemci yavaşsa okuma da bekler.
+// Başlıklar gönderildikten sonra hata olursa bağlantı yarıda kesilir (istemci
+// eksik aktarım görür, yarım dosya tamamlanmış sanılmaz).
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    // Satır başına bir /logs kaydı (varsayılan)
+    #[default]
+    Jsonl,
+    // Başlık satırı + CSV_COLUMNS; details JSON metni olarak tek sütunda
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FormatParams {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+const CSV_COLUMNS: [&str; 16] = [
+    "id",
+    "tenant_id",
+    "timestamp",
+    "level",
+    "severity",
+    "message",
+    "service",
+    "host",
+    "env",
+    "trace_id",
+    "span_id",
+    "remote_addr",
+    "user_agent",
+    "api_key",
+    "received_at",
+    "details",
+];
+
+impl ExportFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "logs.jsonl",
+            ExportFormat::Csv => "logs.csv",
+        }
+    }
+
+    fn header(self) -> Option<String> {
+        match self {
+            ExportFormat::Jsonl => None,
+            ExportFormat::Csv => Some(CSV_COLUMNS.join(",") + "\r\n"),
+        }
+    }
+
+    fn write(self, log: &StoredLog, out: &mut String) {
+        match self {
+            ExportFormat::Jsonl => {
+                out.push_str(&serde_json::to_string(log).unwrap_or_default());
+                out.push('\n');
+            }
+            ExportFormat::Csv => {
+                let id = log.id.to_string();
+                let severity = log.severity.map(|s| s.to_string());
+                let details = (!log.details.is_null()).then(|| log.details.to_string());
+                let fields: [Option<&str>; 16] = [
+                    Some(&id),
+                    Some(&log.tenant_id),
+                    Some(&log.timestamp),
+                    Some(&log.level),
+                    severity.as_deref(),
+                    Some(&log.message),
+                    log.service.as_deref(),
+                    log.host.as_deref(),
+                    log.env.as_deref(),
+                    log.trace_id.as_deref(),
+                    log.span_id.as_deref(),
+                    log.remote_addr.as_deref(),
+                    log.user_agent.as_deref(),
+                    log.api_key.as_deref(),
+                    log.received_at.as_deref(),
+                    details.as_deref(),
+                ];
+                for (i, field) in fields.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    csv_field(field.unwrap_or_default(), out);
+                }
+                out.push_str("\r\n");
+            }
+        }
+    }
+}
+
+// RFC 4180: virgül, tırnak veya satır sonu içeren alanlar tırnak içine alınır,
+// içerideki tırnaklar ikilenir. Boş alan NULL'dur.
+fn csv_field(value: &str, out: &mut String) {
+    if value.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&value.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(value);
+    }
+}
+
+pub async fn stream_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(mut params): Query<LogQuery>,
+    Query(format): Query<FormatParams>,
+) -> Response {
+    params.tenant = Some(auth::tenant_of(caller.as_deref()));
+    for id in [&mut params.trace_id, &mut params.span_id].into_iter().flatten() {
+        id.make_ascii_lowercase();
+    }
+    debug!("📤 {:?} dışa aktarma: {:?}", format.format, params);
+
+    // Kanal küçük tutulur: bellekte en fazla birkaç parça bekler.
+    let (tx, rx) = mpsc::channel(2);
+    tokio::spawn(stream_rows(state.store.clone(), state.metrics.clone(), params, format.format, tx));
+    (
+        [
+            (header::CONTENT_TYPE, format.format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.format.file_name()),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+async fn stream_rows(
+    store: SharedStorage,
+    metrics: Arc<Metrics>,
+    params: LogQuery,
+    format: ExportFormat,
+    tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+) {
+    if let Some(header) = format.header() {
+        if tx.send(Ok(Bytes::from(header))).await.is_err() {
+            return;
+        }
+    }
+    let mut after_id = 0;
+    let mut sent = 0u64;
+    loop {
+        let rows = match store.export(&params, after_id, CHUNK_ROWS).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("❌ {:?} dışa aktarma {} satırdan sonra kesildi: {}", format, sent, e);
+                let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+                return;
+            }
+        };
+        let Some(last_id) = rows.last().map(|r| r.id) else {
+            break;
+        };
+        let mut chunk = String::new();
+        for row in &rows {
+            format.write(row, &mut chunk);
+        }
+        if tx.send(Ok(Bytes::from(chunk))).await.is_err() {
+            debug!("📤 İstemci dışa aktarma bitmeden ayrıldı ({} satır gönderilmişti).", sent);
+            return;
+        }
+        sent += rows.len() as u64;
+        metrics.exported.fetch_add(rows.len() as u64, Ordering::Relaxed);
+        after_id = last_id;
+        if (rows.len() as i64) < CHUNK_ROWS {
+            break;
+        }
+    }
+    debug!("📤 {:?} dışa aktarma tamamlandı: {} satır", format, sent);
+}
+
+// --- Zamanlanmış Dışa Aktarıcı ---
+// Her çalıştırmada (varsayılan saatte bir, saat başına hizalı) son dışa
+// aktarmadan bu yana eklenen tüm kayıtları tek bir Parquet dosyasına yazar:
+//   <dir>/dt=YYYY-MM-DD/hour=HH/logs-<ilk id>-<son id>.parquet
+// Nereye kadar yazıldığı <dir>/.last_exported_id dosyasında tutulur; böylece
+// yeniden başlatmalarda kayıt atlanmaz ve iki kez yazılmaz. Dosya önce geçici
+// adla yazılır, bitince yerine taşınır (okuyucular yarım dosya görmez).
+pub async fn run(store: SharedStorage, metrics: Arc<Metrics>, dir: PathBuf, interval: Duration) {
+    let interval = interval.max(Duration::from_secs(1));
+    loop {
+        tokio::time::sleep(until_next_tick(interval)).await;
+        match export_new(&store, &dir).await {
+            Ok(Some((path, rows))) => {
+                metrics.exported.fetch_add(rows, Ordering::Relaxed);
+                info!("📤 {} kayıt Parquet'e yazıldı: {}", rows, path.display());
+            }
+            Ok(None) => debug!("📤 Dışa aktarılacak yeni kayıt yok."),
+            Err(e) => error!("❌ Zamanlanmış Parquet dışa aktarma başarısız: {}", e),
+        }
+    }
+}
+
+// Bir sonraki 'interval' katına (Unix zamanına göre) kalan süre; saatlik
+// aralıkta dosyalar saat başlarında yazılır.
+fn until_next_tick(interval: Duration) -> Duration {
+    let now = Utc::now();
+    let step = interval.as_millis().max(1) as i64;
+    let elapsed = now.timestamp_millis().rem_euclid(step);
+    Duration::from_millis((step - elapsed) as u64)
+}
+
+async fn export_new(store: &SharedStorage, dir: &Path) -> Result<Option<(PathBuf, u64)>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("{} oluşturulamadı: {}", dir.display(), e))?;
+    let state_path = dir.join(".last_exported_id");
+    let last_id: i64 = match std::fs::read_to_string(&state_path) {
+        Ok(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} geçersiz", state_path.display()))?,
+        Err(_) => 0,
+    };
+
+    let tmp_path = dir.join(".export.parquet.tmp");
+    let file = File::create(&tmp_path).map_err(|e| format!("{} oluşturulamadı: {}", tmp_path.display(), e))?;
+    let mut writer = ParquetWriter::new(BufWriter::new(file))?;
+    let params = LogQuery::default();
+    let (new_last_id, _) = write_matching(store, &params, last_id, u64::MAX, &mut writer).await?;
+    let rows = writer.rows();
+    let first_id = writer.first_id.unwrap_or(last_id + 1);
+    let mut out = writer.finish()?;
+    out.flush().map_err(|e| e.to_string())?;
+    drop(out);
+    if rows == 0 {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    let partition = dir.join(format!("dt={}", now.format("%Y-%m-%d"))).join(format!("hour={}", now.format("%H")));
+    std::fs::create_dir_all(&partition).map_err(|e| format!("{} oluşturulamadı: {}", partition.display(), e))?;
+    let path = partition.join(format!("logs-{}-{}.parquet", first_id, new_last_id));
+    std::fs::rename(&tmp_path, &path).map_err(|e| format!("{} yazılamadı: {}", path.display(), e))?;
+
+    let state_tmp = dir.join(".last_exported_id.tmp");
+    std::fs::write(&state_tmp, new_last_id.to_string())
+        .and_then(|_| std::fs::rename(&state_tmp, &state_path))
+        .map_err(|e| format!("{} yazılamadı: {}", state_path.display(), e))?;
+    Ok(Some((path, rows)))
+}
@@ -0,0 +1,487 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::timestamp;
+
+// Bir ifadedeki en fazla karşılaştırma ve parantez / NOT iç içeliği
+pub const MAX_TERMS: usize = 32;
+const MAX_DEPTH: usize = 16;
+const MAX_LEN: usize = 4096;
+
+// Metin olarak karşılaştırılan sütunlar
+const TEXT_COLUMNS: [&str; 12] = [
+    "level", "message", "tenant_id", "service", "host", "env", "trace_id", "span_id", "remote_addr", "user_agent",
+    "api_key", "received_at",
+];
+// Sayı olarak karşılaştırılan sütunlar ('timestamp' değeri zaman olarak okunur)
+const INTEGER_COLUMNS: [&str; 3] = ["id", "severity", "timestamp"];
+
+// --- Filtre İfadeleri ---
+// GET /logs?filter=...: onlarca parametre yerine tek bir ifade, örn.
+//   level=error AND service="api" AND (extra.user_id=123 OR extra.user_id=456)
+//   level>=warn AND NOT message~"health check" AND timestamp>=2024-05-01
+// Alan: sütun adı (level, message, service, host, env, trace_id, span_id,
+// tenant_id, remote_addr, user_agent, api_key, received_at, id, severity,
+// timestamp) ya da details içindeki anahtar ("extra.user.id", "details.user.id"
+// veya önekiz "user.id"). İşleçler: = != > >= < <= ve ~ (büyük/küçük harf
+// duyarsız içerir). Değerler tırnaklı ("a b") veya tırnaksız yazılır.
+// AND, OR, NOT (büyük/küçük harf duyarsız) ve parantez desteklenir; AND önce bağlar.
+//
+// İfade sunucuda ayrıştırılıp her arka uç için SQL koşuluna çevrilir; değerler
+// her zaman parametre olarak bağlanır. details alanları metin olarak
+// karşılaştırılır (extra.user_id=123 hem 123'ü hem "123"'ü bulur); sıralama
+// işleçlerinde tırnaksız sayı verilirse sayı olarak karşılaştırılır. Olmayan
+// alanla yapılan karşılaştırma yanlıştır; '!=' ise NOT (=) demektir, yani
+// alanı olmayan logları da döner. level'da sıralama işleçleri seviyeye göredir
+// (level>=warn: warn, error, fatal).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Comparison),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub field: Field,
+    pub op: Op,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    // logs tablosundaki sütun
+    Column(&'static str),
+    // details içindeki anahtar ("user.id" iç içe)
+    Details(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    // Büyük/küçük harf duyarsız alt dize
+    Contains,
+}
+
+impl Op {
+    // Contains için arka uç kendi ifadesini kullanır
+    pub fn sql(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Contains => "LIKE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Integer(i64),
+    // details alanlarının sayısal karşılaştırması
+    Float(f64),
+}
+
+// Query parametresi: boş değer filtre yok demektir; hatalı ifade 400 döner.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Expr>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) if !text.trim().is_empty() => parse(&text).map(Some).map_err(serde::de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+pub fn parse(text: &str) -> Result<Expr, String> {
+    if text.len() > MAX_LEN {
+        return Err(format!("filtre en fazla {} karakter olabilir", MAX_LEN));
+    }
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+        terms: 0,
+    };
+    let expr = parser.or(0)?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(expr),
+        Some(token) => Err(format!("filtrede beklenmeyen '{}'", token)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    // = != > >= < <= ~
+    Operator(&'static str),
+    Word(String),
+    Quoted(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Open => f.write_str("("),
+            Token::Close => f.write_str(")"),
+            Token::Operator(op) => f.write_str(op),
+            Token::Word(word) => f.write_str(word),
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '=' | '~' => {
+                chars.next();
+                tokens.push(Token::Operator(if c == '=' { "=" } else { "~" }));
+            }
+            '!' | '>' | '<' => {
+                chars.next();
+                let equals = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Operator(match (c, equals) {
+                    ('!', true) => "!=",
+                    ('>', false) => ">",
+                    ('>', true) => ">=",
+                    ('<', false) => "<",
+                    ('<', true) => "<=",
+                    _ => return Err("filtrede '!' sadece '!=' olarak kullanılabilir".to_string()),
+                }));
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // \" ve \\ kaçışları
+                        Some('\\') => value.push(chars.next().ok_or("filtrede kapanmamış tırnak")?),
+                        Some(c) => value.push(c),
+                        None => return Err("filtrede kapanmamış tırnak".to_string()),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"()=~!<>\"".contains(*c)) {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    terms: usize,
+}
+
+impl Parser {
+    fn or(&mut self, depth: usize) -> Result<Expr, String> {
+        let mut left = self.and(depth)?;
+        while self.keyword("OR") {
+            left = Expr::Or(Box::new(left), Box::new(self.and(depth)?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self, depth: usize) -> Result<Expr, String> {
+        let mut left = self.unary(depth)?;
+        while self.keyword("AND") {
+            left = Expr::And(Box::new(left), Box::new(self.unary(depth)?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self, depth: usize) -> Result<Expr, String> {
+        if depth >= MAX_DEPTH {
+            return Err(format!("filtre en fazla {} düzey iç içe olabilir", MAX_DEPTH));
+        }
+        if self.keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.unary(depth + 1)?)));
+        }
+        if self.tokens.get(self.position) == Some(&Token::Open) {
+            self.position += 1;
+            let expr = self.or(depth + 1)?;
+            return match self.next() {
+                Some(Token::Close) => Ok(expr),
+                _ => Err("filtrede kapanmamış parantez".to_string()),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let field = match self.next() {
+            Some(Token::Word(word)) if !is_keyword(&word) => word,
+            Some(token) => return Err(format!("filtrede alan adı bekleniyordu, '{}' bulundu", token)),
+            None => return Err("filtre eksik: alan adı bekleniyordu".to_string()),
+        };
+        let op = match self.next() {
+            Some(Token::Operator(op)) => op,
+            _ => return Err(format!("filtrede '{}' alanından sonra işleç bekleniyordu (= != > >= < <= ~)", field)),
+        };
+        let (value, quoted) = match self.next() {
+            Some(Token::Word(word)) if !is_keyword(&word) => (word, false),
+            Some(Token::Quoted(text)) => (text, true),
+            _ => return Err(format!("filtrede '{} {}' için değer bekleniyordu", field, op)),
+        };
+        self.terms += 1;
+        if self.terms > MAX_TERMS {
+            return Err(format!("filtre en fazla {} karşılaştırma içerebilir", MAX_TERMS));
+        }
+        comparison(&field, op, value, quoted)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.tokens.get(self.position), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    ["AND", "OR", "NOT"].iter().any(|keyword| word.eq_ignore_ascii_case(keyword))
+}
+
+// Alanı ve değeri türüne göre çözer; '!=' NOT (=) olur.
+fn comparison(name: &str, op: &str, value: String, quoted: bool) -> Result<Expr, String> {
+    let op_kind = match op {
+        "=" | "!=" => Op::Eq,
+        ">" => Op::Gt,
+        ">=" => Op::Ge,
+        "<" => Op::Lt,
+        "<=" => Op::Le,
+        _ => Op::Contains,
+    };
+    let ordering = matches!(op_kind, Op::Gt | Op::Ge | Op::Lt | Op::Le);
+    let field = resolve_field(name)?;
+    let (field, value) = match field {
+        // level>=warn: seviyeye göre (severity sütunu)
+        Field::Column("level") if ordering => {
+            let severity = crate::query::parse_min_level(&value)?;
+            (Field::Column("severity"), Value::Integer(i64::from(severity)))
+        }
+        Field::Column(column) if INTEGER_COLUMNS.contains(&column) => {
+            if op_kind == Op::Contains {
+                return Err(format!("'~' metin alanlarında kullanılabilir, {} sayıdır", column));
+            }
+            let number = match column {
+                "timestamp" => timestamp::parse_micros(&value)?,
+                _ => value.parse().map_err(|_| format!("{} için sayı bekleniyordu: {}", column, value))?,
+            };
+            (Field::Column(column), Value::Integer(number))
+        }
+        // İz kimlikleri küçük harfle saklanır
+        Field::Column(column @ ("trace_id" | "span_id")) => (Field::Column(column), Value::Text(value.to_ascii_lowercase())),
+        Field::Details(path) if ordering && !quoted => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => (Field::Details(path), Value::Float(number)),
+            _ => (Field::Details(path), Value::Text(value)),
+        },
+        field => (field, Value::Text(value)),
+    };
+    let compare = Expr::Compare(Comparison { field, op: op_kind, value });
+    Ok(if op == "!=" { Expr::Not(Box::new(compare)) } else { compare })
+}
+
+fn resolve_field(name: &str) -> Result<Field, String> {
+    let path = match name.split_once('.') {
+        Some(("extra" | "details", rest)) => rest,
+        _ => match TEXT_COLUMNS.iter().chain(&INTEGER_COLUMNS).find(|column| **column == name) {
+            Some(column) => return Ok(Field::Column(column)),
+            None => name,
+        },
+    };
+    let valid = path
+        .split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    if !valid {
+        return Err(format!("geçersiz alan adı: {} (harf, rakam, '_' ve '.')", name));
+    }
+    Ok(Field::Details(path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(field: Field, op: Op, value: Value) -> Expr {
+        Expr::Compare(Comparison { field, op, value })
+    }
+
+    fn text(path: &str, value: &str) -> Expr {
+        compare(Field::Details(path.to_string()), Op::Eq, Value::Text(value.to_string()))
+    }
+
+    fn and(left: Expr, right: Expr) -> Expr {
+        Expr::And(Box::new(left), Box::new(right))
+    }
+
+    fn or(left: Expr, right: Expr) -> Expr {
+        Expr::Or(Box::new(left), Box::new(right))
+    }
+
+    fn not(inner: Expr) -> Expr {
+        Expr::Not(Box::new(inner))
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or_and_not_tightest() {
+        assert_eq!(
+            parse("a=1 OR b=2 AND NOT c=3").unwrap(),
+            or(text("a", "1"), and(text("b", "2"), not(text("c", "3"))))
+        );
+        assert_eq!(
+            parse("(a=1 or b=2) and c=3").unwrap(),
+            and(or(text("a", "1"), text("b", "2")), text("c", "3"))
+        );
+        // Aynı işleçler soldan birleşir
+        assert_eq!(
+            parse("a=1 OR b=2 OR c=3").unwrap(),
+            or(or(text("a", "1"), text("b", "2")), text("c", "3"))
+        );
+        assert_eq!(parse("NOT NOT a=1").unwrap(), not(not(text("a", "1"))));
+        assert_eq!(
+            parse("NOT (a=1 AND b=2)").unwrap(),
+            not(and(text("a", "1"), text("b", "2")))
+        );
+    }
+
+    #[test]
+    fn not_equal_is_negated_equality() {
+        // Olmayan alan için '=' yanlış olduğundan '!=' bu logları da döner
+        assert_eq!(parse("extra.user_id!=5").unwrap(), not(text("user_id", "5")));
+        assert_eq!(
+            parse("service != api").unwrap(),
+            not(compare(Field::Column("service"), Op::Eq, Value::Text("api".to_string())))
+        );
+    }
+
+    #[test]
+    fn quoting_and_escapes() {
+        assert_eq!(
+            parse(r#"message="disk \"sda\" dolu \\ 90%""#).unwrap(),
+            compare(Field::Column("message"), Op::Eq, Value::Text(r#"disk "sda" dolu \ 90%"#.to_string()))
+        );
+        // Tırnak içinde anahtar kelime ve işleç karakterleri değerdir
+        assert_eq!(parse(r#"extra.op="a AND (b>=c)""#).unwrap(), text("op", "a AND (b>=c)"));
+        assert_eq!(
+            parse(r#"message ~ "health check""#).unwrap(),
+            compare(Field::Column("message"), Op::Contains, Value::Text("health check".to_string()))
+        );
+        assert!(parse(r#"message="kapanmadı"#).is_err());
+        assert!(parse(r#"message="kaçış\"#).is_err());
+        assert!(parse("message=AND").is_err());
+    }
+
+    #[test]
+    fn values_are_typed_by_field() {
+        assert_eq!(
+            parse("level>=warn").unwrap(),
+            compare(Field::Column("severity"), Op::Ge, Value::Integer(3))
+        );
+        assert_eq!(
+            parse("level=warn").unwrap(),
+            compare(Field::Column("level"), Op::Eq, Value::Text("warn".to_string()))
+        );
+        assert_eq!(parse("id>10").unwrap(), compare(Field::Column("id"), Op::Gt, Value::Integer(10)));
+        assert_eq!(
+            parse("timestamp>=2024-05-01").unwrap(),
+            compare(Field::Column("timestamp"), Op::Ge, Value::Integer(1_714_521_600_000_000))
+        );
+        assert_eq!(
+            parse("trace_id=ABCDEF").unwrap(),
+            compare(Field::Column("trace_id"), Op::Eq, Value::Text("abcdef".to_string()))
+        );
+        // details'te tırnaksız sayı sıralamada sayı, tırnaklı ya da '=' ile metindir
+        assert_eq!(
+            parse("extra.ms>250").unwrap(),
+            compare(Field::Details("ms".to_string()), Op::Gt, Value::Float(250.0))
+        );
+        assert_eq!(
+            parse(r#"extra.ms>"250""#).unwrap(),
+            compare(Field::Details("ms".to_string()), Op::Gt, Value::Text("250".to_string()))
+        );
+        assert_eq!(parse("extra.ms=250").unwrap(), text("ms", "250"));
+
+        assert!(parse("level>=loud").is_err());
+        assert!(parse("id=abc").is_err());
+        assert!(parse("severity~2").is_err());
+        assert!(parse("timestamp>dün").is_err());
+    }
+
+    #[test]
+    fn field_paths() {
+        assert_eq!(parse("user.id=1").unwrap(), text("user.id", "1"));
+        assert_eq!(parse("details.user.id=1").unwrap(), text("user.id", "1"));
+        assert_eq!(parse("extra.user.id=1").unwrap(), text("user.id", "1"));
+        // Sütun adı önekle verilirse details anahtarıdır
+        assert_eq!(parse("extra.level=x").unwrap(), text("level", "x"));
+
+        for filter in [
+            "extra.a..b=1",
+            "extra.=1",
+            "extra.a.=1",
+            "user-id=1",
+            "extra.a'b=1",
+            "extra.a;DROP=1",
+            "extra.$.a=1",
+            "ünvan=1",
+        ] {
+            let error = parse(filter).unwrap_err();
+            assert!(error.contains("geçersiz alan adı"), "{}: {}", filter, error);
+        }
+    }
+
+    #[test]
+    fn malformed_filters() {
+        for filter in ["", "a=1 b=2", "a=", "a", "=1", "a!1", "(a=1", "a=1)", "a=1 AND", "NOT", "a=1 OR OR b=2"] {
+            assert!(parse(filter).is_err(), "{}", filter);
+        }
+    }
+
+    #[test]
+    fn size_limits() {
+        let terms = |n: usize| (0..n).map(|i| format!("a={}", i)).collect::<Vec<_>>().join(" AND ");
+        assert!(parse(&terms(MAX_TERMS)).is_ok());
+        assert!(parse(&terms(MAX_TERMS + 1)).unwrap_err().contains("karşılaştırma"));
+
+        let nested = |n: usize| format!("{}a=1{}", "(".repeat(n), ")".repeat(n));
+        assert!(parse(&nested(MAX_DEPTH - 1)).is_ok());
+        assert!(parse(&nested(MAX_DEPTH)).unwrap_err().contains("iç içe"));
+        let negated = |n: usize| format!("{}a=1", "NOT ".repeat(n));
+        assert!(parse(&negated(MAX_DEPTH - 1)).is_ok());
+        assert!(parse(&negated(MAX_DEPTH)).unwrap_err().contains("iç içe"));
+
+        let long = |n: usize| format!("message=\"{}\"", "x".repeat(n - "message=\"\"".len()));
+        assert!(parse(&long(MAX_LEN)).is_ok());
+        assert!(parse(&long(MAX_LEN + 1)).unwrap_err().contains("karakter"));
+    }
+}
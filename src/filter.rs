@@ -0,0 +1,195 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::config::{FilterAction, FilterConfig, FilterRuleConfig};
+use crate::LogEntry;
+
+// --- Log Seviyesi Filtresi ---
+// Hangi seviyelerin veritabanına yazılacağını belirler.
+// İki mod desteklenir ([filter] bölümü veya ortam değişkenleri):
+//   - Belirli seviyeler kümesi:   levels = ["error", "warn"]   / INGEST_LEVELS="error,warn"
+//   - Minimum önem derecesi:       min_level = "warn"          / INGEST_MIN_LEVEL="warn"
+// Hiçbiri ayarlanmazsa eski davranış korunur: sadece "error".
+#[derive(Debug, Clone)]
+pub enum LevelFilter {
+    Levels(HashSet<String>),
+    MinSeverity(u8),
+}
+
+impl LevelFilter {
+    // Yapılandırmadan filtreyi oluşturur.
+    // 'levels', 'min_level'dan önceliklidir.
+    pub fn from_config(config: &FilterConfig) -> Self {
+        if let Some(levels) = &config.levels {
+            return Self::parse_levels(&levels.join(","));
+        }
+        if let Some(min) = &config.min_level {
+            if let Some(filter) = Self::parse_min(min) {
+                return filter;
+            }
+            tracing::warn!("⚠️ Bilinmeyen min_level '{}', varsayılan filtre kullanılıyor.", min);
+        }
+        Self::default()
+    }
+
+    // "error, warn" gibi virgülle ayrılmış listeyi kümeye çevirir.
+    pub fn parse_levels(list: &str) -> Self {
+        let levels = list
+            .split(',')
+            .map(|l| l.trim().to_ascii_lowercase())
+            .filter(|l| !l.is_empty())
+            .collect();
+        LevelFilter::Levels(levels)
+    }
+
+    // Minimum seviye adını önem derecesine çevirir.
+    pub fn parse_min(level: &str) -> Option<Self> {
+        severity(level).map(LevelFilter::MinSeverity)
+    }
+
+    // Bu seviyedeki log kabul edilmeli mi?
+    pub fn allows(&self, level: &str) -> bool {
+        match self {
+            LevelFilter::Levels(set) => set.contains(&level.to_ascii_lowercase()),
+            LevelFilter::MinSeverity(min) => severity(level).is_some_and(|s| s >= *min),
+        }
+    }
+}
+
+impl Default for LevelFilter {
+    fn default() -> Self {
+        LevelFilter::Levels(HashSet::from(["error".to_string()]))
+    }
+}
+
+// Seviye adlarının sayısal karşılığı (büyük olan daha önemli).
+pub fn severity(level: &str) -> Option<u8> {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => Some(0),
+        "debug" => Some(1),
+        "info" => Some(2),
+        "warn" | "warning" => Some(3),
+        "error" => Some(4),
+        "fatal" | "critical" => Some(5),
+        _ => None,
+    }
+}
+
+// --- Regex Kuralları ---
+// Seviye filtresinden önce, yazıldıkları sırayla değerlendirilir:
+//   drop  eşleşen log atılır            (örn. bilinen gürültülü health-check hataları)
+//   keep  eşleşen log kabul edilir       (seviye filtresi reddedecek olsa bile)
+//   tag   extra.tags listesine etiket eklenir, sonraki kurallara geçilir
+// İlk eşleşen drop/keep kuralı kararı verir; hiçbiri eşleşmezse seviye filtresi karar verir.
+#[derive(Debug, Default)]
+pub struct FilterRules {
+    rules: Vec<FilterRule>,
+}
+
+#[derive(Debug)]
+struct FilterRule {
+    name: String,
+    // "message", "level" ya da extra içindeki yol
+    field: Vec<String>,
+    regex: Regex,
+    action: FilterAction,
+    tag: Option<String>,
+}
+
+// Kuralların kararı
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Drop(String),
+    Keep(String),
+}
+
+impl FilterRules {
+    pub fn from_config(config: &FilterConfig) -> Result<Self, String> {
+        let rules = config.rules.iter().map(FilterRule::new).collect::<Result<Vec<_>, _>>()?;
+        Ok(FilterRules { rules })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    // Kuralları uygular (tag kuralları logu değiştirir). Karar yoksa None.
+    pub fn evaluate(&self, log: &mut LogEntry) -> Option<Decision> {
+        for rule in &self.rules {
+            let matched = rule
+                .value(log)
+                .is_some_and(|value| rule.regex.is_match(&value));
+            if !matched {
+                continue;
+            }
+            match rule.action {
+                FilterAction::Drop => return Some(Decision::Drop(rule.name.clone())),
+                FilterAction::Keep => return Some(Decision::Keep(rule.name.clone())),
+                FilterAction::Tag => {
+                    if let Some(tag) = &rule.tag {
+                        add_tag(&mut log.extra, tag);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl FilterRule {
+    fn new(config: &FilterRuleConfig) -> Result<Self, String> {
+        let regex = Regex::new(&config.pattern)
+            .map_err(|e| format!("'{}' filtre kuralının deseni geçersiz: {}", config.name, e))?;
+        if config.action == FilterAction::Tag && config.tag.as_deref().is_none_or(str::is_empty) {
+            return Err(format!("'{}' filtre kuralı için 'tag' gerekli", config.name));
+        }
+        Ok(FilterRule {
+            name: config.name.clone(),
+            field: config.field.split('.').map(str::to_string).collect(),
+            regex,
+            action: config.action,
+            tag: config.tag.clone(),
+        })
+    }
+
+    fn value<'a>(&self, log: &'a LogEntry) -> Option<Cow<'a, str>> {
+        field_value(&self.field, log)
+    }
+}
+
+// Kuralın baktığı alanın ("message", "level" ya da extra içindeki yol) metin hali;
+// alan yoksa None. Metin olmayan değerler JSON olarak karşılaştırılır.
+// Yönlendirme kuralları da (storage::Routes) aynı alan tanımını kullanır.
+pub fn field_value<'a>(field: &[String], log: &'a LogEntry) -> Option<Cow<'a, str>> {
+    match field {
+        [field] if field == "message" => return Some(Cow::Borrowed(&log.message)),
+        [field] if field == "level" => return Some(Cow::Borrowed(&log.level)),
+        _ => {}
+    }
+    let value = field.iter().try_fold(&log.extra, |value, key| value.get(key))?;
+    match value {
+        Value::String(text) => Some(Cow::Borrowed(text)),
+        Value::Null => None,
+        other => Some(Cow::Owned(other.to_string())),
+    }
+}
+
+// extra.tags listesine (yoksa oluşturarak) etiketi ekler; aynı etiket iki kez eklenmez.
+pub fn add_tag(extra: &mut Value, tag: &str) {
+    let Value::Object(map) = extra else {
+        return;
+    };
+    let tags = map.entry("tags").or_insert_with(|| Value::Array(Vec::new()));
+    if !tags.is_array() {
+        // Tek bir değer olarak gönderilmiş etiket listeye çevrilir
+        *tags = Value::Array(vec![tags.take()]);
+    }
+    if let Value::Array(list) = tags {
+        if !list.iter().any(|t| t.as_str() == Some(tag)) {
+            list.push(Value::String(tag.to_string()));
+        }
+    }
+}
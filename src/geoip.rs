@@ -0,0 +1,470 @@
+use std::net::{IpAddr, SocketAddr};
+
+use serde_json::{Map, Value};
+
+use crate::config::GeoIpConfig;
+use crate::LogEntry;
+
+// --- GeoIP Zenginleştirme ---
+// Logdaki IP adresi (varsayılan 'ip' alanı; yoksa HTTP ile gönderen istemcinin
+// adresi) MaxMind veritabanlarında (GeoLite2 / GeoIP2, .mmdb) aranır ve
+// sonuç 'extra.geo' altına eklenir:
+//   country, country_name, city, lat, lon  (City veya Country veritabanı)
+//   asn, as_org                            (ASN veritabanı)
+// Veritabanları açılışta belleğe okunur; arama kilitsizdir.
+#[derive(Debug)]
+pub struct GeoIp {
+    city: Option<Reader>,
+    asn: Option<Reader>,
+    field: String,
+    target: String,
+}
+
+impl GeoIp {
+    // Hiç veritabanı verilmemişse None döner (zenginleştirme kapalı).
+    pub fn from_config(config: &GeoIpConfig) -> Result<Option<Self>, String> {
+        if config.city_db.is_none() && config.asn_db.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(GeoIp {
+            city: config.city_db.as_deref().map(Reader::open).transpose()?,
+            asn: config.asn_db.as_deref().map(Reader::open).transpose()?,
+            field: config.field.clone(),
+            target: config.target.clone(),
+        }))
+    }
+
+    // Açılış logu için: yüklenen veritabanlarının türleri
+    pub fn describe(&self) -> String {
+        [&self.city, &self.asn]
+            .into_iter()
+            .flatten()
+            .map(|reader| reader.database_type.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Logu yerinde zenginleştirir; bir şey eklendiyse true döner.
+    pub fn enrich(&self, log: &mut LogEntry) -> bool {
+        let from_field = log.extra.get(&self.field).and_then(Value::as_str).and_then(parse_ip);
+        let Some(ip) = from_field.or(log.source.remote_addr) else {
+            return false;
+        };
+
+        let mut geo = Map::new();
+        if let Some(record) = self.city.as_ref().and_then(|reader| reader.lookup(ip)) {
+            copy(&record, &["country", "iso_code"], "country", &mut geo);
+            copy(&record, &["country", "names", "en"], "country_name", &mut geo);
+            copy(&record, &["city", "names", "en"], "city", &mut geo);
+            copy(&record, &["location", "latitude"], "lat", &mut geo);
+            copy(&record, &["location", "longitude"], "lon", &mut geo);
+        }
+        if let Some(record) = self.asn.as_ref().and_then(|reader| reader.lookup(ip)) {
+            copy(&record, &["autonomous_system_number"], "asn", &mut geo);
+            copy(&record, &["autonomous_system_organization"], "as_org", &mut geo);
+        }
+        if geo.is_empty() {
+            return false;
+        }
+        match &mut log.extra {
+            Value::Object(map) => {
+                map.insert(self.target.clone(), Value::Object(geo));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// "1.2.3.4", "2001:db8::1" ve "1.2.3.4:5678" / "[2001:db8::1]:443" biçimleri
+fn parse_ip(text: &str) -> Option<IpAddr> {
+    let text = text.trim();
+    text.parse::<IpAddr>()
+        .ok()
+        .or_else(|| text.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+fn copy(record: &Value, path: &[&str], name: &str, geo: &mut Map<String, Value>) {
+    let value = path.iter().try_fold(record, |value, key| value.get(key));
+    if let Some(value) = value.filter(|v| !v.is_null()) {
+        geo.insert(name.to_string(), value.clone());
+    }
+}
+
+// --- MaxMind DB Okuyucu ---
+// https://maxmind.github.io/MaxMind-DB/ biçiminin arama için gereken kısmı:
+// dosya başında ikili arama ağacı, ardından 16 sıfır bayt ve veri bölümü,
+// sonda "\xAB\xCD\xEFMaxMind.com" işaretinden sonra meta veri haritası.
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+const DATA_SEPARATOR: usize = 16;
+// İç içe harita / dizi / işaretçi sınırı: bozuk bir dosyada kendini gösteren
+// işaretçi sonsuz özyinelemeye (yığın taşmasına) yol açmasın
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug)]
+struct Reader {
+    buf: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    ip_version: u64,
+    database_type: String,
+    // Veri bölümünün dosyadaki başlangıcı
+    data_start: usize,
+    // IPv6 ağacında IPv4 adreslerinin başladığı düğüm (::/96)
+    ipv4_start: usize,
+}
+
+impl Reader {
+    fn open(path: &str) -> Result<Self, String> {
+        let buf = std::fs::read(path).map_err(|e| format!("{} okunamadı: {}", path, e))?;
+        Self::from_bytes(buf).map_err(|e| format!("{} geçerli bir MaxMind veritabanı değil: {}", path, e))
+    }
+
+    fn from_bytes(buf: Vec<u8>) -> Result<Self, String> {
+        let marker = buf
+            .windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)
+            .ok_or("meta veri bulunamadı")?;
+        let metadata_start = marker + METADATA_MARKER.len();
+        let (metadata, _) = Decoder { data: &buf[metadata_start..] }.decode(0)?;
+        let field = |name: &str| metadata.get(name).and_then(Value::as_u64).ok_or(format!("meta veride '{}' yok", name));
+
+        let node_count = field("node_count")? as usize;
+        let record_size = field("record_size")? as usize;
+        let ip_version = field("ip_version")?;
+        if !matches!(record_size, 24 | 28 | 32) {
+            return Err(format!("desteklenmeyen kayıt boyutu: {}", record_size));
+        }
+        let tree_size = node_count.checked_mul(record_size).map_or(usize::MAX, |bits| bits / 4);
+        if tree_size.saturating_add(DATA_SEPARATOR) > marker {
+            return Err("arama ağacı dosyadan büyük".to_string());
+        }
+        let database_type = metadata
+            .get("database_type")
+            .and_then(Value::as_str)
+            .unwrap_or("bilinmiyor")
+            .to_string();
+
+        let mut reader = Reader {
+            buf,
+            node_count,
+            record_size,
+            ip_version,
+            database_type,
+            data_start: tree_size + DATA_SEPARATOR,
+            ipv4_start: 0,
+        };
+        if ip_version == 6 {
+            let mut node = 0;
+            for _ in 0..96 {
+                if node >= node_count {
+                    break;
+                }
+                node = reader.record(node, 0);
+            }
+            reader.ipv4_start = node;
+        }
+        Ok(reader)
+    }
+
+    // Düğümün sol (bit = 0) veya sağ (bit = 1) kaydı
+    fn record(&self, node: usize, bit: u8) -> usize {
+        let bytes = self.record_size / 4;
+        let base = node * bytes;
+        let b = &self.buf[base..base + bytes];
+        let be = |s: &[u8]| s.iter().fold(0usize, |acc, &x| (acc << 8) | x as usize);
+        match (self.record_size, bit) {
+            (24, 0) => be(&b[0..3]),
+            (24, _) => be(&b[3..6]),
+            (28, 0) => ((b[3] as usize & 0xF0) << 20) | be(&b[0..3]),
+            (28, _) => ((b[3] as usize & 0x0F) << 24) | be(&b[4..7]),
+            (_, 0) => be(&b[0..4]),
+            (_, _) => be(&b[4..8]),
+        }
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Option<Value> {
+        let (address, bits, start) = match ip {
+            IpAddr::V4(v4) if self.ip_version == 6 => (u32::from(v4) as u128, 32, self.ipv4_start),
+            IpAddr::V4(v4) => (u32::from(v4) as u128, 32, 0),
+            IpAddr::V6(v6) if self.ip_version == 6 => (u128::from(v6), 128, 0),
+            // IPv4 veritabanında sadece IPv4'e eşlenmiş IPv6 adresleri aranabilir
+            IpAddr::V6(v6) => (u32::from(v6.to_ipv4_mapped()?) as u128, 32, 0),
+        };
+
+        let mut node = start;
+        for i in 0..bits {
+            if node >= self.node_count {
+                break;
+            }
+            let bit = ((address >> (bits - 1 - i)) & 1) as u8;
+            node = self.record(node, bit);
+        }
+        if node <= self.node_count {
+            // node_count: adres veritabanında yok
+            return None;
+        }
+        // Bozuk veritabanında kayıt ağaç ile veri bölümü arasını gösterebilir
+        let offset = (node - self.node_count).checked_sub(DATA_SEPARATOR)?;
+        Decoder { data: self.buf.get(self.data_start..)? }
+            .decode(offset)
+            .ok()
+            .map(|(value, _)| value)
+    }
+}
+
+// Veri bölümü çözücüsü. İşaretçiler (pointer) veri bölümünün başına göredir.
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl Decoder<'_> {
+    // Değeri ve ardından gelen ilk baytın konumunu döner.
+    fn decode(&self, offset: usize) -> Result<(Value, usize), String> {
+        self.decode_at(offset, 0)
+    }
+
+    fn decode_at(&self, offset: usize, depth: usize) -> Result<(Value, usize), String> {
+        if depth > MAX_DEPTH {
+            return Err("veri çok derin iç içe (işaretçi döngüsü olabilir)".to_string());
+        }
+        let ctrl = self.byte(offset)?;
+        let mut pos = offset + 1;
+        let mut kind = ctrl >> 5;
+        if kind == 1 {
+            let (target, next) = self.pointer(ctrl, pos)?;
+            let (value, _) = self.decode_at(target, depth + 1)?;
+            return Ok((value, next));
+        }
+        if kind == 0 {
+            kind = 7 + self.byte(pos)?;
+            pos += 1;
+        }
+        let (size, pos) = self.size(ctrl, pos)?;
+
+        match kind {
+            // UTF-8 metin
+            2 => {
+                let bytes = self.slice(pos, size)?;
+                Ok((Value::String(String::from_utf8_lossy(bytes).into_owned()), pos + size))
+            }
+            // double
+            3 => {
+                let bytes: [u8; 8] = self.slice(pos, 8)?.try_into().map_err(|_| "bozuk double")?;
+                Ok((Value::from(f64::from_be_bytes(bytes)), pos + 8))
+            }
+            // ham baytlar: onaltılık metin
+            4 => Ok((Value::String(hex::encode(self.slice(pos, size)?)), pos + size)),
+            // uint16, uint32, uint64, uint128
+            5 | 6 | 9 | 10 => {
+                let n = self.slice(pos, size)?.iter().fold(0u128, |acc, &x| (acc << 8) | x as u128);
+                let value = u64::try_from(n).map_or_else(|_| Value::String(n.to_string()), Value::from);
+                Ok((value, pos + size))
+            }
+            // map
+            7 => {
+                let mut map = Map::new();
+                let mut pos = pos;
+                for _ in 0..size {
+                    let (key, next) = self.decode_at(pos, depth + 1)?;
+                    let (value, next) = self.decode_at(next, depth + 1)?;
+                    let Value::String(key) = key else {
+                        return Err("harita anahtarı metin değil".to_string());
+                    };
+                    map.insert(key, value);
+                    pos = next;
+                }
+                Ok((Value::Object(map), pos))
+            }
+            // int32
+            8 => {
+                let n = self.slice(pos, size)?.iter().fold(0u32, |acc, &x| (acc << 8) | x as u32);
+                Ok((Value::from(n as i32), pos + size))
+            }
+            // array
+            11 => {
+                let mut items = Vec::with_capacity(size.min(64));
+                let mut pos = pos;
+                for _ in 0..size {
+                    let (value, next) = self.decode_at(pos, depth + 1)?;
+                    items.push(value);
+                    pos = next;
+                }
+                Ok((Value::Array(items), pos))
+            }
+            // boolean (değer boyut alanındadır)
+            14 => Ok((Value::Bool(size != 0), pos)),
+            // float
+            15 => {
+                let bytes: [u8; 4] = self.slice(pos, 4)?.try_into().map_err(|_| "bozuk float")?;
+                Ok((Value::from(f32::from_be_bytes(bytes) as f64), pos + 4))
+            }
+            other => Err(format!("desteklenmeyen veri türü: {}", other)),
+        }
+    }
+
+    fn pointer(&self, ctrl: u8, pos: usize) -> Result<(usize, usize), String> {
+        let high = (ctrl & 0x07) as usize;
+        let n = ((ctrl >> 3) & 0x03) as usize + 1;
+        let bytes = self.slice(pos, n)?;
+        let be = bytes.iter().fold(0usize, |acc, &x| (acc << 8) | x as usize);
+        let target = match n {
+            1 => (high << 8) | be,
+            2 => ((high << 16) | be) + 2048,
+            3 => ((high << 24) | be) + 526_336,
+            _ => be,
+        };
+        Ok((target, pos + n))
+    }
+
+    fn size(&self, ctrl: u8, pos: usize) -> Result<(usize, usize), String> {
+        let size = (ctrl & 0x1F) as usize;
+        let extra = |n: usize| -> Result<usize, String> {
+            Ok(self.slice(pos, n)?.iter().fold(0usize, |acc, &x| (acc << 8) | x as usize))
+        };
+        match size {
+            29 => Ok((29 + extra(1)?, pos + 1)),
+            30 => Ok((285 + extra(2)?, pos + 2)),
+            31 => Ok((65_821 + extra(3)?, pos + 3)),
+            _ => Ok((size, pos)),
+        }
+    }
+
+    fn byte(&self, pos: usize) -> Result<u8, String> {
+        self.data.get(pos).copied().ok_or_else(|| "veri bölümü beklenenden kısa".to_string())
+    }
+
+    fn slice(&self, pos: usize, len: usize) -> Result<&[u8], String> {
+        self.data
+            .get(pos..pos + len)
+            .ok_or_else(|| "veri bölümü beklenenden kısa".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // tests/fixtures/make_mmdb.py ile üretilir
+    const CITY_DB: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/GeoIP2-City-Test.mmdb");
+    const ASN_DB: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/GeoLite2-ASN-Test.mmdb");
+
+    fn geoip() -> GeoIp {
+        let config = GeoIpConfig {
+            city_db: Some(CITY_DB.to_string()),
+            asn_db: Some(ASN_DB.to_string()),
+            ..GeoIpConfig::default()
+        };
+        GeoIp::from_config(&config).unwrap().unwrap()
+    }
+
+    fn entry(extra: Value) -> LogEntry {
+        let mut raw = json!({"level": "info", "message": "istek"});
+        if let (Some(raw), Value::Object(extra)) = (raw.as_object_mut(), extra) {
+            raw.extend(extra);
+        }
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn city_and_asn_records_are_merged() {
+        let geoip = geoip();
+        assert_eq!(geoip.describe(), "GeoIP2-City, GeoLite2-ASN");
+
+        let mut log = entry(json!({"ip": "81.2.69.160"}));
+        assert!(geoip.enrich(&mut log));
+        assert_eq!(
+            log.extra["geo"],
+            json!({
+                "country": "GB",
+                "country_name": "United Kingdom",
+                "city": "London",
+                "lat": 51.5142,
+                "lon": -0.0931,
+                "asn": 20712,
+                "as_org": "Andrews & Arnold Ltd"
+            })
+        );
+    }
+
+    #[test]
+    fn ipv6_and_socket_addresses() {
+        let geoip = geoip();
+        // IPv4 ASN veritabanında IPv6 adresi aranmaz; ülke işaretçiyle paylaşılan kayıttan gelir
+        let mut log = entry(json!({"ip": "[2001:db8::1]:443"}));
+        assert!(geoip.enrich(&mut log));
+        assert_eq!(log.extra["geo"], json!({"country": "GB", "country_name": "United Kingdom"}));
+
+        // IPv4'e eşlenmiş IPv6 adresi ASN veritabanında bulunur
+        let mut log = entry(json!({"ip": "::ffff:81.2.69.1"}));
+        assert!(geoip.enrich(&mut log));
+        assert_eq!(log.extra["geo"]["asn"], 20712);
+        assert_eq!(log.extra["geo"].get("city"), None);
+
+        let mut log = entry(json!({"ip": "81.2.69.7:5678"}));
+        assert!(geoip.enrich(&mut log));
+        assert_eq!(log.extra["geo"]["city"], "London");
+    }
+
+    #[test]
+    fn unknown_or_missing_addresses_leave_the_entry_alone() {
+        let geoip = geoip();
+        for extra in [json!({"ip": "8.8.8.8"}), json!({"ip": "2001:db9::1"}), json!({"ip": "yok"}), json!({})] {
+            let mut log = entry(extra.clone());
+            assert!(!geoip.enrich(&mut log), "{}", extra);
+            assert_eq!(log.extra.get("geo"), None);
+        }
+
+        // Alan yoksa gönderen istemcinin adresi kullanılır
+        let mut log = entry(json!({}));
+        log.source.remote_addr = Some("81.2.69.200".parse().unwrap());
+        assert!(geoip.enrich(&mut log));
+        assert_eq!(log.extra["geo"]["country"], "GB");
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        let buf = std::fs::read(CITY_DB).unwrap();
+        assert!(Reader::from_bytes(buf[..buf.len() - 40].to_vec()).is_err());
+        assert!(Reader::from_bytes(b"bir mmdb dosyasi degil".to_vec()).is_err());
+        // Meta veri doğru ama ağaç dosyadan büyük
+        let marker = buf.windows(METADATA_MARKER.len()).rposition(|w| w == METADATA_MARKER).unwrap();
+        assert!(Reader::from_bytes(buf[marker - 20..].to_vec()).is_err());
+
+        let error = GeoIp::from_config(&GeoIpConfig {
+            city_db: Some("/yok/GeoLite2-City.mmdb".to_string()),
+            ..GeoIpConfig::default()
+        })
+        .unwrap_err();
+        assert!(error.contains("okunamadı"), "{}", error);
+    }
+
+    #[test]
+    fn malformed_pointers_fail_the_lookup() {
+        let mut buf = std::fs::read(CITY_DB).unwrap();
+        let reader = Reader::from_bytes(buf.clone()).unwrap();
+        let data_start = reader.data_start;
+
+        // Londra kaydındaki "country" işaretçisi kendini göstersin
+        let needle = b"\x47country\x20\x00";
+        let at = buf.windows(needle.len()).position(|w| w == needle).unwrap() + needle.len() - 2;
+        let target = at - data_start;
+        buf[at] = 0x20 | (target >> 8) as u8;
+        buf[at + 1] = target as u8;
+        let reader = Reader::from_bytes(buf.clone()).unwrap();
+        assert_eq!(reader.lookup("81.2.69.160".parse().unwrap()), None);
+        // Aynı işaretçiyi kullanmayan kayıt etkilenmez
+        assert!(reader.lookup("2001:db8::1".parse().unwrap()).is_some());
+
+        // Veri bölümünün dışını gösteren işaretçi
+        buf[at] = 0x27;
+        buf[at + 1] = 0xFF;
+        let reader = Reader::from_bytes(buf).unwrap();
+        assert_eq!(reader.lookup("81.2.69.160".parse().unwrap()), None);
+
+        // Kendini içeren harita: {"a": <işaretçi 0>}
+        let cycle = [0xE1, 0x41, b'a', 0x20, 0x00];
+        assert!(Decoder { data: &cycle }.decode(0).is_err());
+    }
+}
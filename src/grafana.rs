@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, error};
+
+use crate::auth::{self, Caller};
+use crate::level::CANONICAL;
+use crate::query::{LogQuery, StoredLog};
+use crate::timestamp;
+use crate::AppState;
+
+// Zaman serisindeki en fazla nokta (çok küçük intervalMs ile çok geniş aralık)
+const MAX_POINTS: i64 = 10_000;
+// Tablo ve işaretlerde dönen en fazla log
+const DEFAULT_ROWS: i64 = 100;
+const MAX_ROWS: i64 = 1_000;
+
+// --- Grafana JSON Veri Kaynağı ---
+// Grafana'nın SimpleJSON / "JSON API" eklentisinin beklediği uçlar (/grafana altında):
+//   GET  /            bağlantı testi
+//   POST /search      seçilebilir metrik adları
+//   POST /query       zaman serisi (seviye sayıları, hata oranı) veya log tablosu
+//   POST /annotations eşleşen logları grafik üzerinde işaret olarak döner
+// Hedef biçimi: "<metrik>" veya "<metrik> | <filtre ifadesi>" (bkz. expr), ör.
+// `error_rate | service="api"`. Kiracı sınırı /logs ile aynıdır.
+
+// Hedefteki metrik: bir dilimdeki seviye sayılarından tek değer üretir.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    // Tüm loglar
+    Logs,
+    // Tek seviye (trace .. fatal)
+    Level(&'static str),
+    // error + fatal
+    Errors,
+    // (error + fatal) / tüm loglar; log olmayan dilimde boş
+    ErrorRate,
+}
+
+impl Metric {
+    fn parse(name: &str) -> Option<Metric> {
+        match name {
+            "logs" => Some(Metric::Logs),
+            "errors" => Some(Metric::Errors),
+            "error_rate" => Some(Metric::ErrorRate),
+            _ => CANONICAL.iter().find(|level| **level == name).map(|level| Metric::Level(level)),
+        }
+    }
+
+    fn names() -> Vec<String> {
+        ["logs", "errors", "error_rate"]
+            .into_iter()
+            .chain(CANONICAL)
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn value(self, levels: &BTreeMap<String, i64>) -> Option<f64> {
+        let count = |level: &str| levels.get(level).copied().unwrap_or(0);
+        let errors = count("error") + count("fatal");
+        match self {
+            Metric::Logs => Some(levels.values().sum::<i64>() as f64),
+            Metric::Level(level) => Some(count(level) as f64),
+            Metric::Errors => Some(errors as f64),
+            Metric::ErrorRate => {
+                let total: i64 = levels.values().sum();
+                (total > 0).then(|| errors as f64 / total as f64)
+            }
+        }
+    }
+
+    // Tablo ve işaretlerde satırları seçen seviye filtresi
+    fn narrow(self, params: &mut LogQuery) {
+        match self {
+            Metric::Logs => {}
+            Metric::Level(level) => params.level = Some(level.to_string()),
+            Metric::Errors | Metric::ErrorRate => params.min_level = crate::filter::severity("error"),
+        }
+    }
+}
+
+// "error_rate | service=\"api\"" -> (ErrorRate, filtre)
+fn parse_target(target: &str) -> Result<(Metric, Option<crate::expr::Expr>), String> {
+    let (name, filter) = match target.split_once('|') {
+        Some((name, filter)) => (name.trim(), Some(filter.trim())),
+        None => (target.trim(), None),
+    };
+    let metric = match name {
+        "" => Metric::Logs,
+        name => Metric::parse(name).ok_or_else(|| {
+            format!("bilinmeyen metrik: {} ({} olabilir)", name, Metric::names().join(", "))
+        })?,
+    };
+    let filter = match filter {
+        Some("") | None => None,
+        Some(filter) => Some(crate::expr::parse(filter)?),
+    };
+    Ok((metric, filter))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Range {
+    from: String,
+    to: String,
+}
+
+impl Range {
+    fn micros(&self) -> Result<(i64, i64), String> {
+        let from = timestamp::parse_micros(&self.from)?;
+        let to = timestamp::parse_micros(&self.to)?;
+        if from > to {
+            return Err("'from', 'to'dan sonra olamaz".to_string());
+        }
+        Ok((from, to))
+    }
+}
+
+// GET /grafana/: veri kaynağı kaydedilirken "Save & test" bunu çağırır
+pub async fn test_handler() -> StatusCode {
+    StatusCode::OK
+}
+
+// --- Metrik Arama ---
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchRequest {
+    #[serde(default)]
+    target: String,
+}
+
+// POST /grafana/search: sorgu düzenleyicisindeki açılır liste
+pub async fn search_handler(body: Option<Json<SearchRequest>>) -> Json<Vec<String>> {
+    let Json(request) = body.unwrap_or_default();
+    let prefix = request.target.trim();
+    Json(Metric::names().into_iter().filter(|name| name.starts_with(prefix)).collect())
+}
+
+// --- Sorgu ---
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    range: Range,
+    // Panelin önerdiği dilim genişliği; verilmezse 60 sn
+    interval_ms: Option<i64>,
+    // Tabloda en fazla satır
+    max_data_points: Option<i64>,
+    targets: Vec<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    hide: bool,
+    #[serde(default, rename = "type")]
+    kind: TargetKind,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TargetKind {
+    #[default]
+    Timeserie,
+    Table,
+}
+
+// POST /grafana/query: her hedef için bir zaman serisi ({target, datapoints:
+// [[değer, epoch ms]]}) veya log tablosu ({type: "table", columns, rows}).
+// Dilimler epoch'a hizalıdır; log olmayan dilimler de sıfırla döner.
+pub async fn query_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Json(request): Json<QueryRequest>,
+) -> Result<Json<Vec<Value>>, (StatusCode, String)> {
+    let (from, to) = request.range.micros().map_err(bad_request)?;
+    let bucket = request.interval_ms.unwrap_or(60_000).max(1_000).saturating_mul(1_000);
+    let first = from - from.rem_euclid(bucket);
+    if (to - first) / bucket >= MAX_POINTS {
+        return Err(bad_request(format!("en fazla {} zaman dilimi istenebilir", MAX_POINTS)));
+    }
+
+    let mut results = Vec::new();
+    for target in request.targets.iter().filter(|target| !target.hide) {
+        let (metric, filter) = parse_target(&target.target).map_err(bad_request)?;
+        let mut params = LogQuery {
+            from: Some(from),
+            to: Some(to),
+            filter,
+            tenant: Some(auth::tenant_of(caller.as_deref())),
+            ..LogQuery::default()
+        };
+        debug!("📈 Grafana sorgusu: {} ({:?})", target.target, target.kind);
+
+        match target.kind {
+            TargetKind::Timeserie => {
+                let counts = state.store.level_counts(&params, bucket).await.map_err(internal)?;
+                let mut buckets: BTreeMap<i64, BTreeMap<String, i64>> =
+                    (0..=(to - first) / bucket).map(|n| (first + n * bucket, BTreeMap::new())).collect();
+                for count in counts {
+                    *buckets.entry(count.bucket).or_default().entry(count.level).or_default() += count.count;
+                }
+                let datapoints: Vec<(Option<f64>, i64)> = buckets
+                    .iter()
+                    .map(|(start, levels)| (metric.value(levels), start / 1_000))
+                    .collect();
+                results.push(json!({ "target": target.target, "datapoints": datapoints }));
+            }
+            TargetKind::Table => {
+                metric.narrow(&mut params);
+                params.limit = Some(request.max_data_points.unwrap_or(DEFAULT_ROWS).clamp(1, MAX_ROWS));
+                let logs = state.store.query(&params).await.map_err(internal)?;
+                results.push(table(&logs));
+            }
+        }
+    }
+    Ok(Json(results))
+}
+
+fn table(logs: &[StoredLog]) -> Value {
+    let rows: Vec<Value> = logs
+        .iter()
+        .map(|log| {
+            json!([
+                epoch_millis(&log.timestamp),
+                log.level,
+                field(log, &log.service, "service"),
+                field(log, &log.host, "host"),
+                log.message,
+            ])
+        })
+        .collect();
+    json!({
+        "type": "table",
+        "columns": [
+            { "text": "Time", "type": "time" },
+            { "text": "Level", "type": "string" },
+            { "text": "Service", "type": "string" },
+            { "text": "Host", "type": "string" },
+            { "text": "Message", "type": "string" },
+        ],
+        "rows": rows,
+    })
+}
+
+// --- İşaretler ---
+#[derive(Debug, Deserialize)]
+pub struct AnnotationRequest {
+    range: Range,
+    annotation: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Annotation {
+    // Grafana isteği olduğu gibi geri bekler
+    annotation: Value,
+    // epoch ms
+    time: i64,
+    title: String,
+    text: String,
+    tags: Vec<String>,
+}
+
+// POST /grafana/annotations: işaret sorgusundaki ("query") hedefe uyan en yeni
+// loglar; sorgu boşsa "errors". Seviye ve servis etiket olarak eklenir.
+pub async fn annotations_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Json(request): Json<AnnotationRequest>,
+) -> Result<Json<Vec<Annotation>>, (StatusCode, String)> {
+    let (from, to) = request.range.micros().map_err(bad_request)?;
+    let query = request.annotation.get("query").and_then(Value::as_str).unwrap_or("").trim();
+    let (metric, filter) = parse_target(if query.is_empty() { "errors" } else { query }).map_err(bad_request)?;
+    let mut params = LogQuery {
+        from: Some(from),
+        to: Some(to),
+        filter,
+        limit: Some(DEFAULT_ROWS),
+        tenant: Some(auth::tenant_of(caller.as_deref())),
+        ..LogQuery::default()
+    };
+    metric.narrow(&mut params);
+    debug!("📍 Grafana işaret sorgusu: {:?}", params);
+
+    let logs = state.store.query(&params).await.map_err(internal)?;
+    Ok(Json(
+        logs.iter()
+            .map(|log| {
+                let service = field(log, &log.service, "service");
+                Annotation {
+                    annotation: request.annotation.clone(),
+                    time: epoch_millis(&log.timestamp),
+                    title: format!("{} {}", log.level, service).trim_end().to_string(),
+                    text: log.message.clone(),
+                    tags: [log.level.clone(), service].into_iter().filter(|tag| !tag.is_empty()).collect(),
+                }
+            })
+            .collect(),
+    ))
+}
+
+// Öne çıkarılan sütun boşsa details içindeki alan
+fn field(log: &StoredLog, column: &Option<String>, name: &str) -> String {
+    column
+        .clone()
+        .or_else(|| log.details.get(name).and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn epoch_millis(text: &str) -> i64 {
+    timestamp::parse_micros(text).map(|micros| micros / 1_000).unwrap_or(0)
+}
+
+fn bad_request(message: String) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message)
+}
+
+fn internal(e: crate::storage::StorageError) -> (StatusCode, String) {
+    error!("❌ Grafana sorgusu başarısız: {}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+}
@@ -0,0 +1,58 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::AppState;
+
+// --- Sağlık Kontrolleri ---
+// /healthz: süreç ayakta mı (liveness). Her zaman 200 döner.
+// /readyz:  trafik alabilir miyiz (readiness). Veritabanı sorgu çalıştırabilmeli
+//           ve kanal doluluk oranı eşiğin altında olmalı; alım duraklatılmamış olmalı.
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub database: bool,
+    // Bakım için duraklatıldıysa (POST /admin/pause) trafik başka örneklere gitmeli
+    pub paused: bool,
+    pub channel_depth: usize,
+    pub channel_capacity: usize,
+}
+
+pub async fn healthz_handler() -> StatusCode {
+    StatusCode::OK
+}
+
+pub async fn readyz_handler(State(state): State<AppState>) -> (StatusCode, Json<Readiness>) {
+    let database = state.store.ping().await.is_ok();
+
+    let channel_capacity = state.tx.capacity();
+    let channel_depth = state.tx.depth();
+    let fill = channel_depth as f64 / channel_capacity as f64;
+    let saturated = fill >= state.ready_max_fill;
+
+    let paused = state.pause.is_paused();
+    let ready = database && !saturated && !paused;
+    if !ready {
+        warn!(
+            "⚠️ Hazır değil: veritabanı={} duraklatıldı={} kanal={}/{}",
+            database, paused, channel_depth, channel_capacity
+        );
+    }
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(Readiness {
+            ready,
+            database,
+            paused,
+            channel_depth,
+            channel_capacity,
+        }),
+    )
+}
@@ -0,0 +1,37 @@
+use crate::LogEntry;
+
+// --- Eklenti Kancaları ---
+// Gömülü kullanımda (Ingestor::builder().hook(..)) her log, [[transform]]
+// adımlarından sonra ve seviye eşlemesi / filtrelerden önce, kayıt sırasıyla
+// kancalardan geçer. Kanca logu değiştirebilir ya da atabilir; atılan loglar
+// filtrelenmiş sayılır. Kancalar istek yolunda eşzamanlı çalışır: ağ veya disk
+// gibi bekleyen işler burada yapılmamalı.
+//
+// 'wasm' özelliğiyle derlenmiş ikilide [[wasm]] ile verilen modüller de birer
+// kancadır (bkz. wasm); gömülü uygulamanın kancalarından sonra çalışırlar.
+pub trait Hook: Send + Sync {
+    // Kancanın kısa adı (loglar için)
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    // Log kabul edilecekse Keep, atılacaksa Drop döner.
+    fn apply(&self, log: &mut LogEntry) -> Verdict;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Keep,
+    Drop,
+}
+
+// Kapanışlar da kanca olarak verilebilir:
+//   .hook(|log: &mut LogEntry| if log.message.contains("healthz") { Verdict::Drop } else { Verdict::Keep })
+impl<F> Hook for F
+where
+    F: Fn(&mut LogEntry) -> Verdict + Send + Sync,
+{
+    fn apply(&self, log: &mut LogEntry) -> Verdict {
+        self(log)
+    }
+}
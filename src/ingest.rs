@@ -0,0 +1,494 @@
+use std::borrow::Cow;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::async_trait;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::error::Category;
+use serde_json::Value;
+use tokio::sync::mpsc::error::TrySendError;
+use tracing::{debug, debug_span, Instrument};
+
+use crate::alert::{Alerts, Matched};
+use crate::auth::Caller;
+use crate::config::Backpressure;
+use crate::deadletter::SavedLog;
+use crate::dedup::Offer;
+use crate::filter::Decision;
+use crate::hook::Verdict;
+use crate::limits::Fit;
+use crate::pipeline::Ack;
+use crate::ratelimit;
+use crate::sanitize;
+use crate::tail::{Tail, TailEvent};
+use crate::timestamp;
+use crate::{AppState, LogEntry};
+
+// Tek bir logun boru hattındaki sonucu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    // Kanala gönderildi
+    Accepted,
+    // Seviye filtresine veya bir kurala takıldı
+    Filtered,
+    // Örnekleme dışında kaldı
+    Sampled,
+    // Bekleyen aynı logla birleştirildi (repeat_count)
+    Merged,
+    // Kanal kapalı olduğu için gönderilemedi
+    Dropped,
+    // Kanal dolu olduğu için atıldı veya reddedildi (backpressure = drop / reject)
+    Overloaded,
+    // Alan boyutu sınırını aştığı için reddedildi ([limits] oversize = "reject")
+    Oversized,
+}
+
+// backpressure = "reject" iken kanalda 'count' log için yer yoksa döndürülecek 503.
+// Handler'lar gövdeyi çözdükten sonra, hiçbir logu işlemeden önce çağırır;
+// böylece istek ya tamamen kabul edilir ya da tamamen reddedilir.
+// Alım duraklatılmışsa (POST /admin/pause) kanal durumundan bağımsız olarak reddedilir.
+pub fn check_capacity(state: &AppState, count: usize) -> Option<Response> {
+    if state.pause.is_paused() {
+        return Some(paused_response(state));
+    }
+    (state.backpressure == Backpressure::Reject && state.tx.free() < count).then(|| overloaded_response(state))
+}
+
+// Alım duraklatıldı: 503 + Retry-After (istemciler kuyruk doluymuş gibi tekrar dener)
+pub fn paused_response(state: &AppState) -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, state.retry_after_secs.to_string())],
+        "alım bakım için duraklatıldı, daha sonra tekrar deneyin",
+    )
+        .into_response()
+}
+
+// Kanal dolu: 503 + Retry-After
+pub fn overloaded_response(state: &AppState) -> Response {
+    debug!("🚧 Kanal dolu, istek reddedildi ({} sn sonra tekrar).", state.retry_after_secs);
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, state.retry_after_secs.to_string())],
+        "kuyruk dolu, daha sonra tekrar deneyin",
+    )
+        .into_response()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct IngestQuery {
+    // true: yanıt gövdesinde kabul / filtre / red sayıları ve hatalı kayıtların nedenleri
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+// --- Gövde Çözümleme ---
+// Content-Type'a göre log dizisini çözer:
+//   application/msgpack, application/x-msgpack -> MessagePack (rmp-serde)
+//   diğerleri                                  -> JSON (application/json veya +json)
+// JSON gövdesindeki geçersiz UTF-8 ve kontrol karakterleri çözülmeden önce
+// temizlenir (bkz. sanitize); ikili çöp içeren tek bir mesaj isteği düşürmez.
+// Normalde tek bir hatalı kayıt tüm isteği reddeder. '?verbose=true' ile her kayıt
+// ayrı çözülür; hatalı olanlar Err olarak kalır, diğerleri işlenir.
+pub struct Batch(pub Vec<Result<LogEntry, String>>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequest<S> for Batch {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let is_msgpack = matches!(
+            content_type.split(';').next().map(str::trim),
+            Some("application/msgpack" | "application/x-msgpack")
+        );
+        let verbose = Query::<IngestQuery>::try_from_uri(req.uri()).is_ok_and(|Query(query)| query.verbose);
+
+        if verbose {
+            let entries: Vec<Value> = if is_msgpack {
+                let body = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+                rmp_serde::from_slice(&body).map_err(|e| {
+                    (StatusCode::BAD_REQUEST, format!("geçersiz msgpack gövdesi: {}", e)).into_response()
+                })?
+            } else {
+                from_json(req, state).await?
+            };
+            let entries = entries
+                .into_iter()
+                .map(|entry| LogEntry::deserialize(entry).map_err(|e| e.to_string()))
+                .collect();
+            return Ok(Batch(entries));
+        }
+
+        let logs: Vec<LogEntry> = if is_msgpack {
+            let body = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+            rmp_serde::from_slice(&body).map_err(|e| {
+                (StatusCode::BAD_REQUEST, format!("geçersiz msgpack gövdesi: {}", e)).into_response()
+            })?
+        } else {
+            from_json(req, state).await?
+        };
+        Ok(Batch(logs.into_iter().map(Ok).collect()))
+    }
+}
+
+// axum Json gibi: Content-Type JSON değilse 415, sözdizimi hatası 400, alanları
+// uymayan kayıt 422. Tek fark gövdenin önce temizlenmesi.
+async fn from_json<T: DeserializeOwned, S: Send + Sync>(req: Request, state: &S) -> Result<T, Response> {
+    let is_json = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .is_some_and(|mime| mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json")));
+    if !is_json {
+        return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, "Content-Type: application/json bekleniyor").into_response());
+    }
+    let body = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+    let text = sanitize::json(&body);
+    if let Cow::Owned(_) = text {
+        debug!("🧹 JSON gövdesi temizlendi (geçersiz UTF-8 / kontrol karakteri)");
+    }
+    serde_json::from_str(&text).map_err(|e| {
+        let status = match e.classify() {
+            Category::Data => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        (status, format!("geçersiz JSON gövdesi: {}", e)).into_response()
+    })
+}
+
+// '?verbose=true' yanıtı. Örneklemeye takılanlar 'filtered', birleştirilenler
+// 'accepted' sayılır; 'errors' reddedilen kayıtların dizideki yerini ve nedenini verir.
+#[derive(Debug, Default, Serialize)]
+pub struct IngestReport {
+    pub accepted: usize,
+    pub filtered: usize,
+    pub rejected: usize,
+    pub errors: Vec<EntryError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntryError {
+    pub index: usize,
+    pub reason: String,
+}
+
+// --- 6. Request Handler (Producer) ---
+// HTTP isteğini karşılar, filtreler ve kanala atar.
+// Dosya yazma işlemini beklemez, hemen cevap döner.
+pub async fn ingest_handler(
+    State(state): State<AppState>,
+    Query(params): Query<IngestQuery>,
+    caller: Option<Extension<Caller>>,
+    Batch(payload): Batch, // Batch (dizi) olarak log kabul eder (JSON veya msgpack)
+) -> Response {
+    debug!("📥 İstek alındı: {} adet log", payload.len());
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), payload.len()) {
+        return rejection;
+    }
+    if let Some(rejection) = check_capacity(&state, payload.len()) {
+        return rejection;
+    }
+    let mut report = IngestReport::default();
+    let mut overloaded = false;
+    for (index, entry) in payload.into_iter().enumerate() {
+        let mut log = match entry {
+            Ok(log) => log,
+            Err(reason) => {
+                debug!("ℹ️ {}. kayıt geçersiz: {}", index, reason);
+                state.metrics.invalid_entries.fetch_add(1, Ordering::Relaxed);
+                report.rejected += 1;
+                report.errors.push(EntryError { index, reason });
+                continue;
+            }
+        };
+        if let Some(Extension(caller)) = &caller {
+            caller.tag(&mut log);
+        }
+        match submit(&state, log).await {
+            Outcome::Accepted | Outcome::Merged => report.accepted += 1,
+            Outcome::Filtered | Outcome::Sampled => report.filtered += 1,
+            Outcome::Dropped => {
+                report.rejected += 1;
+                report.errors.push(EntryError {
+                    index,
+                    reason: "sunucu kapanıyor, kayıt kuyruğa alınamadı".to_string(),
+                });
+            }
+            Outcome::Overloaded => {
+                overloaded = true;
+                report.rejected += 1;
+                report.errors.push(EntryError {
+                    index,
+                    reason: "kuyruk dolu, kayıt atıldı".to_string(),
+                });
+            }
+            Outcome::Oversized => {
+                report.rejected += 1;
+                report.errors.push(EntryError {
+                    index,
+                    reason: "kayıt alan boyutu sınırını aşıyor (limits)".to_string(),
+                });
+            }
+        }
+    }
+
+    // Ön kontrolden sonra kanal dolduysa (eşzamanlı istekler) istemci tekrar denesin;
+    // yoksa istemciye "Kabul Edildi" (202 Accepted) dönüyoruz.
+    let rejected = overloaded && state.backpressure == Backpressure::Reject;
+    match (params.verbose, rejected) {
+        (false, false) => StatusCode::ACCEPTED.into_response(),
+        (false, true) => overloaded_response(&state),
+        (true, false) => (StatusCode::ACCEPTED, Json(report)).into_response(),
+        (true, true) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, state.retry_after_secs.to_string())],
+            Json(report),
+        )
+            .into_response(),
+    }
+}
+
+// --- Zincirleme Aktarım (Merkez) ---
+// POST /ingest/relay: uç sunucuların [upstream] gönderdiği partiler. Kayıtlar
+// kiracı ve kaynak bilgisini de taşır (bkz. deadletter::SavedLog); uç katman
+// 'relay' rolünü istediği için bu bilgiye sadece güvenilen anahtarlarda uyulur.
+// Loglar istemcinin kiracısıyla etiketlenmez, uçtaki kiracılarına yazılır.
+pub async fn relay_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Json(logs): Json<Vec<SavedLog>>,
+) -> Response {
+    debug!("📥 Aktarım isteği alındı: {} adet log", logs.len());
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), logs.len()) {
+        return rejection;
+    }
+    if let Some(rejection) = check_capacity(&state, logs.len()) {
+        return rejection;
+    }
+    let mut overloaded = false;
+    for saved in &logs {
+        if submit(&state, LogEntry::from(saved)).await == Outcome::Overloaded {
+            overloaded = true;
+        }
+    }
+    // Uç sunucu 503'te partiyi yeniden gönderir (teslim en az bir keredir)
+    if overloaded && state.backpressure == Backpressure::Reject {
+        return overloaded_response(&state);
+    }
+    StatusCode::ACCEPTED.into_response()
+}
+
+// --- Ortak Giriş Noktası ---
+// Tüm kaynaklar (HTTP, syslog, ...) logları buradan boru hattına sokar:
+// sayaçlar, seviye filtresi, zaman damgası ve kanala gönderim tek yerde yapılır.
+pub async fn submit(state: &AppState, log: LogEntry) -> Outcome {
+    submit_with_ack(state, log, None).await
+}
+
+// submit ile aynıdır; ek olarak log veritabanına yazılınca (veya filtrelenip
+// atılınca) 'ack' sonuçlandırılır. Kanala giremeyen log başarısız sayılır.
+pub async fn submit_with_ack(state: &AppState, mut log: LogEntry, ack: Option<Ack>) -> Outcome {
+    // Duraklatılmışken HTTP / gRPC istekleri check_capacity'de reddedilir; akış
+    // kaynakları (dosya, Kafka, syslog TCP, ...) burada sürdürülmeyi bekler.
+    state.pause.wait().await;
+    state.metrics.received.fetch_add(1, Ordering::Relaxed);
+    // Çözülmüş metinlerdeki NUL'lar (JSON "\u0000", msgpack, GELF, ...) görünür yazı olur
+    sanitize::scrub_nul(&mut log);
+
+    // Farklı biçimlerdeki loglar ([[transform]] adımlarıyla) ortak şekle getirilir
+    let failed = state.transforms.load().apply(&mut log);
+    if failed > 0 {
+        debug!("ℹ️ {} dönüştürme adımı uygulanamadı.", failed);
+        state.metrics.transform_errors.fetch_add(failed as u64, Ordering::Relaxed);
+    }
+    // Gömülü uygulamanın kancaları logu değiştirebilir veya atabilir
+    for hook in state.hooks.iter() {
+        if hook.apply(&mut log) == Verdict::Drop {
+            debug!("ℹ️ Log '{}' kancasıyla atıldı.", hook.name());
+            state.metrics.filtered.fetch_add(1, Ordering::Relaxed);
+            if let Some(ack) = ack {
+                ack.complete(true);
+            }
+            return Outcome::Filtered;
+        }
+    }
+
+    // "WARNING", "err", 30 gibi seviyeler kurallar ve filtre görmeden kanonik hale gelir
+    state.levels.load().apply(&mut log);
+
+    // Önce regex kuralları (drop / keep / tag), karar vermezlerse seviye filtresi
+    let allowed = match state.rules.load().evaluate(&mut log) {
+        Some(Decision::Keep(_)) => true,
+        Some(Decision::Drop(rule)) => {
+            debug!("ℹ️ Log '{}' kuralıyla atıldı.", rule);
+            false
+        }
+        None => {
+            let allowed = state.filter.load().allows(&log.level);
+            if !allowed {
+                debug!("ℹ️ Log seviyesi '{}', filtrelendi.", log.level);
+            }
+            allowed
+        }
+    };
+    if !allowed {
+        state.metrics.filtered.fetch_add(1, Ordering::Relaxed);
+        if let Some(ack) = ack {
+            ack.complete(true);
+        }
+        return Outcome::Filtered;
+    }
+
+    // Yüksek hacimli seviyelerin sadece bir kısmını tut
+    if !state.sampler.load().sample(&mut log) {
+        state.metrics.sampled_out.fetch_add(1, Ordering::Relaxed);
+        if let Some(ack) = ack {
+            ack.complete(true);
+        }
+        return Outcome::Sampled;
+    }
+
+    // IP adresine ülke / şehir / ASN ekle (maskelemeden önce: IP maskelense de konum kalır)
+    if state.geoip.as_ref().is_some_and(|geoip| geoip.enrich(&mut log)) {
+        state.metrics.geoip_enriched.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Alınma zamanını kaydet. 'timestamp' UTC RFC 3339'a çevrilir; yoksa alınma
+    // zamanı kullanılır, çözülemezse özgün değer 'timestamp_raw'a taşınır.
+    let now = chrono::Utc::now().to_rfc3339();
+    if let serde_json::Value::Object(ref mut map) = log.extra {
+        let timestamp = match map.get("timestamp").filter(|v| !v.is_null()) {
+            None => now.clone(),
+            Some(value) => match timestamp::normalize(value) {
+                Some(normalized) => normalized,
+                None => {
+                    debug!("ℹ️ Zaman damgası çözülemedi: {}", value);
+                    state.metrics.invalid_timestamps.fetch_add(1, Ordering::Relaxed);
+                    let raw = map.remove("timestamp").unwrap_or_default();
+                    map.insert("timestamp_raw".to_string(), raw);
+                    now.clone()
+                }
+            },
+        };
+        map.insert("timestamp".to_string(), serde_json::Value::String(timestamp));
+    }
+    log.source.received_at.get_or_insert(now);
+
+    // E-posta, kart numarası vb. kişisel veriyi maskele (yazılmadan, canlı akışa ve
+    // uyarılara gitmeden önce). Zaman damgası artık RFC 3339 metnidir, maskelenmez.
+    if state.redactor.load().apply(&mut log) {
+        state.metrics.redacted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Aşırı büyük mesaj / details kesilir veya kayıt reddedilir ([limits]). Zaman
+    // damgasından sonra bakılır: yazılacak details'in tamamı sınıra dahildir.
+    // Onay bekleyen kaynaklar reddedilen kaydı tekrar göndermesin diye onaylanır.
+    match state.field_limits.apply(&mut log) {
+        Fit::Fits => {}
+        Fit::Truncated => {
+            debug!("✂️ Log alan boyutu sınırına kadar kesildi.");
+            state.metrics.truncated.fetch_add(1, Ordering::Relaxed);
+        }
+        Fit::Rejected(reason) => {
+            debug!("ℹ️ Log boyut sınırını aştığı için reddedildi: {}", reason);
+            state.metrics.oversized.fetch_add(1, Ordering::Relaxed);
+            if let Some(ack) = ack {
+                ack.complete(true);
+            }
+            return Outcome::Oversized;
+        }
+    }
+
+    debug!("✅ '{}' logu kabul edildi, kanala gönderiliyor...", log.level);
+
+    // Tekrarlar pencere boyunca tek satırda birleştirilir; ilk geliş pencere sonunda gönderilir
+    let (log, ack) = match &state.dedup {
+        Some(dedup) => match dedup.offer(log, ack) {
+            Offer::Held => {
+                state.metrics.accepted.fetch_add(1, Ordering::Relaxed);
+                return Outcome::Accepted;
+            }
+            Offer::Merged => {
+                state.metrics.deduplicated.fetch_add(1, Ordering::Relaxed);
+                return Outcome::Merged;
+            }
+            Offer::Pass(log, ack) => (*log, ack),
+        },
+        None => (log, ack),
+    };
+    let observed = Observed::of(&state.tail, &state.alerts, &log);
+
+    // Kanala gönder.
+    // await kullanıyoruz ama bu işlem sadece belleğe yazdığı için nanosaniyeler sürer.
+    // Eğer kanal doluysa (channel_capacity kadar log birikmişse) 'block' politikasında
+    // burada bekler; 'drop' / 'reject' politikasında beklemeden Overloaded döner.
+    // Onay bekleyen kaynaklar (Kafka) her zaman bekler.
+    let outcome = if state.backpressure == Backpressure::Block || ack.is_some() {
+        let started = Instant::now();
+        let sent = state.tx.send(log, ack.clone()).instrument(debug_span!("channel_send")).await;
+        state.metrics.send_wait.observe(started.elapsed().as_secs_f64());
+        match sent {
+            Ok(()) => Outcome::Accepted,
+            Err(_) => Outcome::Dropped,
+        }
+    } else {
+        match state.tx.try_send(log, ack.clone()) {
+            Ok(()) => Outcome::Accepted,
+            Err(TrySendError::Full(_)) => Outcome::Overloaded,
+            Err(TrySendError::Closed(_)) => Outcome::Dropped,
+        }
+    };
+    let counter = match outcome {
+        Outcome::Accepted => &state.metrics.accepted,
+        Outcome::Overloaded => &state.metrics.overloaded,
+        _ => &state.metrics.dropped,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    if outcome == Outcome::Accepted {
+        observed.report(&state.tail, &state.alerts);
+    } else if let Some(ack) = ack {
+        ack.complete(false);
+    }
+    outcome
+}
+
+// --- Canlı Akış ve Uyarılar ---
+// Sadece kanala giren loglar izleyicilere dağıtılır ve uyarı kurallarına
+// işlenir; birleştirilen, atılan veya reddedilen loglar gitmez. Log gönderimde
+// taşındığı için gerekenler önceden hazırlanır, gönderim başarılı olunca bildirilir.
+pub(crate) struct Observed {
+    tail: Option<Arc<TailEvent>>,
+    alerts: Option<Matched>,
+}
+
+impl Observed {
+    pub(crate) fn of(tail: &Tail, alerts: &Alerts, log: &LogEntry) -> Self {
+        Observed {
+            tail: tail.prepare(log),
+            alerts: alerts.matching(log),
+        }
+    }
+
+    pub(crate) fn report(self, tail: &Tail, alerts: &Alerts) {
+        if let Some(event) = self.tail {
+            tail.publish(event);
+        }
+        if let Some(matched) = self.alerts {
+            alerts.record(matched);
+        }
+    }
+}
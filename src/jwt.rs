@@ -0,0 +1,510 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Sha256, Sha384, Sha512};
+use tracing::{info, warn};
+
+use crate::auth::Roles;
+use crate::config::{JwtConfig, Role};
+
+const JWKS_TIMEOUT: Duration = Duration::from_secs(10);
+
+// --- JWT Doğrulaması ---
+// SSO'nun verdiği token'lar API anahtarı gibi "Authorization: Bearer <jwt>" ile
+// gönderilir. İmza (HS* paylaşılan anahtarla, diğerleri JWKS'teki açık anahtarla),
+// exp / nbf ve verilmişse iss / aud doğrulanır; kiracı ve roller claim'lerden okunur.
+// "none" algoritması ve anahtar türüyle uyuşmayan algoritmalar reddedilir.
+pub struct JwtVerifier {
+    config: JwtConfig,
+    // jwks_url'den düzenli olarak yenilenir
+    keys: RwLock<Arc<Vec<Jwk>>>,
+    client: reqwest::Client,
+}
+
+// Doğrulanmış token'ın istemci bilgileri
+#[derive(Debug)]
+pub struct Claims {
+    pub subject: String,
+    pub tenant: String,
+    // Yetkilerden çıkan roller (read_scope, write_scope, admin_scope)
+    pub roles: Roles,
+}
+
+#[derive(Debug, Deserialize)]
+struct Header {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Value>,
+}
+
+#[derive(Debug, Clone)]
+struct Jwk {
+    kid: Option<String>,
+    key: PublicKey,
+}
+
+#[derive(Debug, Clone)]
+enum PublicKey {
+    Rsa { n: Vec<u8>, e: Vec<u8> },
+    // Sıkıştırılmamış nokta: 0x04 || x || y
+    Ec { curve: String, point: Vec<u8> },
+    Ed25519(Vec<u8>),
+}
+
+impl JwtVerifier {
+    pub fn from_config(config: &JwtConfig) -> Result<Option<Self>, String> {
+        if !config.enabled() {
+            return Ok(None);
+        }
+        let keys = match &config.jwks_path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path).map_err(|e| format!("JWKS dosyası okunamadı ({}): {}", path, e))?;
+                parse_jwks(&text).map_err(|e| format!("JWKS dosyası geçersiz ({}): {}", path, e))?
+            }
+            None => Vec::new(),
+        };
+        if let Some(url) = &config.jwks_url {
+            url::Url::parse(url).map_err(|e| format!("auth.jwt.jwks_url geçersiz: {}", e))?;
+        }
+        let client = reqwest::Client::builder()
+            .timeout(JWKS_TIMEOUT)
+            .build()
+            .map_err(|e| format!("JWKS istemcisi kurulamadı: {}", e))?;
+        Ok(Some(JwtVerifier {
+            config: config.clone(),
+            keys: RwLock::new(Arc::new(keys)),
+            client,
+        }))
+    }
+
+    pub fn jwks_url(&self) -> Option<&str> {
+        self.config.jwks_url.as_deref()
+    }
+
+    // Bearer değeri JWT'ye benziyor mu (üç parça, ilki base64url JSON başlığı)
+    pub fn looks_like_jwt(token: &str) -> bool {
+        token.starts_with("eyJ") && token.split('.').count() == 3
+    }
+
+    // jwks_url'den anahtarları okur; hata olursa eldeki anahtarlar geçerli kalır.
+    pub async fn refresh(&self) -> Result<usize, String> {
+        let Some(url) = self.jwks_url() else {
+            return Ok(self.keys.read().unwrap().len());
+        };
+        let response = self.client.get(url).send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        let text = response.text().await.map_err(|e| e.to_string())?;
+        let mut keys = parse_jwks(&text)?;
+        // jwks_path ile verilen sabit anahtarlar da korunur
+        if let Some(path) = &self.config.jwks_path {
+            if let Ok(text) = std::fs::read_to_string(path) {
+                keys.extend(parse_jwks(&text).unwrap_or_default());
+            }
+        }
+        let count = keys.len();
+        *self.keys.write().unwrap() = Arc::new(keys);
+        Ok(count)
+    }
+
+    // İmza, süre veya claim hatasında nedeni döner (-> 401).
+    pub fn verify(&self, token: &str) -> Result<Claims, String> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err("biçim hatalı".to_string());
+        };
+        let header: Header = decode_json(header).ok_or("başlık çözülemedi")?;
+        let claims: Value = decode_json(payload).ok_or("claim'ler çözülemedi")?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature.trim_end_matches('='))
+            .map_err(|_| "imza çözülemedi")?;
+        let signed = &token[..header_len(token)];
+        self.check_signature(&header, signed.as_bytes(), &signature)?;
+        self.check_claims(&claims)?;
+
+        let tenant = match claims.get(&self.config.tenant_claim).and_then(Value::as_str) {
+            Some(tenant) if !tenant.is_empty() => tenant.to_string(),
+            _ => self
+                .config
+                .default_tenant
+                .clone()
+                .ok_or_else(|| format!("'{}' claim'i yok", self.config.tenant_claim))?,
+        };
+        let scopes: Vec<&str> = match claims.get(&self.config.scope_claim) {
+            Some(Value::String(text)) => text.split_whitespace().collect(),
+            Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let roles = [
+            (&self.config.write_scope, Role::Ingest),
+            (&self.config.read_scope, Role::Read),
+            (&self.config.admin_scope, Role::Admin),
+        ]
+        .into_iter()
+        .filter(|(scope, _)| scopes.contains(&scope.as_str()))
+        .fold(Roles::default(), |roles, (_, role)| roles.with(role));
+        Ok(Claims {
+            subject: claims.get("sub").and_then(Value::as_str).unwrap_or("?").to_string(),
+            tenant,
+            roles,
+        })
+    }
+
+    fn check_signature(&self, header: &Header, signed: &[u8], signature: &[u8]) -> Result<(), String> {
+        let alg = header.alg.as_str();
+        if let Some(bits) = alg.strip_prefix("HS") {
+            let secret = self.config.secret.as_deref().ok_or("HS* için auth.jwt.secret tanımlı değil")?;
+            let valid = match bits {
+                "256" => verify_hmac::<Hmac<Sha256>>(secret, signed, signature),
+                "384" => verify_hmac::<Hmac<Sha384>>(secret, signed, signature),
+                "512" => verify_hmac::<Hmac<Sha512>>(secret, signed, signature),
+                _ => return Err(format!("desteklenmeyen algoritma: {}", alg)),
+            };
+            return if valid { Ok(()) } else { Err("imza geçersiz".to_string()) };
+        }
+
+        let keys = self.keys.read().unwrap().clone();
+        let candidates: Vec<&Jwk> = keys
+            .iter()
+            .filter(|jwk| header.kid.is_none() || jwk.kid == header.kid)
+            .collect();
+        if candidates.is_empty() {
+            return Err(format!("anahtar bulunamadı (kid: {})", header.kid.as_deref().unwrap_or("-")));
+        }
+        for jwk in candidates {
+            let valid = match (alg, &jwk.key) {
+                ("RS256", PublicKey::Rsa { n, e }) => verify_rsa(&signature::RSA_PKCS1_2048_8192_SHA256, n, e, signed, signature),
+                ("RS384", PublicKey::Rsa { n, e }) => verify_rsa(&signature::RSA_PKCS1_2048_8192_SHA384, n, e, signed, signature),
+                ("RS512", PublicKey::Rsa { n, e }) => verify_rsa(&signature::RSA_PKCS1_2048_8192_SHA512, n, e, signed, signature),
+                ("PS256", PublicKey::Rsa { n, e }) => verify_rsa(&signature::RSA_PSS_2048_8192_SHA256, n, e, signed, signature),
+                ("PS384", PublicKey::Rsa { n, e }) => verify_rsa(&signature::RSA_PSS_2048_8192_SHA384, n, e, signed, signature),
+                ("PS512", PublicKey::Rsa { n, e }) => verify_rsa(&signature::RSA_PSS_2048_8192_SHA512, n, e, signed, signature),
+                ("ES256", PublicKey::Ec { curve, point }) if curve == "P-256" => {
+                    UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point).verify(signed, signature).is_ok()
+                }
+                ("ES384", PublicKey::Ec { curve, point }) if curve == "P-384" => {
+                    UnparsedPublicKey::new(&signature::ECDSA_P384_SHA384_FIXED, point).verify(signed, signature).is_ok()
+                }
+                ("EdDSA", PublicKey::Ed25519(x)) => {
+                    UnparsedPublicKey::new(&signature::ED25519, x).verify(signed, signature).is_ok()
+                }
+                _ => false,
+            };
+            if valid {
+                return Ok(());
+            }
+        }
+        Err(format!("imza geçersiz ({})", alg))
+    }
+
+    fn check_claims(&self, claims: &Value) -> Result<(), String> {
+        let now = chrono::Utc::now().timestamp();
+        let leeway = self.config.leeway_secs as i64;
+        let exp = claims.get("exp").and_then(Value::as_i64).ok_or("'exp' claim'i yok")?;
+        if now > exp + leeway {
+            return Err("süresi dolmuş".to_string());
+        }
+        if let Some(nbf) = claims.get("nbf").and_then(Value::as_i64) {
+            if now + leeway < nbf {
+                return Err("henüz geçerli değil".to_string());
+            }
+        }
+        if let Some(issuer) = &self.config.issuer {
+            if claims.get("iss").and_then(Value::as_str) != Some(issuer.as_str()) {
+                return Err("'iss' eşleşmiyor".to_string());
+            }
+        }
+        if let Some(audience) = &self.config.audience {
+            let matches = match claims.get("aud") {
+                Some(Value::String(aud)) => aud == audience,
+                Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(audience)),
+                _ => false,
+            };
+            if !matches {
+                return Err("'aud' eşleşmiyor".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+// jwks_url verilmişse anahtarları açılışta ve her jwks_refresh_secs'te yeniden okur.
+pub async fn run(verifier: Arc<JwtVerifier>) {
+    let Some(url) = verifier.jwks_url().map(str::to_string) else {
+        return;
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs(verifier.config.jwks_refresh_secs.max(1)));
+    let mut loaded = false;
+    loop {
+        interval.tick().await;
+        match verifier.refresh().await {
+            Ok(count) => {
+                if !loaded {
+                    info!("🔑 JWKS yüklendi: {} ({} anahtar)", url, count);
+                    loaded = true;
+                }
+            }
+            Err(e) => warn!("⚠️ JWKS okunamadı, eldeki anahtarlar geçerli: {} ({})", url, e),
+        }
+    }
+}
+
+fn parse_jwks(text: &str) -> Result<Vec<Jwk>, String> {
+    let set: JwkSet = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    Ok(set.keys.iter().filter_map(parse_jwk).collect())
+}
+
+// Tanınmayan veya şifreleme için ("use": "enc") olan anahtarlar atlanır.
+fn parse_jwk(jwk: &Value) -> Option<Jwk> {
+    if jwk.get("use").and_then(Value::as_str) == Some("enc") {
+        return None;
+    }
+    let field = |name: &str| {
+        jwk.get(name)
+            .and_then(Value::as_str)
+            .and_then(|v| URL_SAFE_NO_PAD.decode(v.trim_end_matches('=')).ok())
+    };
+    let key = match jwk.get("kty").and_then(Value::as_str)? {
+        "RSA" => PublicKey::Rsa {
+            n: field("n")?,
+            e: field("e")?,
+        },
+        "EC" => {
+            let mut point = vec![0x04];
+            point.extend(field("x")?);
+            point.extend(field("y")?);
+            PublicKey::Ec {
+                curve: jwk.get("crv").and_then(Value::as_str)?.to_string(),
+                point,
+            }
+        }
+        "OKP" if jwk.get("crv").and_then(Value::as_str) == Some("Ed25519") => PublicKey::Ed25519(field("x")?),
+        _ => return None,
+    };
+    Some(Jwk {
+        kid: jwk.get("kid").and_then(Value::as_str).map(str::to_string),
+        key,
+    })
+}
+
+fn decode_json<T: serde::de::DeserializeOwned>(part: &str) -> Option<T> {
+    let bytes = URL_SAFE_NO_PAD.decode(part.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+// İmzalanan kısım: "başlık.claim'ler" (son noktaya kadar)
+fn header_len(token: &str) -> usize {
+    token.rfind('.').unwrap_or(0)
+}
+
+fn verify_hmac<M: Mac + hmac::digest::KeyInit>(secret: &str, signed: &[u8], signature: &[u8]) -> bool {
+    let Ok(mut mac) = <M as hmac::digest::KeyInit>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(signed);
+    mac.verify_slice(signature).is_ok()
+}
+
+fn verify_rsa(
+    params: &'static signature::RsaParameters,
+    n: &[u8],
+    e: &[u8],
+    signed: &[u8],
+    signature: &[u8],
+) -> bool {
+    RsaPublicKeyComponents { n, e }.verify(params, signed, signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // tests/fixtures/make_jwt.py ile üretilen sabit token'lar (2100'e kadar geçerli)
+    const FIXTURES: &str = include_str!("../tests/fixtures/jwt.json");
+    const SECRET: &str = "paylasilan-anahtar";
+
+    fn fixture(name: &str) -> String {
+        let fixtures: Value = serde_json::from_str(FIXTURES).unwrap();
+        match &fixtures[name] {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    fn config() -> JwtConfig {
+        JwtConfig {
+            issuer: Some("https://sso.example".to_string()),
+            audience: Some("ingestor".to_string()),
+            ..JwtConfig::default()
+        }
+    }
+
+    fn verifier(config: JwtConfig) -> JwtVerifier {
+        JwtVerifier {
+            config,
+            keys: RwLock::new(Arc::new(parse_jwks(&fixture("jwks")).unwrap())),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn hs256(claims: Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(format!("{}.{}", header, payload).as_bytes());
+        format!("{}.{}.{}", header, payload, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+    }
+
+    // Token'ın başlığını değiştirir, claim'leri ve imzayı korur
+    fn with_header(token: &str, header: &str) -> String {
+        let (_, rest) = token.split_once('.').unwrap();
+        format!("{}.{}", URL_SAFE_NO_PAD.encode(header), rest)
+    }
+
+    #[test]
+    fn valid_rs256_token() {
+        let verifier = verifier(config());
+        for name in ["rs256", "rs256_no_kid"] {
+            let claims = verifier.verify(&fixture(name)).unwrap();
+            assert_eq!(claims.subject, "svc-1");
+            assert_eq!(claims.tenant, "acme");
+            assert!(claims.roles.contains(Role::Read));
+            assert!(claims.roles.contains(Role::Ingest));
+            assert!(!claims.roles.contains(Role::Admin));
+        }
+    }
+
+    #[test]
+    fn alg_none_is_rejected() {
+        let token = fixture("alg_none");
+        assert!(verifier(config()).verify(&token).unwrap_err().contains("none"));
+        // Geçerli bir token'ın başlığı "none" yapılsa da imza aranır
+        let token = with_header(&fixture("rs256"), r#"{"alg":"none","kid":"k1"}"#);
+        assert!(verifier(config()).verify(&token).is_err());
+    }
+
+    #[test]
+    fn hs_rs_confusion_is_rejected() {
+        // Açık anahtarla HMAC imzalanmış token: HS* sadece auth.jwt.secret ile doğrulanır
+        let token = fixture("hs256_signed_with_public_key");
+        assert!(verifier(config()).verify(&token).unwrap_err().contains("secret"));
+        let with_secret = JwtConfig {
+            secret: Some(SECRET.to_string()),
+            ..config()
+        };
+        assert_eq!(verifier(with_secret.clone()).verify(&token).unwrap_err(), "imza geçersiz");
+
+        // RS256 imzası HS256 başlığıyla, HS256 imzası RS256 başlığıyla geçmez
+        let token = with_header(&fixture("rs256"), r#"{"alg":"HS256","kid":"k1"}"#);
+        assert_eq!(verifier(with_secret.clone()).verify(&token).unwrap_err(), "imza geçersiz");
+        let claims = json!({"sub": "x", "tenant": "acme", "iss": "https://sso.example", "aud": "ingestor", "exp": 4102444800u64});
+        let token = with_header(&hs256(claims), r#"{"alg":"RS256","kid":"k1"}"#);
+        assert_eq!(verifier(with_secret).verify(&token).unwrap_err(), "imza geçersiz (RS256)");
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let token = fixture("rs256");
+        let parts: Vec<&str> = token.split('.').collect();
+        let forged = URL_SAFE_NO_PAD.encode(
+            json!({"sub": "svc-1", "tenant": "other", "scope": "admin", "iss": "https://sso.example", "aud": "ingestor", "exp": 4102444800u64})
+                .to_string(),
+        );
+        let token = format!("{}.{}.{}", parts[0], forged, parts[2]);
+        assert_eq!(verifier(config()).verify(&token).unwrap_err(), "imza geçersiz (RS256)");
+    }
+
+    #[test]
+    fn unknown_kid_is_rejected() {
+        let error = verifier(config()).verify(&fixture("rs256_unknown_kid")).unwrap_err();
+        assert_eq!(error, "anahtar bulunamadı (kid: k2)");
+    }
+
+    #[test]
+    fn missing_exp_is_rejected() {
+        assert_eq!(verifier(config()).verify(&fixture("rs256_no_exp")).unwrap_err(), "'exp' claim'i yok");
+    }
+
+    #[test]
+    fn issuer_and_audience() {
+        let verifier = verifier(config());
+        assert_eq!(verifier.verify(&fixture("rs256_wrong_iss")).unwrap_err(), "'iss' eşleşmiyor");
+        // aud metin veya dizi olabilir
+        assert!(verifier.verify(&fixture("rs256_aud_string")).is_ok());
+        assert!(verifier.verify(&fixture("rs256")).is_ok());
+        assert_eq!(verifier.verify(&fixture("rs256_other_aud")).unwrap_err(), "'aud' eşleşmiyor");
+
+        // iss / aud yapılandırılmadıysa bakılmaz
+        let open = self::verifier(JwtConfig::default());
+        assert!(open.verify(&fixture("rs256_wrong_iss")).is_ok());
+        assert!(open.verify(&fixture("rs256_other_aud")).is_ok());
+    }
+
+    #[test]
+    fn exp_and_nbf_leeway() {
+        let config = JwtConfig {
+            secret: Some(SECRET.to_string()),
+            leeway_secs: 60,
+            ..JwtConfig::default()
+        };
+        let verifier = verifier(config);
+        let now = chrono::Utc::now().timestamp();
+        let token = |exp: i64, nbf: Option<i64>| {
+            let mut claims = json!({"sub": "x", "tenant": "acme", "exp": exp});
+            if let Some(nbf) = nbf {
+                claims["nbf"] = json!(nbf);
+            }
+            hs256(claims)
+        };
+
+        assert!(verifier.verify(&token(now + 600, None)).is_ok());
+        // Süresi 30 sn önce dolmuş ama tolerans 60 sn
+        assert!(verifier.verify(&token(now - 30, None)).is_ok());
+        assert_eq!(verifier.verify(&token(now - 120, None)).unwrap_err(), "süresi dolmuş");
+        assert!(verifier.verify(&token(now + 600, Some(now + 30))).is_ok());
+        assert_eq!(verifier.verify(&token(now + 600, Some(now + 120))).unwrap_err(), "henüz geçerli değil");
+
+        let strict = self::verifier(JwtConfig {
+            secret: Some(SECRET.to_string()),
+            leeway_secs: 0,
+            ..JwtConfig::default()
+        });
+        assert_eq!(strict.verify(&token(now - 5, None)).unwrap_err(), "süresi dolmuş");
+        assert_eq!(strict.verify(&token(now + 600, Some(now + 5))).unwrap_err(), "henüz geçerli değil");
+    }
+
+    #[test]
+    fn malformed_tokens() {
+        let verifier = verifier(config());
+        for token in ["", "a.b", "a.b.c.d", "eyJ.eyJ.x", "!!.!!.!!"] {
+            assert!(verifier.verify(token).is_err(), "{}", token);
+        }
+        // Kiracı claim'i yoksa ve varsayılan kiracı yoksa reddedilir
+        let config = JwtConfig {
+            secret: Some(SECRET.to_string()),
+            ..JwtConfig::default()
+        };
+        let token = hs256(json!({"sub": "x", "exp": 4102444800u64}));
+        assert_eq!(self::verifier(config.clone()).verify(&token).unwrap_err(), "'tenant' claim'i yok");
+        let fallback = JwtConfig {
+            default_tenant: Some("ortak".to_string()),
+            ..config
+        };
+        assert_eq!(self::verifier(fallback).verify(&token).unwrap().tenant, "ortak");
+    }
+}
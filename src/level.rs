@@ -0,0 +1,113 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::config::{LevelsConfig, NumericLevels};
+use crate::LogEntry;
+
+// Kanonik seviyeler (önem sırasıyla; filter::severity ile aynı sıra)
+pub const CANONICAL: [&str; 6] = ["trace", "debug", "info", "warn", "error", "fatal"];
+
+// --- Seviye Normalleştirme ---
+// Farklı log kütüphanelerinin seviye adları ve sayıları, filtreler ve kurallar
+// çalışmadan önce kanonik seviyelere çevrilir: "WARNING", "wrn", "W" -> "warn",
+// "err", "E" -> "error", "CRITICAL", "emerg" -> "fatal", pino/bunyan 30 -> "info".
+// Tanınmayan seviyeler olduğu gibi bırakılır (severity sütunu boş kalır).
+#[derive(Debug)]
+pub struct LevelNormalizer {
+    enabled: bool,
+    numeric: NumericLevels,
+    // küçük harfli ad -> kanonik seviye (yerleşik eşlemelerden önce bakılır)
+    aliases: HashMap<String, &'static str>,
+}
+
+impl LevelNormalizer {
+    pub fn from_config(config: &LevelsConfig) -> Result<Self, String> {
+        let aliases = config
+            .aliases
+            .iter()
+            .map(|(alias, target)| {
+                let canonical = canonical(target)
+                    .ok_or_else(|| format!("'{}' takma adının hedefi '{}' bilinen bir seviye değil", alias, target))?;
+                Ok((alias.trim().to_ascii_lowercase(), canonical))
+            })
+            .collect::<Result<HashMap<_, _>, String>>()?;
+        Ok(LevelNormalizer {
+            enabled: config.normalize,
+            numeric: config.numeric,
+            aliases,
+        })
+    }
+
+    // Seviyenin kanonik karşılığı; tanınmıyorsa None.
+    pub fn normalize(&self, level: &str) -> Option<&'static str> {
+        let level = level.trim();
+        if let Some(&alias) = self.aliases.get(&level.to_ascii_lowercase()) {
+            return Some(alias);
+        }
+        match level.parse::<f64>() {
+            Ok(number) => self.numeric.level(number),
+            Err(_) => canonical(level),
+        }
+    }
+
+    // Logun seviyesini yerinde kanonik hale getirir.
+    pub fn apply(&self, log: &mut LogEntry) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(level) = self.normalize(&log.level).filter(|level| log.level != *level) {
+            log.level = level.to_string();
+        }
+    }
+}
+
+impl NumericLevels {
+    fn level(self, number: f64) -> Option<&'static str> {
+        if !number.is_finite() || number < 0.0 {
+            return None;
+        }
+        // Ara değerler bir alttaki seviyeye yuvarlanır (örn. pino'da 35 -> info)
+        let index = match self {
+            // pino / bunyan: 10 trace, 20 debug, 30 info, 40 warn, 50 error, 60 fatal
+            NumericLevels::Bunyan => ((number / 10.0) as usize).clamp(1, 6) - 1,
+            // Python logging: 5 trace, 10 debug, 20 info, 30 warning, 40 error, 50 critical
+            NumericLevels::Python => ((number / 10.0) as usize).min(5),
+            // syslog önem derecesi: 0 emerg .. 7 debug
+            NumericLevels::Syslog => match number as u8 {
+                0..=2 => 5,
+                3 => 4,
+                4 => 3,
+                5 | 6 => 2,
+                7 => 1,
+                _ => return None,
+            },
+        };
+        Some(CANONICAL[index])
+    }
+}
+
+// Yaygın seviye adları ve kısaltmaları (büyük/küçük harf duyarsız)
+fn canonical(level: &str) -> Option<&'static str> {
+    let level = match level.trim().to_ascii_lowercase().as_str() {
+        "trace" | "trc" | "t" | "v" | "verbose" | "finest" | "finer" | "all" => "trace",
+        "debug" | "dbg" | "d" | "fine" | "config" => "debug",
+        "info" | "inf" | "i" | "information" | "informational" | "notice" | "note" => "info",
+        "warn" | "wrn" | "w" | "warning" => "warn",
+        "error" | "err" | "e" | "eror" | "severe" => "error",
+        "fatal" | "ftl" | "f" | "critical" | "crit" | "c" | "alert" | "emerg" | "emergency" | "panic" | "dpanic" => {
+            "fatal"
+        }
+        _ => return None,
+    };
+    Some(level)
+}
+
+// LogEntry.level için: metin veya sayı kabul eder (30 -> "30", 7.5 -> "7.5").
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(level) => Ok(level),
+        Value::Number(number) => Ok(number.to_string()),
+        other => Err(serde::de::Error::custom(format!("seviye metin veya sayı olmalı: {}", other))),
+    }
+}
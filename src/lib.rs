@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+// --- Kütüphane ---
+// Sunucu başka bir servisin içine gömülebilir (veya testlerde süreç içinde
+// çalıştırılabilir); 'log-ingestor' ikilisi de aynı kütüphaneyi kullanır:
+//   let ingestor = Ingestor::builder().db_path("logs.db").build().await?;
+//   ingestor.serve(listener).await;
+// Dışa açık modüller: config, ingest, pipeline, storage, server, testing
+// (ve komut satırının kullandığı query, expr, archive, selflog, timestamp).
+
+mod admin;
+mod alert;
+pub mod archive;
+mod audit;
+mod auth;
+pub mod config;
+mod cors;
+mod deadletter;
+mod dedup;
+mod erasure;
+mod export;
+pub mod expr;
+mod filter;
+mod geoip;
+mod grafana;
+mod health;
+pub mod hook;
+pub mod ingest;
+mod jwt;
+mod level;
+mod limits;
+mod metrics;
+pub mod pipeline;
+pub mod query;
+mod ratelimit;
+mod redact;
+mod reload;
+mod retention;
+mod sampling;
+mod sanitize;
+pub mod selflog;
+pub mod server;
+mod sinks;
+mod sources;
+mod stats;
+pub mod storage;
+#[cfg(unix)]
+mod systemd;
+mod tail;
+pub mod testing;
+pub mod timestamp;
+mod tls;
+mod tokens;
+mod transform;
+#[cfg(unix)]
+mod uds;
+mod ui;
+mod wal;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(windows)]
+pub mod winservice;
+mod writer;
+
+pub use config::Config;
+pub use server::{Ingestor, IngestorBuilder};
+pub use sources::stdin::PipeSummary;
+
+use alert::Alerts;
+use auth::ApiKeys;
+use config::{Backpressure, OtlpConfig};
+use deadletter::DeadLetter;
+use dedup::Deduplicator;
+use erasure::ErasureAudit;
+use filter::{FilterRules, LevelFilter};
+use geoip::GeoIp;
+use hook::Hook;
+use level::LevelNormalizer;
+use metrics::Metrics;
+use pipeline::Dispatcher;
+use ratelimit::RateLimiter;
+use redact::Redactor;
+use reload::Live;
+use sampling::Sampler;
+use sources::hec::HecAcks;
+use sources::raw::RawParsers;
+use storage::{Routes, SharedStorage};
+use tail::Tail;
+use transform::Transforms;
+
+// --- 1. Veri Modeli ---
+// Gelen JSON verisini karşılayacak yapı.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LogEntry {
+    // Sayı olarak gelen seviyeler (pino: 30, Python: 20) metne çevrilerek okunur.
+    #[serde(deserialize_with = "level::deserialize")]
+    pub level: String,
+    pub message: String,
+    // Gelen JSON'da tanımlamadığımız diğer tüm alanları 'extra' içine atar.
+    // Böylece veri kaybı olmaz.
+    #[serde(flatten)]
+    pub extra: serde_json::Value,
+    // Logun ait olduğu kiracı. Gövdeden okunmaz; istemcinin kimliğinden belirlenir
+    // (HTTP dışı kaynaklar ve anahtarsız istemciler varsayılan kiracıya yazar).
+    #[serde(skip, default = "default_tenant")]
+    tenant_id: String,
+    // Logun nereden geldiği. Gövdeden okunmaz; ayrı sütunlarda saklanır.
+    #[serde(skip)]
+    source: Source,
+}
+
+// --- Kaynak Bilgisi ---
+// Sunucu tarafında eklenir; istemci değiştiremez (denetim için).
+#[derive(Debug, Clone, Default)]
+struct Source {
+    // Gönderen bağlantının adresi (HTTP, syslog, GELF UDP, Fluentd, OTLP gRPC)
+    remote_addr: Option<IpAddr>,
+    // HTTP User-Agent başlığı
+    user_agent: Option<String>,
+    // İstemcinin API anahtarının adı (anahtarın kendisi değil), örn. "payments#2"
+    api_key: Option<String>,
+    // Sunucunun logu aldığı an (RFC 3339, UTC)
+    received_at: Option<String>,
+}
+
+fn default_tenant() -> String {
+    auth::DEFAULT_TENANT.to_string()
+}
+
+// --- 2. Uygulama Durumu (State) ---
+// Axum handler'ları arasında veri paylaşmak için kullanılır.
+// Kanalların gönderici uçlarını (Dispatcher) burada tutuyoruz.
+#[derive(Clone)]
+pub struct AppState {
+    tx: Dispatcher,
+    // Hangi seviyelerin kabul edileceği (INGEST_LEVELS / INGEST_MIN_LEVEL)
+    filter: Arc<Live<LevelFilter>>,
+    // Seviye adlarının ve sayılarının kanonik seviyelere çevrilmesi
+    levels: Arc<Live<LevelNormalizer>>,
+    // Seviye eşlemesinden önce uygulanan dönüştürme adımları ([[transform]])
+    transforms: Arc<Live<Transforms>>,
+    // Gömülü uygulamanın kaydettiği kancalar (bkz. hook)
+    hooks: Arc<[Arc<dyn Hook>]>,
+    // Seviye filtresinden önce değerlendirilen regex kuralları (drop / keep / tag)
+    rules: Arc<Live<FilterRules>>,
+    // Filtreden geçen logların seviyeye göre örneklenmesi
+    sampler: Arc<Live<Sampler>>,
+    // Tekrar birleştirme (kapalıysa None)
+    dedup: Option<Arc<Deduplicator>>,
+    // GeoIP zenginleştirme (veritabanı verilmemişse None)
+    geoip: Option<Arc<GeoIp>>,
+    // Kanala girmeden önce uygulanan kişisel veri maskeleme
+    redactor: Arc<Live<Redactor>>,
+    // Okuma API'si (GET /logs) için veritabanı (SQLite veya PostgreSQL)
+    store: SharedStorage,
+    // Yazma yönlendirme kuralları ve depoları (ölü mektup yeniden yazma, silme talepleri)
+    routes: Arc<Routes>,
+    // Prometheus sayaçları (yazıcı görevi ile paylaşılır)
+    metrics: Arc<Metrics>,
+    // Sunucunun başladığı an (GET /admin/status çalışma süresi)
+    started_at: chrono::DateTime<chrono::Utc>,
+    // POST /admin/pause ile yeni logların alımı durdurulur
+    pause: Arc<admin::Pause>,
+    // POST /admin/drain'de süre verilmezse beklenecek en fazla süre (shutdown_timeout_secs)
+    drain_timeout_secs: u64,
+    // Kanal bu orandan fazla doluysa /readyz 503 döner
+    ready_max_fill: f64,
+    // Kanal doluyken bekle / at / 503 (ve 503'teki Retry-After saniyesi)
+    backpressure: Backpressure,
+    retry_after_secs: u64,
+    // /ingest/raw satır ayrıştırıcıları
+    raw: Arc<RawParsers>,
+    // Splunk HEC kanal onayları
+    hec: Arc<HecAcks>,
+    // OTLP kaynak / kapsam özniteliklerinin saklanma biçimi
+    otlp: Arc<OtlpConfig>,
+    // Yazma uçları için API anahtarları (boşsa doğrulama kapalı)
+    api_keys: Arc<Live<ApiKeys>>,
+    // [auth.jwt] açıksa Bearer JWT doğrulayıcısı
+    jwt: Option<Arc<jwt::JwtVerifier>>,
+    // /admin/tokens ile oluşturulan anahtarlar (auth.token_file boşsa None)
+    tokens: Option<Arc<tokens::TokenStore>>,
+    // İstemci başına hız sınırları
+    limiter: Arc<RateLimiter>,
+    // Yazma uçlarında izin verilen en büyük gövde (bayt)
+    max_body_bytes: usize,
+    // Tek bir kaydın mesaj / details boyutu sınırları
+    field_limits: limits::FieldLimits,
+    // /export/parquet yanıtındaki en fazla satır
+    export_max_rows: u64,
+    // Canlı akış (/tail, /tail/sse) izleyicilerine dağıtım
+    tail: Tail,
+    // Uyarı kuralları (kabul edilen her log kurallara işlenir)
+    alerts: Arc<Alerts>,
+    // Yazılamayan partilerin tutulduğu dosya (kapalıysa None)
+    dead_letter: Option<Arc<DeadLetter>>,
+    // DELETE /logs denetim dosyası (kapalıysa None)
+    erasure_audit: Option<Arc<ErasureAudit>>,
+    // Yeniden yükleme (SIGHUP, POST /reload) için yapılandırma dosyasının yolu
+    // (gömülü kullanımda dosya verilmemişse None)
+    config_path: Option<Arc<str>>,
+}
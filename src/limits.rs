@@ -0,0 +1,327 @@
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::config::{LimitsConfig, Oversize};
+use crate::{AppState, LogEntry};
+
+// --- İstek Gövdesi Boyut Sınırı ---
+// Yazma uçlarında gövde 'server.max_body_bytes' ile sınırlıdır. Sınır iki
+// yerde uygulanır:
+//   - Content-Length sınırı aşıyorsa istek gövde okunmadan reddedilir.
+//   - Content-Length yoksa (chunked) veya gövde açıldıktan sonra (gzip/zstd)
+//     büyüyorsa, axum'un DefaultBodyLimit'i okurken keser.
+// Her iki durumda da istemciye aynı yapılandırılmış 413 yanıtı döner.
+#[derive(Debug, Serialize)]
+pub struct PayloadTooLarge {
+    pub error: &'static str,
+    pub message: String,
+    pub limit_bytes: usize,
+}
+
+pub fn payload_too_large(limit: usize) -> Response {
+    let body = PayloadTooLarge {
+        error: "payload_too_large",
+        message: format!("istek gövdesi {} baytı aşıyor", limit),
+        limit_bytes: limit,
+    };
+    (StatusCode::PAYLOAD_TOO_LARGE, Json(body)).into_response()
+}
+
+pub async fn enforce_body_limit(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let limit = state.max_body_bytes;
+    let declared = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|len| len > limit as u64) {
+        debug!("📦 Gövde çok büyük: {} ({:?} bayt)", req.uri().path(), declared);
+        return payload_too_large(limit);
+    }
+
+    // İçeride (extractor'lar, hız sınırı) üretilen düz metin 413'leri aynı biçime getir
+    let response = next.run(req).await;
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return payload_too_large(limit);
+    }
+    response
+}
+
+// --- Alan Boyut Sınırları ---
+// Tek bir aşırı büyük log (yığın dökümü, gömülü dosya) veritabanını şişirmesin
+// diye mesaj ve details ([limits]) ayrı ayrı sınırlanır. details mesaj ve
+// seviye dışındaki tüm alanların JSON halidir. Kesmede en uzun metin değerleri
+// kısaltılır; metin kısaltmak yetmezse (ör. dev sayı dizileri) en büyük üst
+// düzey alanlar çıkarılır; zaman damgasına dokunulmaz. Kesilen kayda
+// truncated = true eklenir.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldLimits {
+    message: Option<usize>,
+    details: Option<usize>,
+    oversize: Oversize,
+}
+
+// Kesilen kayıtlara eklenen alan
+const TRUNCATED_KEY: &str = "truncated";
+// Kesilmeyen / çıkarılmayan üst düzey alanlar
+const KEPT_KEYS: [&str; 2] = [TRUNCATED_KEY, "timestamp"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fit {
+    // Sınırlar içinde
+    Fits,
+    // Sınıra kadar kesildi
+    Truncated,
+    // oversize = "reject": nedeniyle birlikte
+    Rejected(String),
+}
+
+impl FieldLimits {
+    pub fn from_config(config: &LimitsConfig) -> Self {
+        FieldLimits {
+            message: (config.max_message_bytes > 0).then_some(config.max_message_bytes),
+            details: (config.max_details_bytes > 0).then_some(config.max_details_bytes),
+            oversize: config.oversize,
+        }
+    }
+
+    pub fn apply(&self, log: &mut LogEntry) -> Fit {
+        let message_over = self.message.filter(|limit| log.message.len() > *limit);
+        let details_over = self.details.filter(|limit| json_len(&log.extra) > *limit);
+        if message_over.is_none() && details_over.is_none() {
+            return Fit::Fits;
+        }
+
+        if self.oversize == Oversize::Reject {
+            let reason = match (message_over, details_over) {
+                (Some(limit), _) => format!("message {} bayt, sınır {} bayt", log.message.len(), limit),
+                (None, Some(limit)) => format!("details {} bayt, sınır {} bayt", json_len(&log.extra), limit),
+                (None, None) => unreachable!(),
+            };
+            return Fit::Rejected(reason);
+        }
+
+        if let Some(limit) = message_over {
+            truncate_text(&mut log.message, limit);
+        }
+        if let Value::Object(map) = &mut log.extra {
+            map.insert(TRUNCATED_KEY.to_string(), Value::Bool(true));
+        }
+        // Eklenen alan da sınıra dahildir
+        if let Some(limit) = self.details {
+            shrink(&mut log.extra, limit);
+        }
+        Fit::Truncated
+    }
+}
+
+// JSON halinin bayt uzunluğu (ara bellek ayırmadan)
+fn json_len(value: &Value) -> usize {
+    struct Count(usize);
+    impl std::io::Write for Count {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut count = Count(0);
+    let _ = serde_json::to_writer(&mut count, value);
+    count.0
+}
+
+// Metni en fazla 'limit' bayta, karakter sınırında keser.
+fn truncate_text(text: &mut String, limit: usize) {
+    if text.len() <= limit {
+        return;
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+}
+
+// details'i 'limit' bayta sığdırır. Bir metinden k bayt kesmek JSON halini en az
+// k bayt kısaltır; en uzun metin fazlalık kadar kesilir, yetmezse sıradakine geçilir.
+fn shrink(extra: &mut Value, limit: usize) {
+    loop {
+        let size = json_len(extra);
+        if size <= limit {
+            return;
+        }
+        let excess = size - limit;
+        let Value::Object(map) = extra else {
+            return;
+        };
+        let mut texts = Vec::new();
+        for (key, value) in map.iter_mut() {
+            if !KEPT_KEYS.contains(&key.as_str()) {
+                collect_texts(value, &mut texts);
+            }
+        }
+        match texts.into_iter().max_by_key(|text| text.len()) {
+            Some(text) if !text.is_empty() => {
+                let keep = text.len().saturating_sub(excess);
+                truncate_text(text, keep);
+            }
+            _ => {
+                // Kesilecek metin kalmadı: en büyük üst düzey alan çıkarılır
+                let largest = map
+                    .iter()
+                    .filter(|(key, _)| !KEPT_KEYS.contains(&key.as_str()))
+                    .max_by_key(|(_, value)| json_len(value))
+                    .map(|(key, _)| key.clone());
+                match largest {
+                    Some(key) => {
+                        map.remove(&key);
+                    }
+                    None => return,
+                }
+            }
+        }
+    }
+}
+
+fn collect_texts<'a>(value: &'a mut Value, out: &mut Vec<&'a mut String>) {
+    match value {
+        Value::String(text) => out.push(text),
+        Value::Array(items) => items.iter_mut().for_each(|item| collect_texts(item, out)),
+        Value::Object(map) => map.values_mut().for_each(|item| collect_texts(item, out)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn limits(message: usize, details: usize, oversize: Oversize) -> FieldLimits {
+        FieldLimits::from_config(&LimitsConfig {
+            max_message_bytes: message,
+            max_details_bytes: details,
+            oversize,
+        })
+    }
+
+    fn entry(message: &str, extra: Value) -> LogEntry {
+        let mut raw = json!({"level": "info", "message": message});
+        if let (Some(raw), Value::Object(extra)) = (raw.as_object_mut(), extra) {
+            raw.extend(extra);
+        }
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn message_is_cut_at_a_char_boundary() {
+        // "ğ" iki bayttır; sınır ikinci baytına denk gelir
+        let mut log = entry("aaaaağbc", json!({}));
+        assert_eq!(limits(6, 0, Oversize::Truncate).apply(&mut log), Fit::Truncated);
+        assert_eq!(log.message, "aaaaa");
+        assert_eq!(log.extra["truncated"], true);
+
+        // Dört baytlık karakterin her baytında kesme geriye kayar
+        for limit in 1..4 {
+            let mut text = "🦀🦀".to_string();
+            truncate_text(&mut text, limit);
+            assert_eq!(text, "");
+        }
+        let mut text = "🦀🦀".to_string();
+        truncate_text(&mut text, 7);
+        assert_eq!(text, "🦀");
+    }
+
+    #[test]
+    fn multibyte_details_fit_within_the_limit() {
+        let mut log = entry("kısa", json!({"stack": "çğüşöı".repeat(200)}));
+        assert_eq!(limits(0, 100, Oversize::Truncate).apply(&mut log), Fit::Truncated);
+        assert!(json_len(&log.extra) <= 100, "{}", log.extra);
+        let stack = log.extra["stack"].as_str().unwrap();
+        assert!(!stack.is_empty());
+        assert!("çğüşöı".repeat(200).starts_with(stack));
+    }
+
+    #[test]
+    fn details_limit_leaves_the_message_alone() {
+        let message = "m".repeat(50);
+        let mut log = entry(
+            &message,
+            json!({"timestamp": "2026-01-02T03:04:05Z", "stack": "s".repeat(500), "user": "ayşe"}),
+        );
+        assert_eq!(limits(100, 120, Oversize::Truncate).apply(&mut log), Fit::Truncated);
+        assert_eq!(log.message, message);
+        assert!(json_len(&log.extra) <= 120, "{}", log.extra);
+        // En uzun metin kısaltılır; kısa alanlar ve zaman damgası olduğu gibi kalır
+        assert!(log.extra["stack"].as_str().unwrap().len() < 500);
+        assert_eq!(log.extra["user"], "ayşe");
+        assert_eq!(log.extra["timestamp"], "2026-01-02T03:04:05Z");
+        assert_eq!(log.extra["truncated"], true);
+    }
+
+    #[test]
+    fn texts_are_shortened_before_fields_are_dropped() {
+        let mut log = entry(
+            "sayılar",
+            json!({"timestamp": "2026-01-02T03:04:05Z", "samples": vec![1234567; 100], "note": "n".repeat(40)}),
+        );
+        assert_eq!(limits(0, 80, Oversize::Truncate).apply(&mut log), Fit::Truncated);
+        assert!(json_len(&log.extra) <= 80, "{}", log.extra);
+        // Metin kısaltmak yetmedi: sayı dizisi çıkarıldı, not alanı (boş da olsa) kaldı
+        assert!(log.extra.get("samples").is_none(), "{}", log.extra);
+        assert!(log.extra.get("note").is_some(), "{}", log.extra);
+        assert_eq!(log.extra["timestamp"], "2026-01-02T03:04:05Z");
+    }
+
+    #[test]
+    fn within_limits_is_untouched() {
+        let mut log = entry("tamam", json!({"user": "ayşe"}));
+        assert_eq!(limits(64, 64, Oversize::Truncate).apply(&mut log), Fit::Fits);
+        assert!(log.extra.get("truncated").is_none());
+
+        // 0 = sınırsız
+        let mut log = entry(&"m".repeat(10_000), json!({"stack": "s".repeat(10_000)}));
+        assert_eq!(limits(0, 0, Oversize::Reject).apply(&mut log), Fit::Fits);
+    }
+
+    #[test]
+    fn reject_reports_the_reason_and_keeps_the_entry() {
+        let mut log = entry(&"m".repeat(20), json!({"stack": "s".repeat(200)}));
+        let fit = limits(10, 50, Oversize::Reject).apply(&mut log);
+        assert_eq!(fit, Fit::Rejected("message 20 bayt, sınır 10 bayt".to_string()));
+        assert_eq!(log.message.len(), 20);
+
+        let fit = limits(100, 50, Oversize::Reject).apply(&mut log);
+        let Fit::Rejected(reason) = fit else {
+            panic!("{:?}", fit);
+        };
+        assert!(reason.starts_with("details "), "{}", reason);
+        assert!(reason.ends_with("sınır 50 bayt"), "{}", reason);
+        assert!(log.extra.get("truncated").is_none());
+    }
+
+    #[tokio::test]
+    async fn payload_too_large_body() {
+        let response = payload_too_large(1024);
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "error": "payload_too_large",
+                "message": "istek gövdesi 1024 baytı aşıyor",
+                "limit_bytes": 1024
+            })
+        );
+    }
+}
@@ -1,152 +1,148 @@
-use axum::{
-    extract::State,
-    http::StatusCode,
-    routing::post,
-    Json, Router,
-};
-use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
-use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool};
-use tracing::{debug, info};
+use clap::Parser;
+use tracing::{debug, error, info};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
-// --- 1. Veri Modeli ---
-// Gelen JSON verisini karşılayacak yapı.
-#[derive(Debug, Deserialize, Serialize)]
-struct LogEntry {
-    level: String,
-    message: String,
-    // Gelen JSON'da tanımlamadığımız diğer tüm alanları 'extra' içine atar.
-    // Böylece veri kaybı olmaz.
-    #[serde(flatten)]
-    extra: serde_json::Value,
-}
+use log_ingestor::{archive, expr, query, selflog, storage, timestamp, Config, Ingestor};
 
-// --- 2. Uygulama Durumu (State) ---
-// Axum handler'ları arasında veri paylaşmak için kullanılır.
-// Kanalın gönderici ucunu (Sender) burada tutuyoruz.
-#[derive(Clone)]
-struct AppState {
-    tx: mpsc::Sender<LogEntry>,
-}
+mod cli;
+use cli::{Cli, Command};
 
 #[tokio::main]
 async fn main() {
-    // Loglamayı başlat (Konsola bilgi basmak için)
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-
-    // --- 3. MPSC Kanalı Kurulumu ---
-    // tx: Transmitter (Gönderici), rx: Receiver (Alıcı)
-    // 10.000 kapasiteli bir kanal açıyoruz.
-    let (tx, mut rx) = mpsc::channel::<LogEntry>(10000);
-
-    // --- 4. Veritabanı Kurulumu (SQLite) ---
-    // WAL Modu (Write-Ahead Logging) performansı artırır.
-    let db_options = SqliteConnectOptions::new()
-        .filename("logs.db")
-        .create_if_missing(true)
-        .journal_mode(SqliteJournalMode::Wal);
-
-    let pool = SqlitePool::connect_with(db_options)
-        .await
-        .expect("Veritabanına bağlanılamadı");
+    let cli = Cli::parse();
 
-    // Tabloyu oluştur (Yoksa)
-    sqlx::query(
-        "CREATE TABLE IF NOT EXISTS logs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            level TEXT NOT NULL,
-            message TEXT NOT NULL,
-            timestamp TEXT NOT NULL,
-            details TEXT
-        )",
-    )
-    .execute(&pool)
-    .await
-    .expect("Tablo oluşturulamadı");
+    // Loglamayı başlat (Konsola bilgi basmak için)
+    // --log-level verilirse RUST_LOG yerine o kullanılır.
+    let env_filter = match &cli.log_level {
+        Some(level) => tracing_subscriber::EnvFilter::new(level),
+        None => tracing_subscriber::EnvFilter::from_default_env(),
+    };
+    // pipe modunda standart çıktı (--tee) girdiye aittir; loglar stderr'e yazılır
+    // Kendi logları katmanı, self_log.enabled kapalıyken etkisizdir (bkz. selflog)
+    let registry = tracing_subscriber::registry().with(selflog::layer());
+    if matches!(cli.command, Some(Command::Pipe(_))) {
+        registry
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr).with_filter(env_filter))
+            .init();
+    } else {
+        registry.with(tracing_subscriber::fmt::layer().with_filter(env_filter)).init();
+    }
 
-    // --- 5. Arka Plan Veritabanı Yazıcısı (Consumer) ---
-    // Bu görev (task) ana sunucudan bağımsız, ayrı bir thread gibi çalışır.
-    let writer_task = tokio::spawn(async move {
-        // Kanal açık olduğu sürece gelen verileri al
-        while let Some(log) = rx.recv().await {
-            debug!("💾 DB'ye yazılıyor: {}", log.message);
-            
-            // Timestamp'i extra alanından çek (ingest_handler eklemişti)
-            let timestamp = log.extra.get("timestamp").and_then(|v| v.as_str()).unwrap_or("");
+    // Ayarları yükle (config.toml + ortam değişkenleri + komut satırı)
+    let mut config = Config::load(&cli.config).expect("Yapılandırma yüklenemedi");
+    cli.apply_to(&mut config);
+    debug!("⚙️ Yapılandırma: {:?}", config);
 
-            // Geri kalan veriyi JSON string'e çevir (details sütunu için)
-            let details = serde_json::to_string(&log.extra).unwrap_or_default();
+    #[cfg(windows)]
+    if let Some(Command::Serve { service: true, .. }) = &cli.command {
+        log_ingestor::winservice::start(config.pipeline.shutdown_timeout_secs).expect("Windows hizmeti başlatılamadı");
+    }
 
-            // SQL Insert
-            let _ = sqlx::query("INSERT INTO logs (level, message, timestamp, details) VALUES (?, ?, ?, ?)")
-                .bind(&log.level)
-                .bind(&log.message)
-                .bind(timestamp)
-                .bind(details)
-                .execute(&pool)
-                .await;
+    match cli.command {
+        None | Some(Command::Serve { .. }) => {
+            let ingestor = Ingestor::builder()
+                .config(config)
+                .config_path(&cli.config)
+                .build()
+                .await
+                .expect("Sunucu başlatılamadı");
+            ingestor.run().await;
         }
-        // Veritabanı bağlantı havuzu (pool) otomatik kapanır.
-    });
-
-    // --- 6. Sunucu Ayarları ---
-    let state = AppState { tx };
-
-    let app = Router::new()
-        .route("/ingest", post(ingest_handler))
-        .with_state(state);
-
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3002").await.unwrap();
-    info!("🚀 Log Ingestion Sunucusu 3002 portunda çalışıyor...");
-    
-    // Graceful Shutdown ile sunucuyu başlat
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
-
-    // Sunucu durduğunda, arka plandaki yazıcının işini bitirmesini bekle
-    let _ = writer_task.await;
-    info!("✅ Tüm loglar diske yazıldı ve sunucu güvenle kapandı.");
-}
-
-// CTRL+C sinyalini dinleyen yardımcı fonksiyon
-async fn shutdown_signal() {
-    let _ = tokio::signal::ctrl_c().await;
-    info!("🛑 Kapatma sinyali alındı (CTRL+C). İstekler durduruluyor...");
-}
-
-// --- 6. Request Handler (Producer) ---
-// HTTP isteğini karşılar, filtreler ve kanala atar.
-// Dosya yazma işlemini beklemez, hemen cevap döner.
-async fn ingest_handler(
-    State(state): State<AppState>,
-    Json(payload): Json<Vec<LogEntry>>, // Batch (dizi) olarak log kabul eder
-) -> StatusCode {
-    
-    debug!("📥 İstek alındı: {} adet log", payload.len());
-    for mut log in payload {
-        // Sadece "error" seviyesindeki logları filtrele
-        if log.level == "error" {
-            // Eğer 'timestamp' alanı yoksa, şu anki UTC zamanını ekle
-            if let serde_json::Value::Object(ref mut map) = log.extra {
-                if !map.contains_key("timestamp") {
-                    let now = chrono::Utc::now().to_rfc3339();
-                    map.insert("timestamp".to_string(), serde_json::Value::String(now));
-                }
+        Some(Command::Purge(args)) => {
+            let store = storage::open(&config.storage.db_path, &config.storage)
+                .await
+                .expect("Veritabanına bağlanılamadı");
+            let before = match (args.before, args.older_than_days) {
+                (Some(before), _) => before,
+                (None, Some(days)) => (chrono::Utc::now() - chrono::Duration::days(days)).timestamp_micros(),
+                (None, None) => unreachable!("clap en az birini zorunlu kılar"),
+            };
+            let filter = storage::PurgeFilter {
+                before: Some(before),
+                level: args.level,
+                tenant: args.tenant,
+                ..Default::default()
+            };
+            let deleted = store
+                .purge(&filter)
+                .await
+                .expect("Silme işlemi başarısız");
+            info!("🧹 {} öncesine ait {} kayıt silindi.", timestamp::format_micros(before), deleted);
+            let event = storage::AuditEvent {
+                timestamp: chrono::Utc::now().timestamp_micros(),
+                actor: "cli".to_string(),
+                remote_addr: None,
+                action: "cli.purge".to_string(),
+                params: serde_json::json!({
+                    "before": timestamp::format_micros(before),
+                    "level": filter.level,
+                    "tenant": filter.tenant,
+                    "deleted": deleted,
+                }),
+            };
+            if let Err(e) = store.record_audit(&event).await {
+                error!("❌ Denetim kaydı yazılamadı: {}", e);
+            }
+        }
+        Some(Command::Query(args)) => {
+            let store = storage::open(&config.storage.db_path, &config.storage)
+                .await
+                .expect("Veritabanına bağlanılamadı");
+            let params = query::LogQuery {
+                level: args.level,
+                min_level: args.min_level,
+                service: args.service,
+                host: args.host,
+                env: args.env,
+                trace_id: args.trace_id.map(|id| id.to_ascii_lowercase()),
+                span_id: args.span_id.map(|id| id.to_ascii_lowercase()),
+                from: args.from,
+                to: args.to,
+                q: args.q,
+                filter: args.filter,
+                limit: Some(args.limit),
+                offset: Some(args.offset),
+                before_id: args.before_id,
+                after_id: args.after_id,
+                tenant: args.tenant,
+                oldest_first: false,
+            };
+            let logs = store.query(&params).await.expect("Sorgu başarısız");
+            for log in logs {
+                println!("{}", serde_json::to_string(&log).unwrap_or_default());
+            }
+        }
+        Some(Command::Import(args)) => {
+            let store = storage::open(&config.storage.db_path, &config.storage)
+                .await
+                .expect("Veritabanına bağlanılamadı");
+            for file in &args.files {
+                let imported = archive::import_file(&store, file, config.pipeline.batch_size)
+                    .await
+                    .expect("Arşiv yüklenemedi");
+                info!("📥 {}: {} kayıt geri yüklendi.", file, imported);
             }
-            debug!("✅ Hata logu tespit edildi, kanala gönderiliyor...");
-            // Kanala gönder.
-            // await kullanıyoruz ama bu işlem sadece belleğe yazdığı için nanosaniyeler sürer.
-            // Eğer kanal doluysa (10.000 log birikmişse) burada bekler (Backpressure).
-            let _ = state.tx.send(log).await;
-        } else {
-            debug!("ℹ️ Log seviyesi '{}', filtrelendi.", log.level);
+        }
+        Some(Command::Pipe(args)) => {
+            // HTTP sunucusu ve ek kaynaklar açılmaz; sadece boru hattı ve yazıcılar
+            let ingestor = Ingestor::builder()
+                .config(config)
+                .without_sources()
+                .build()
+                .await
+                .expect("Boru hattı başlatılamadı");
+            let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+            let summary = ingestor
+                .pipe(stdin, args.parser.as_deref(), args.tee)
+                .await
+                .expect("Standart girdi okunamadı");
+            info!(
+                "📥 {} satır okundu: {} log yazıldı, {} filtrelendi, {} atıldı.",
+                summary.lines, summary.accepted, summary.filtered, summary.dropped
+            );
         }
     }
-
-    // İstemciye "Kabul Edildi" (202 Accepted) dönüyoruz.
-    StatusCode::ACCEPTED
 }
+
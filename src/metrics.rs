@@ -0,0 +1,372 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use axum::{extract::State, http::header, response::IntoResponse};
+use serde::Serialize;
+
+use crate::AppState;
+
+// --- Metrikler (Prometheus) ---
+// Harici bir kütüphane kullanmadan atomik sayaçlarla tutulur.
+// GET /metrics Prometheus text formatında (0.0.4) döner.
+#[derive(Debug)]
+pub struct Metrics {
+    // HTTP ile gelen toplam log sayısı
+    pub received: AtomicU64,
+    // Filtreden geçip kanala gönderilenler
+    pub accepted: AtomicU64,
+    // Seviye filtresine takılanlar
+    pub filtered: AtomicU64,
+    // Uygulanamayan dönüştürme adımları (geçersiz JSON, çevrilemeyen değer)
+    pub transform_errors: AtomicU64,
+    // WASM kancasında hata (tuzak, yakıtın bitmesi, geçersiz çıktı); log değişmeden geçer
+    pub hook_errors: AtomicU64,
+    // Pencere içinde bekleyen aynı logla birleştirilen tekrarlar
+    pub deduplicated: AtomicU64,
+    // Örnekleme dışında kalıp atılan loglar
+    pub sampled_out: AtomicU64,
+    // İçinde kişisel veri maskelenen loglar
+    pub redacted: AtomicU64,
+    // GeoIP bilgisi eklenen loglar
+    pub geoip_enriched: AtomicU64,
+    // Zaman damgası çözülemeyip alınma zamanıyla değiştirilen loglar
+    pub invalid_timestamps: AtomicU64,
+    // '?verbose=true' isteklerinde çözülemeyip tek tek reddedilen kayıtlar
+    pub invalid_entries: AtomicU64,
+    // Alan boyutu sınırını aşıp kesilen / reddedilen loglar ([limits])
+    pub truncated: AtomicU64,
+    pub oversized: AtomicU64,
+    // Kanala gönderilemeyenler (kanal kapalı)
+    pub dropped: AtomicU64,
+    // Kanal dolu olduğu için atılan veya reddedilen loglar (backpressure = drop / reject)
+    pub overloaded: AtomicU64,
+    // Veritabanına yazılan satırlar
+    pub written: AtomicU64,
+    // Yönlendirme kurallarıyla ana veritabanı yerine başka depoya yazılanlar (written'a dahil)
+    pub routed: AtomicU64,
+    // Başarısız veritabanı yazmaları
+    pub write_errors: AtomicU64,
+    // Geçici hatadan sonra yapılan yeniden yazma denemeleri
+    pub write_retries: AtomicU64,
+    // İlk denemede yazılamayıp yeniden denemeyle yazılan partiler
+    pub write_recovered: AtomicU64,
+    // Yazılamayıp ölü mektup dosyasına eklenen loglar
+    pub dead_lettered: AtomicU64,
+    // Ölü mektup dosyasından yeniden yazılan loglar
+    pub dead_letter_retried: AtomicU64,
+    // Açılışta önceki çalışmanın WAL parçalarından yeniden yüklenen loglar
+    pub wal_replayed: AtomicU64,
+    // Başarılı / başarısız yapılandırma yeniden yüklemeleri (SIGHUP, POST /reload)
+    pub config_reloads: AtomicU64,
+    pub config_reload_errors: AtomicU64,
+    // Hız sınırı nedeniyle 429 ile reddedilen istekler
+    pub rate_limited: AtomicU64,
+    // Saklama süresi dolduğu için silinen satırlar
+    pub retention_purged: AtomicU64,
+    pub erased: AtomicU64,
+    // Boyut sınırı nedeniyle silinen en eski satırlar
+    pub retention_evicted: AtomicU64,
+    // Son ölçülen veritabanı boyutu (bayt; saklama görevi günceller)
+    pub db_size_bytes: AtomicU64,
+    // Silinmeden önce nesne deposuna arşivlenen satırlar
+    pub archived: AtomicU64,
+    // Parquet'e yazılan satırlar (uç ve zamanlanmış dışa aktarıcı)
+    pub exported: AtomicU64,
+    // Başarısız saklama (silme) çalıştırmaları
+    pub retention_errors: AtomicU64,
+    // Bağlı canlı akış (/tail, /tail/sse) izleyicileri
+    pub tail_clients: AtomicU64,
+    // Yetişemediği için kapatılan SSE izleyicileri
+    pub tail_dropped: AtomicU64,
+    // Tetiklenen uyarılar
+    pub alerts_fired: AtomicU64,
+    // Gönderilemeyen uyarı webhook'ları
+    pub alert_errors: AtomicU64,
+    // Yönlendiricilere (Loki, ...) gönderilen loglar
+    pub forwarded: AtomicU64,
+    // Geçici hatadan sonra yeniden denenen gönderimler
+    pub forward_retries: AtomicU64,
+    // Kuyruk dolu olduğu veya hedef reddettiği için gönderilemeyen loglar
+    pub forward_dropped: AtomicU64,
+    // Tek bir yazma işleminin süresi (saniye)
+    pub write_latency: Histogram,
+    // Bir yazma işleminde kaç satır yazıldığı
+    pub batch_size: Histogram,
+    // Kanal dolu olduğu için gönderimde beklenen süre (saniye; 'block' politikası ve onaylı kaynaklar)
+    pub send_wait: Histogram,
+    // Partideki en eski logun kanala girişinden yazılmasına kadar geçen süre (saniye)
+    pub write_lag: Histogram,
+    // Son yazılan partinin gecikmesi (milisaniye)
+    pub write_lag_last_ms: AtomicU64,
+    // Sunucunun kendi loglarından depoya alınanlar / hız sınırı veya dolu kuyruk nedeniyle atılanlar
+    pub self_logged: AtomicU64,
+    pub self_log_dropped: AtomicU64,
+    // Yeniden denemelere rağmen başarısız olan son yazma (GET /admin/status)
+    pub last_write_error: Mutex<Option<WriteFailure>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteFailure {
+    // RFC 3339, UTC
+    pub at: String,
+    pub rows: usize,
+    pub error: String,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            received: AtomicU64::new(0),
+            accepted: AtomicU64::new(0),
+            filtered: AtomicU64::new(0),
+            transform_errors: AtomicU64::new(0),
+            hook_errors: AtomicU64::new(0),
+            deduplicated: AtomicU64::new(0),
+            sampled_out: AtomicU64::new(0),
+            redacted: AtomicU64::new(0),
+            geoip_enriched: AtomicU64::new(0),
+            invalid_timestamps: AtomicU64::new(0),
+            invalid_entries: AtomicU64::new(0),
+            truncated: AtomicU64::new(0),
+            oversized: AtomicU64::new(0),
+            overloaded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            written: AtomicU64::new(0),
+            routed: AtomicU64::new(0),
+            write_errors: AtomicU64::new(0),
+            write_retries: AtomicU64::new(0),
+            write_recovered: AtomicU64::new(0),
+            dead_lettered: AtomicU64::new(0),
+            dead_letter_retried: AtomicU64::new(0),
+            wal_replayed: AtomicU64::new(0),
+            config_reloads: AtomicU64::new(0),
+            config_reload_errors: AtomicU64::new(0),
+            rate_limited: AtomicU64::new(0),
+            retention_purged: AtomicU64::new(0),
+            erased: AtomicU64::new(0),
+            retention_evicted: AtomicU64::new(0),
+            db_size_bytes: AtomicU64::new(0),
+            archived: AtomicU64::new(0),
+            exported: AtomicU64::new(0),
+            retention_errors: AtomicU64::new(0),
+            tail_clients: AtomicU64::new(0),
+            tail_dropped: AtomicU64::new(0),
+            alerts_fired: AtomicU64::new(0),
+            alert_errors: AtomicU64::new(0),
+            forwarded: AtomicU64::new(0),
+            forward_retries: AtomicU64::new(0),
+            forward_dropped: AtomicU64::new(0),
+            write_latency: Histogram::new(&[0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]),
+            batch_size: Histogram::new(&[1.0, 10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 5000.0]),
+            send_wait: Histogram::new(&[0.0001, 0.001, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0]),
+            write_lag: Histogram::new(&[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]),
+            write_lag_last_ms: AtomicU64::new(0),
+            self_logged: AtomicU64::new(0),
+            self_log_dropped: AtomicU64::new(0),
+            last_write_error: Mutex::new(None),
+        }
+    }
+}
+
+// Basit, kilitsiz histogram. Kova sayaçları kümülatif değildir;
+// kümülatif toplam render sırasında hesaplanır.
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<AtomicU64>,
+    count: AtomicU64,
+    // f64 toplam, bit olarak saklanır
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Histogram {
+            bounds,
+            counts: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        if let Some(i) = self.bounds.iter().position(|b| value <= *b) {
+            self.counts[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self.sum.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            Some((f64::from_bits(bits) + value).to_bits())
+        });
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let total = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, total);
+        let _ = writeln!(out, "{}_sum {}", name, f64::from_bits(self.sum.load(Ordering::Relaxed)));
+        let _ = writeln!(out, "{}_count {}", name, total);
+    }
+}
+
+impl Metrics {
+    // Prometheus text formatını üretir. Kanal doluluğu (toplam ve şerit başına)
+    // anlık olarak dışarıdan verilir.
+    pub fn render(
+        &self,
+        channel_depth: usize,
+        channel_capacity: usize,
+        stripe_depths: &[usize],
+        wal_pending: Option<usize>,
+    ) -> String {
+        let mut out = String::new();
+        counter(&mut out, "log_ingestor_received_total", "Alinan toplam log sayisi", &self.received);
+        counter(&mut out, "log_ingestor_accepted_total", "Filtreden gecip kanala gonderilen loglar", &self.accepted);
+        counter(&mut out, "log_ingestor_filtered_total", "Seviye filtresine takilan loglar", &self.filtered);
+        counter(
+            &mut out,
+            "log_ingestor_transform_errors_total",
+            "Uygulanamayan donusturme adimlari (gecersiz JSON, cevrilemeyen deger)",
+            &self.transform_errors,
+        );
+        counter(
+            &mut out,
+            "log_ingestor_hook_errors_total",
+            "Basarisiz WASM kancasi cagrilari (log degismeden gecer)",
+            &self.hook_errors,
+        );
+        counter(&mut out, "log_ingestor_deduplicated_total", "Ayni logla birlestirilen tekrarlar", &self.deduplicated);
+        counter(&mut out, "log_ingestor_sampled_out_total", "Ornekleme disinda kalip atilan loglar", &self.sampled_out);
+        counter(&mut out, "log_ingestor_redacted_total", "Kisisel verisi maskelenen loglar", &self.redacted);
+        counter(&mut out, "log_ingestor_geoip_enriched_total", "GeoIP bilgisi eklenen loglar", &self.geoip_enriched);
+        counter(
+            &mut out,
+            "log_ingestor_invalid_timestamps_total",
+            "Zaman damgasi cozulemeyen loglar",
+            &self.invalid_timestamps,
+        );
+        counter(&mut out, "log_ingestor_invalid_entries_total", "Cozulemeyip reddedilen kayitlar", &self.invalid_entries);
+        counter(&mut out, "log_ingestor_truncated_total", "Alan boyutu siniri nedeniyle kesilen loglar", &self.truncated);
+        counter(&mut out, "log_ingestor_oversized_total", "Alan boyutu siniri nedeniyle reddedilen loglar", &self.oversized);
+        counter(&mut out, "log_ingestor_dropped_total", "Kanala gonderilemeyen loglar", &self.dropped);
+        counter(
+            &mut out,
+            "log_ingestor_overloaded_total",
+            "Kanal dolu oldugu icin atilan veya reddedilen loglar",
+            &self.overloaded,
+        );
+        counter(&mut out, "log_ingestor_written_total", "Veritabanina yazilan satirlar", &self.written);
+        counter(&mut out, "log_ingestor_routed_total", "Yonlendirme kurallariyla ayri depoya yazilan satirlar", &self.routed);
+        counter(&mut out, "log_ingestor_write_errors_total", "Yeniden denemelere ragmen basarisiz veritabani yazmalari", &self.write_errors);
+        counter(&mut out, "log_ingestor_write_retries_total", "Gecici hatadan sonra yeniden yazma denemeleri", &self.write_retries);
+        counter(
+            &mut out,
+            "log_ingestor_write_recovered_total",
+            "Yeniden denemeyle yazilabilen partiler",
+            &self.write_recovered,
+        );
+        counter(&mut out, "log_ingestor_dead_lettered_total", "Olu mektup dosyasina eklenen loglar", &self.dead_lettered);
+        counter(
+            &mut out,
+            "log_ingestor_dead_letter_retried_total",
+            "Olu mektup dosyasindan yeniden yazilan loglar",
+            &self.dead_letter_retried,
+        );
+        counter(&mut out, "log_ingestor_wal_replayed_total", "Acilista WAL'dan yeniden yuklenen loglar", &self.wal_replayed);
+        counter(&mut out, "log_ingestor_config_reloads_total", "Basarili yapilandirma yeniden yuklemeleri", &self.config_reloads);
+        counter(
+            &mut out,
+            "log_ingestor_config_reload_errors_total",
+            "Gecersiz dosya nedeniyle yapilamayan yeniden yuklemeler",
+            &self.config_reload_errors,
+        );
+        counter(&mut out, "log_ingestor_rate_limited_total", "Hiz siniri nedeniyle reddedilen istekler", &self.rate_limited);
+        counter(&mut out, "log_ingestor_retention_purged_total", "Saklama suresi doldugu icin silinen satirlar", &self.retention_purged);
+        counter(&mut out, "log_ingestor_erased_total", "Silme talepleriyle (DELETE /logs) silinen satirlar", &self.erased);
+        counter(&mut out, "log_ingestor_retention_evicted_total", "Boyut siniri nedeniyle silinen satirlar", &self.retention_evicted);
+        counter(&mut out, "log_ingestor_archived_total", "Silinmeden once arsivlenen satirlar", &self.archived);
+        counter(&mut out, "log_ingestor_exported_total", "Disa aktarilan satirlar (Parquet, CSV, JSONL)", &self.exported);
+        counter(&mut out, "log_ingestor_retention_errors_total", "Basarisiz saklama silmeleri", &self.retention_errors);
+        counter(&mut out, "log_ingestor_tail_dropped_total", "Yetisemedigi icin kapatilan SSE izleyicileri", &self.tail_dropped);
+        counter(&mut out, "log_ingestor_alerts_fired_total", "Tetiklenen uyarilar", &self.alerts_fired);
+        counter(&mut out, "log_ingestor_alert_errors_total", "Gonderilemeyen uyari webhooklari", &self.alert_errors);
+        counter(&mut out, "log_ingestor_forwarded_total", "Yonlendiricilere gonderilen loglar", &self.forwarded);
+        counter(&mut out, "log_ingestor_forward_retries_total", "Yeniden denenen yonlendirici gonderimleri", &self.forward_retries);
+        counter(
+            &mut out,
+            "log_ingestor_forward_dropped_total",
+            "Kuyruk dolu oldugu veya hedef reddettigi icin gonderilemeyen loglar",
+            &self.forward_dropped,
+        );
+        gauge(&mut out, "log_ingestor_channel_depth", "Kanalda bekleyen log sayisi", channel_depth as u64);
+        gauge(&mut out, "log_ingestor_channel_capacity", "Kanal kapasitesi", channel_capacity as u64);
+        let _ = writeln!(out, "# HELP log_ingestor_stripe_depth Seritte (yazici kanalinda) bekleyen log sayisi");
+        let _ = writeln!(out, "# TYPE log_ingestor_stripe_depth gauge");
+        for (stripe, depth) in stripe_depths.iter().enumerate() {
+            let _ = writeln!(out, "log_ingestor_stripe_depth{{stripe=\"{}\"}} {}", stripe, depth);
+        }
+        if let Some(pending) = wal_pending {
+            gauge(&mut out, "log_ingestor_wal_pending", "WAL'da olup henuz yazilmamis loglar", pending as u64);
+        }
+        gauge(&mut out, "log_ingestor_db_size_bytes", "Son olculen veritabani boyutu", self.db_size_bytes.load(Ordering::Relaxed));
+        gauge(&mut out, "log_ingestor_tail_clients", "Bagli canli akis izleyicileri", self.tail_clients.load(Ordering::Relaxed));
+        self.write_latency.render(
+            &mut out,
+            "log_ingestor_write_duration_seconds",
+            "Veritabani yazma suresi",
+        );
+        self.batch_size.render(&mut out, "log_ingestor_batch_size", "Yazma basina satir sayisi");
+        self.send_wait.render(
+            &mut out,
+            "log_ingestor_send_wait_seconds",
+            "Kanal dolu oldugu icin gonderimde beklenen sure",
+        );
+        self.write_lag.render(
+            &mut out,
+            "log_ingestor_write_lag_seconds",
+            "Partideki en eski logun kanala girisinden yazilmasina kadar gecen sure",
+        );
+        gauge(
+            &mut out,
+            "log_ingestor_write_lag_last_milliseconds",
+            "Son yazilan partinin gecikmesi (ms)",
+            self.write_lag_last_ms.load(Ordering::Relaxed),
+        );
+        counter(&mut out, "log_ingestor_self_logged_total", "Isleme alinan kendi loglari", &self.self_logged);
+        counter(
+            &mut out,
+            "log_ingestor_self_log_dropped_total",
+            "Hiz siniri veya dolu kuyruk nedeniyle atilan kendi loglari",
+            &self.self_log_dropped,
+        );
+        out
+    }
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: &AtomicU64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value.load(Ordering::Relaxed));
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+// GET /metrics
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let capacity = state.tx.capacity();
+    let depth = state.tx.depth();
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(depth, capacity, &state.tx.depths(), state.tx.wal_pending()),
+    )
+}
@@ -0,0 +1,250 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use tracing::error;
+
+use crate::wal::{Wal, WalTicket};
+use crate::LogEntry;
+
+// Kanaldan yazıcıya giden öğe: log, (varsa) yazma onayı ve WAL kaydı
+pub struct Envelope {
+    pub log: LogEntry,
+    pub ack: Option<Ack>,
+    pub wal: Option<WalTicket>,
+    // Kanala girdiği an; yazıcı gecikmesi (kanal + tampon bekleme) buradan ölçülür
+    pub queued_at: Instant,
+}
+
+// --- Yazma Onayı ---
+// Bazı kaynaklar (örn. Kafka) ancak loglar veritabanına yazıldıktan sonra
+// ilerleyebilir. Bir gruptaki her log aynı Ack'i taşır; yazıcı her logu
+// sonuçlandırdıkça sayaç düşer, hepsi bitince kaynağa tek bir sonuç gider:
+// hepsi yazıldıysa true, biri bile yazılamadıysa false.
+#[derive(Clone)]
+pub struct Ack(Arc<AckGroup>);
+
+struct AckGroup {
+    pending: AtomicUsize,
+    failed: AtomicBool,
+    done: Mutex<Option<oneshot::Sender<bool>>>,
+}
+
+impl Ack {
+    // 'count' log için onay grubu açar. Tüm Ack kopyaları sonuçlanmadan
+    // düşürülürse (örn. yazıcı durdu) alıcı hata alır.
+    pub fn group(count: usize) -> (Ack, oneshot::Receiver<bool>) {
+        let (tx, rx) = oneshot::channel();
+        let group = AckGroup {
+            pending: AtomicUsize::new(count),
+            failed: AtomicBool::new(false),
+            done: Mutex::new(Some(tx)),
+        };
+        (Ack(Arc::new(group)), rx)
+    }
+
+    // Gruptaki bir logu sonuçlandırır.
+    pub fn complete(&self, ok: bool) {
+        if !ok {
+            self.0.failed.store(true, Ordering::Relaxed);
+        }
+        if self.0.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            if let Some(tx) = self.0.done.lock().unwrap().take() {
+                let _ = tx.send(!self.0.failed.load(Ordering::Relaxed));
+            }
+        }
+    }
+}
+
+// --- Şeritli (Striped) Kanallar ---
+// Her yazıcı görevinin kendi kanalı vardır. Handler'lar logları sırayla
+// (round-robin) kanallara dağıtır; böylece tek bir tüketiciye bağlı kalmadan
+// birden fazla yazıcı paralel çalışabilir. WAL açıksa log, kanalda yer
+// ayrıldıktan sonra ve kanala girmeden hemen önce diske eklenir.
+#[derive(Clone)]
+pub struct Dispatcher {
+    senders: Arc<Vec<mpsc::Sender<Envelope>>>,
+    next: Arc<AtomicUsize>,
+    wal: Option<Arc<Wal>>,
+    // Kanala girip yazıcıda henüz sonuçlanmamış loglar (kanaldakiler + yazıcı tamponları)
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Dispatcher {
+    // 'writers' adet kanal açar. Toplam kapasite 'capacity' kadardır,
+    // şeritlere eşit bölünür.
+    pub fn new(writers: usize, capacity: usize, wal: Option<Arc<Wal>>) -> (Self, Vec<mpsc::Receiver<Envelope>>) {
+        let writers = writers.max(1);
+        let per_stripe = capacity.div_ceil(writers).max(1);
+        let (senders, receivers) = (0..writers).map(|_| mpsc::channel(per_stripe)).unzip();
+        let dispatcher = Dispatcher {
+            senders: Arc::new(senders),
+            next: Arc::new(AtomicUsize::new(0)),
+            wal,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        };
+        (dispatcher, receivers)
+    }
+
+    // Logu sıradaki şeride gönderir. Şerit doluysa bekler (Backpressure).
+    // Kanal kapalıysa log geri döner.
+    pub async fn send(&self, log: LogEntry, ack: Option<Ack>) -> Result<(), LogEntry> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.senders.len();
+        let Ok(permit) = self.senders[i].reserve().await else {
+            return Err(log);
+        };
+        let wal = self.journal(&log, &ack);
+        // Sayaç kanala girmeden artırılır; yazıcı logu hemen alıp düşürebilir
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        permit.send(Envelope { log, ack, wal, queued_at: Instant::now() });
+        Ok(())
+    }
+
+    // Beklemeden gönderir: sıradaki şerit doluysa diğerleri denenir, hepsi
+    // doluysa Full, kanal kapalıysa Closed döner (log düşürülür).
+    pub fn try_send(&self, log: LogEntry, ack: Option<Ack>) -> Result<(), TrySendError<()>> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.senders.len() {
+            match self.senders[(start + offset) % self.senders.len()].try_reserve() {
+                Ok(permit) => {
+                    let wal = self.journal(&log, &ack);
+                    self.in_flight.fetch_add(1, Ordering::Relaxed);
+                    permit.send(Envelope { log, ack, wal, queued_at: Instant::now() });
+                    return Ok(());
+                }
+                Err(TrySendError::Full(())) => {}
+                Err(TrySendError::Closed(())) => return Err(TrySendError::Closed(())),
+            }
+        }
+        Err(TrySendError::Full(()))
+    }
+
+    // WAL açıksa logu diske ekler. Onay bekleyen loglar yazılmaz (kaynak yeniden gönderir);
+    // disk hatasında log yine de kanala girer (sadece bellekte).
+    fn journal(&self, log: &LogEntry, ack: &Option<Ack>) -> Option<WalTicket> {
+        let wal = self.wal.as_ref().filter(|_| ack.is_none())?;
+        match wal.append(log) {
+            Ok(ticket) => Some(ticket),
+            Err(e) => {
+                error!("❌ Log WAL'a yazılamadı: {}", e);
+                None
+            }
+        }
+    }
+
+    // WAL dosyasını diske zorlar (WAL kapalıysa etkisiz)
+    pub fn checkpoint_wal(&self) -> Result<(), String> {
+        self.wal.as_ref().map_or(Ok(()), |wal| wal.checkpoint())
+    }
+
+    // WAL'da olup henüz yazılmamış loglar (WAL kapalıysa None)
+    pub fn wal_pending(&self) -> Option<usize> {
+        self.wal.as_ref().map(|wal| wal.pending())
+    }
+
+    // Sonuçlanmamış log sayacı; yazıcılar partiyi bitirince düşer. Sayacı tutmak
+    // kanalı açık tutmaz (kapanışta Dispatcher düşürülürken izlemek için).
+    pub fn in_flight(&self) -> Arc<AtomicUsize> {
+        self.in_flight.clone()
+    }
+
+    // Tüm şeritlerdeki boş yer
+    pub fn free(&self) -> usize {
+        self.senders.iter().map(|s| s.capacity()).sum()
+    }
+
+    // Tüm şeritlerde bekleyen toplam log sayısı
+    pub fn depth(&self) -> usize {
+        self.senders.iter().map(|s| s.max_capacity() - s.capacity()).sum()
+    }
+
+    // Şerit başına bekleyen log sayısı (dengesiz dağılımı görmek için)
+    pub fn depths(&self) -> Vec<usize> {
+        self.senders.iter().map(|s| s.max_capacity() - s.capacity()).collect()
+    }
+
+    // Tüm şeritlerin toplam kapasitesi
+    pub fn capacity(&self) -> usize {
+        self.senders.iter().map(|s| s.max_capacity()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRODUCERS: usize = 8;
+    const PER_PRODUCER: usize = 2_000;
+
+    fn entry(i: usize) -> LogEntry {
+        serde_json::from_value(serde_json::json!({"level": "info", "message": format!("log {}", i)})).unwrap()
+    }
+
+    // Yazıcı gibi: partiyi alır, "yazar", sonra sayaçtan düşer. Sayaç gönderimden
+    // sonra artırılsaydı yazıcı ondan önce düşebilir ve sayaç sarardı.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn in_flight_never_wraps_under_concurrent_send_and_drain() {
+        let (tx, receivers) = Dispatcher::new(3, 8, None);
+        let in_flight = tx.in_flight();
+        let total = PRODUCERS * PER_PRODUCER;
+
+        let mut consumers = Vec::new();
+        for mut rx in receivers {
+            let in_flight = in_flight.clone();
+            consumers.push(tokio::spawn(async move {
+                let mut buffer = Vec::with_capacity(16);
+                let mut written = 0;
+                while rx.recv_many(&mut buffer, 16).await > 0 {
+                    let count = buffer.len();
+                    buffer.clear();
+                    let before = in_flight.fetch_sub(count, Ordering::Relaxed);
+                    assert!(before >= count, "sayaç {} iken {} düşülemez", before, count);
+                    written += count;
+                }
+                written
+            }));
+        }
+
+        let watcher = {
+            let in_flight = in_flight.clone();
+            tokio::spawn(async move {
+                for _ in 0..10_000 {
+                    assert!(in_flight.load(Ordering::Relaxed) <= total);
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let mut producers = Vec::new();
+        for p in 0..PRODUCERS {
+            let tx = tx.clone();
+            producers.push(tokio::spawn(async move {
+                for i in 0..PER_PRODUCER {
+                    let n = p * PER_PRODUCER + i;
+                    if i % 2 == 0 {
+                        tx.send(entry(n), None).await.unwrap();
+                        continue;
+                    }
+                    // try_send dolu kanalda logu düşürür; yenisiyle tekrar denenir
+                    while let Err(TrySendError::Full(())) = tx.try_send(entry(n), None) {
+                        tokio::task::yield_now().await;
+                    }
+                }
+            }));
+        }
+        for producer in producers {
+            producer.await.unwrap();
+        }
+        drop(tx);
+
+        let mut written = 0;
+        for consumer in consumers {
+            written += consumer.await.unwrap();
+        }
+        watcher.await.unwrap();
+        assert_eq!(written, total);
+        assert_eq!(in_flight.load(Ordering::Relaxed), 0);
+    }
+}
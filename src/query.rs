@@ -0,0 +1,220 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::{debug, error};
+
+use crate::auth::{self, Caller};
+use crate::AppState;
+
+// --- Sorgu Parametreleri ---
+// GET /logs?level=error&from=2024-01-01T00:00:00Z&to=...&q=timeout&limit=50&before_id=1234
+//   &filter=service="api" AND extra.user_id=123
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogQuery {
+    pub level: Option<String>,
+    // Bu seviye ve üstü ("error" -> error + fatal); severity sütunu üzerinden.
+    // Bilinmeyen seviye 400 döner.
+    #[serde(default, deserialize_with = "deserialize_min_level")]
+    pub min_level: Option<u8>,
+    // Zaman aralığı (dahil); timestamp modülündeki tüm biçimler kabul edilir,
+    // epoch mikro saniyeye çevrilir
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_micros")]
+    pub from: Option<i64>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_micros")]
+    pub to: Option<i64>,
+    // Öne çıkarılan sütunlarda tam eşleşme
+    pub service: Option<String>,
+    pub host: Option<String>,
+    pub env: Option<String>,
+    pub trace_id: Option<String>,
+    pub span_id: Option<String>,
+    // Aranan metin: SQLite'ta message/details üzerinde tam metin (FTS5), diğerlerinde mesajda alt dize
+    pub q: Option<String>,
+    // Filtre ifadesi: level=error AND extra.user_id=123 (bkz. expr)
+    #[serde(default, deserialize_with = "crate::expr::deserialize")]
+    pub filter: Option<crate::expr::Expr>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    // İmleçli sayfalama: sıralama (timestamp, id) üzerindendir. before_id verilen
+    // id'li kayıttan eski, after_id ondan yeni kayıtları döner (imleç kaydın kendisi hariç).
+    pub before_id: Option<i64>,
+    pub after_id: Option<i64>,
+    // Sadece bu kiracının logları. İstemciden okunmaz; HTTP'de istemcinin
+    // kiracısı, CLI'da --tenant (verilmezse tüm kiracılar).
+    #[serde(skip)]
+    pub tenant: Option<String>,
+    // İmleç olmadan da en eski kayıtlardan başlanır (reads_forward). İstemciden
+    // okunmaz; günlük bölümler imlecin gününden sonraki günleri böyle okur.
+    #[serde(skip)]
+    pub oldest_first: bool,
+}
+
+impl LogQuery {
+    // Öne çıkarılan sütun filtreleri (storage::PROMOTED_COLUMNS sırasıyla)
+    pub fn promoted(&self) -> [&Option<String>; 5] {
+        [&self.service, &self.host, &self.env, &self.trace_id, &self.span_id]
+    }
+
+    // Sadece after_id verilmişse imlece en yakın (en eski) kayıtlar artan sırayla
+    // okunur; arka uçlar sonucu yine en yeniden eskiye çevirir.
+    pub fn reads_forward(&self) -> bool {
+        (self.oldest_first || self.after_id.is_some()) && self.before_id.is_none()
+    }
+}
+
+// Veritabanından okunan ham satır
+#[derive(Debug, sqlx::FromRow, Deserialize)]
+pub struct LogRow {
+    id: i64,
+    tenant_id: String,
+    level: String,
+    message: String,
+    // UTC epoch mikro saniye
+    timestamp: i64,
+    details: Option<String>,
+    #[serde(default)]
+    remote_addr: Option<String>,
+    #[serde(default)]
+    user_agent: Option<String>,
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    received_at: Option<String>,
+    #[serde(default)]
+    severity: Option<i32>,
+    #[serde(default)]
+    service: Option<String>,
+    #[serde(default)]
+    host: Option<String>,
+    #[serde(default)]
+    env: Option<String>,
+    #[serde(default)]
+    trace_id: Option<String>,
+    #[serde(default)]
+    span_id: Option<String>,
+}
+
+// İstemciye dönen kayıt. 'details' JSON olarak geri açılır.
+// Arşiv dosyalarında da bu biçim kullanılır (bkz. archive).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoredLog {
+    pub id: i64,
+    pub tenant_id: String,
+    pub level: String,
+    pub message: String,
+    pub timestamp: String,
+    pub details: serde_json::Value,
+    // Kaynak bilgisi (bkz. Source). Eski kayıtlarda ve arşivlerde boştur.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_addr: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<String>,
+    // Seviyenin sayısal karşılığı (0 trace .. 5 fatal); tanınmayan seviyelerde boş
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<i32>,
+    // 'extra'dan kendi sütunlarına taşınan alanlar (details'te yer almazlar)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+}
+
+impl From<LogRow> for StoredLog {
+    fn from(row: LogRow) -> Self {
+        let details = row
+            .details
+            .as_deref()
+            .and_then(|d| serde_json::from_str(d).ok())
+            .unwrap_or(serde_json::Value::Null);
+        StoredLog {
+            id: row.id,
+            tenant_id: row.tenant_id,
+            level: row.level,
+            message: row.message,
+            timestamp: crate::timestamp::format_micros(row.timestamp),
+            details,
+            remote_addr: row.remote_addr,
+            user_agent: row.user_agent,
+            api_key: row.api_key,
+            received_at: row.received_at,
+            severity: row.severity,
+            service: row.service,
+            host: row.host,
+            env: row.env,
+            trace_id: row.trace_id,
+            span_id: row.span_id,
+        }
+    }
+}
+
+fn deserialize_min_level<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(level) => parse_min_level(&level).map(Some).map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+// "error", "WARNING" veya 0-5 arası sayı -> severity
+pub fn parse_min_level(level: &str) -> Result<u8, String> {
+    match level.parse::<u8>() {
+        Ok(n) if n <= 5 => Ok(n),
+        _ => crate::filter::severity(level).ok_or_else(|| format!("bilinmeyen seviye: {}", level)),
+    }
+}
+
+// --- Okuma Handler'ı ---
+// Filtrelere uyan, istemcinin kiracısına ait logları en yeniden eskiye doğru
+// (timestamp, sonra id) döner. Sonraki sayfa: son kaydın id'si before_id olarak.
+pub async fn logs_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(mut params): Query<LogQuery>,
+) -> Result<Json<Vec<StoredLog>>, StatusCode> {
+    params.tenant = Some(auth::tenant_of(caller.as_deref()));
+    // İz kimlikleri küçük harfle saklanır
+    for id in [&mut params.trace_id, &mut params.span_id].into_iter().flatten() {
+        id.make_ascii_lowercase();
+    }
+    debug!("🔍 Sorgu alındı: {:?}", params);
+
+    let logs = state.store.query(&params).await.map_err(|e| {
+        error!("❌ Sorgu başarısız: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(logs))
+}
+
+// --- İz Korelasyonu ---
+// GET /traces/{trace_id}/logs: bir izdeki (Jaeger / Tempo'daki trace) tüm log satırları.
+// /logs ile aynı filtreler ve kiracı sınırı geçerlidir; kimlik büyük/küçük harf duyarsızdır.
+pub async fn trace_logs_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(trace_id): Path<String>,
+    Query(mut params): Query<LogQuery>,
+) -> Result<Json<Vec<StoredLog>>, StatusCode> {
+    params.tenant = Some(auth::tenant_of(caller.as_deref()));
+    params.trace_id = Some(trace_id.to_ascii_lowercase());
+    debug!("🔍 İz sorgusu alındı: {:?}", params);
+
+    let logs = state.store.query(&params).await.map_err(|e| {
+        error!("❌ İz sorgusu başarısız: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(logs))
+}
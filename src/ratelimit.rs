@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use http_body_util::LengthLimitError;
+use tracing::debug;
+
+use crate::auth::Caller;
+use crate::config::RateLimitConfig;
+use crate::limits;
+use crate::AppState;
+
+// --- İstemci Başına Hız Sınırı ---
+// Her istemcinin (API anahtarı / sertifika CN'i, bkz. auth::Caller) iki token
+// kovası vardır: saniyedeki log sayısı ve saniyedeki bayt. Kovalar saniyede
+// 'rate' kadar dolar, en fazla 'rate * burst_secs' token tutar.
+//
+// Kova boş değilse istek kabul edilir ve maliyeti düşülür; büyük bir batch
+// kovayı eksiye düşürebilir (borç). Borç ödenene kadar gelen istekler 429 ve
+// borcun kapanacağı süreyi gösteren Retry-After ile reddedilir. Böylece
+// kapasiteden büyük tek bir batch sonsuza kadar reddedilmez, ortalama hız da korunur.
+#[derive(Debug)]
+pub struct RateLimiter {
+    entries: Option<Rate>,
+    bytes: Option<Rate>,
+    buckets: Mutex<HashMap<String, Buckets>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rate {
+    per_sec: f64,
+    capacity: f64,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    entries: Option<Bucket>,
+    bytes: Option<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    // Kovayı doldurur; borçtaysa borcun kapanmasına kalan süreyi döner.
+    fn take(&mut self, rate: Rate, amount: f64, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate.per_sec).min(rate.capacity);
+        self.updated = now;
+        if self.tokens <= 0.0 {
+            return Err(Duration::from_secs_f64(-self.tokens / rate.per_sec));
+        }
+        self.tokens -= amount;
+        Ok(())
+    }
+}
+
+impl RateLimiter {
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        let burst = config.burst_secs.max(0.001);
+        let rate = |per_sec: f64| {
+            (per_sec > 0.0).then(|| Rate {
+                per_sec,
+                capacity: (per_sec * burst).max(1.0),
+            })
+        };
+        RateLimiter {
+            entries: rate(config.entries_per_sec),
+            bytes: rate(config.bytes_per_sec),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.entries.is_some() || self.bytes.is_some()
+    }
+
+    // 'caller' için 'count' log düşer; sınır aşıldıysa beklenecek süreyi döner.
+    pub fn check_entries(&self, caller: &str, count: usize) -> Result<(), Duration> {
+        let Some(rate) = self.entries else {
+            return Ok(());
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(caller.to_string()).or_default();
+        let bucket = bucket.entries.get_or_insert_with(|| full(rate));
+        bucket.take(rate, count as f64, Instant::now())
+    }
+
+    // 'caller' için 'len' bayt düşer.
+    pub fn check_bytes(&self, caller: &str, len: usize) -> Result<(), Duration> {
+        let Some(rate) = self.bytes else {
+            return Ok(());
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(caller.to_string()).or_default();
+        let bucket = bucket.bytes.get_or_insert_with(|| full(rate));
+        bucket.take(rate, len as f64, Instant::now())
+    }
+}
+
+fn full(rate: Rate) -> Bucket {
+    Bucket {
+        tokens: rate.capacity,
+        updated: Instant::now(),
+    }
+}
+
+// 429 yanıtı; Retry-After tam saniyeye yukarı yuvarlanır.
+fn too_many_requests(state: &AppState, caller: &str, retry_after: Duration) -> Response {
+    state.metrics.rate_limited.fetch_add(1, Ordering::Relaxed);
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    debug!("🚦 Hız sınırı aşıldı: {} ({} sn sonra tekrar)", caller, secs);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, secs.to_string())],
+        "hız sınırı aşıldı",
+    )
+        .into_response()
+}
+
+// Handler'larda, gövde çözüldükten sonra log sayısı için çağrılır.
+// Sınır aşıldıysa döndürülecek 429 yanıtını verir.
+pub fn limit_entries(state: &AppState, caller: Option<&Caller>, count: usize) -> Option<Response> {
+    let caller = caller?;
+    let retry_after = state.limiter.check_entries(&caller.id, count).err()?;
+    Some(too_many_requests(state, &caller.id, retry_after))
+}
+
+// --- Bayt Sınırı Katmanı ---
+// auth::require_api_key'den sonra çalışır (Caller'a ihtiyaç duyar). Gövde
+// okunup (sıkıştırılmış haliyle) boyutu düşülür, ardından istek aynen devam eder.
+pub async fn limit_bytes(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(Extension(caller)) = caller.filter(|_| state.limiter.bytes.is_some()) else {
+        return next.run(req).await;
+    };
+
+    let (parts, body) = req.into_parts();
+    let body = match axum::body::to_bytes(body, state.max_body_bytes).await {
+        Ok(body) => body,
+        Err(e) => {
+            let e = e.into_inner();
+            if e.downcast_ref::<LengthLimitError>().is_some() {
+                return limits::payload_too_large(state.max_body_bytes);
+            }
+            return (StatusCode::BAD_REQUEST, format!("gövde okunamadı: {}", e)).into_response();
+        }
+    };
+    if let Err(retry_after) = state.limiter.check_bytes(&caller.id, body.len()) {
+        return too_many_requests(&state, &caller.id, retry_after);
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
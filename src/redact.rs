@@ -0,0 +1,241 @@
+use std::borrow::Cow;
+
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+use crate::config::RedactConfig;
+use crate::LogEntry;
+
+const EMAIL_PATTERN: &str = r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b";
+// 13-19 rakam, aralarında tek boşluk veya tire olabilir; Luhn kontrolünden geçenler maskelenir
+const CARD_PATTERN: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+
+// --- Kişisel Veri Maskeleme ---
+// Loglar kanala girmeden önce 'message' ve 'extra' içindeki tüm metin
+// değerlerinde (iç içe nesne ve diziler dahil) e-posta adresleri, kredi kartı
+// numaraları ve tanımlı desenler maskelenir. Böylece hassas veri veritabanına,
+// canlı akışa ya da uyarı örneklerine hiç ulaşmaz. Alan adları değişmez.
+#[derive(Debug, Default)]
+pub struct Redactor {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    replacement: String,
+    // Kredi kartı kuralı: eşleşme ancak Luhn kontrolünden geçerse maskelenir
+    luhn: bool,
+}
+
+impl Redactor {
+    pub fn from_config(config: &RedactConfig) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        if config.emails {
+            rules.push(Rule {
+                regex: Regex::new(EMAIL_PATTERN).expect("e-posta deseni geçerli"),
+                replacement: config.email_replacement.clone(),
+                luhn: false,
+            });
+        }
+        if config.credit_cards {
+            rules.push(Rule {
+                regex: Regex::new(CARD_PATTERN).expect("kart deseni geçerli"),
+                replacement: config.card_replacement.clone(),
+                luhn: true,
+            });
+        }
+        for pattern in &config.patterns {
+            let regex = Regex::new(&pattern.pattern)
+                .map_err(|e| format!("'{}' maskeleme deseni geçersiz: {}", pattern.name, e))?;
+            rules.push(Rule {
+                regex,
+                replacement: pattern.replacement.clone(),
+                luhn: false,
+            });
+        }
+        Ok(Redactor { rules })
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    // Logu yerinde maskeler; bir şey değiştiyse true döner.
+    pub fn apply(&self, log: &mut LogEntry) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let mut changed = self.redact_string(&mut log.message);
+        changed |= self.redact_value(&mut log.extra);
+        changed
+    }
+
+    fn redact_value(&self, value: &mut Value) -> bool {
+        match value {
+            Value::String(text) => self.redact_string(text),
+            Value::Array(items) => items.iter_mut().fold(false, |changed, item| self.redact_value(item) | changed),
+            Value::Object(map) => map.values_mut().fold(false, |changed, item| self.redact_value(item) | changed),
+            // Sayılara dokunulmaz: epoch zaman damgaları ve sayısal kimlikler Luhn
+            // kontrolünden rastgele geçebilir (yaklaşık onda biri)
+            _ => false,
+        }
+    }
+
+    fn redact_string(&self, text: &mut String) -> bool {
+        let mut changed = false;
+        for rule in &self.rules {
+            if let Cow::Owned(redacted) = rule.apply(text) {
+                if redacted != *text {
+                    *text = redacted;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl Rule {
+    fn apply<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if !self.luhn {
+            return self.regex.replace_all(text, self.replacement.as_str());
+        }
+        self.regex.replace_all(text, |caps: &Captures| {
+            let matched = &caps[0];
+            if luhn_valid(matched) {
+                self.replacement.clone()
+            } else {
+                matched.to_string()
+            }
+        })
+    }
+}
+
+// Kart numaralarının kontrol basamağı (boşluk ve tireler yok sayılır)
+fn luhn_valid(number: &str) -> bool {
+    let sum: u32 = number
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .rev()
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor() -> Redactor {
+        let config = RedactConfig {
+            emails: true,
+            credit_cards: true,
+            ..RedactConfig::default()
+        };
+        Redactor::from_config(&config).unwrap()
+    }
+
+    fn entry(message: &str, extra: Value) -> LogEntry {
+        let mut raw = json!({"level": "info", "message": message});
+        if let (Some(raw), Value::Object(extra)) = (raw.as_object_mut(), extra) {
+            raw.extend(extra);
+        }
+        serde_json::from_value(raw).unwrap()
+    }
+
+    fn redact(message: &str) -> String {
+        let mut log = entry(message, json!({}));
+        redactor().apply(&mut log);
+        log.message
+    }
+
+    #[test]
+    fn luhn_valid_cards_are_masked() {
+        assert_eq!(redact("kart 4111111111111111 reddedildi"), "kart [CARD] reddedildi");
+        assert_eq!(redact("kart 4111 1111 1111 1111"), "kart [CARD]");
+        assert_eq!(redact("kart 4111-1111-1111-1111"), "kart [CARD]");
+        // 13 ve 19 haneli sınırlar
+        assert_eq!(redact("4222222222222"), "[CARD]");
+        assert_eq!(redact("6011000000000000001"), "[CARD]");
+    }
+
+    #[test]
+    fn luhn_invalid_runs_are_left_intact() {
+        for text in [
+            "sipariş 4111111111111112",
+            "takip 1234-5678-9012-3456",
+            "kısa 411111111111",
+            "çift boşluk 4111  1111  1111  1111",
+        ] {
+            let mut log = entry(text, json!({}));
+            assert!(!redactor().apply(&mut log), "{}", text);
+            assert_eq!(log.message, text);
+        }
+    }
+
+    #[test]
+    fn emails_are_masked() {
+        assert_eq!(redact("ali.veli+test@Example.com.tr giriş yaptı"), "[EMAIL] giriş yaptı");
+        assert_eq!(redact("a@b.co ve c@d.io"), "[EMAIL] ve [EMAIL]");
+        assert_eq!(redact("kullanıcı@localhost"), "kullanıcı@localhost");
+    }
+
+    #[test]
+    fn nested_strings_are_masked_but_numbers_untouched() {
+        let mut log = entry(
+            "tamam",
+            json!({
+                "user": {"email": "ayse@example.com", "cards": ["4111111111111111", 4111111111111111u64]},
+                "timestamp_ms": 1700000000000u64,
+                "order_id": 4242424242424242u64,
+                "ok": true
+            }),
+        );
+        assert!(redactor().apply(&mut log));
+        assert_eq!(
+            log.extra,
+            json!({
+                "user": {"email": "[EMAIL]", "cards": ["[CARD]", 4111111111111111u64]},
+                "timestamp_ms": 1700000000000u64,
+                "order_id": 4242424242424242u64,
+                "ok": true
+            })
+        );
+    }
+
+    #[test]
+    fn custom_patterns_use_group_references() {
+        let config: RedactConfig = toml::from_str(
+            r#"
+            [[patterns]]
+            name = "token"
+            pattern = "token=(?P<prefix>\\w{4})\\w+"
+            replacement = "token=$prefix***"
+            "#,
+        )
+        .unwrap();
+        let redactor = Redactor::from_config(&config).unwrap();
+        let mut log = entry("istek token=abcd1234efgh", json!({}));
+        assert!(redactor.apply(&mut log));
+        assert_eq!(log.message, "istek token=abcd***");
+
+        let mut config = RedactConfig::default();
+        config.patterns = vec![crate::config::RedactPatternConfig {
+            name: "bozuk".to_string(),
+            pattern: "(".to_string(),
+            replacement: String::new(),
+        }];
+        assert!(Redactor::from_config(&config).unwrap_err().contains("'bozuk'"));
+    }
+}
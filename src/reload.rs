@@ -0,0 +1,142 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::Serialize;
+use tracing::{error, info};
+
+use crate::audit;
+use crate::auth::{self, ApiKeys, Caller};
+use crate::config::Config;
+use crate::filter::{FilterRules, LevelFilter};
+use crate::level::LevelNormalizer;
+use crate::redact::Redactor;
+use crate::sampling::Sampler;
+use crate::transform::Transforms;
+use crate::AppState;
+
+// --- Çalışırken Yeniden Yükleme ---
+// SIGHUP (Unix) veya POST /reload ile yapılandırma dosyası yeniden okunur ve
+// şu ayarlar sunucu durmadan değiştirilir:
+//   [[transform]], [filter] (seviyeler ve kurallar), [levels], [sampling], [redact], [auth] ([auth.read] dahil)
+// Önce hepsi derlenir; biri bile geçersizse hiçbiri değişmez. İşlenmekte olan
+// istekler eski ayarlarla biter, sonraki istekler yenilerini görür. Kanal,
+// yazıcılar ve dinlenen portlar etkilenmez (onlar için yeniden başlatma gerekir).
+
+// Yeniden yüklenebilen ayar: okuyan taraf o anki sürümün bir kopyasını (Arc) alır.
+pub struct Live<T>(RwLock<Arc<T>>);
+
+impl<T> Live<T> {
+    pub fn new(value: T) -> Self {
+        Live(RwLock::new(Arc::new(value)))
+    }
+
+    pub fn load(&self) -> Arc<T> {
+        self.0.read().unwrap().clone()
+    }
+
+    fn store(&self, value: T) {
+        *self.0.write().unwrap() = Arc::new(value);
+    }
+}
+
+// POST /reload yanıtı: yeni ayarların özeti
+#[derive(Debug, Serialize)]
+pub struct ReloadReport {
+    transforms: usize,
+    rules: usize,
+    redact_rules: usize,
+    api_keys: usize,
+    tokens: usize,
+    sampling: bool,
+}
+
+// Dosyayı okur, ayarları doğrular ve hepsini birlikte değiştirir.
+pub fn reload(state: &AppState) -> Result<ReloadReport, String> {
+    let result = build_and_swap(state);
+    let counter = match result {
+        Ok(_) => &state.metrics.config_reloads,
+        Err(_) => &state.metrics.config_reload_errors,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    result
+}
+
+// Denetim kaydının parametreleri: dosya ve sonuç (özet ya da hata)
+fn audit_params(state: &AppState, result: &Result<ReloadReport, String>) -> serde_json::Value {
+    let path = state.config_path.as_deref();
+    match result {
+        Ok(report) => serde_json::json!({ "path": path, "ok": true, "report": report }),
+        Err(e) => serde_json::json!({ "path": path, "ok": false, "error": e }),
+    }
+}
+
+fn build_and_swap(state: &AppState) -> Result<ReloadReport, String> {
+    let path = state.config_path.as_deref().ok_or("yapılandırma dosyası verilmedi")?;
+    let config = Config::load(path)?;
+    let transforms = Transforms::from_config(&config.transform)?;
+    let levels = LevelNormalizer::from_config(&config.levels)?;
+    let filter = LevelFilter::from_config(&config.filter);
+    let rules = FilterRules::from_config(&config.filter)?;
+    let sampler = Sampler::from_config(&config.sampling)?;
+    let redactor = Redactor::from_config(&config.redact)?;
+    let api_keys = ApiKeys::from_config(&config.auth)?;
+
+    let report = ReloadReport {
+        transforms: transforms.len(),
+        rules: rules.len(),
+        redact_rules: redactor.len(),
+        api_keys: api_keys.len(),
+        tokens: api_keys.tokens().len(),
+        sampling: sampler.enabled(),
+    };
+    state.transforms.store(transforms);
+    state.levels.store(levels);
+    state.filter.store(filter);
+    state.rules.store(rules);
+    state.sampler.store(sampler);
+    state.redactor.store(redactor);
+    state.api_keys.store(api_keys);
+    info!("🔄 Yapılandırma yeniden yüklendi: {:?}", report);
+    Ok(report)
+}
+
+// SIGHUP geldikçe yeniden yükler; hata olursa eski ayarlar geçerli kalır.
+// Sadece yapılandırma dosyası verildiyse başlatılır.
+#[cfg(unix)]
+pub async fn run(state: AppState) {
+    let path = state.config_path.clone().unwrap_or_default();
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!("❌ SIGHUP dinlenemedi: {}", e);
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        info!("🔄 SIGHUP alındı, yapılandırma yeniden yükleniyor: {}", path);
+        let result = reload(&state);
+        if let Err(e) = &result {
+            error!("❌ Yapılandırma yeniden yüklenemedi, eski ayarlar geçerli: {}", e);
+        }
+        audit::record(&state.store, "sighup", "config.reload", audit_params(&state, &result)).await;
+    }
+}
+
+// POST /reload: varsayılan kiracının anahtarlarıyla kullanılabilir (diğerleri 403).
+// Geçersiz dosyada 400 ve hata mesajı döner; eski ayarlar geçerli kalır.
+pub async fn reload_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<ReloadReport>, (StatusCode, String)> {
+    auth::require_default_tenant(caller.as_deref()).map_err(|status| (status, String::new()))?;
+    let result = reload(&state);
+    audit::record_caller(&state.store, caller.as_deref(), "config.reload", audit_params(&state, &result)).await;
+    result.map(Json).map_err(|e| {
+        error!("❌ Yapılandırma yeniden yüklenemedi, eski ayarlar geçerli: {}", e);
+        (StatusCode::BAD_REQUEST, e)
+    })
+}
@@ -0,0 +1,284 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{debug, error, info};
+
+use crate::archive::Archiver;
+use crate::audit;
+use crate::config::RetentionConfig;
+use crate::metrics::Metrics;
+use crate::storage::{PurgeFilter, SharedStorage};
+use crate::timestamp;
+
+// --- Saklama Politikası ---
+// Belirli bir yaştan eski loglar arka planda silinir. Seviyeye özel süreler
+// genel süreyi ezer; örn. max_age_days = 7 ve levels.error = 90 ise hatalar
+// 90 gün, diğer her şey 7 gün tutulur. Her çalıştırmada:
+//   - seviyeye özel süresi olan her seviye kendi süresiyle silinir,
+//   - geri kalan seviyeler genel süreyle silinir (seviyeye özel olanlar hariç).
+//
+// Boyut sınırı ('max_db_bytes') verilmişse ardından veritabanı boyutu ölçülür;
+// sınır aşılmışsa boyutu sınırın EVICT_TARGET oranına indirecek kadar en eski
+// satır parça parça silinir ve boşalan alan geri kazanılır (Storage::compact).
+//
+// [archive] yapılandırılmışsa silinecek kayıtlar önce parça parça kovaya
+// yüklenir; her parça ancak yüklendikten sonra silinir.
+//
+// Bir şey silen her çalıştırma ana veritabanının denetim tablosuna yazılır
+// ("retention.purge" / "retention.evict", actor "retention").
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    // Genel süre (gün); None = süresiz
+    default_days: Option<u64>,
+    // (seviye, gün); gün 0 ise o seviye hiç silinmez
+    levels: Vec<(String, u64)>,
+    // Veritabanı boyut sınırı (bayt); None = sınırsız
+    max_bytes: Option<u64>,
+    // Tek DELETE ile silinecek en fazla satır
+    chunk_rows: u64,
+}
+
+// Sınır aşılınca boyut sınırın bu oranına indirilir; böylece her çalıştırmada
+// birkaç satır silmek yerine yeni loglar için pay bırakılır.
+const EVICT_TARGET: f64 = 0.9;
+
+impl RetentionPolicy {
+    pub fn from_config(config: &RetentionConfig) -> Self {
+        let mut levels: Vec<(String, u64)> = config
+            .levels
+            .iter()
+            .map(|(level, days)| (level.trim().to_lowercase(), *days))
+            .filter(|(level, _)| !level.is_empty())
+            .collect();
+        levels.sort();
+        RetentionPolicy {
+            default_days: (config.max_age_days > 0).then_some(config.max_age_days),
+            levels,
+            max_bytes: (config.max_db_bytes > 0).then_some(config.max_db_bytes),
+            chunk_rows: config.evict_chunk_rows.max(1),
+        }
+    }
+
+    // Silinecek bir şey var mı?
+    pub fn enabled(&self) -> bool {
+        self.default_days.is_some() || self.levels.iter().any(|(_, days)| *days > 0) || self.max_bytes.is_some()
+    }
+
+    // 'now' anına göre çalıştırılacak silme filtreleri.
+    pub fn filters(&self, now: DateTime<Utc>) -> Vec<PurgeFilter> {
+        let cutoff = |days: u64| (now - chrono::Duration::days(days as i64)).timestamp_micros();
+        let mut filters: Vec<PurgeFilter> = self
+            .levels
+            .iter()
+            .filter(|(_, days)| *days > 0)
+            .map(|(level, days)| PurgeFilter {
+                before: Some(cutoff(*days)),
+                level: Some(level.clone()),
+                ..Default::default()
+            })
+            .collect();
+        if let Some(days) = self.default_days {
+            filters.push(PurgeFilter {
+                before: Some(cutoff(days)),
+                except_levels: self.levels.iter().map(|(level, _)| level.clone()).collect(),
+                ..Default::default()
+            });
+        }
+        filters
+    }
+}
+
+// Süresi dolan kayıtları siler ve silinen toplam satır sayısını döner. Bir şey
+// silindiyse filtreler ve silinen sayılar 'audit' deposuna yazılır.
+pub async fn purge_expired(
+    store: &SharedStorage,
+    policy: &RetentionPolicy,
+    archiver: Option<&Archiver>,
+    metrics: &Metrics,
+    audit: &SharedStorage,
+    route: Option<&str>,
+) -> Result<u64, String> {
+    let mut total = 0;
+    let mut purged = Vec::new();
+    for filter in policy.filters(Utc::now()) {
+        let deleted = match archiver {
+            Some(archiver) => archive_and_purge(store, archiver, &filter, None, metrics).await?,
+            None => store.purge(&filter).await.map_err(|e| e.to_string())?,
+        };
+        debug!("🧹 Saklama: {:?} -> {} kayıt silindi", filter, deleted);
+        if deleted > 0 {
+            purged.push(serde_json::json!({
+                "before": filter.before.map(timestamp::format_micros),
+                "level": filter.level,
+                "except_levels": filter.except_levels,
+                "deleted": deleted,
+            }));
+        }
+        total += deleted;
+    }
+    if total > 0 {
+        let params = serde_json::json!({ "route": route, "deleted": total, "filters": purged });
+        audit::record(audit, "retention", "retention.purge", params).await;
+    }
+    Ok(total)
+}
+
+// Filtreye uyan kayıtları (en fazla 'limit' tanesini) id sırasıyla parça parça
+// arşivler ve siler. Yükleme başarısız olursa kalan kayıtlara dokunulmaz.
+async fn archive_and_purge(
+    store: &SharedStorage,
+    archiver: &Archiver,
+    filter: &PurgeFilter,
+    limit: Option<u64>,
+    metrics: &Metrics,
+) -> Result<u64, String> {
+    let mut deleted = 0;
+    let mut after_id = 0;
+    loop {
+        let want = match limit {
+            Some(limit) => limit.saturating_sub(deleted).min(archiver.chunk_rows() as u64) as i64,
+            None => archiver.chunk_rows(),
+        };
+        if want == 0 {
+            break;
+        }
+        let rows = store
+            .select_purge(filter, after_id, want)
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(last_id) = rows.last().map(|row| row.id) else {
+            break;
+        };
+        archiver.upload(&rows).await?;
+        metrics.archived.fetch_add(rows.len() as u64, Ordering::Relaxed);
+
+        let archived = PurgeFilter {
+            max_id: Some(last_id),
+            ..filter.clone()
+        };
+        deleted += store.purge(&archived).await.map_err(|e| e.to_string())?;
+        after_id = last_id;
+        if (rows.len() as i64) < want {
+            break;
+        }
+    }
+    Ok(deleted)
+}
+
+// Boyut sınırı aşılmışsa en eski kayıtları siler; silinen satır sayısını döner.
+// Sınır her depoya ayrı uygulanır; db_size_bytes yalnızca ana veritabanını ('route' None) gösterir.
+pub async fn enforce_size(
+    store: &SharedStorage,
+    policy: &RetentionPolicy,
+    archiver: Option<&Archiver>,
+    metrics: &Metrics,
+    audit: &SharedStorage,
+    route: Option<&str>,
+) -> Result<u64, String> {
+    let Some(max_bytes) = policy.max_bytes else {
+        return Ok(0);
+    };
+    let usage = store.usage().await.map_err(|e| e.to_string())?;
+    if route.is_none() {
+        metrics.db_size_bytes.store(usage.bytes, Ordering::Relaxed);
+    }
+    if usage.bytes <= max_bytes || usage.rows == 0 {
+        return Ok(0);
+    }
+
+    // Satırların ortalama aynı yeri kapladığı varsayılır; tahmin eksik kalırsa
+    // bir sonraki çalıştırma kalanını siler.
+    let excess = 1.0 - (max_bytes as f64 * EVICT_TARGET) / usage.bytes as f64;
+    let mut remaining = (usage.rows as f64 * excess).ceil() as u64;
+    info!(
+        "💾 Veritabanı boyutu sınırı aştı{} ({} > {} bayt), en eski {} kayıt silinecek.",
+        describe(route),
+        usage.bytes,
+        max_bytes,
+        remaining
+    );
+
+    let mut evicted = 0;
+    while remaining > 0 {
+        let chunk = remaining.min(policy.chunk_rows);
+        let deleted = match archiver {
+            // Boş filtre + id sırası = en eski kayıtlar
+            Some(archiver) => archive_and_purge(store, archiver, &PurgeFilter::default(), Some(chunk), metrics).await?,
+            None => store.evict_oldest(chunk).await.map_err(|e| e.to_string())?,
+        };
+        if deleted == 0 {
+            break;
+        }
+        metrics.retention_evicted.fetch_add(deleted, Ordering::Relaxed);
+        evicted += deleted;
+        remaining = remaining.saturating_sub(deleted);
+        // Parçalar arasında yazıcılara nefes aldır
+        tokio::task::yield_now().await;
+    }
+
+    if evicted > 0 {
+        let params = serde_json::json!({
+            "route": route,
+            "max_db_bytes": max_bytes,
+            "db_bytes": usage.bytes,
+            "deleted": evicted,
+        });
+        audit::record(audit, "retention", "retention.evict", params).await;
+    }
+
+    store.compact().await.map_err(|e| e.to_string())?;
+    if let Ok(usage) = store.usage().await {
+        if route.is_none() {
+            metrics.db_size_bytes.store(usage.bytes, Ordering::Relaxed);
+        }
+        debug!("💾 Silme sonrası veritabanı boyutu{}: {} bayt", describe(route), usage.bytes);
+    }
+    Ok(evicted)
+}
+
+// Arka plan görevi: açılışta ve sonra her 'interval'da bir çalışır. 'route',
+// yönlendirme kuralının ayrı deposu için çalışan görevlerde kuralın adıdır;
+// 'audit' her zaman ana veritabanıdır.
+pub async fn run(
+    store: SharedStorage,
+    audit: SharedStorage,
+    metrics: Arc<Metrics>,
+    policy: RetentionPolicy,
+    archiver: Option<Archiver>,
+    interval: Duration,
+    route: Option<String>,
+) {
+    let route = route.as_deref();
+    let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match purge_expired(&store, &policy, archiver.as_ref(), &metrics, &audit, route).await {
+            Ok(deleted) => {
+                metrics.retention_purged.fetch_add(deleted, Ordering::Relaxed);
+                if deleted > 0 {
+                    info!("🧹 Saklama süresi dolan {} kayıt silindi{}.", deleted, describe(route));
+                }
+            }
+            Err(e) => {
+                metrics.retention_errors.fetch_add(1, Ordering::Relaxed);
+                error!("❌ Saklama silmesi başarısız{}: {}", describe(route), e);
+            }
+        }
+        match enforce_size(&store, &policy, archiver.as_ref(), &metrics, &audit, route).await {
+            Ok(0) => {}
+            Ok(evicted) => info!("💾 Boyut sınırı için en eski {} kayıt silindi{}.", evicted, describe(route)),
+            Err(e) => {
+                metrics.retention_errors.fetch_add(1, Ordering::Relaxed);
+                error!("❌ Boyut sınırı uygulanamadı{}: {}", describe(route), e);
+            }
+        }
+    }
+}
+
+// Log mesajlarında yönlendirme deposunun adı (ana veritabanı için boş)
+fn describe(route: Option<&str>) -> String {
+    route.map(|name| format!(" ('{}' deposu)", name)).unwrap_or_default()
+}
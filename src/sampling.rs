@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::config::SamplingConfig;
+use crate::LogEntry;
+
+// --- Örnekleme ---
+// Çok hacimli seviyelerin sadece bir kısmı saklanır, örn. error'ların hepsi,
+// debug'ların %1'i. Her log bağımsız olarak, seviyesinin oranıyla tutulur.
+// Tutulan loglara oran 'extra.sample_rate' olarak yazılır; böylece sayımlar
+// sonradan 1 / sample_rate ile ağırlıklandırılabilir. Oranı 1 olan (tamamı
+// saklanan) loglara alan eklenmez.
+#[derive(Debug, Default)]
+pub struct Sampler {
+    default_rate: f64,
+    // küçük harfli seviye -> oran
+    levels: HashMap<String, f64>,
+}
+
+impl Sampler {
+    pub fn from_config(config: &SamplingConfig) -> Result<Self, String> {
+        let check = |name: &str, rate: f64| {
+            if (0.0..=1.0).contains(&rate) {
+                Ok(rate)
+            } else {
+                Err(format!("sampling oranı 0 ile 1 arasında olmalı ({} = {})", name, rate))
+            }
+        };
+        let default_rate = check("default_rate", config.default_rate)?;
+        let levels = config
+            .levels
+            .iter()
+            .map(|(level, &rate)| Ok((level.to_ascii_lowercase(), check(level, rate)?)))
+            .collect::<Result<HashMap<_, _>, String>>()?;
+        Ok(Sampler { default_rate, levels })
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.default_rate < 1.0 || self.levels.values().any(|&rate| rate < 1.0)
+    }
+
+    fn rate(&self, level: &str) -> f64 {
+        self.levels
+            .get(&level.to_ascii_lowercase())
+            .copied()
+            .unwrap_or(self.default_rate)
+    }
+
+    // Log tutulacaksa true döner ve (oran 1'den küçükse) oranı loga yazar.
+    pub fn sample(&self, log: &mut LogEntry) -> bool {
+        let rate = self.rate(&log.level);
+        if rate >= 1.0 {
+            return true;
+        }
+        if rate <= 0.0 || rand::random::<f64>() >= rate {
+            return false;
+        }
+        if let Value::Object(map) = &mut log.extra {
+            map.insert("sample_rate".to_string(), Value::from(rate));
+        }
+        true
+    }
+}
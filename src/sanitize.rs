@@ -0,0 +1,207 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::LogEntry;
+
+// --- Gövde Temizleme ---
+// Uygulamaların logladığı ikili veriler (yarım kesilmiş UTF-8, terminal renk
+// kodları, NUL baytları) tüm isteği 400 ile düşürmesin diye gövde çözülmeden önce
+// temizlenir:
+//   - geçersiz UTF-8 baytları U+FFFD (�) olur
+//   - metin içindeki kontrol karakterleri görünür "\u001b" yazısına çevrilir;
+//     JSON metinlerindeki ham satır sonu ve sekmeler ise \n, \r, \t kaçışı olur
+// Geçerli UTF-8 olup hiç kontrol karakteri içermeyen gövdeler kopyalanmadan döner.
+
+// JSON gövdesi: yalnızca tırnak içindeki metinlere dokunulur, JSON'un kendi
+// kaçışları (\", \\, \u0000) olduğu gibi kalır.
+pub fn json(body: &[u8]) -> Cow<'_, str> {
+    let text = String::from_utf8_lossy(body);
+    if !text.chars().any(char::is_control) {
+        return text;
+    }
+
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut in_string = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if !in_string {
+            in_string = c == '"';
+            out.push(c);
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = false;
+                out.push(c);
+            }
+            '\\' => {
+                out.push(c);
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // JSON içinde ters bölü de kaçışlanır: değer "\u001b" yazısı olur
+            c if c.is_control() => {
+                let _ = write!(out, "\\\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+// Düz metin gövdesi (satır başına bir log): satır sonu ve sekmeler korunur.
+pub fn text(body: &[u8]) -> Cow<'_, str> {
+    let text = String::from_utf8_lossy(body);
+    if !text.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')) {
+        return text;
+    }
+
+    let mut out = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        if c.is_control() && !matches!(c, '\n' | '\r' | '\t') {
+            let _ = write!(out, "\\u{:04x}", c as u32);
+        } else {
+            out.push(c);
+        }
+    }
+    Cow::Owned(out)
+}
+
+// --- Çözülmüş Loglar ---
+// Gövde temizliği JSON'un kendi kaçışlarına dokunmaz; "\u0000" çözülünce gerçek
+// bir NUL olur (msgpack, GELF, OTLP gibi kaynaklarda da doğrudan gelebilir).
+// PostgreSQL metin sütunları NUL kabul etmez, C tabanlı araçlar metni orada keser.
+// Bu yüzden her kaynağın logunda level, message ve details'teki metinler
+// (alan adları dahil) NUL yerine görünür "\u0000" yazısını taşır.
+pub fn scrub_nul(log: &mut LogEntry) {
+    scrub_text(&mut log.level);
+    scrub_text(&mut log.message);
+    scrub_value(&mut log.extra);
+}
+
+fn scrub_text(text: &mut String) {
+    if text.contains('\0') {
+        *text = text.replace('\0', "\\u0000");
+    }
+}
+
+fn scrub_value(value: &mut Value) {
+    match value {
+        Value::String(text) => scrub_text(text),
+        Value::Array(items) => items.iter_mut().for_each(scrub_value),
+        Value::Object(map) => {
+            if map.keys().any(|key| key.contains('\0')) {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(mut key, value)| {
+                        scrub_text(&mut key);
+                        (key, value)
+                    })
+                    .collect();
+            }
+            map.values_mut().for_each(scrub_value);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(body: &[u8]) -> Value {
+        serde_json::from_str(&json(body)).unwrap()
+    }
+
+    #[test]
+    fn clean_bodies_are_borrowed() {
+        assert!(matches!(json(br#"[{"message":"tamam"}]"#), Cow::Borrowed(_)));
+        assert!(matches!(text(b"satir 1\nsatir 2\tsekme\r\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn invalid_utf8_becomes_replacement_character() {
+        let body = b"[{\"message\":\"yar\xc4\"}]";
+        assert_eq!(parse(body), json!([{"message": "yar\u{fffd}"}]));
+        assert_eq!(text(b"ok \xff\xfe son"), "ok \u{fffd}\u{fffd} son");
+    }
+
+    #[test]
+    fn raw_escape_in_string_becomes_visible_text() {
+        let body = b"[{\"message\":\"\x1b[31mred\x1b[0m\"}]";
+        assert_eq!(parse(body), json!([{"message": "\\u001b[31mred\\u001b[0m"}]));
+        assert_eq!(text(b"\x1b[31mred\n"), "\\u001b[31mred\n");
+    }
+
+    #[test]
+    fn raw_newline_and_tab_in_string_are_escaped() {
+        let body = b"[{\"message\":\"line 1\nline 2\r\n\tat main\"}]";
+        assert_eq!(parse(body), json!([{"message": "line 1\nline 2\r\n\tat main"}]));
+    }
+
+    #[test]
+    fn json_escapes_are_left_alone() {
+        let body = br#"[{"message":"quote \" slash \\ nul \u0000","\u001b":1}]"#;
+        assert!(matches!(json(body), Cow::Borrowed(_)));
+        // Kaçışlı tırnaktan sonraki ham kontrol karakteri hâlâ metnin içindedir
+        let body = b"[{\"message\":\"a \\\" b\x07\"}]";
+        assert_eq!(parse(body), json!([{"message": "a \" b\\u0007"}]));
+    }
+
+    #[test]
+    fn trailing_backslash_does_not_panic() {
+        assert_eq!(json(b"[\"abc\\"), "[\"abc\\");
+        assert_eq!(json(b"[\"\x01\\"), "[\"\\\\u0001\\");
+        assert_eq!(text(b"abc\\"), "abc\\");
+    }
+
+    #[test]
+    fn control_characters_outside_strings_are_untouched() {
+        // Girintili JSON'daki boşluklar geçerli kalır
+        let body = b"[\n\t{\"message\": \"x\"}\r\n]";
+        assert_eq!(json(body), "[\n\t{\"message\": \"x\"}\r\n]");
+        assert_eq!(parse(body), json!([{"message": "x"}]));
+        // Metin dışındaki diğer kontrol karakterleri de değişmez; JSON hatası olarak kalır
+        let body = b"\x01[{\"message\":\"x\"}]";
+        assert_eq!(json(body), "\x01[{\"message\":\"x\"}]");
+    }
+
+    #[test]
+    fn text_keeps_line_breaks_and_escapes_other_controls() {
+        assert_eq!(text(b"a\x00b\x7fc\r\n"), "a\\u0000b\\u007fc\r\n");
+    }
+
+    #[test]
+    fn nul_is_scrubbed_from_decoded_fields() {
+        let mut log: LogEntry = serde_json::from_value(json!({
+            "level": "err\u{0}or",
+            "message": "a\u{0}b",
+            "user": {"name\u{0}": "x\u{0}", "tags": ["t\u{0}", 1, null]},
+            "count": 3
+        }))
+        .unwrap();
+        scrub_nul(&mut log);
+        assert_eq!(log.level, "err\\u0000or");
+        assert_eq!(log.message, "a\\u0000b");
+        assert_eq!(
+            log.extra,
+            json!({"user": {"name\\u0000": "x\\u0000", "tags": ["t\\u0000", 1, null]}, "count": 3})
+        );
+    }
+
+    #[test]
+    fn escaped_nul_in_json_body_is_scrubbed_after_decoding() {
+        let body = json(br#"[{"level":"info","message":"before\u0000after"}]"#);
+        let mut logs: Vec<LogEntry> = serde_json::from_str(&body).unwrap();
+        assert_eq!(logs[0].message, "before\0after");
+        scrub_nul(&mut logs[0]);
+        assert_eq!(logs[0].message, "before\\u0000after");
+    }
+}
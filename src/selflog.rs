@@ -0,0 +1,194 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::{Filtered, LevelFilter};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::{SelfLogConfig, SelfLogLevel};
+use crate::ingest;
+use crate::metrics::Metrics;
+use crate::{AppState, LogEntry};
+
+// Kendi loglarının bekleyebileceği kuyruk; dolarsa yenileri atılır
+const QUEUE: usize = 1024;
+
+// --- Kendi Logları (Self-Log) ---
+// Sunucunun uyarı ve hata logları kendi deposuna da yazılabilir. tracing
+// katmanı (SelfLogLayer) olayları kuyruğa bırakır, 'run' görevi bunları diğer
+// loglar gibi ingest::submit ile işler. Döngüye karşı:
+//   - bu görev içinde üretilen olaylar tekrar alınmaz (task-local işaret)
+//   - saniyede en fazla 'max_per_sec' kayıt alınır (yazma hatası -> hata logu ->
+//     yazma hatası zinciri hız sınırında durur)
+//   - kuyruk doluysa olay beklemeden atılır (log çağrısı asla bloklanmaz)
+// Katman her zaman kurulur; 'run' çalışmıyorsa (self_log.enabled = false) etkisizdir.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+
+tokio::task_local! {
+    static DRAINING: ();
+}
+
+struct Sink {
+    tx: mpsc::Sender<LogEntry>,
+    min_level: Level,
+    max_per_sec: u32,
+    // (saniye başlangıcı, o saniyede alınan kayıt)
+    window: Mutex<(Instant, u32)>,
+    metrics: Arc<Metrics>,
+}
+
+impl Sink {
+    fn admit(&self) -> bool {
+        let mut window = self.window.lock().unwrap();
+        if window.0.elapsed().as_secs() >= 1 {
+            *window = (Instant::now(), 0);
+        }
+        if window.1 >= self.max_per_sec {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+}
+
+// Gömülü kullanımda uygulama kendi abonesine eklemelidir:
+//   tracing_subscriber::registry().with(fmt::layer()).with(selflog::layer())
+// INFO'dan ayrıntılı olaylar katmana hiç ulaşmaz.
+pub fn layer<S>() -> Filtered<SelfLogLayer, LevelFilter, S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    SelfLogLayer.with_filter(LevelFilter::INFO)
+}
+
+pub struct SelfLogLayer;
+
+impl<S> Layer<S> for SelfLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if !ACTIVE.load(Ordering::Relaxed) || DRAINING.try_with(|_| ()).is_ok() {
+            return;
+        }
+        let Ok(sink) = SINK.try_read() else {
+            return;
+        };
+        let Some(sink) = sink.as_ref() else {
+            return;
+        };
+        let metadata = event.metadata();
+        if *metadata.level() > sink.min_level {
+            return;
+        }
+        if !sink.admit() {
+            sink.metrics.self_log_dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut extra = visitor.fields;
+        extra.insert("source".to_string(), Value::String("self".to_string()));
+        extra.insert("service".to_string(), Value::String("log-ingestor".to_string()));
+        extra.insert("target".to_string(), Value::String(metadata.target().to_string()));
+        if let Some(span) = ctx.event_span(event) {
+            extra.insert("span".to_string(), Value::String(span.name().to_string()));
+        }
+        let log = LogEntry {
+            level: metadata.level().as_str().to_ascii_lowercase(),
+            message: visitor.message,
+            extra: Value::Object(extra),
+            tenant_id: crate::default_tenant(),
+            source: crate::Source::default(),
+        };
+        if sink.tx.try_send(log).is_err() {
+            sink.metrics.self_log_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        match (field.name(), value) {
+            ("message", Value::String(message)) => self.message = message,
+            (name, value) => {
+                self.fields.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, Value::String(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::Bool(value));
+    }
+}
+
+// Görev durunca (kapanışta iptal edilir) katman tekrar etkisiz hale gelir
+struct Installed;
+
+impl Drop for Installed {
+    fn drop(&mut self) {
+        ACTIVE.store(false, Ordering::Relaxed);
+        *SINK.write().unwrap() = None;
+    }
+}
+
+// Kuyruktaki kendi loglarını işler. Aynı süreçte birden fazla Ingestor varsa
+// sonuncusu kayıtları alır.
+pub async fn run(config: SelfLogConfig, state: AppState) {
+    let (tx, mut rx) = mpsc::channel(QUEUE);
+    let min_level = match config.min_level {
+        SelfLogLevel::Info => Level::INFO,
+        SelfLogLevel::Warn => Level::WARN,
+        SelfLogLevel::Error => Level::ERROR,
+    };
+    *SINK.write().unwrap() = Some(Sink {
+        tx,
+        min_level,
+        max_per_sec: config.max_per_sec,
+        window: Mutex::new((Instant::now(), 0)),
+        metrics: state.metrics.clone(),
+    });
+    ACTIVE.store(true, Ordering::Relaxed);
+    let _installed = Installed;
+    tracing::info!("🪞 Kendi logları depoya alınıyor ({:?} ve üstü).", config.min_level);
+
+    while let Some(log) = rx.recv().await {
+        DRAINING.scope((), ingest::submit(&state, log)).await;
+        state.metrics.self_logged.fetch_add(1, Ordering::Relaxed);
+    }
+}
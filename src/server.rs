@@ -0,0 +1,945 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{any, delete, get, post},
+    Router,
+};
+use tokio::io::AsyncBufRead;
+use tokio::net::TcpListener;
+use tokio::task::{JoinHandle, JoinSet};
+use tower_http::decompression::RequestDecompressionLayer;
+use tracing::info;
+
+use crate::admin;
+use crate::cors;
+use crate::audit;
+use crate::alert::Alerts;
+use crate::archive::Archiver;
+use crate::auth::{self, ApiKeys};
+use crate::config::{ArchiveConfig, Config};
+use crate::deadletter::{self, DeadLetter};
+use crate::dedup::{self, Deduplicator};
+use crate::erasure::{self, ErasureAudit};
+use crate::filter::{FilterRules, LevelFilter};
+use crate::geoip::GeoIp;
+use crate::hook::Hook;
+use crate::jwt::{self, JwtVerifier};
+use crate::level::LevelNormalizer;
+use crate::metrics::{self, Metrics};
+use crate::pipeline::Dispatcher;
+use crate::ratelimit::{self, RateLimiter};
+use crate::redact::Redactor;
+use crate::reload::{self, Live};
+use crate::retention::{self, RetentionPolicy};
+use crate::sampling::Sampler;
+use crate::sinks::{self, Sinks};
+use crate::sources::stdin::PipeSummary;
+use crate::sources::{self, hec::HecAcks, raw::RawParsers};
+use crate::tail::{self, Tail};
+use crate::tokens::{self, TokenStore};
+use crate::transform::Transforms;
+use crate::wal::{self, Wal};
+use crate::writer::{RetryPolicy, Writer};
+#[cfg(unix)]
+use crate::{systemd, uds};
+use crate::storage::{self, Routes};
+use crate::{export, grafana, health, ingest, limits, query, selflog, stats, tls, ui, AppState};
+#[cfg(windows)]
+use crate::winservice;
+
+// --- Sunucu Modu ---
+// Kanalı, yazıcıları ve HTTP sunucusunu ayağa kaldırır. İkili (log-ingestor
+// serve) de gömülü kullanım da buradan geçer:
+//   let ingestor = Ingestor::builder().db_path("logs.db").build().await?;
+//   ingestor.serve(TcpListener::bind("127.0.0.1:3002").await?).await?;
+pub struct Ingestor {
+    config: Config,
+    router: Router,
+    // Kaynakların paylaştığı durum (pipe); kapanışta router ile birlikte düşürülür
+    state: AppState,
+    // Kapanışta durdurulan görevler: ek kaynaklar (syslog, GELF, ...) ve
+    // saklama / dışa aktarma / yeniden yükleme
+    source_tasks: Vec<JoinHandle<()>>,
+    background_tasks: Vec<JoinHandle<()>>,
+    dedup: Option<(Arc<Deduplicator>, JoinHandle<()>)>,
+    writer_tasks: JoinSet<()>,
+    in_flight: Arc<AtomicUsize>,
+    // Yönlendiricilerin gönderim görevleri; yazıcılardan sonra boşaltılır
+    sink_tasks: Vec<JoinHandle<()>>,
+}
+
+// Ayarlar verilmezse Config::default() kullanılır (ortam değişkenleri okunmaz;
+// onlar için Config::load ile yüklenip 'config' ile verilmelidir).
+#[derive(Default)]
+pub struct IngestorBuilder {
+    config: Config,
+    // Verilirse SIGHUP ve POST /reload bu dosyayı yeniden okur
+    config_path: Option<Arc<str>>,
+    // Her loga (kayıt sırasıyla) uygulanan kancalar
+    hooks: Vec<Arc<dyn Hook>>,
+    // Ek kaynaklar (syslog, GELF, dosya, ...) başlatılmaz (log-ingestor pipe)
+    without_sources: bool,
+}
+
+impl IngestorBuilder {
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config_path(mut self, path: impl Into<String>) -> Self {
+        self.config_path = Some(path.into().into());
+        self
+    }
+
+    // Her logu değiştirebilen veya atabilen bir kanca ekler (bkz. hook).
+    pub fn hook(mut self, hook: impl Hook + 'static) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    // Yapılandırmadaki ek kaynakları (syslog, GELF, dosya, Docker, ...) başlatmaz;
+    // loglar sadece HTTP uçlarından veya Ingestor::pipe ile gelir.
+    pub fn without_sources(mut self) -> Self {
+        self.without_sources = true;
+        self
+    }
+
+    // storage.db_path: SQLite dosyası, postgres:// veya clickhouse:// adresi
+    pub fn db_path(mut self, db_path: impl Into<String>) -> Self {
+        self.config.storage.db_path = db_path.into();
+        self
+    }
+
+    // Kanalı, yazıcıları, arka plan görevlerini ve ek kaynakları başlatır,
+    // HTTP uçlarını kurar. Dinlemeye serve / run ile başlanır.
+    pub async fn build(self) -> Result<Ingestor, String> {
+        let IngestorBuilder { config, config_path, hooks, without_sources } = self;
+
+        // --- 3. MPSC Kanalı Kurulumu ---
+        // tx: Transmitter (Gönderici), rx: Receiver (Alıcı)
+        // Her yazıcının kendi kanalı (şeridi) vardır; toplam kapasite yapılandırmadan gelir (varsayılan 10.000).
+        // WAL açıksa kanala giren loglar yazılana kadar diskte de tutulur.
+        let (wal, wal_leftovers) = match config.wal.dir.as_deref() {
+            Some(dir) => {
+                let (wal, leftovers) = Wal::open(dir, &config.wal)
+                    .map_err(|e| format!("WAL dizini açılamadı: {}", e))?;
+                info!("📒 WAL açık: {}", dir);
+                (Some(wal), leftovers)
+            }
+            None => (None, Vec::new()),
+        };
+        let (tx, receivers) = Dispatcher::new(config.pipeline.writers, config.pipeline.channel_capacity, wal);
+
+        // --- 4. Veritabanı Kurulumu (SQLite / PostgreSQL) ---
+        let store = storage::open(&config.storage.db_path, &config.storage)
+            .await
+            .map_err(|e| format!("Veritabanına bağlanılamadı: {}", e))?;
+        info!("🗄️ Depolama arka ucu: {}", store.name());
+        if config.storage.db_path == storage::MEMORY {
+            tracing::warn!("⚠️ Veritabanı bellekte tutuluyor; loglar kapanışta kaybolacak.");
+        }
+        let routes = Arc::new(
+            Routes::open(store.clone(), &config.storage)
+                .await
+                .map_err(|e| format!("Yönlendirme kuralları yüklenemedi: {}", e))?,
+        );
+        for route in routes.routes() {
+            info!("🔀 '{}' kuralına uyan loglar ayrı depoya yazılacak ({}).", route.name, route.store.name());
+        }
+
+        // --- 5. Arka Plan Veritabanı Yazıcıları (Consumer) ---
+        // Her görev (task) ana sunucudan bağımsız, ayrı bir thread gibi çalışır.
+        // Havuz klonlanabilir; aynı bağlantıları okuma API'si de kullanır.
+        let metrics = Arc::new(Metrics::default());
+        // [[wasm]] kancaları gömülü uygulamanın kancalarından sonra çalışır
+        #[cfg(feature = "wasm")]
+        let hooks = {
+            let mut hooks = hooks;
+            for module in &config.wasm {
+                let hook = crate::wasm::WasmHook::load(module, metrics.clone())
+                    .map_err(|e| format!("WASM kancası yüklenemedi: {}", e))?;
+                info!("🧩 WASM kancası yüklendi: {}", module.path);
+                hooks.push(Arc::new(hook));
+            }
+            hooks
+        };
+        #[cfg(not(feature = "wasm"))]
+        if !config.wasm.is_empty() {
+            tracing::warn!("⚠️ [[wasm]] tanımlı ama ikili 'wasm' özelliği olmadan derlenmiş; WASM kancaları kapalı.");
+        }
+        let dead_letter = DeadLetter::from_config(&config.dead_letter)
+            .map_err(|e| format!("Ölü mektup dosyası açılamadı: {}", e))?
+            .map(Arc::new);
+        if let Some(dead_letter) = &dead_letter {
+            info!("🪦 Yazılamayan partiler şuraya eklenecek: {}", dead_letter.path().display());
+        }
+        let erasure_audit = ErasureAudit::from_config(&config.erasure).map(Arc::new);
+        if let Some(audit) = &erasure_audit {
+            info!("🗑️ Silme talepleri şuraya kaydedilecek: {}", audit.path().display());
+        }
+        let (sinks, sink_tasks) =
+            Sinks::start(&config, metrics.clone()).map_err(|e| format!("Yönlendiriciler kurulamadı: {}", e))?;
+        let writer = Writer {
+            routes: routes.clone(),
+            metrics: metrics.clone(),
+            dead_letter: dead_letter.clone(),
+            sinks,
+            retry: RetryPolicy::from_config(&config.pipeline),
+            batch_size: config.pipeline.batch_size,
+            flush_interval: Duration::from_millis(config.pipeline.flush_interval_ms),
+            in_flight: tx.in_flight(),
+        };
+        let in_flight = tx.in_flight();
+        let mut writer_tasks = JoinSet::new();
+        for rx in receivers {
+            writer_tasks.spawn(writer.clone().run(rx));
+        }
+        info!("✍️ {} yazıcı görevi başlatıldı.", writer_tasks.len());
+
+        // Önceki çalışmada yazılamadan kalan loglar (çökme) kanala yeniden gönderilir.
+        if !wal_leftovers.is_empty() {
+            let replayed = wal::replay(wal_leftovers, &tx).await
+                .map_err(|e| format!("WAL yeniden yüklenemedi: {}", e))?;
+            info!("♻️ WAL'dan {} log yeniden yüklendi.", replayed);
+            metrics.wal_replayed.fetch_add(replayed, Ordering::Relaxed);
+        }
+
+        // --- Saklama Süresi ---
+        // Eski logları düzenli aralıklarla silen görev (politika boşsa başlatılmaz).
+        // Arşiv yapılandırılmışsa silinecek kayıtlar önce nesne deposuna yüklenir.
+        let retention = RetentionPolicy::from_config(&config.retention);
+        let archiver = config
+            .archive
+            .as_ref()
+            .map(Archiver::from_config)
+            .transpose()
+            .map_err(|e| format!("Arşiv yapılandırılamadı: {}", e))?;
+        // Yönlendirme kurallarının ayrı depoları aynı politikayla kendi görevlerinde
+        // temizlenir. Arşivleri kuralın adını taşıyan alt önekte tutulur; id'ler
+        // depolar arasında çakışabilir.
+        let mut route_retention_tasks = Vec::new();
+        if retention.enabled() {
+            for (name, target) in routes.targets() {
+                let archiver = config
+                    .archive
+                    .as_ref()
+                    .map(|archive| {
+                        Archiver::from_config(&ArchiveConfig {
+                            prefix: format!("{}route={}/", archive.prefix, name),
+                            ..archive.clone()
+                        })
+                    })
+                    .transpose()
+                    .map_err(|e| format!("Arşiv yapılandırılamadı: {}", e))?;
+                route_retention_tasks.push(tokio::spawn(retention::run(
+                    target.clone(),
+                    store.clone(),
+                    metrics.clone(),
+                    retention.clone(),
+                    archiver,
+                    Duration::from_secs(config.retention.interval_secs),
+                    Some(name.to_string()),
+                )));
+            }
+        }
+        let retention_task = retention.enabled().then(|| {
+            info!("🧹 Saklama politikası: {:?}", retention);
+            if let Some(archive) = &config.archive {
+                info!("📦 Silinen kayıtlar arşivlenecek: {}/{}", archive.endpoint, archive.bucket);
+            }
+            tokio::spawn(retention::run(
+                store.clone(),
+                store.clone(),
+                metrics.clone(),
+                retention,
+                archiver,
+                Duration::from_secs(config.retention.interval_secs),
+                None,
+            ))
+        });
+
+        // --- Zamanlanmış Parquet Dışa Aktarma ---
+        let export_task = config.export.dir.clone().map(|dir| {
+            info!("📤 Yeni kayıtlar düzenli olarak Parquet'e yazılacak: {}", dir);
+            tokio::spawn(export::run(
+                store.clone(),
+                metrics.clone(),
+                dir.into(),
+                Duration::from_secs(config.export.interval_secs),
+            ))
+        });
+
+        // --- 6. Sunucu Ayarları ---
+        let levels = LevelNormalizer::from_config(&config.levels)
+            .map_err(|e| format!("Seviye eşlemeleri geçersiz: {}", e))?;
+        let transforms = Transforms::from_config(&config.transform)
+            .map_err(|e| format!("Dönüştürme adımları yüklenemedi: {}", e))?;
+        if transforms.len() > 0 {
+            info!("🔧 {} dönüştürme adımı yüklendi.", transforms.len());
+        }
+        let filter = LevelFilter::from_config(&config.filter);
+        info!("🔎 Seviye filtresi: {:?}", filter);
+        let rules = FilterRules::from_config(&config.filter)
+            .map_err(|e| format!("Filtre kuralları yüklenemedi: {}", e))?;
+        if rules.len() > 0 {
+            info!("🔎 {} filtre kuralı yüklendi.", rules.len());
+        }
+        let sampler = Sampler::from_config(&config.sampling)
+            .map_err(|e| format!("Örnekleme ayarları geçersiz: {}", e))?;
+        if sampler.enabled() {
+            info!("🎲 Örnekleme: {:?}", config.sampling);
+        }
+        let dedup = Deduplicator::from_config(&config.dedup).map(Arc::new);
+        if let Some(dedup) = &dedup {
+            info!("🔁 Aynı loglar {} sn içinde tek satırda birleştirilecek.", dedup.window().as_secs());
+        }
+        let geoip = GeoIp::from_config(&config.geoip)
+            .map_err(|e| format!("GeoIP veritabanı açılamadı: {}", e))?
+            .map(Arc::new);
+        if let Some(geoip) = &geoip {
+            info!("🌍 GeoIP zenginleştirme açık: {}", geoip.describe());
+        }
+        let redactor = Redactor::from_config(&config.redact)
+            .map_err(|e| format!("Maskeleme desenleri yüklenemedi: {}", e))?;
+        if redactor.enabled() {
+            info!("🕶️ Kişisel veri maskeleme açık ({} kural).", redactor.len());
+        }
+        let raw = RawParsers::from_config(&config.raw)
+            .map_err(|e| format!("Düz metin ayrıştırıcıları yüklenemedi: {}", e))?;
+        if !config.files.paths.is_empty() && raw.get(config.files.parser.as_deref()).is_none() {
+            return Err(format!(
+                "files.parser '{}' tanımlı değil (tanımlı: {})",
+                config.files.parser.clone().unwrap_or_default(),
+                raw.names().join(", ")
+            ));
+        }
+        if config.docker.enabled && raw.get(config.docker.parser.as_deref()).is_none() {
+            return Err(format!(
+                "docker.parser '{}' tanımlı değil (tanımlı: {})",
+                config.docker.parser.clone().unwrap_or_default(),
+                raw.names().join(", ")
+            ));
+        }
+        let api_keys = ApiKeys::from_config(&config.auth)?;
+        let jwt = JwtVerifier::from_config(&config.auth.jwt)?.map(Arc::new);
+        let tokens = TokenStore::open(&config.auth.token_file)?.map(Arc::new);
+        if let Some(tokens) = tokens.as_ref().filter(|tokens| !tokens.is_empty()) {
+            info!("🔑 {} anahtar yüklendi: {}", tokens.len(), tokens.path().display());
+        }
+        if api_keys.enabled() {
+            info!("🔐 API anahtarı doğrulaması açık ({} anahtar / kullanıcı).", api_keys.len());
+        } else if jwt.is_none() && tokens.as_ref().is_none_or(|tokens| tokens.is_empty()) {
+            tracing::warn!("⚠️ API anahtarı tanımlı değil; yazma uçları herkese açık.");
+        }
+        if let Some(jwt) = &jwt {
+            match jwt.jwks_url() {
+                Some(url) => info!("🔐 JWT doğrulaması açık (JWKS: {}).", url),
+                None => info!("🔐 JWT doğrulaması açık."),
+            }
+        }
+        let limiter = RateLimiter::from_config(&config.rate_limit);
+        if limiter.enabled() {
+            info!("🚦 Hız sınırı: {:?}", config.rate_limit);
+        }
+        let alerts = Alerts::from_config(&config.alerts, metrics.clone())
+            .map_err(|e| format!("Uyarı kuralları yüklenemedi: {}", e))?;
+        if alerts.len() > 0 {
+            info!("🚨 {} uyarı kuralı yüklendi.", alerts.len());
+        }
+        let state = AppState {
+            tx,
+            filter: Arc::new(Live::new(filter)),
+            levels: Arc::new(Live::new(levels)),
+            transforms: Arc::new(Live::new(transforms)),
+            hooks: hooks.into(),
+            rules: Arc::new(Live::new(rules)),
+            sampler: Arc::new(Live::new(sampler)),
+            dedup: dedup.clone(),
+            geoip,
+            redactor: Arc::new(Live::new(redactor)),
+            store,
+            routes,
+            metrics,
+            started_at: chrono::Utc::now(),
+            pause: Arc::default(),
+            drain_timeout_secs: config.pipeline.shutdown_timeout_secs,
+            ready_max_fill: config.pipeline.ready_max_fill,
+            backpressure: config.pipeline.backpressure,
+            retry_after_secs: config.pipeline.retry_after_secs,
+            raw: Arc::new(raw),
+            hec: Arc::new(HecAcks::from_config(&config.hec)),
+            otlp: Arc::new(config.otlp.clone()),
+            api_keys: Arc::new(Live::new(api_keys)),
+            jwt: jwt.clone(),
+            tokens,
+            limiter: Arc::new(limiter),
+            max_body_bytes: config.server.max_body_bytes,
+            field_limits: limits::FieldLimits::from_config(&config.limits),
+            export_max_rows: config.export.max_rows,
+            tail: Tail::new(config.tail.buffer, config.tail.sse_buffer),
+            alerts: Arc::new(alerts),
+            dead_letter,
+            erasure_audit,
+            config_path,
+        };
+
+        // SIGHUP ile filtre, örnekleme, maskeleme ve anahtar ayarları yeniden yüklenir
+        #[cfg(unix)]
+        let reload_task = state.config_path.is_some().then(|| tokio::spawn(reload::run(state.clone())));
+        #[cfg(not(unix))]
+        let reload_task = None;
+
+        // JWKS adresi verildiyse açık anahtarlar düzenli olarak yenilenir
+        let jwks_task = jwt.map(|jwt| tokio::spawn(jwt::run(jwt)));
+
+        // Penceresi dolan birleştirilmiş logları kanala gönderen görev
+        let dedup_task = dedup
+            .clone()
+            .map(|dedup| {
+                tokio::spawn(dedup::run(
+                    dedup,
+                    state.tx.clone(),
+                    state.metrics.clone(),
+                    state.tail.clone(),
+                    state.alerts.clone(),
+                ))
+            });
+
+        // --- 7. Ek Kaynaklar ---
+        // Sunucu kapanırken durdurulurlar; böylece kanalların gönderici uçları
+        // düşer ve yazıcılar kalan logları yazıp çıkar.
+        let source_tasks = if without_sources { Vec::new() } else { start_sources(&config, &state) };
+
+        // Yazma uçları: API anahtarı ister (anahtar tanımlıysa), sonra gövde boyutu
+        // ve istemci başına bayt sınırı uygulanır. route_layer'lar dıştan içe ters
+        // sırada çalışır.
+        let ingest_routes = Router::new()
+            // Content-Encoding: gzip / zstd gövdeler handler'dan önce açılır;
+            // desteklenmeyen kodlamalar 415 ile reddedilir.
+            .route(
+                "/ingest",
+                post(ingest::ingest_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route(
+                "/ingest/raw",
+                post(sources::raw::raw_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route("/gelf", post(sources::gelf::gelf_handler))
+            .route("/logplex", post(sources::heroku::logplex_handler))
+            .route(
+                "/loki/api/v1/push",
+                post(sources::loki::push_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route("/v1/logs", post(sources::otlp::http_handler))
+            // Elasticsearch bulk API uyumluluğu (Filebeat, Logstash çıktıları)
+            .route("/", get(sources::elasticsearch::info_handler))
+            .route(
+                "/_bulk",
+                post(sources::elasticsearch::bulk_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route(
+                "/:index/_bulk",
+                post(sources::elasticsearch::bulk_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route("/_index_template/:name", any(sources::elasticsearch::setup_handler))
+            .route("/_template/:name", any(sources::elasticsearch::setup_handler))
+            .route("/_ilm/policy/:name", any(sources::elasticsearch::setup_handler))
+            .route("/_license", get(sources::elasticsearch::license_handler))
+            // Splunk HTTP Event Collector uyumluluğu
+            .route(
+                "/services/collector",
+                post(sources::hec::event_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route(
+                "/services/collector/event",
+                post(sources::hec::event_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route(
+                "/services/collector/event/1.0",
+                post(sources::hec::event_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route("/services/collector/ack", post(sources::hec::ack_handler))
+            // Datadog log girişi (Agent, Vector, Fluent Bit)
+            .route(
+                "/api/v2/logs",
+                post(sources::datadog::logs_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route_layer(DefaultBodyLimit::max(config.server.max_body_bytes))
+            .route_layer(middleware::from_fn_with_state(state.clone(), ratelimit::limit_bytes))
+            .route_layer(middleware::from_fn_with_state(state.clone(), limits::enforce_body_limit))
+            .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_api_key));
+
+        // Uç sunucuların zincirleme aktarımı: kayıtlar kiracısını taşıdığı için
+        // sadece 'relay' rollü kimlik bilgileri kabul edilir.
+        let relay_routes = Router::new()
+            .route(
+                "/ingest/relay",
+                post(ingest::relay_handler).layer(RequestDecompressionLayer::new()),
+            )
+            .route_layer(DefaultBodyLimit::max(config.server.max_body_bytes))
+            .route_layer(middleware::from_fn_with_state(state.clone(), ratelimit::limit_bytes))
+            .route_layer(middleware::from_fn_with_state(state.clone(), limits::enforce_body_limit))
+            .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_relay));
+
+        // Web arayüzü: gömülü statik dosyalar, korumasız (veriyi okuma uçları korur)
+        let ui_routes: Router<AppState> = if config.ui.enabled {
+            Router::new()
+                .route("/ui", get(ui::redirect_handler))
+                .route("/ui/", get(ui::asset_handler))
+                .route("/ui/:file", get(ui::asset_handler))
+        } else {
+            Router::new()
+        };
+
+        // Grafana JSON veri kaynağı; okuma uçları gibi korunur. Bağlantı testi
+        // adresin sonuna '/' ekleyerek de gelebilir.
+        let grafana_routes = Router::new()
+            .route("/grafana", get(grafana::test_handler))
+            .route("/grafana/", get(grafana::test_handler))
+            .route("/grafana/search", post(grafana::search_handler))
+            .route("/grafana/query", post(grafana::query_handler))
+            .route("/grafana/annotations", post(grafana::annotations_handler))
+            .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access));
+
+        // Sorgu ve canlı akış uçları. CORS açıksa katman doğrulamanın dışındadır:
+        // ön kontrol istekleri anahtarsız yanıtlanır.
+        let read_routes = Router::new()
+            .route(
+                "/logs",
+                get(query::logs_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/traces/:trace_id/logs",
+                get(query::trace_logs_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/stats/levels",
+                get(stats::levels_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/stats/top-messages",
+                get(stats::top_messages_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/export",
+                get(export::stream_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/export/parquet",
+                get(export::parquet_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/tail",
+                get(tail::ws_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            )
+            .route(
+                "/tail/sse",
+                get(tail::sse_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_read_access)),
+            );
+        let read_routes = match cors::layer(&config.cors)? {
+            Some(cors) => {
+                info!("🌐 CORS açık, izin verilen kaynaklar: {}", config.cors.allowed_origins.join(", "));
+                read_routes.layer(cors)
+            }
+            None => read_routes,
+        };
+
+        let app = Router::new()
+            .merge(ingest_routes)
+            .merge(relay_routes)
+            .merge(ui_routes)
+            .merge(grafana_routes)
+            // Silme talepleri okuma değil yönetim işidir; admin rolü ister. Okuma
+            // uçlarından önce eklenir: aynı yoldaki GET ile birleşince OPTIONS
+            // istekleri okuma uçlarının (CORS) yanıtına düşer.
+            .route(
+                "/logs",
+                delete(erasure::delete_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .merge(read_routes)
+            .route(
+                "/dead-letter",
+                get(deadletter::list_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/dead-letter/retry",
+                post(deadletter::retry_all_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/dead-letter/:id/retry",
+                post(deadletter::retry_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/reload",
+                post(reload::reload_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/status",
+                get(admin::status_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/pause",
+                post(admin::pause_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/resume",
+                post(admin::resume_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/drain",
+                post(admin::drain_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/tokens",
+                get(tokens::list_handler)
+                    .post(tokens::create_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/tokens/:name",
+                delete(tokens::revoke_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route(
+                "/admin/audit",
+                get(audit::list_handler)
+                    .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin)),
+            )
+            .route("/metrics", get(metrics::metrics_handler))
+            .route("/healthz", get(health::healthz_handler))
+            .route("/services/collector/health", get(sources::hec::health_handler))
+            .route("/readyz", get(health::readyz_handler))
+            .with_state(state.clone());
+
+        Ok(Ingestor {
+            config,
+            router: app,
+            state,
+            source_tasks,
+            background_tasks: [retention_task, export_task, reload_task, jwks_task]
+                .into_iter()
+                .flatten()
+                .chain(route_retention_tasks)
+                .collect(),
+            dedup: dedup.zip(dedup_task),
+            writer_tasks,
+            in_flight,
+            sink_tasks,
+        })
+    }
+}
+
+impl Ingestor {
+    pub fn builder() -> IngestorBuilder {
+        IngestorBuilder::default()
+    }
+
+    // Tüm uçlar; başka bir axum uygulamasına eklenebilir. İstemci adresinin
+    // kaydedilmesi için into_make_service_with_connect_info::<SocketAddr> ile sunulmalıdır.
+    // Kopyalar shutdown'dan önce düşürülmelidir (yoksa yazıcılar kapanış süresini bekler).
+    pub fn router(&self) -> Router {
+        self.router.clone()
+    }
+
+    // Verilen dinleyicide kapatma sinyali (CTRL+C, SIGTERM, ...) gelene kadar
+    // sunar, sonra kalan logları yazıp döner.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        self.serve_with_shutdown(listener, shutdown_signal()).await
+    }
+
+    // serve ile aynıdır; kapanış 'signal' tamamlanınca başlar (testler, gömülü kullanım).
+    pub async fn serve_with_shutdown(
+        self,
+        listener: TcpListener,
+        signal: impl Future<Output = ()> + Send + 'static,
+    ) -> std::io::Result<()> {
+        let app = self.router.clone().into_make_service_with_connect_info::<SocketAddr>();
+        let result = axum::serve(listener, app)
+            .with_graceful_shutdown(closing_tail(signal, self.state.tail.clone()))
+            .await;
+        self.shutdown().await;
+        result
+    }
+
+    // Satırları 'input'tan (log-ingestor pipe'ta standart girdi) okuyup boru
+    // hattına sokar; girdi bitince kalan logları yazıp özeti döner.
+    pub async fn pipe(
+        self,
+        input: impl AsyncBufRead + Unpin,
+        parser: Option<&str>,
+        tee: bool,
+    ) -> std::io::Result<PipeSummary> {
+        let result = sources::stdin::run(input, &self.state, parser, tee).await;
+        self.shutdown().await;
+        result
+    }
+
+    // Yapılandırmadaki adreste sunar: unix soketi, systemd soketi, HTTPS veya
+    // düz HTTP (log-ingestor serve).
+    pub async fn run(self) {
+        run_server(&self.config, self.router.clone(), self.state.tail.clone()).await;
+        self.shutdown().await;
+    }
+
+    // Düzenli kapanış: kaynaklar ve arka plan görevleri durdurulur, bekleyen
+    // birleştirmeler bırakılır ve yazıcıların kalan logları yazması beklenir.
+    // Sunucu durduktan sonra çağrılmalıdır (serve / run bunu kendisi yapar).
+    pub async fn shutdown(self) {
+        // Uçlar kanalın gönderici ucunu tutar; yazıcılar ancak hepsi düşünce biter
+        self.state.tail.close();
+        drop(self.router);
+        drop(self.state);
+
+        // Yeni log kabul etmeyi bırak
+        for task in self.source_tasks {
+            task.abort();
+            let _ = task.await;
+        }
+
+        for task in self.background_tasks {
+            task.abort();
+        }
+
+        // Birleştirme için bekletilen loglar kanala bırakılır
+        if let Some((dedup, task)) = self.dedup {
+            dedup.close();
+            let _ = task.await;
+        }
+
+        // Sunucu durduğunda, arka plandaki yazıcıların işini bitirmesini bekle.
+        // Süre dolarsa kalan loglar bırakılır (WAL açıksa açılışta yeniden yüklenir).
+        drain(self.writer_tasks, &self.in_flight, self.config.pipeline.shutdown_timeout_secs).await;
+        // Yazılan ama henüz yönlendirilmemiş partiler (Loki, ...)
+        sinks::drain(self.sink_tasks, self.config.pipeline.shutdown_timeout_secs).await;
+        #[cfg(windows)]
+        winservice::stopped();
+    }
+}
+
+// Yapılandırılan ek kaynakları (syslog, GELF, OTLP, Fluentd, dosya, Docker,
+// journald, Kafka) başlatır.
+fn start_sources(config: &Config, state: &AppState) -> Vec<JoinHandle<()>> {
+    let mut source_tasks = Vec::new();
+    if let Some(bind) = config.syslog.udp_bind.clone() {
+        source_tasks.push(tokio::spawn(sources::syslog::run_udp(bind, state.clone())));
+    }
+    if let Some(bind) = config.syslog.tcp_bind.clone() {
+        source_tasks.push(tokio::spawn(sources::syslog::run_tcp(bind, state.clone())));
+    }
+    if let Some(bind) = config.gelf.udp_bind.clone() {
+        source_tasks.push(tokio::spawn(sources::gelf::run_udp(bind, state.clone())));
+    }
+    if let Some(bind) = config.otlp.grpc_bind.clone() {
+        source_tasks.push(tokio::spawn(sources::otlp::run_grpc(bind, state.clone())));
+    }
+    if let Some(bind) = config.fluentd.forward_bind.clone() {
+        source_tasks.push(tokio::spawn(sources::fluentd::run_forward(bind, state.clone())));
+    }
+    if config.self_log.enabled {
+        source_tasks.push(tokio::spawn(selflog::run(config.self_log.clone(), state.clone())));
+    }
+    if !config.files.paths.is_empty() {
+        source_tasks.push(tokio::spawn(sources::file::run(config.files.clone(), state.clone())));
+    }
+    if config.docker.enabled {
+        #[cfg(unix)]
+        source_tasks.push(tokio::spawn(sources::docker::run(config.docker.clone(), state.clone())));
+        #[cfg(not(unix))]
+        tracing::warn!("⚠️ docker.enabled ayarlı ama Docker soketi sadece Unix'te destekleniyor; giriş kapalı.");
+    }
+    if config.journald.enabled {
+        #[cfg(target_os = "linux")]
+        source_tasks.push(tokio::spawn(sources::journald::run(
+            config.journald.clone(),
+            state.clone(),
+            config.pipeline.batch_size,
+            Duration::from_millis(config.pipeline.flush_interval_ms),
+        )));
+        #[cfg(not(target_os = "linux"))]
+        tracing::warn!("⚠️ journald.enabled ayarlı ama journald sadece Linux'ta desteklenir; giriş kapalı.");
+    }
+    if config.kafka.brokers.is_some() {
+        #[cfg(feature = "kafka")]
+        source_tasks.push(tokio::spawn(sources::kafka::run(
+            config.kafka.clone(),
+            state.clone(),
+            config.pipeline.batch_size,
+            Duration::from_millis(config.pipeline.flush_interval_ms),
+        )));
+        #[cfg(not(feature = "kafka"))]
+        tracing::warn!("⚠️ kafka.brokers ayarlı ama ikili 'kafka' özelliği olmadan derlenmiş; Kafka tüketicisi kapalı.");
+    }
+    source_tasks
+}
+
+// HTTP sunucusunu kapatma sinyali gelene kadar çalıştırır: unix soketi,
+// HTTPS veya düz HTTP (TCP; systemd soket aktivasyonu desteklenir).
+async fn run_server(config: &Config, app: Router, tail: Tail) {
+    let shutdown = closing_tail(shutdown_signal(), tail);
+    // Unix soketi verilmişse TCP portu hiç açılmaz
+    if let Some(path) = &config.server.unix_socket {
+        #[cfg(unix)]
+        {
+            if config.tls.is_some() {
+                tracing::warn!("⚠️ Unix soketinde TLS kullanılmaz; [tls] ayarları yok sayılıyor.");
+            }
+            let listener = uds::bind(path, config.server.unix_socket_mode).expect("Unix soketi açılamadı");
+            systemd::notify("READY=1");
+            info!("🚀 Log Ingestion Sunucusu {} unix soketinde çalışıyor...", path);
+            uds::serve(listener, path, app, shutdown).await;
+            return;
+        }
+        #[cfg(not(unix))]
+        panic!("Unix soketi bu platformda desteklenmiyor: {}", path);
+    }
+
+    // systemd soket aktivasyonuyla başlatıldıysa verilen soket dinlenir
+    #[cfg(unix)]
+    let activated = systemd::listener();
+    #[cfg(not(unix))]
+    let activated: Option<std::net::TcpListener> = None;
+    let listener = match activated {
+        Some(socket) => {
+            socket.set_nonblocking(true).expect("systemd soketi ayarlanamadı");
+            tokio::net::TcpListener::from_std(socket).expect("systemd soketi kullanılamadı")
+        }
+        None => {
+            let addr = format!("{}:{}", config.server.bind_address, config.server.port);
+            tokio::net::TcpListener::bind(&addr).await.unwrap()
+        }
+    };
+    let addr = listener.local_addr().expect("Dinleme adresi alınamadı");
+    #[cfg(unix)]
+    systemd::notify("READY=1");
+
+    // Graceful Shutdown ile sunucuyu başlat
+    match config.tls.clone() {
+        Some(tls) => {
+            info!("🚀 Log Ingestion Sunucusu {} adresinde çalışıyor (HTTPS)...", addr);
+            tls::serve(listener, app, tls, shutdown)
+                .await
+                .expect("HTTPS sunucusu başlatılamadı");
+        }
+        None => {
+            info!("🚀 Log Ingestion Sunucusu {} adresinde çalışıyor...", addr);
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(shutdown)
+                .await
+                .unwrap();
+        }
+    }
+}
+
+// Kapatma sinyali gelince canlı akış izleyicilerini de sonlandırır; açık
+// WebSocket / SSE bağlantıları düzenli kapanışı bekletmez.
+async fn closing_tail(signal: impl Future<Output = ()>, tail: Tail) {
+    signal.await;
+    tail.close();
+}
+
+// Yazıcıların kanalda ve tamponlarda kalan logları yazmasını en fazla
+// 'timeout_secs' saniye (0 = süresiz) bekler; ilerlemeyi düzenli olarak loglar.
+async fn drain(mut writer_tasks: JoinSet<()>, in_flight: &AtomicUsize, timeout_secs: u64) {
+    let pending = in_flight.load(Ordering::Relaxed);
+    if pending > 0 {
+        info!("⏳ {} log yazılmayı bekliyor (en fazla {} sn).", pending, timeout_secs);
+    }
+    let deadline = (timeout_secs > 0).then(|| tokio::time::Instant::now() + Duration::from_secs(timeout_secs));
+    let mut progress = tokio::time::interval(Duration::from_secs(5));
+    progress.tick().await;
+
+    loop {
+        let timer = async {
+            match deadline {
+                Some(at) => tokio::time::sleep_until(at).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            finished = writer_tasks.join_next() => {
+                if finished.is_none() {
+                    info!("✅ {} log yazıldı; tüm loglar diske yazıldı ve sunucu güvenle kapandı.", pending);
+                    return;
+                }
+            }
+            _ = progress.tick() => {
+                info!("⏳ Hâlâ {} log yazılmayı bekliyor...", in_flight.load(Ordering::Relaxed));
+            }
+            _ = timer => break,
+        }
+    }
+
+    writer_tasks.abort_all();
+    let abandoned = in_flight.load(Ordering::Relaxed);
+    tracing::warn!(
+        "⚠️ Kapanış süresi ({} sn) doldu: {} log yazıldı, {} log bırakıldı.",
+        timeout_secs,
+        pending.saturating_sub(abandoned),
+        abandoned
+    );
+}
+
+// Kapatma sinyallerini dinleyen yardımcı fonksiyon: CTRL+C (SIGINT), Unix'te
+// SIGTERM (Kubernetes, Docker, systemd) ile SIGQUIT, Windows'ta konsol olayları ve
+// hizmet durdurma isteği. Hepsi aynı düzenli kapanışı başlatır.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut term = signal(SignalKind::terminate()).expect("SIGTERM dinlenemedi");
+        let mut quit = signal(SignalKind::quit()).expect("SIGQUIT dinlenemedi");
+        tokio::select! {
+            _ = term.recv() => "SIGTERM",
+            _ = quit.recv() => "SIGQUIT",
+        }
+    };
+    // Windows: konsol penceresinin kapatılması, oturum kapanışı / sistem kapanışı
+    // ve (hizmet olarak çalışılıyorsa) SCM'in durdurma isteği
+    #[cfg(windows)]
+    let terminate = async {
+        use tokio::signal::windows::{ctrl_break, ctrl_close, ctrl_shutdown};
+        let mut ctrl_break = ctrl_break().expect("CTRL+BREAK dinlenemedi");
+        let mut close = ctrl_close().expect("CTRL_CLOSE dinlenemedi");
+        let mut shutdown = ctrl_shutdown().expect("CTRL_SHUTDOWN dinlenemedi");
+        tokio::select! {
+            _ = ctrl_break.recv() => "CTRL+BREAK",
+            _ = close.recv() => "CTRL_CLOSE",
+            _ = shutdown.recv() => "CTRL_SHUTDOWN",
+            _ = winservice::stop_requested() => "hizmet durdurma isteği",
+        }
+    };
+    #[cfg(not(any(unix, windows)))]
+    let terminate = std::future::pending::<&str>();
+
+    let name = tokio::select! {
+        _ = tokio::signal::ctrl_c() => "CTRL+C",
+        name = terminate => name,
+    };
+    info!("🛑 Kapatma sinyali alındı ({}). İstekler durduruluyor...", name);
+    #[cfg(unix)]
+    systemd::notify("STOPPING=1");
+}
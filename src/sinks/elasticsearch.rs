@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::format::{Item, StrftimeItems};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use url::Url;
+
+use super::{log_time, status_error, Sink, SinkError};
+use crate::config::ElasticsearchConfig;
+use crate::LogEntry;
+
+// --- Elasticsearch / OpenSearch ---
+// Partiler _bulk API'sine NDJSON olarak gönderilir. Her log bir belgedir:
+// logun JSON hali + '@timestamp' ve 'tenant_id'. Dizin adı logun zamanından
+// üretilir ("logs-%Y.%m.%d" -> "logs-2024.05.01"); günlük dizinler ve veri
+// akışları (data stream) için 'create' işlemi kullanılır.
+// Belge kimliği içerikten türetilir: yeniden denenen partide önceden yazılmış
+// belgeler 409 döner ve kopya oluşmaz. Bulk yanıtında 429 / 5xx alan belge
+// varsa parti yeniden denenir; eşleme (mapping) hataları gibi kalıcı
+// hatalar yeniden denenmez, sayılıp loglanır.
+pub struct Elasticsearch {
+    client: reqwest::Client,
+    url: Url,
+    index: String,
+    username: Option<String>,
+    password: Option<String>,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkResponse {
+    errors: bool,
+    #[serde(default)]
+    items: Vec<HashMap<String, BulkItem>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkItem {
+    status: u16,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+impl Elasticsearch {
+    pub fn from_config(config: &ElasticsearchConfig) -> Result<Self, String> {
+        let url = Url::parse(&format!("{}/_bulk", config.url.trim_end_matches('/')))
+            .map_err(|e| format!("Elasticsearch adresi geçersiz: {}", e))?;
+        if config.index.trim().is_empty() || StrftimeItems::new(&config.index).any(|item| item == Item::Error) {
+            return Err(format!("geçersiz Elasticsearch dizin adı: {}", config.index));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| format!("Elasticsearch istemcisi kurulamadı: {}", e))?;
+        Ok(Elasticsearch {
+            client,
+            url,
+            index: config.index.clone(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+            api_key: config.api_key.clone(),
+        })
+    }
+
+    // Her log için işlem satırı + belge satırı. Dizin adları küçük harfe çevrilir
+    // (Elasticsearch büyük harfli dizin adını reddeder).
+    fn body(&self, logs: &[LogEntry]) -> String {
+        let mut body = String::new();
+        for (position, log) in logs.iter().enumerate() {
+            let time = log_time(log);
+            let mut doc = serde_json::to_value(log).unwrap_or_default();
+            if let Value::Object(map) = &mut doc {
+                map.insert("@timestamp".to_string(), Value::String(time.to_rfc3339()));
+                map.insert("tenant_id".to_string(), Value::String(log.tenant_id.clone()));
+            }
+            let doc = doc.to_string();
+            let action = serde_json::json!({
+                "create": {
+                    "_index": time.format(&self.index).to_string().to_lowercase(),
+                    "_id": document_id(position, &doc),
+                }
+            });
+            let _ = writeln!(body, "{}", action);
+            let _ = writeln!(body, "{}", doc);
+        }
+        body
+    }
+}
+
+#[async_trait]
+impl Sink for Elasticsearch {
+    fn name(&self) -> &'static str {
+        "elasticsearch"
+    }
+
+    async fn send(&self, logs: &[LogEntry]) -> Result<u64, SinkError> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(self.body(logs));
+        if let Some(api_key) = &self.api_key {
+            request = request.header(reqwest::header::AUTHORIZATION, format!("ApiKey {}", api_key));
+        } else if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+        let response = request.send().await.map_err(|e| SinkError::Transient(e.to_string()))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(status_error(status, &text));
+        }
+        let bulk: BulkResponse = serde_json::from_str(&text)
+            .map_err(|e| SinkError::Transient(format!("bulk yanıtı okunamadı: {}", e)))?;
+        if !bulk.errors {
+            return Ok(0);
+        }
+
+        // 409: belge önceki denemede yazılmış
+        let (mut retry, mut rejected) = (0u64, 0u64);
+        let (mut retry_reason, mut reject_reason) = (None, None);
+        for item in bulk.items.iter().flat_map(HashMap::values) {
+            let (count, reason) = match item.status {
+                200..=299 | 409 => continue,
+                429 | 500.. => (&mut retry, &mut retry_reason),
+                _ => (&mut rejected, &mut reject_reason),
+            };
+            *count += 1;
+            if reason.is_none() {
+                *reason = item.error.as_ref().map(Value::to_string);
+            }
+        }
+        if retry > 0 {
+            let reason = retry_reason.unwrap_or_default();
+            return Err(SinkError::Transient(format!("{} belge yazılamadı: {}", retry, reason)));
+        }
+        if rejected > 0 {
+            let reason = reject_reason.unwrap_or_default();
+            warn!("⚠️ Elasticsearch {} belgeyi reddetti (yeniden denenmeyecek): {}", rejected, reason);
+        }
+        Ok(rejected)
+    }
+}
+
+// Partideki sırası ve içeriğinden türetilen kimlik (aynı parti yeniden
+// denendiğinde aynı kimlikler üretilir).
+fn document_id(position: usize, doc: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(position.to_le_bytes());
+    hasher.update(doc.as_bytes());
+    hex::encode(&hasher.finalize()[..20])
+}
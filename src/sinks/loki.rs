@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::json;
+use url::Url;
+
+use super::{field, log_time, status_error, Sink, SinkError};
+use crate::config::LokiConfig;
+use crate::LogEntry;
+
+// Hiç etiketi olmayan akışlara eklenen etiket (Loki etiketsiz akışı reddeder)
+const FALLBACK_LABEL: (&str, &str) = ("job", "log-ingestor");
+
+// --- Grafana Loki ---
+// Partiler push API'sine (POST /loki/api/v1/push) JSON olarak gönderilir.
+// Loglar etiketlerine göre akışlara (stream) ayrılır; etiketler 'labels'
+// eşlemesiyle log alanlarından ve 'static_labels'tan gelir. Satır, logun
+// /ingest'teki JSON hali olur (Grafana'da '| json' ile alanlara ayrılır).
+pub struct Loki {
+    client: reqwest::Client,
+    url: Url,
+    tenant_id: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    labels: Vec<(String, String)>,
+    static_labels: BTreeMap<String, String>,
+}
+
+impl Loki {
+    pub fn from_config(config: &LokiConfig) -> Result<Self, String> {
+        let url = Url::parse(&config.url).map_err(|e| format!("Loki adresi geçersiz: {}", e))?;
+        for label in config.labels.keys().chain(config.static_labels.keys()) {
+            if !valid_label(label) {
+                return Err(format!("geçersiz Loki etiketi: {} (harf, rakam ve '_')", label));
+            }
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| format!("Loki istemcisi kurulamadı: {}", e))?;
+        let mut labels: Vec<(String, String)> =
+            config.labels.iter().map(|(label, path)| (label.clone(), path.clone())).collect();
+        labels.sort();
+        Ok(Loki {
+            client,
+            url,
+            tenant_id: config.tenant_id.clone(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+            labels,
+            static_labels: config.static_labels.clone().into_iter().collect(),
+        })
+    }
+
+    // Logları etiket kümelerine göre akışlara ayırır; her akış zamana göre sıralanır.
+    fn streams(&self, logs: &[LogEntry]) -> serde_json::Value {
+        let mut streams: BTreeMap<BTreeMap<String, String>, Vec<(i64, String)>> = BTreeMap::new();
+        for log in logs {
+            let mut labels = self.static_labels.clone();
+            for (label, path) in &self.labels {
+                if let Some(value) = field(log, path) {
+                    labels.insert(label.clone(), value);
+                }
+            }
+            if labels.is_empty() {
+                labels.insert(FALLBACK_LABEL.0.to_string(), FALLBACK_LABEL.1.to_string());
+            }
+            let nanos = log_time(log).timestamp_nanos_opt().unwrap_or_default();
+            let line = serde_json::to_string(log).unwrap_or_default();
+            streams.entry(labels).or_default().push((nanos, line));
+        }
+        let streams: Vec<_> = streams
+            .into_iter()
+            .map(|(labels, mut values)| {
+                values.sort_by_key(|(nanos, _)| *nanos);
+                let values: Vec<_> = values.into_iter().map(|(nanos, line)| json!([nanos.to_string(), line])).collect();
+                json!({ "stream": labels, "values": values })
+            })
+            .collect();
+        json!({ "streams": streams })
+    }
+}
+
+#[async_trait]
+impl Sink for Loki {
+    fn name(&self) -> &'static str {
+        "loki"
+    }
+
+    async fn send(&self, logs: &[LogEntry]) -> Result<u64, SinkError> {
+        let mut request = self.client.post(self.url.clone()).json(&self.streams(logs));
+        if let Some(tenant_id) = &self.tenant_id {
+            request = request.header("X-Scope-OrgID", tenant_id);
+        }
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+        let response = request.send().await.map_err(|e| SinkError::Transient(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(0);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(status_error(status, &body))
+    }
+}
+
+// Prometheus / Loki etiket adı: [a-zA-Z_][a-zA-Z0-9_]*
+fn valid_label(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
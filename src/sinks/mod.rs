@@ -0,0 +1,211 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::timestamp;
+use crate::writer::RetryPolicy;
+use crate::LogEntry;
+
+pub mod elasticsearch;
+pub mod loki;
+pub mod upstream;
+
+// --- Yönlendiriciler (Sinks) ---
+// Veritabanına yazılan her partinin bir kopyası ek hedeflere de gönderilir
+// (Loki, Elasticsearch / OpenSearch, başka bir log-ingestor); böylece sunucu
+// bu sistemlerin önünde tampon görevi gören bir aktarıcı olarak çalışabilir.
+// Yerel yazma önce yapılır ve hedefleri hiç beklemez: her hedefin kendi
+// görevi ve kuyruğu vardır. Hedef çalışmıyorken partiler kuyrukta bekler ve
+// beklemeli olarak yeniden denenir; kuyruk dolarsa yeni partiler o hedef
+// için atılır. Hedefin reddettiği partiler (400 gibi) yeniden denenmez.
+
+// Yazıcıdan gelen parti; hedefler arasında kopyalanmadan paylaşılır.
+pub type Batch = Arc<Vec<LogEntry>>;
+
+#[derive(Debug)]
+pub enum SinkError {
+    // Bağlantı hatası, zaman aşımı, 429, 5xx: yeniden denenir
+    Transient(String),
+    // Hedef partiyi kabul etmedi: atılır
+    Rejected(String),
+}
+
+impl std::fmt::Display for SinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SinkError::Transient(e) | SinkError::Rejected(e) => f.write_str(e),
+        }
+    }
+}
+
+// HTTP yanıt durumunu sınıflandırır: 429 ve 5xx geçicidir, diğerleri ret.
+pub fn status_error(status: reqwest::StatusCode, body: &str) -> SinkError {
+    let message = format!("{}: {}", status, body.trim());
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        SinkError::Transient(message)
+    } else {
+        SinkError::Rejected(message)
+    }
+}
+
+#[async_trait]
+pub trait Sink: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+    // Başarılıysa hedefin tek tek reddettiği log sayısını döner (Elasticsearch
+    // bulk yanıtındaki eşleme hataları gibi; bunlar yeniden denenmez).
+    async fn send(&self, logs: &[LogEntry]) -> Result<u64, SinkError>;
+}
+
+// Yazıcıların elindeki uç: yazılan partileri hedeflerin kuyruklarına bırakır.
+// Tüm kopyaları düşünce kuyruklar kapanır, görevler kalanları gönderip biter.
+#[derive(Clone)]
+pub struct Sinks {
+    queues: Arc<Vec<(&'static str, mpsc::Sender<Batch>)>>,
+    metrics: Arc<Metrics>,
+}
+
+impl Sinks {
+    // Yapılandırılmış hedefleri kurar ve her biri için gönderim görevini başlatır.
+    pub fn start(config: &Config, metrics: Arc<Metrics>) -> Result<(Self, Vec<JoinHandle<()>>), String> {
+        let mut sinks: Vec<(Arc<dyn Sink>, usize, RetryPolicy)> = Vec::new();
+        if let Some(loki) = &config.loki {
+            let retry = RetryPolicy::new(loki.max_retries, loki.retry_backoff_ms, loki.retry_backoff_max_ms);
+            sinks.push((Arc::new(loki::Loki::from_config(loki)?), loki.queue_batches, retry));
+        }
+        if let Some(es) = &config.elasticsearch {
+            let retry = RetryPolicy::new(es.max_retries, es.retry_backoff_ms, es.retry_backoff_max_ms);
+            sinks.push((Arc::new(elasticsearch::Elasticsearch::from_config(es)?), es.queue_batches, retry));
+        }
+
+        let mut queues = Vec::new();
+        let mut tasks = Vec::new();
+        for (sink, capacity, retry) in sinks {
+            let (tx, rx) = mpsc::channel(capacity.max(1));
+            info!("📮 Yazılan loglar '{}' hedefine de gönderilecek.", sink.name());
+            queues.push((sink.name(), tx));
+            tasks.push(tokio::spawn(run(sink, rx, retry, metrics.clone())));
+        }
+        // Üst sunucu partileri bellekte değil diskte bekletir; kendi görevi vardır.
+        if let Some(config) = &config.upstream {
+            let upstream = upstream::Upstream::from_config(config)?;
+            let retry = RetryPolicy::new(config.max_retries, config.retry_backoff_ms, config.retry_backoff_max_ms);
+            let (tx, rx) = mpsc::channel(config.queue_batches.max(1));
+            info!("📮 Yazılan loglar üst sunucuya da gönderilecek: {}", config.url);
+            queues.push(("upstream", tx));
+            tasks.push(tokio::spawn(upstream::run(upstream, rx, retry, metrics.clone())));
+        }
+        Ok((Sinks { queues: Arc::new(queues), metrics }, tasks))
+    }
+
+    // Partiyi her hedefin kuyruğuna bırakır; dolu kuyruk beklenmez, parti o hedef için atılır.
+    pub fn offer(&self, logs: Vec<LogEntry>) {
+        if self.queues.is_empty() || logs.is_empty() {
+            return;
+        }
+        let batch: Batch = Arc::new(logs);
+        for (name, tx) in self.queues.iter() {
+            if tx.try_send(batch.clone()).is_err() {
+                warn!("⚠️ '{}' kuyruğu dolu, {} log bu hedefe gönderilmeyecek.", name, batch.len());
+                self.metrics.forward_dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+async fn run(sink: Arc<dyn Sink>, mut rx: mpsc::Receiver<Batch>, retry: RetryPolicy, metrics: Arc<Metrics>) {
+    while let Some(batch) = rx.recv().await {
+        let mut attempt = 0;
+        loop {
+            match sink.send(&batch).await {
+                Ok(rejected) => {
+                    debug!("📮 {} log '{}' hedefine gönderildi.", batch.len(), sink.name());
+                    metrics.forwarded.fetch_add(batch.len() as u64 - rejected, Ordering::Relaxed);
+                    metrics.forward_dropped.fetch_add(rejected, Ordering::Relaxed);
+                    break;
+                }
+                Err(SinkError::Transient(e)) if retry.attempts() == 0 || attempt < retry.attempts() => {
+                    let delay = retry.delay(attempt);
+                    attempt += 1;
+                    warn!(
+                        "⏳ {} log '{}' hedefine gönderilemedi, {} ms sonra yeniden denenecek ({}. deneme): {}",
+                        batch.len(),
+                        sink.name(),
+                        delay.as_millis(),
+                        attempt,
+                        e
+                    );
+                    metrics.forward_retries.fetch_add(1, Ordering::Relaxed);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    error!("❌ {} log '{}' hedefine gönderilemedi, atıldı: {}", batch.len(), sink.name(), e);
+                    metrics.forward_dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// Kapanışta kuyruklarda kalan partilerin gönderilmesini en fazla
+// 'timeout_secs' saniye (0 = süresiz) bekler; süre dolarsa kalanlar bırakılır.
+// Yazıcılar bittikten sonra çağrılmalıdır (kuyruklar ancak o zaman kapanır).
+pub async fn drain(tasks: Vec<JoinHandle<()>>, timeout_secs: u64) {
+    if tasks.is_empty() {
+        return;
+    }
+    let handles: Vec<_> = tasks.iter().map(JoinHandle::abort_handle).collect();
+    let all = async {
+        for task in tasks {
+            let _ = task.await;
+        }
+    };
+    let finished = match timeout_secs {
+        0 => {
+            all.await;
+            true
+        }
+        secs => tokio::time::timeout(Duration::from_secs(secs), all).await.is_ok(),
+    };
+    if !finished {
+        warn!("⚠️ Yönlendirici kuyrukları {} sn içinde boşalmadı; kalan partiler bırakıldı.", timeout_secs);
+        handles.iter().for_each(|handle| handle.abort());
+    }
+}
+
+// Logun zamanı: ingest'in yazdığı 'timestamp' alanı, yoksa alınma zamanı
+// (veritabanındaki timestamp sütunuyla aynı kural).
+pub fn log_time(log: &LogEntry) -> DateTime<Utc> {
+    [log.extra.get("timestamp").and_then(|v| v.as_str()), log.source.received_at.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(timestamp::parse)
+        .unwrap_or_else(Utc::now)
+}
+
+// Etiket / dizin adları için alan değeri: "level", "tenant_id", "message" veya
+// 'extra'daki anahtar ("resource.host" iç içe). Metin ve sayılar döner.
+pub fn field(log: &LogEntry, path: &str) -> Option<String> {
+    match path {
+        "level" => return Some(log.level.clone()),
+        "tenant_id" => return Some(log.tenant_id.clone()),
+        "message" => return Some(log.message.clone()),
+        _ => {}
+    }
+    let value = path.split('.').try_fold(&log.extra, |value, key| value.get(key))?;
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+use tracing::{debug, error, info, warn};
+use url::Url;
+
+use super::{status_error, Batch, SinkError};
+use crate::config::UpstreamConfig;
+use crate::deadletter::SavedLog;
+use crate::metrics::Metrics;
+use crate::writer::RetryPolicy;
+
+// --- Zincirleme Aktarım (Üst Sunucu) ---
+// Uç sunucular loglarını yerelde saklar, yazılan partilerin kopyasını da
+// merkezdeki başka bir log-ingestor'ın /ingest/relay ucuna gönderir. Diğer
+// hedeflerden farkı partilerin diskte beklemesidir: kuyruktan alınan her
+// parti hemen 'buffer_dir'e gzip'li JSON olarak yazılır ve sırayla gönderilir.
+// Bağlantı saatlerce kopsa da (dizin dolana kadar) veri kaybolmaz; süreç
+// yeniden başlatılınca kalan partiler gönderilmeye devam edilir. Kayıtlar
+// kiracı ve kaynak bilgisiyle birlikte gönderilir (SavedLog); merkezdeki
+// anahtarın 'relay' rolü olmalıdır.
+//
+// Dosya adı: <sıra>-<log sayısı>.batch. Teslim en az bir keredir: gönderilip
+// silinmeden önce çöken partiler ve üst sunucunun 503 ile kısmen kabul ettiği
+// partiler yeniden gönderilir.
+pub struct Upstream {
+    client: reqwest::Client,
+    url: Url,
+    api_key: Option<String>,
+    buffer: Buffer,
+}
+
+// Diskteki gönderilmeyi bekleyen partiler (eskiden yeniye)
+struct Buffer {
+    dir: PathBuf,
+    max_bytes: u64,
+    files: VecDeque<Pending>,
+    bytes: u64,
+    next: u64,
+}
+
+struct Pending {
+    path: PathBuf,
+    count: u64,
+    size: u64,
+}
+
+impl Upstream {
+    pub fn from_config(config: &UpstreamConfig) -> Result<Self, String> {
+        let url = Url::parse(&format!("{}/ingest/relay", config.url.trim_end_matches('/')))
+            .map_err(|e| format!("üst sunucu adresi geçersiz: {}", e))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| format!("üst sunucu istemcisi kurulamadı: {}", e))?;
+        let buffer = Buffer::open(Path::new(&config.buffer_dir), config.buffer_max_bytes)?;
+        if !buffer.files.is_empty() {
+            info!(
+                "♻️ Önceki çalışmadan {} parti üst sunucuya gönderilmeyi bekliyor ({}).",
+                buffer.files.len(),
+                buffer.dir.display()
+            );
+        }
+        Ok(Upstream {
+            client,
+            url,
+            api_key: config.api_key.clone(),
+            buffer,
+        })
+    }
+
+    async fn send(&self, pending: &Pending) -> Result<(), SinkError> {
+        let body = tokio::fs::read(&pending.path)
+            .await
+            .map_err(|e| SinkError::Rejected(format!("{} okunamadı: {}", pending.path.display(), e)))?;
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::CONTENT_ENCODING, "gzip")
+            .body(body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await.map_err(|e| SinkError::Transient(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(status_error(status, &body))
+    }
+
+    // Kuyruktan gelen partiyi diske yazar; dizin doluysa parti atılır.
+    fn store(&mut self, batch: &Batch, metrics: &Metrics) {
+        if let Err(e) = self.buffer.push(batch) {
+            warn!("⚠️ {} log üst sunucu için diske alınamadı, atıldı: {}", batch.len(), e);
+            metrics.forward_dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Buffer {
+    fn open(dir: &Path, max_bytes: u64) -> Result<Self, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{} oluşturulamadı: {}", dir.display(), e))?;
+        let mut files: Vec<(u64, Pending)> = std::fs::read_dir(dir)
+            .map_err(|e| format!("{} okunamadı: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let (sequence, count) = parse_name(&path)?;
+                let size = entry.metadata().ok()?.len();
+                Some((sequence, Pending { path, count, size }))
+            })
+            .collect();
+        files.sort_by_key(|(sequence, _)| *sequence);
+        Ok(Buffer {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            next: files.last().map_or(1, |(sequence, _)| sequence + 1),
+            bytes: files.iter().map(|(_, pending)| pending.size).sum(),
+            files: files.into_iter().map(|(_, pending)| pending).collect(),
+        })
+    }
+
+    // Partiyi önce geçici dosyaya yazıp yeniden adlandırır: çökmede yarım parti kalmaz.
+    fn push(&mut self, batch: &Batch) -> Result<(), String> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let logs: Vec<SavedLog> = batch.iter().map(SavedLog::from).collect();
+        serde_json::to_writer(&mut encoder, &logs).map_err(|e| e.to_string())?;
+        let body = encoder.finish().map_err(|e| e.to_string())?;
+        let size = body.len() as u64;
+        if self.bytes + size > self.max_bytes {
+            return Err(format!("tampon dizini dolu ({} bayt)", self.bytes));
+        }
+
+        let path = self.dir.join(format!("{:020}-{}.batch", self.next, batch.len()));
+        let temp = path.with_extension("tmp");
+        let written = std::fs::File::create(&temp).and_then(|mut file| {
+            file.write_all(&body)?;
+            file.sync_data()
+        });
+        if let Err(e) = written.and_then(|_| std::fs::rename(&temp, &path)) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(format!("{} yazılamadı: {}", path.display(), e));
+        }
+        self.next += 1;
+        self.bytes += size;
+        self.files.push_back(Pending {
+            path,
+            count: batch.len() as u64,
+            size,
+        });
+        Ok(())
+    }
+
+    fn pop(&mut self) {
+        let Some(pending) = self.files.pop_front() else {
+            return;
+        };
+        self.bytes -= pending.size;
+        if let Err(e) = std::fs::remove_file(&pending.path) {
+            warn!("⚠️ Gönderilen parti dosyası silinemedi ({}): {}", pending.path.display(), e);
+        }
+    }
+}
+
+// Üst sunucuya gönderim görevi. Kuyruk kapandıktan sonra (kapanış) gönderim
+// hata verirse beklenmez: kalan partiler diskte kalır, sonraki açılışta gönderilir.
+pub async fn run(mut upstream: Upstream, mut rx: mpsc::Receiver<Batch>, retry: RetryPolicy, metrics: Arc<Metrics>) {
+    let mut open = true;
+    let mut attempt = 0;
+    loop {
+        while open {
+            match rx.try_recv() {
+                Ok(batch) => upstream.store(&batch, &metrics),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => open = false,
+            }
+        }
+        let Some(pending) = upstream.buffer.files.front() else {
+            if !open {
+                break;
+            }
+            match rx.recv().await {
+                Some(batch) => upstream.store(&batch, &metrics),
+                None => open = false,
+            }
+            continue;
+        };
+
+        let count = pending.count;
+        match upstream.send(pending).await {
+            Ok(()) => {
+                debug!("📮 {} log üst sunucuya gönderildi.", count);
+                metrics.forwarded.fetch_add(count, Ordering::Relaxed);
+                upstream.buffer.pop();
+                attempt = 0;
+            }
+            Err(SinkError::Transient(e)) if !open => {
+                warn!(
+                    "⚠️ Üst sunucuya gönderilemedi ({}); {} parti diskte kaldı, sonraki açılışta gönderilecek.",
+                    e,
+                    upstream.buffer.files.len()
+                );
+                break;
+            }
+            Err(SinkError::Transient(e)) if retry.attempts() == 0 || attempt < retry.attempts() => {
+                let delay = retry.delay(attempt);
+                attempt += 1;
+                warn!(
+                    "⏳ {} log üst sunucuya gönderilemedi, {} ms sonra yeniden denenecek ({}. deneme): {}",
+                    count,
+                    delay.as_millis(),
+                    attempt,
+                    e
+                );
+                metrics.forward_retries.fetch_add(1, Ordering::Relaxed);
+                // Beklerken gelen partiler de diske alınır (bellekteki kuyruk dolmasın)
+                let sleep = tokio::time::sleep(delay);
+                tokio::pin!(sleep);
+                loop {
+                    tokio::select! {
+                        _ = &mut sleep => break,
+                        batch = rx.recv(), if open => match batch {
+                            Some(batch) => upstream.store(&batch, &metrics),
+                            None => open = false,
+                        },
+                    }
+                }
+            }
+            Err(e) => {
+                error!("❌ {} log üst sunucuya gönderilemedi, atıldı: {}", count, e);
+                metrics.forward_dropped.fetch_add(count, Ordering::Relaxed);
+                upstream.buffer.pop();
+                attempt = 0;
+            }
+        }
+    }
+}
+
+// "00000000000000000001-250.batch" -> (1, 250)
+fn parse_name(path: &Path) -> Option<(u64, u64)> {
+    if path.extension()? != "batch" {
+        return None;
+    }
+    let (sequence, count) = path.file_stem()?.to_str()?.split_once('-')?;
+    Some((sequence.parse().ok()?, count.parse().ok()?))
+}
@@ -0,0 +1,179 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use chrono::DateTime;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tracing::{debug, warn};
+
+use crate::auth::Caller;
+use crate::config::Backpressure;
+use crate::ingest::{self, Outcome};
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// --- Datadog Log Girişi ---
+// POST /api/v2/logs (DD-API-KEY başlığıyla): Datadog Agent'ın HTTP log
+// gönderimi, Vector 'datadog_logs' ve Fluent Bit 'datadog' çıkışları. Gövde JSON
+// dizisi veya tek nesnedir (gzip / zstd açılır). Eşleme:
+//   message                  -> message (JSON nesnesi içeriyorsa alanları açılır)
+//   status                   -> level (yoksa "info")
+//   timestamp (ms)           -> extra.timestamp
+//   hostname                 -> extra.host
+//   service                  -> extra.service
+//   ddsource                 -> extra.ddsource
+//   ddtags "env:prod,team:x" -> extra.env, extra.team (değersiz etiketler extra.tags)
+//   diğer öznitelikler       -> extra
+// ddtags, ddsource, service ve hostname istek adresinde de verilebilir (varsayılan).
+pub async fn logs_handler(
+    State(state): State<AppState>,
+    Query(defaults): Query<Defaults>,
+    caller: Option<Extension<Caller>>,
+    body: Bytes,
+) -> Response {
+    let items = match serde_json::from_slice::<Value>(&body) {
+        Ok(Value::Array(items)) => items,
+        Ok(Value::Object(item)) => vec![Value::Object(item)],
+        Ok(_) => return error(StatusCode::BAD_REQUEST, "gövde JSON nesnesi veya dizisi olmalı"),
+        Err(e) => {
+            warn!("⚠️ Datadog log isteği çözülemedi: {}", e);
+            return error(StatusCode::BAD_REQUEST, &format!("geçersiz JSON: {}", e));
+        }
+    };
+    let logs: Vec<Map<String, Value>> = items
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::Object(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+
+    debug!("📥 Datadog log isteği alındı: {} kayıt", logs.len());
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), logs.len()) {
+        return rejection;
+    }
+    if let Some(rejection) = ingest::check_capacity(&state, logs.len()) {
+        return rejection;
+    }
+    let mut overloaded = false;
+    for item in logs {
+        let mut log = convert(item, &defaults);
+        if let Some(Extension(caller)) = &caller {
+            caller.tag(&mut log);
+        }
+        overloaded |= ingest::submit(&state, log).await == Outcome::Overloaded;
+    }
+
+    if overloaded && state.backpressure == Backpressure::Reject {
+        return ingest::overloaded_response(&state);
+    }
+    // Datadog intake başarılı isteğe boş nesneyle 202 döner
+    (StatusCode::ACCEPTED, Json(json!({}))).into_response()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Defaults {
+    ddtags: Option<String>,
+    ddsource: Option<String>,
+    service: Option<String>,
+    hostname: Option<String>,
+}
+
+fn convert(mut item: Map<String, Value>, defaults: &Defaults) -> LogEntry {
+    let mut extra = Map::new();
+    let mut level = text(item.remove("status"));
+
+    let message = match item.remove("message") {
+        Some(Value::String(message)) => match parse_json_message(&message) {
+            // Uygulama JSON log yazmışsa (Datadog'un yaptığı gibi) alanları açılır
+            Some(mut inner) => {
+                level = text(inner.remove("level")).or_else(|| text(inner.remove("severity"))).or(level);
+                let message = text(inner.remove("message"))
+                    .or_else(|| text(inner.remove("msg")))
+                    .unwrap_or(message);
+                extra.extend(inner);
+                message
+            }
+            None => message,
+        },
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+
+    if let Some(timestamp) = item.remove("timestamp") {
+        let timestamp = match timestamp {
+            Value::Number(millis) => millis
+                .as_i64()
+                .and_then(DateTime::from_timestamp_millis)
+                .map(|time| Value::String(time.to_rfc3339())),
+            other => Some(other),
+        };
+        if let Some(timestamp) = timestamp {
+            extra.entry("timestamp").or_insert(timestamp);
+        }
+    }
+    let attributes = [
+        ("host", text(item.remove("hostname")).or_else(|| defaults.hostname.clone())),
+        ("service", text(item.remove("service")).or_else(|| defaults.service.clone())),
+        ("ddsource", text(item.remove("ddsource")).or_else(|| defaults.ddsource.clone())),
+    ];
+    for (key, value) in attributes {
+        if let Some(value) = value {
+            extra.entry(key).or_insert(Value::String(value));
+        }
+    }
+    let tags = text(item.remove("ddtags")).or_else(|| defaults.ddtags.clone());
+    for (key, value) in item {
+        extra.entry(key).or_insert(value);
+    }
+    if let Some(tags) = tags {
+        apply_tags(&mut extra, &tags);
+    }
+    extra.entry("source").or_insert_with(|| Value::String("datadog".to_string()));
+
+    LogEntry {
+        level: level.filter(|level| !level.is_empty()).unwrap_or_else(|| "info".to_string()),
+        message,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// "env:prod,version:1.2,canary" -> env, version alanları; değersizler "tags" dizisine.
+// Aynı anahtar tekrar ederse ilki, öznitelikle çakışırsa öznitelik kalır.
+fn apply_tags(extra: &mut Map<String, Value>, tags: &str) {
+    let mut bare = Vec::new();
+    for tag in tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        match tag.split_once(':') {
+            Some((key, value)) if !key.is_empty() => {
+                extra.entry(key.to_string()).or_insert_with(|| Value::String(value.to_string()));
+            }
+            _ => bare.push(Value::String(tag.to_string())),
+        }
+    }
+    if !bare.is_empty() {
+        extra.entry("tags").or_insert(Value::Array(bare));
+    }
+}
+
+fn parse_json_message(message: &str) -> Option<Map<String, Value>> {
+    if !message.trim_start().starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(message).ok()
+}
+
+fn text(value: Option<Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "errors": [{ "status": status.as_str(), "title": message }] }))).into_response()
+}
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::net::UnixStream;
+use tokio::task::{AbortHandle, JoinSet};
+use tracing::{debug, info, warn};
+
+use crate::config::DockerConfig;
+use crate::ingest;
+use crate::{AppState, LogEntry};
+
+// Akış koptuğunda konteyner hâlâ çalışıyorsa yeniden bağlanmadan önce beklenen süre
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+// Yeni satır gelmeden birikebilecek en büyük satır; aşılırsa bölünerek alınır
+const MAX_LINE: usize = 1024 * 1024;
+
+// --- Docker Konteyner Logları ---
+// Docker Engine API'sine unix soketi üzerinden bağlanır. Etiketlere uyan çalışan
+// konteynerler poll_interval_secs aralıkla listelenir; her biri için
+// /containers/{id}/logs?follow=1 akışı ayrı bir görevde okunur.
+//
+// Satırlar JSON log nesnesiyse (/ingest gövdesindeki gibi) olduğu gibi, değilse
+// [raw] ayrıştırıcısıyla alınır. Konteyner bilgisi extra'ya eklenir
+// (container_id, container_name, image, stream).
+//
+// Başlangıçta zaten çalışan konteynerlerin sadece yeni satırları, sonradan
+// başlayanların tüm çıktısı okunur. Konum diskte saklanmaz.
+pub async fn run(config: DockerConfig, state: AppState) {
+    let interval = Duration::from_secs(config.poll_interval_secs.max(1));
+    let path = containers_path(&config.labels);
+    info!("📡 Docker konteyner logları okunuyor: {} (etiketler: {:?})", config.socket, config.labels);
+
+    let started = chrono::Utc::now().timestamp();
+    let mut first_pass = true;
+    // Konteyner görevleri kümede tutulur; kaynak durdurulunca hepsi iptal edilir.
+    let mut tasks = JoinSet::new();
+    let mut followed: HashMap<String, AbortHandle> = HashMap::new();
+    loop {
+        while tasks.try_join_next().is_some() {}
+        followed.retain(|_, task| !task.is_finished());
+
+        match get_json::<Vec<ContainerSummary>>(&config.socket, &path).await {
+            Ok(containers) => {
+                for summary in containers {
+                    if followed.contains_key(&summary.id) {
+                        continue;
+                    }
+                    let container = match get_json::<Inspect>(&config.socket, &format!("/containers/{}/json", summary.id)).await {
+                        Ok(inspect) => Container::from(inspect),
+                        Err(e) => {
+                            debug!("Konteyner {} incelenemedi: {}", summary.id, e);
+                            continue;
+                        }
+                    };
+                    let since = if first_pass { started.to_string() } else { "0".to_string() };
+                    info!("🐳 {} ({}) konteynerinin logları okunuyor.", container.name, container.short_id());
+                    let task = tasks.spawn(follow(config.clone(), container, since, state.clone()));
+                    followed.insert(summary.id, task);
+                }
+                first_pass = false;
+            }
+            Err(e) => warn!("⚠️ Docker konteynerleri listelenemedi ({}): {}", config.socket, e),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// /containers/json?filters={"label":[...]} (sadece çalışanlar)
+fn containers_path(labels: &[String]) -> String {
+    if labels.is_empty() {
+        return "/containers/json".to_string();
+    }
+    let filters = serde_json::json!({ "label": labels }).to_string();
+    let encoded: String = url::form_urlencoded::byte_serialize(filters.as_bytes()).collect();
+    format!("/containers/json?filters={}", encoded)
+}
+
+#[derive(Deserialize)]
+struct ContainerSummary {
+    #[serde(rename = "Id")]
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct Inspect {
+    #[serde(rename = "Id")]
+    id: String,
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Config")]
+    config: InspectConfig,
+    #[serde(rename = "State")]
+    state: InspectState,
+}
+
+#[derive(Deserialize)]
+struct InspectConfig {
+    #[serde(rename = "Image")]
+    image: String,
+    #[serde(rename = "Tty", default)]
+    tty: bool,
+}
+
+#[derive(Deserialize)]
+struct InspectState {
+    #[serde(rename = "Running", default)]
+    running: bool,
+}
+
+struct Container {
+    id: String,
+    name: String,
+    image: String,
+    // TTY'li konteynerlerde akış ham metindir; değilse stdout/stderr çerçevelidir
+    tty: bool,
+}
+
+impl From<Inspect> for Container {
+    fn from(inspect: Inspect) -> Self {
+        Container {
+            id: inspect.id,
+            name: inspect.name.trim_start_matches('/').to_string(),
+            image: inspect.config.image,
+            tty: inspect.config.tty,
+        }
+    }
+}
+
+impl Container {
+    fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+}
+
+// Konteynerin log akışını izler; akış koparsa ve konteyner çalışıyorsa son
+// görülen zamandan yeniden bağlanır.
+async fn follow(config: DockerConfig, container: Container, mut since: String, state: AppState) {
+    loop {
+        if let Err(e) = stream_logs(&config, &container, &mut since, &state).await {
+            debug!("{} log akışı koptu: {}", container.name, e);
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+        match get_json::<Inspect>(&config.socket, &format!("/containers/{}/json", container.id)).await {
+            Ok(inspect) if inspect.state.running => {}
+            _ => {
+                info!("🐳 {} konteyneri durdu, log akışı kapatıldı.", container.name);
+                return;
+            }
+        }
+    }
+}
+
+async fn stream_logs(config: &DockerConfig, container: &Container, since: &mut String, state: &AppState) -> Result<(), String> {
+    let path = format!(
+        "/containers/{}/logs?follow=1&stdout=1&stderr=1&timestamps=1&since={}",
+        container.id, since
+    );
+    let mut body = request(&config.socket, &path).await?.into_body();
+    let mut demux = Demux::new(container.tty);
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| e.to_string())?;
+        let Some(data) = frame.data_ref() else {
+            continue;
+        };
+        for (stream, line) in demux.push(data) {
+            // timestamps=1: "2024-05-01T10:00:00.123456789Z satır"
+            let (timestamp, text) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+            // Yeniden bağlanınca bu satırdan sonrası istenir ('since' saniye.nanosaniye)
+            if let Some(nanos) = chrono::DateTime::parse_from_rfc3339(timestamp)
+                .ok()
+                .and_then(|time| time.timestamp_nanos_opt())
+            {
+                let next = nanos + 1;
+                *since = format!("{}.{:09}", next.div_euclid(1_000_000_000), next.rem_euclid(1_000_000_000));
+            }
+            let text = text.trim_end_matches('\r');
+            if text.trim().is_empty() {
+                continue;
+            }
+            let Some(log) = parse(text, config, state) else {
+                continue;
+            };
+            ingest::submit(state, tag(log, container, stream, timestamp)).await;
+        }
+    }
+    Ok(())
+}
+
+// JSON log nesnesi ise olduğu gibi, değilse düz metin ayrıştırıcısıyla
+fn parse(text: &str, config: &DockerConfig, state: &AppState) -> Option<LogEntry> {
+    if text.starts_with('{') {
+        if let Ok(log) = serde_json::from_str::<LogEntry>(text) {
+            return Some(log);
+        }
+    }
+    let parser = state.raw.get(config.parser.as_deref())?;
+    let mut log = parser.parse(text);
+    if let Value::Object(ref mut map) = log.extra {
+        map.remove("source");
+    }
+    Some(log)
+}
+
+fn tag(mut log: LogEntry, container: &Container, stream: &str, timestamp: &str) -> LogEntry {
+    if let Value::Object(ref mut map) = log.extra {
+        map.entry("source").or_insert_with(|| Value::String("docker".to_string()));
+        map.entry("timestamp").or_insert_with(|| Value::String(timestamp.to_string()));
+        map.insert("container_id".to_string(), Value::String(container.short_id().to_string()));
+        map.insert("container_name".to_string(), Value::String(container.name.clone()));
+        map.insert("image".to_string(), Value::String(container.image.clone()));
+        map.insert("stream".to_string(), Value::String(stream.to_string()));
+    }
+    log
+}
+
+// --- Akış Çözücü ---
+// TTY'siz konteynerlerde Docker her parçanın önüne 8 baytlık başlık ekler:
+// [akış (1 = stdout, 2 = stderr), 0, 0, 0, uzunluk (u32, big-endian)].
+// Parçalar satır sınırına denk gelmez; her akışın yarım satırı ayrı tutulur.
+struct Demux {
+    tty: bool,
+    pending: Vec<u8>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl Demux {
+    fn new(tty: bool) -> Self {
+        Demux {
+            tty,
+            pending: Vec::new(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        }
+    }
+
+    // Gelen baytları ekler, tamamlanan (akış, satır) çiftlerini döner.
+    fn push(&mut self, data: &[u8]) -> Vec<(&'static str, String)> {
+        let mut lines = Vec::new();
+        if self.tty {
+            self.stdout.extend_from_slice(data);
+            split_lines(&mut self.stdout, "stdout", &mut lines);
+            return lines;
+        }
+        self.pending.extend_from_slice(data);
+        let mut offset = 0;
+        while self.pending.len() - offset >= 8 {
+            let header = &self.pending[offset..offset + 8];
+            let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+            if self.pending.len() - offset - 8 < size {
+                break;
+            }
+            let payload = &self.pending[offset + 8..offset + 8 + size];
+            match header[0] {
+                2 => self.stderr.extend_from_slice(payload),
+                _ => self.stdout.extend_from_slice(payload),
+            }
+            offset += 8 + size;
+        }
+        self.pending.drain(..offset);
+        split_lines(&mut self.stdout, "stdout", &mut lines);
+        split_lines(&mut self.stderr, "stderr", &mut lines);
+        lines
+    }
+}
+
+fn split_lines(buffer: &mut Vec<u8>, stream: &'static str, lines: &mut Vec<(&'static str, String)>) {
+    let mut start = 0;
+    while let Some(end) = buffer[start..].iter().position(|&b| b == b'\n') {
+        lines.push((stream, String::from_utf8_lossy(&buffer[start..start + end]).into_owned()));
+        start += end + 1;
+    }
+    buffer.drain(..start);
+    if buffer.len() > MAX_LINE {
+        lines.push((stream, String::from_utf8_lossy(buffer).into_owned()));
+        buffer.clear();
+    }
+}
+
+// --- Docker Engine API ---
+// Her istek için yeni bir unix soketi bağlantısı açılır (HTTP/1.1).
+async fn request(socket: &str, path: &str) -> Result<Response<Incoming>, String> {
+    let stream = UnixStream::connect(socket).await.map_err(|e| e.to_string())?;
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            debug!("Docker bağlantısı kapandı: {}", e);
+        }
+    });
+    let request = Request::get(path)
+        .header(hyper::header::HOST, "docker")
+        .body(Empty::<Bytes>::new())
+        .map_err(|e| e.to_string())?;
+    let response = sender.send_request(request).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.into_body().collect().await.map(|b| b.to_bytes()).unwrap_or_default();
+        return Err(format!("{} {}", status, String::from_utf8_lossy(&body).trim()));
+    }
+    Ok(response)
+}
+
+async fn get_json<T: DeserializeOwned>(socket: &str, path: &str) -> Result<T, String> {
+    let body = request(socket, path)
+        .await?
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| e.to_string())?
+        .to_bytes();
+    serde_json::from_slice(&body).map_err(|e| format!("yanıt çözülemedi: {}", e))
+}
@@ -0,0 +1,240 @@
+use std::time::Instant;
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+use crate::auth::Caller;
+use crate::ingest::{self, Outcome};
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// İstemcilere bildirilen sürüm. Beats ve Logstash bağlanırken GET / ile sürümü
+// okur; 8.x istemcileri 7.x ve 8.x sunucularla konuşur.
+const VERSION: &str = "8.11.0";
+
+// 7.14 sonrası istemciler bu başlık olmadan yanıtları kabul etmez
+const PRODUCT_HEADER: (HeaderName, HeaderValue) =
+    (HeaderName::from_static("x-elastic-product"), HeaderValue::from_static("Elasticsearch"));
+
+// --- Elasticsearch Bulk API Uyumluluğu ---
+// Filebeat, Logstash ve Fluent Bit'in Elasticsearch çıktıları hiçbir değişiklik
+// yapmadan bu sunucuya yazabilir:
+//   POST /_bulk, POST /{index}/_bulk   NDJSON: eylem satırı + belge satırı
+//   GET /                              sürüm bilgisi (bağlantı kontrolü)
+//   /_index_template, /_template,
+//   /_ilm/policy, /_license            kurulum istekleri, etkisiz onay
+// Sadece 'index' ve 'create' eylemleri kabul edilir; 'update' ve 'delete' kalemleri
+// 400 ile reddedilir. Belgeler şöyle eşlenir:
+//   message                          -> message (yoksa belgenin JSON hali)
+//   level / log.level / severity     -> level (yoksa "info")
+//   @timestamp                       -> extra.timestamp
+//   _index                           -> extra.index
+//   diğer alanlar                    -> extra (ECS host.name, service.name kolonlara taşınır)
+pub async fn bulk_handler(
+    State(state): State<AppState>,
+    index: Option<Path<String>>,
+    caller: Option<Extension<Caller>>,
+    body: Bytes,
+) -> Response {
+    let started = Instant::now();
+    let default_index = index.map(|Path(index)| index);
+    let actions = match parse_bulk(&body, default_index.as_deref()) {
+        Ok(actions) => actions,
+        Err(reason) => return error_response(StatusCode::BAD_REQUEST, "illegal_argument_exception", &reason),
+    };
+
+    let count = actions.iter().filter(|action| action.document.is_ok()).count();
+    debug!("📥 Elasticsearch bulk isteği alındı: {} kalem, {} belge", actions.len(), count);
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), count) {
+        return rejection;
+    }
+    if let Some(rejection) = ingest::check_capacity(&state, count) {
+        return rejection;
+    }
+
+    // Kalem bazında sonuç: istemciler sadece 429 / 5xx kalemleri tekrar gönderir
+    let mut errors = false;
+    let mut items = Vec::with_capacity(actions.len());
+    for action in actions {
+        let status = match action.document {
+            Ok(document) => {
+                let mut log = convert(document, action.index.as_deref());
+                if let Some(Extension(caller)) = &caller {
+                    caller.tag(&mut log);
+                }
+                match ingest::submit(&state, log).await {
+                    Outcome::Dropped => Err((StatusCode::SERVICE_UNAVAILABLE, "unavailable_shards_exception", "sunucu kapanıyor".to_string())),
+                    Outcome::Overloaded => Err((StatusCode::TOO_MANY_REQUESTS, "es_rejected_execution_exception", "kuyruk dolu".to_string())),
+                    Outcome::Oversized => Err((StatusCode::BAD_REQUEST, "illegal_argument_exception", "kayıt boyut sınırını aşıyor".to_string())),
+                    _ => Ok(()),
+                }
+            }
+            Err((kind, reason)) => Err((StatusCode::BAD_REQUEST, kind, reason)),
+        };
+        let mut item = json!({
+            "_index": action.index.unwrap_or_default(),
+            "_id": action.id.unwrap_or_else(generate_id),
+        });
+        match status {
+            Ok(()) => {
+                item["status"] = json!(201);
+                item["result"] = json!("created");
+            }
+            Err((status, kind, reason)) => {
+                errors = true;
+                item["status"] = json!(status.as_u16());
+                item["error"] = json!({ "type": kind, "reason": reason });
+            }
+        }
+        items.push(json!({ action.kind: item }));
+    }
+
+    let response = json!({
+        "took": started.elapsed().as_millis() as u64,
+        "errors": errors,
+        "items": items,
+    });
+    ([PRODUCT_HEADER], Json(response)).into_response()
+}
+
+// Bulk gövdesindeki bir kalem
+struct Action {
+    kind: &'static str,
+    index: Option<String>,
+    id: Option<String>,
+    // Belge veya kalemin reddedilme nedeni (hata türü, açıklama)
+    document: Result<Map<String, Value>, (&'static str, String)>,
+}
+
+// NDJSON: her eylem satırını (index / create / update) bir belge satırı izler;
+// 'delete' tek satırdır. Eylem satırı bozuksa gövdenin geri kalanı güvenilir
+// şekilde eşlenemeyeceği için istek tamamen reddedilir.
+fn parse_bulk(body: &[u8], default_index: Option<&str>) -> Result<Vec<Action>, String> {
+    let text = std::str::from_utf8(body).map_err(|e| format!("gövde UTF-8 değil: {}", e))?;
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut actions = Vec::new();
+    while let Some(line) = lines.next() {
+        let header = serde_json::from_str::<Map<String, Value>>(line).map_err(|e| format!("geçersiz eylem satırı: {}", e))?;
+        let (name, meta) = header.into_iter().next().ok_or("boş eylem satırı")?;
+        let kind = match name.as_str() {
+            "index" => "index",
+            "create" => "create",
+            "update" => "update",
+            "delete" => "delete",
+            other => return Err(format!("bilinmeyen eylem '{}'", other)),
+        };
+        let field = |key: &str| meta.get(key).and_then(Value::as_str).map(str::to_string);
+        let index = field("_index").or_else(|| default_index.map(str::to_string));
+        let id = field("_id");
+
+        let document = match kind {
+            "delete" => Err(("action_request_validation_exception", "silme desteklenmez".to_string())),
+            _ => {
+                let line = lines.next().ok_or_else(|| format!("'{}' eyleminin belge satırı eksik", kind))?;
+                match serde_json::from_str::<Map<String, Value>>(line) {
+                    Err(e) => Err(("mapper_parsing_exception", format!("geçersiz belge: {}", e))),
+                    Ok(_) if kind == "update" => {
+                        Err(("action_request_validation_exception", "güncelleme desteklenmez".to_string()))
+                    }
+                    Ok(document) => Ok(document),
+                }
+            }
+        };
+        actions.push(Action { kind, index, id, document });
+    }
+    Ok(actions)
+}
+
+fn convert(mut document: Map<String, Value>, index: Option<&str>) -> LogEntry {
+    let message = match document.remove("message") {
+        Some(Value::String(message)) => message,
+        Some(other) => other.to_string(),
+        None => Value::Object(document.clone()).to_string(),
+    };
+    let level = take_level(&mut document).unwrap_or_else(|| "info".to_string());
+
+    if let Some(timestamp) = document.remove("@timestamp") {
+        document.entry("timestamp").or_insert(timestamp);
+    }
+    document.entry("source").or_insert_with(|| Value::String("elasticsearch".to_string()));
+    if let Some(index) = index {
+        document.entry("index").or_insert_with(|| Value::String(index.to_string()));
+    }
+
+    LogEntry {
+        level,
+        message,
+        extra: Value::Object(document),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// level, ECS log.level (iç içe veya noktalı anahtar), severity
+fn take_level(document: &mut Map<String, Value>) -> Option<String> {
+    let text = |value: Value| match value {
+        Value::String(level) if !level.is_empty() => Some(level),
+        Value::Number(level) => Some(level.to_string()),
+        _ => None,
+    };
+    if let Some(level) = document.remove("level").and_then(text) {
+        return Some(level);
+    }
+    if let Some(Value::Object(log)) = document.get_mut("log") {
+        if let Some(level) = log.remove("level").and_then(text) {
+            if log.is_empty() {
+                document.remove("log");
+            }
+            return Some(level);
+        }
+    }
+    ["log.level", "severity"].into_iter().find_map(|key| document.remove(key).and_then(text))
+}
+
+// _id verilmeyen kalemler için (Elasticsearch'ün ürettiği gibi 20 karakter)
+fn generate_id() -> String {
+    format!("{:016x}{:04x}", rand::random::<u64>(), rand::random::<u16>())
+}
+
+fn error_response(status: StatusCode, kind: &str, reason: &str) -> Response {
+    let body = json!({
+        "error": { "root_cause": [{ "type": kind, "reason": reason }], "type": kind, "reason": reason },
+        "status": status.as_u16(),
+    });
+    (status, [PRODUCT_HEADER], Json(body)).into_response()
+}
+
+// GET /  (HEAD / ile sağlık kontrolü de buraya düşer)
+pub async fn info_handler() -> Response {
+    let body = json!({
+        "name": "log-ingestor",
+        "cluster_name": "log-ingestor",
+        "version": {
+            "number": VERSION,
+            "build_flavor": "default",
+            "lucene_version": "9.8.0",
+            "minimum_wire_compatibility_version": "7.17.0",
+            "minimum_index_compatibility_version": "7.0.0",
+        },
+        "tagline": "You Know, for Search",
+    });
+    ([PRODUCT_HEADER], Json(body)).into_response()
+}
+
+// Şablon ve ILM kurulum istekleri: dizin yönetimi olmadığı için hepsi onaylanır.
+// Varlık sorgularına (GET / HEAD) de 200 dönülür ki istemci her bağlantıda
+// yeniden yüklemeye çalışmasın.
+pub async fn setup_handler() -> Response {
+    ([PRODUCT_HEADER], Json(json!({ "acknowledged": true }))).into_response()
+}
+
+// Logstash ve Beats lisans sorgusu (ILM ve izleme özellikleri için)
+pub async fn license_handler() -> Response {
+    let body = json!({ "license": { "status": "active", "type": "basic", "mode": "basic" } });
+    ([PRODUCT_HEADER], Json(body)).into_response()
+}
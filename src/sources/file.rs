@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing::{debug, error, info, warn};
+
+use crate::config::{FileStart, FilesConfig};
+use crate::ingest;
+use crate::pipeline::Ack;
+use crate::AppState;
+
+// Tek okumada alınan en fazla bayt. Bu boyutu aşan satır bölünerek alınır.
+const READ_CHUNK: usize = 256 * 1024;
+
+// Yazma başarısız olduğunda dosyaya tekrar dönmeden önce beklenen süre
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// --- Dosya İzleme (tail) ---
+// files.paths desenlerine uyan yerel dosyaları okur; her satır [raw]
+// ayrıştırıcısıyla LogEntry'ye çevrilip boru hattına girer. Böylece ikili,
+// uygulama sunucularında hafif bir toplayıcı olarak da çalışabilir.
+//
+// Okunan konumlar, satırlar veritabanına yazıldıktan sonra state_path dosyasına
+// işlenir (en az bir kez teslim). Dosyalar inode ile tanınır:
+//   - yeniden adlandırılıp yerine yenisi açılan dosya (logrotate 'create'),
+//     eski tanıtıcıdan sonuna kadar okunur, sonra yeni dosya baştan okunur;
+//   - yeni adı da desene uyuyorsa kaldığı yerden devam edilir (tekrar okunmaz);
+//   - boyutu konumun altına düşen dosya ('copytruncate') baştan okunur.
+pub async fn run(config: FilesConfig, state: AppState) {
+    let interval = Duration::from_millis(config.poll_interval_ms.max(10));
+    let mut checkpoints = load_checkpoints(&config.state_path);
+    info!(
+        "📡 Dosya izleme çalışıyor: {:?} ({} kayıtlı konum)",
+        config.paths,
+        checkpoints.len()
+    );
+
+    let mut files: HashMap<PathBuf, Tailed> = HashMap::new();
+    let mut first_pass = true;
+    loop {
+        let mut dirty = false;
+
+        // --- İzlenen dosyalar: döndürme / kesme kontrolü ve yeni satırlar ---
+        // Döndürülen dosyaların son konumu; yeni adıyla bulunursa devam edilir.
+        let mut rotated: HashMap<u64, u64> = HashMap::new();
+        let paths: Vec<PathBuf> = files.keys().cloned().collect();
+        for path in paths {
+            let Some(tailed) = files.get_mut(&path) else {
+                continue;
+            };
+            let current = tokio::fs::metadata(&path).await.ok().map(|meta| file_id(&meta));
+            if current != Some(tailed.inode) {
+                // Eski tanıtıcı hâlâ açık: kalan satırları (son yarım satır dahil) al
+                dirty |= tailed.read(&path, &config, &state, true).await;
+                info!("🔄 {} döndürüldü, {}. bayta kadar okundu.", path.display(), tailed.offset);
+                rotated.insert(tailed.inode, tailed.offset);
+                files.remove(&path);
+                continue;
+            }
+            match tailed.file.metadata().await {
+                Ok(meta) if meta.len() < tailed.offset => {
+                    info!("✂️ {} kesildi, baştan okunuyor.", path.display());
+                    tailed.offset = 0;
+                    dirty = true;
+                }
+                Ok(_) => {}
+                Err(e) => debug!("{} okunamadı: {}", path.display(), e),
+            }
+            dirty |= tailed.read(&path, &config, &state, false).await;
+        }
+
+        // --- Desenlere yeni uyan dosyalar ---
+        for path in config.paths.iter().flat_map(|pattern| expand(pattern)) {
+            if files.contains_key(&path) {
+                continue;
+            }
+            let file = match File::open(&path).await {
+                Ok(file) => file,
+                Err(e) => {
+                    debug!("{} açılamadı: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let Ok(meta) = file.metadata().await else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            let inode = file_id(&meta);
+            let known = rotated.get(&inode).copied().or_else(|| {
+                checkpoints
+                    .iter()
+                    .find(|c| c.inode == inode && (c.path == path || !c.path.exists()))
+                    .map(|c| c.offset)
+            });
+            let offset = match known {
+                Some(offset) if offset <= meta.len() => offset,
+                // Başlangıçta zaten var olan dosya; sonradan açılanlar baştan okunur
+                _ if first_pass && config.start_at == FileStart::End => meta.len(),
+                _ => 0,
+            };
+            info!("📄 {} izleniyor ({}. bayttan).", path.display(), offset);
+            let mut tailed = Tailed { file, inode, offset };
+            dirty = true;
+            tailed.read(&path, &config, &state, false).await;
+            files.insert(path, tailed);
+        }
+        first_pass = false;
+
+        if dirty {
+            checkpoints = files
+                .iter()
+                .map(|(path, tailed)| Checkpoint {
+                    path: path.clone(),
+                    inode: tailed.inode,
+                    offset: tailed.offset,
+                })
+                .collect();
+            if let Err(e) = save_checkpoints(&config.state_path, &checkpoints) {
+                warn!("⚠️ Dosya konumları {} dosyasına yazılamadı: {}", config.state_path, e);
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// İzlenen bir dosya ve satırları yazılmış olan son konum
+struct Tailed {
+    file: File,
+    inode: u64,
+    offset: u64,
+}
+
+impl Tailed {
+    // Konumdan dosya sonuna kadar olan tam satırları gönderir; 'drain' ise sondaki
+    // yarım satırı da. Yazılan satırlar kadar konum ilerler. Konum değiştiyse true.
+    async fn read(&mut self, path: &Path, config: &FilesConfig, state: &AppState, drain: bool) -> bool {
+        let start = self.offset;
+        let mut buf = vec![0u8; READ_CHUNK];
+        loop {
+            let read = match self.read_at(&mut buf).await {
+                Ok(read) => read,
+                Err(e) => {
+                    warn!("⚠️ {} okunamadı: {}", path.display(), e);
+                    break;
+                }
+            };
+            if read == 0 {
+                break;
+            }
+            let data = &buf[..read];
+            // Son satır sonuna kadar olan kısım; tampon yeni satırsız dolduysa tamamı
+            let consumed = match data.iter().rposition(|&b| b == b'\n') {
+                Some(last) => last + 1,
+                None if read == READ_CHUNK || drain => read,
+                None => break,
+            };
+            if !self.submit(&data[..consumed], path, config, state).await {
+                error!("❌ {} satırları veritabanına yazılamadı, tekrar okunacak.", path.display());
+                tokio::time::sleep(RETRY_DELAY).await;
+                break;
+            }
+            self.offset += consumed as u64;
+            if read < READ_CHUNK && consumed == read {
+                break;
+            }
+        }
+        self.offset != start
+    }
+
+    async fn read_at(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.file.seek(SeekFrom::Start(self.offset)).await?;
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.file.read(&mut buf[filled..]).await? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        Ok(filled)
+    }
+
+    // Satırları ayrıştırıp gönderir ve yazılmalarını bekler.
+    async fn submit(&self, data: &[u8], path: &Path, config: &FilesConfig, state: &AppState) -> bool {
+        let Some(parser) = state.raw.get(config.parser.as_deref()) else {
+            return false;
+        };
+        let text = String::from_utf8_lossy(data);
+        let lines: Vec<&str> = text
+            .split('\n')
+            .map(|l| l.trim_end_matches('\r'))
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+        if lines.is_empty() {
+            return true;
+        }
+        debug!("📥 {} dosyasından {} satır okundu", path.display(), lines.len());
+
+        let file = Value::String(path.display().to_string());
+        let (ack, done) = Ack::group(lines.len());
+        for line in lines {
+            let mut log = parser.parse(line);
+            if let Value::Object(ref mut map) = log.extra {
+                map.insert("source".to_string(), Value::String("file".to_string()));
+                map.insert("file".to_string(), file.clone());
+            }
+            ingest::submit_with_ack(state, log, Some(ack.clone())).await;
+        }
+        drop(ack);
+        done.await.unwrap_or(false)
+    }
+}
+
+// Dosyanın kimliği: Unix'te inode, diğer sistemlerde oluşturulma zamanı.
+#[cfg(unix)]
+fn file_id(meta: &std::fs::Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::ino(meta)
+}
+
+#[cfg(not(unix))]
+fn file_id(meta: &std::fs::Metadata) -> u64 {
+    meta.created()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+// --- Konum Dosyası ---
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    path: PathBuf,
+    inode: u64,
+    offset: u64,
+}
+
+fn load_checkpoints(path: &str) -> Vec<Checkpoint> {
+    match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+            warn!("⚠️ Dosya konumları okunamadı ({}): {}; dosyalar baştan değerlendirilecek.", path, e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Yarım yazılmış dosya kalmaması için önce geçici dosyaya yazılıp taşınır.
+fn save_checkpoints(path: &str, checkpoints: &[Checkpoint]) -> std::io::Result<()> {
+    let tmp = format!("{}.tmp", path);
+    std::fs::write(&tmp, serde_json::to_vec_pretty(checkpoints)?)?;
+    std::fs::rename(&tmp, path)
+}
+
+// --- Glob ---
+// Desen bileşenlere ayrılır; '*' veya '?' içeren bileşenler dizin içeriğiyle
+// eşleştirilir, diğerleri olduğu gibi eklenir. '**' desteklenmez.
+fn expand(pattern: &str) -> Vec<PathBuf> {
+    let path = Path::new(pattern);
+    let mut matches = vec![PathBuf::new()];
+    for component in path.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !part.contains(['*', '?']) {
+            for candidate in &mut matches {
+                candidate.push(component);
+            }
+            continue;
+        }
+        let mut next = Vec::new();
+        for dir in &matches {
+            let read_from = if dir.as_os_str().is_empty() { Path::new(".") } else { dir.as_path() };
+            let Ok(entries) = std::fs::read_dir(read_from) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                // Gizli dosyalar sadece desen de '.' ile başlıyorsa eşleşir
+                if name.starts_with('.') && !part.starts_with('.') {
+                    continue;
+                }
+                if wildcard(&part, &name) {
+                    next.push(dir.join(&*name));
+                }
+            }
+        }
+        matches = next;
+    }
+    let mut seen = HashSet::new();
+    matches.retain(|p| !p.as_os_str().is_empty() && p.is_file() && seen.insert(p.clone()));
+    matches.sort();
+    matches
+}
+
+// '*' (herhangi bir dizi) ve '?' (tek karakter) destekli eşleştirme
+fn wildcard(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Son '*' konumu ve o sırada isimde kalınan yer (geri dönmek için)
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
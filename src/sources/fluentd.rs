@@ -0,0 +1,600 @@
+use std::io::{Cursor, Read};
+use std::net::SocketAddr;
+
+use rmpv::Value as Msgpack;
+use serde_json::{Map, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
+
+use super::from_peer;
+use crate::ingest::{self, Outcome};
+use crate::{AppState, LogEntry};
+
+// Tek bir forward mesajının (açılmış hali dahil) izin verilen en büyük boyutu
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+// --- Fluentd Forward Protokolü ---
+// fluent-bit / fluentd 'forward' çıkışı ile uyumlu TCP girişi (varsayılan port 24224).
+// Her mesaj bir msgpack dizisidir:
+//   Message:                 [tag, time, record, option?]
+//   Forward:                 [tag, [[time, record], ...], option?]
+//   PackedForward:           [tag, bin(ardışık [time, record]), option?]
+//   CompressedPackedForward: PackedForward + option.compressed = "gzip"
+// option.chunk varsa mesaj işlendikten sonra {"ack": chunk} yanıtı gönderilir.
+// Kanala giremeyen kayıt olursa ack gönderilmez; gönderen aynı chunk'ı tekrar yollar.
+// Not: shared_key el sıkışması (HELO/PING) desteklenmez.
+pub async fn run_forward(bind: String, state: AppState) {
+    let listener = match TcpListener::bind(&bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("❌ Fluentd forward {} dinlenemedi: {}", bind, e);
+            return;
+        }
+    };
+    info!("📡 Fluentd forward dinleyicisi {} adresinde çalışıyor...", bind);
+
+    // Bağlantı görevleri bu kümede tutulur; dinleyici durdurulunca hepsi iptal edilir.
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    debug!("🔌 Fluentd forward bağlantısı: {}", peer);
+                    connections.spawn(handle_conn(stream, peer, state.clone()));
+                }
+                Err(e) => error!("❌ Fluentd forward bağlantı hatası: {}", e),
+            },
+            // Biten bağlantıları kümeden temizle
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+async fn handle_conn(mut stream: TcpStream, peer: SocketAddr, state: AppState) {
+    let mut buffer: Vec<u8> = Vec::with_capacity(64 * 1024);
+    let mut chunk = vec![0u8; 64 * 1024];
+    let mut frame = FrameScanner::default();
+    loop {
+        let read = match stream.read(&mut chunk).await {
+            Ok(0) => return,
+            Ok(n) => n,
+            Err(e) => {
+                debug!("Fluentd forward okuma hatası: {}", e);
+                return;
+            }
+        };
+        buffer.extend_from_slice(&chunk[..read]);
+
+        // Tamponda tamamlanmış olan tüm mesajları işle. Mesajın bitip bitmediğine
+        // başlıklarına bakılarak karar verilir; çözme bir kez, mesaj tamamlanınca yapılır.
+        loop {
+            let end = match frame.scan(&buffer) {
+                Ok(Some(end)) => end,
+                Ok(None) => break,
+                Err(reason) => {
+                    warn!("⚠️ Fluentd forward bağlantısı kapatılıyor: {}", reason);
+                    return;
+                }
+            };
+            let message = match rmpv::decode::read_value(&mut &buffer[..end]) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("⚠️ Fluentd forward bağlantısı kapatılıyor: geçersiz msgpack: {}", e);
+                    return;
+                }
+            };
+            buffer.drain(..end);
+
+            let (entries, ack) = match decode_message(message) {
+                Ok(decoded) => decoded,
+                Err(reason) => {
+                    warn!("⚠️ Geçersiz Fluentd forward mesajı atlandı: {}", reason);
+                    continue;
+                }
+            };
+            debug!("📥 Fluentd forward mesajı alındı: {} adet log", entries.len());
+
+            let mut dropped = false;
+            for log in entries {
+                if matches!(ingest::submit(&state, from_peer(log, peer)).await, Outcome::Dropped | Outcome::Overloaded) {
+                    dropped = true;
+                }
+            }
+
+            if let Some(chunk_id) = ack.filter(|_| !dropped) {
+                let response = Msgpack::Map(vec![(Msgpack::from("ack"), Msgpack::from(chunk_id))]);
+                let mut out = Vec::new();
+                if rmpv::encode::write_value(&mut out, &response).is_err() || stream.write_all(&out).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// --- Mesaj Sınırları ---
+// Tampondaki ilk msgpack değerinin nerede bittiğini, değeri çözmeden başlıklardan
+// bulur. Kaldığı yeri hatırlar: yeni gelen baytlar için baştan taranmaz, böylece
+// parça parça gelen büyük bir mesaj doğrusal sürede tamamlanır.
+#[derive(Debug, Default)]
+struct FrameScanner {
+    // Taranan kısmın sonu (bir sonraki başlığın yeri; veri henüz gelmemiş olabilir)
+    pos: usize,
+    // Okunması gereken değer sayısı; 0 ise yeni mesaj başlar
+    pending: u64,
+}
+
+impl FrameScanner {
+    // Mesaj tamamsa uzunluğunu döner ve baştan başlar; eksikse None.
+    fn scan(&mut self, buffer: &[u8]) -> Result<Option<usize>, String> {
+        if self.pos == 0 && self.pending == 0 {
+            self.pending = 1;
+        }
+        while self.pending > 0 {
+            let Some(&marker) = buffer.get(self.pos) else {
+                return Ok(None);
+            };
+            let Some((header, payload, children)) = value_header(marker, &buffer[self.pos..])? else {
+                return Ok(None);
+            };
+            self.pos += header + payload;
+            self.pending = self.pending - 1 + children;
+            if self.pos > MAX_FRAME {
+                return Err(format!("mesaj {} baytı aşıyor", MAX_FRAME));
+            }
+        }
+        // Son değerin gövdesi henüz gelmemiş olabilir
+        if self.pos > buffer.len() {
+            return Ok(None);
+        }
+        let end = self.pos;
+        *self = FrameScanner::default();
+        Ok(Some(end))
+    }
+}
+
+// Değerin başlık uzunluğu, gövde uzunluğu (str / bin / ext) ve içerdiği değer
+// sayısı (dizi / eşlem). Başlık henüz tamamen gelmediyse None.
+fn value_header(marker: u8, bytes: &[u8]) -> Result<Option<(usize, usize, u64)>, String> {
+    // Başlıktaki büyük-sonlu uzunluk alanı
+    let length = |size: usize| -> Option<u64> {
+        let field = bytes.get(1..1 + size)?;
+        Some(field.iter().fold(0u64, |n, b| (n << 8) | u64::from(*b)))
+    };
+    let header = match marker {
+        0x00..=0x7f | 0xe0..=0xff | 0xc0 | 0xc2 | 0xc3 => (1, 0, 0),
+        0x80..=0x8f => (1, 0, u64::from(marker & 0x0f) * 2),
+        0x90..=0x9f => (1, 0, u64::from(marker & 0x0f)),
+        0xa0..=0xbf => (1, usize::from(marker & 0x1f), 0),
+        // bin8/16/32, str8/16/32
+        0xc4 | 0xd9 => match length(1) {
+            Some(n) => (2, n as usize, 0),
+            None => return Ok(None),
+        },
+        0xc5 | 0xda => match length(2) {
+            Some(n) => (3, n as usize, 0),
+            None => return Ok(None),
+        },
+        0xc6 | 0xdb => match length(4) {
+            Some(n) => (5, n as usize, 0),
+            None => return Ok(None),
+        },
+        // ext8/16/32: uzunluktan sonra bir bayt tür
+        0xc7 => match length(1) {
+            Some(n) => (3, n as usize, 0),
+            None => return Ok(None),
+        },
+        0xc8 => match length(2) {
+            Some(n) => (4, n as usize, 0),
+            None => return Ok(None),
+        },
+        0xc9 => match length(4) {
+            Some(n) => (6, n as usize, 0),
+            None => return Ok(None),
+        },
+        0xca => (5, 0, 0),
+        0xcb => (9, 0, 0),
+        0xcc | 0xd0 => (2, 0, 0),
+        0xcd | 0xd1 => (3, 0, 0),
+        0xce | 0xd2 => (5, 0, 0),
+        0xcf | 0xd3 => (9, 0, 0),
+        // fixext1/2/4/8/16: tür + sabit gövde
+        0xd4 => (2, 1, 0),
+        0xd5 => (2, 2, 0),
+        0xd6 => (2, 4, 0),
+        0xd7 => (2, 8, 0),
+        0xd8 => (2, 16, 0),
+        0xdc => match length(2) {
+            Some(n) => (3, 0, n),
+            None => return Ok(None),
+        },
+        0xdd => match length(4) {
+            Some(n) => (5, 0, n),
+            None => return Ok(None),
+        },
+        0xde => match length(2) {
+            Some(n) => (3, 0, n * 2),
+            None => return Ok(None),
+        },
+        0xdf => match length(4) {
+            Some(n) => (5, 0, n * 2),
+            None => return Ok(None),
+        },
+        0xc1 => return Err("geçersiz msgpack: 0xc1 işaretçisi".to_string()),
+    };
+    Ok(Some(header))
+}
+
+// Forward mesajını loglara ve (varsa) ack edilecek chunk kimliğine çevirir.
+fn decode_message(message: Msgpack) -> Result<(Vec<LogEntry>, Option<String>), &'static str> {
+    let Msgpack::Array(mut parts) = message else {
+        return Err("mesaj dizi değil");
+    };
+    if parts.len() < 2 {
+        return Err("eksik alan");
+    }
+    let tag = parts.remove(0);
+    let tag = tag.as_str().ok_or("tag metin değil")?.to_string();
+
+    let mut entries = Vec::new();
+    let option = match parts.remove(0) {
+        // Forward: [[time, record], ...]
+        Msgpack::Array(events) => {
+            for event in events {
+                entries.push(decode_event(&tag, event)?);
+            }
+            parts.into_iter().next()
+        }
+        // PackedForward / CompressedPackedForward
+        packed @ (Msgpack::Binary(_) | Msgpack::String(_)) => {
+            let option = parts.into_iter().next();
+            let gzip = option
+                .as_ref()
+                .and_then(|o| map_get(o, "compressed"))
+                .and_then(Msgpack::as_str)
+                == Some("gzip");
+            let raw = match packed {
+                Msgpack::Binary(bytes) => bytes,
+                Msgpack::String(s) => s.into_bytes(),
+                _ => unreachable!(),
+            };
+            let raw = if gzip { gunzip(&raw).ok_or("gzip açılamadı")? } else { raw };
+
+            let mut cursor = Cursor::new(&raw[..]);
+            while (cursor.position() as usize) < raw.len() {
+                let event = rmpv::decode::read_value(&mut cursor).map_err(|_| "paketli olay çözülemedi")?;
+                entries.push(decode_event(&tag, event)?);
+            }
+            option
+        }
+        // Message: [tag, time, record, option?]
+        time => {
+            if parts.is_empty() {
+                return Err("kayıt yok");
+            }
+            let record = parts.remove(0);
+            entries.push(to_entry(&tag, &time, record));
+            parts.into_iter().next()
+        }
+    };
+
+    let ack = option
+        .as_ref()
+        .and_then(|o| map_get(o, "chunk"))
+        .and_then(Msgpack::as_str)
+        .map(str::to_string);
+    Ok((entries, ack))
+}
+
+fn decode_event(tag: &str, event: Msgpack) -> Result<LogEntry, &'static str> {
+    let Msgpack::Array(mut pair) = event else {
+        return Err("olay [time, record] değil");
+    };
+    if pair.len() < 2 {
+        return Err("olay [time, record] değil");
+    }
+    let record = pair.remove(1);
+    Ok(to_entry(tag, &pair[0], record))
+}
+
+// Kayıt alanları LogEntry'ye şöyle eşlenir:
+//   message / log / msg          -> message
+//   level / severity / log_level -> level (metin veya sayı; yoksa "info")
+//   time (saniye veya EventTime) -> extra.timestamp
+//   tag                          -> extra.tag
+//   diğer alanlar                -> extra
+fn to_entry(tag: &str, time: &Msgpack, record: Msgpack) -> LogEntry {
+    let mut extra = match to_json(record) {
+        Value::Object(map) => map,
+        other => {
+            let mut map = Map::new();
+            map.insert("message".to_string(), other);
+            map
+        }
+    };
+
+    let message = ["message", "log", "msg"]
+        .iter()
+        .find_map(|key| extra.remove(*key))
+        .map(|v| match v {
+            Value::String(s) => s.trim_end_matches('\n').to_string(),
+            other => other.to_string(),
+        })
+        .unwrap_or_default();
+    let level = take_level(&mut extra).unwrap_or_else(|| "info".to_string());
+
+    extra.insert("source".to_string(), Value::String("fluentd".to_string()));
+    extra.insert("tag".to_string(), Value::String(tag.to_string()));
+    if let Some(ts) = event_time(time) {
+        extra.insert("timestamp".to_string(), Value::String(ts));
+    }
+
+    LogEntry {
+        level,
+        message,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// Sayısal seviyeler (pino 50, syslog 3) metne çevrilir; kanonik karşılığına
+// ingest::submit içindeki seviye normalleştirmesi karar verir.
+fn take_level(record: &mut Map<String, Value>) -> Option<String> {
+    ["level", "severity", "log_level"].into_iter().find_map(|key| match record.remove(key)? {
+        Value::String(level) if !level.is_empty() => Some(level.to_ascii_lowercase()),
+        Value::Number(level) => Some(level.to_string()),
+        other => {
+            record.insert(key.to_string(), other);
+            None
+        }
+    })
+}
+
+// Zaman ya epoch saniye (tam sayı / ondalıklı) ya da EventTime ext tipidir:
+// ext 0, 8 bayt = saniye (u32 BE) + nanosaniye (u32 BE)
+fn event_time(time: &Msgpack) -> Option<String> {
+    let (secs, nanos) = match time {
+        Msgpack::Integer(i) => (i.as_i64()?, 0),
+        Msgpack::F32(f) => (f.trunc() as i64, (f.fract() * 1e9) as u32),
+        Msgpack::F64(f) => (f.trunc() as i64, (f.fract() * 1e9) as u32),
+        Msgpack::Ext(0, data) if data.len() == 8 => (
+            u32::from_be_bytes(data[0..4].try_into().ok()?) as i64,
+            u32::from_be_bytes(data[4..8].try_into().ok()?),
+        ),
+        _ => return None,
+    };
+    chrono::DateTime::from_timestamp(secs, nanos.min(999_999_999)).map(|t| t.to_rfc3339())
+}
+
+fn map_get<'a>(value: &'a Msgpack, key: &str) -> Option<&'a Msgpack> {
+    value.as_map()?.iter().find(|(k, _)| k.as_str() == Some(key)).map(|(_, v)| v)
+}
+
+fn gunzip(data: &[u8]) -> Option<Vec<u8>> {
+    // fluent-bit her chunk'ı ayrı bir gzip üyesi olarak ekleyebilir
+    let mut out = Vec::new();
+    flate2::read::MultiGzDecoder::new(data)
+        .take(MAX_FRAME as u64)
+        .read_to_end(&mut out)
+        .ok()?;
+    Some(out)
+}
+
+fn to_json(value: Msgpack) -> Value {
+    match value {
+        Msgpack::Nil => Value::Null,
+        Msgpack::Boolean(b) => Value::Bool(b),
+        Msgpack::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(v), _) => Value::from(v),
+            (None, Some(v)) => Value::from(v),
+            _ => Value::Null,
+        },
+        Msgpack::F32(f) => Value::from(f as f64),
+        Msgpack::F64(f) => Value::from(f),
+        Msgpack::String(s) => Value::String(s.into_str().unwrap_or_default()),
+        // fluent-bit bazı metinleri bin olarak gönderir
+        Msgpack::Binary(bytes) => Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+        Msgpack::Array(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        Msgpack::Map(pairs) => Value::Object(
+            pairs
+                .into_iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        Msgpack::String(s) => s.into_str().unwrap_or_default(),
+                        other => other.to_string(),
+                    };
+                    (key, to_json(v))
+                })
+                .collect(),
+        ),
+        Msgpack::Ext(..) => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::config::LevelsConfig;
+    use crate::level::LevelNormalizer;
+
+    fn encode(value: &Msgpack) -> Vec<u8> {
+        let mut out = Vec::new();
+        rmpv::encode::write_value(&mut out, value).unwrap();
+        out
+    }
+
+    fn record(pairs: &[(&str, Msgpack)]) -> Msgpack {
+        Msgpack::Map(pairs.iter().map(|(k, v)| (Msgpack::from(*k), v.clone())).collect())
+    }
+
+    fn event(message: &str) -> Msgpack {
+        Msgpack::Array(vec![
+            Msgpack::from(1_700_000_000),
+            record(&[("message", Msgpack::from(message)), ("level", Msgpack::from("error"))]),
+        ])
+    }
+
+    // Mesajın her önekinde tamamlanmadığını, tamamında uzunluğunu bildirmeli.
+    fn assert_frame(bytes: &[u8]) {
+        let mut frame = FrameScanner::default();
+        for end in 0..bytes.len() {
+            assert_eq!(frame.scan(&bytes[..end]), Ok(None), "önek {}/{}", end, bytes.len());
+        }
+        assert_eq!(frame.scan(bytes), Ok(Some(bytes.len())));
+    }
+
+    #[test]
+    fn frames_split_across_reads() {
+        let message = Msgpack::Array(vec![Msgpack::from("app"), Msgpack::Array(vec![event("bir"), event("iki")])]);
+        let bytes = encode(&message);
+        assert_frame(&bytes);
+
+        // Arka arkaya gelen iki mesaj: ilki bitince tarayıcı baştan başlar
+        let mut stream = bytes.clone();
+        stream.extend_from_slice(&bytes);
+        let mut frame = FrameScanner::default();
+        assert_eq!(frame.scan(&stream), Ok(Some(bytes.len())));
+        assert_eq!(frame.scan(&stream[bytes.len()..]), Ok(Some(bytes.len())));
+    }
+
+    #[test]
+    fn length_prefixed_headers() {
+        // str8/16/32, bin8/16/32, ext8/16/32 ve fixext başlıkları
+        let cases = [
+            (Msgpack::from("x".repeat(40)), 0xd9),
+            (Msgpack::from("x".repeat(300)), 0xda),
+            (Msgpack::from("x".repeat(70_000)), 0xdb),
+            (Msgpack::Binary(vec![1; 40]), 0xc4),
+            (Msgpack::Binary(vec![1; 300]), 0xc5),
+            (Msgpack::Binary(vec![1; 70_000]), 0xc6),
+            (Msgpack::Ext(5, vec![1; 3]), 0xc7),
+            (Msgpack::Ext(5, vec![1; 300]), 0xc8),
+            (Msgpack::Ext(5, vec![1; 70_000]), 0xc9),
+            (Msgpack::Ext(0, vec![1; 8]), 0xd7),
+            (Msgpack::Ext(5, vec![1; 16]), 0xd8),
+        ];
+        for (value, marker) in cases {
+            let bytes = encode(&Msgpack::Array(vec![Msgpack::from("app"), value, Msgpack::Nil]));
+            assert_eq!(bytes[5], marker);
+            assert_frame(&bytes);
+        }
+    }
+
+    #[test]
+    fn nested_maps_and_arrays() {
+        let inner = record(&[
+            ("list", Msgpack::Array(vec![Msgpack::from(1), record(&[("b", Msgpack::from("c"))])])),
+            ("map", record(&[("deep", Msgpack::Array(vec![Msgpack::Array(vec![Msgpack::Nil])]))])),
+            ("float", Msgpack::F64(1.5)),
+            ("big", Msgpack::from(u64::MAX)),
+        ]);
+        let message = Msgpack::Array(vec![Msgpack::from("app"), Msgpack::from(1), inner]);
+        let bytes = encode(&message);
+        assert_frame(&bytes);
+
+        let (entries, _) = decode_message(message).unwrap();
+        assert_eq!(entries[0].extra["list"], serde_json::json!([1, {"b": "c"}]));
+        assert_eq!(entries[0].extra["map"]["deep"], serde_json::json!([[null]]));
+        assert_eq!(entries[0].extra["big"], serde_json::json!(u64::MAX));
+    }
+
+    #[test]
+    fn oversized_frame_is_refused() {
+        let mut bytes = vec![0x92, 0xa3, b'a', b'p', b'p', 0xc6];
+        bytes.extend_from_slice(&(MAX_FRAME as u32 + 1).to_be_bytes());
+        let mut frame = FrameScanner::default();
+        assert!(frame.scan(&bytes).is_err());
+
+        // 0xc1 hiçbir zaman geçerli değildir
+        assert!(FrameScanner::default().scan(&[0x91, 0xc1]).is_err());
+    }
+
+    #[test]
+    fn forward_modes() {
+        let chunk = record(&[("chunk", Msgpack::from("c-1"))]);
+
+        // Message
+        let message = Msgpack::Array(vec![
+            Msgpack::from("app.web"),
+            Msgpack::Ext(0, [1_700_000_000u32.to_be_bytes(), 5u32.to_be_bytes()].concat()),
+            record(&[("log", Msgpack::from("tek\n"))]),
+        ]);
+        let (entries, ack) = decode_message(message).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "tek");
+        assert_eq!(entries[0].level, "info");
+        assert_eq!(entries[0].extra["tag"], "app.web");
+        assert_eq!(entries[0].extra["timestamp"], "2023-11-14T22:13:20.000000005+00:00");
+        assert_eq!(ack, None);
+
+        // Forward
+        let message = Msgpack::Array(vec![
+            Msgpack::from("app"),
+            Msgpack::Array(vec![event("bir"), event("iki")]),
+            chunk.clone(),
+        ]);
+        let (entries, ack) = decode_message(message).unwrap();
+        assert_eq!(entries.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), ["bir", "iki"]);
+        assert_eq!(ack.as_deref(), Some("c-1"));
+
+        // PackedForward
+        let packed = [encode(&event("bir")), encode(&event("iki")), encode(&event("üç"))].concat();
+        let message = Msgpack::Array(vec![Msgpack::from("app"), Msgpack::Binary(packed.clone()), chunk]);
+        let (entries, ack) = decode_message(message).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].message, "üç");
+        assert_eq!(ack.as_deref(), Some("c-1"));
+
+        // CompressedPackedForward: fluent-bit her chunk'ı ayrı gzip üyesi olarak ekleyebilir
+        let gzip = |data: &[u8]| {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        };
+        let compressed = [gzip(&packed), gzip(&encode(&event("dört")))].concat();
+        let message = Msgpack::Array(vec![
+            Msgpack::from("app"),
+            Msgpack::Binary(compressed),
+            record(&[("compressed", Msgpack::from("gzip")), ("chunk", Msgpack::from("c-2"))]),
+        ]);
+        let (entries, ack) = decode_message(message).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[3].message, "dört");
+        assert_eq!(ack.as_deref(), Some("c-2"));
+
+        // Bozuk gzip mesajı reddeder
+        let message = Msgpack::Array(vec![
+            Msgpack::from("app"),
+            Msgpack::Binary(vec![1, 2, 3]),
+            record(&[("compressed", Msgpack::from("gzip"))]),
+        ]);
+        assert!(decode_message(message).is_err());
+    }
+
+    #[test]
+    fn numeric_levels_reach_the_normalizer() {
+        let normalizer = LevelNormalizer::from_config(&LevelsConfig::default()).unwrap();
+        let cases = [
+            (record(&[("level", Msgpack::from(50))]), "50", "error"),
+            (record(&[("severity", Msgpack::from(30))]), "30", "info"),
+            (record(&[("log_level", Msgpack::F64(40.0))]), "40.0", "warn"),
+            (record(&[("level", Msgpack::from("WARNING"))]), "warning", "warn"),
+        ];
+        for (record, raw, canonical) in cases {
+            let mut log = to_entry("app", &Msgpack::Nil, record);
+            assert_eq!(log.level, raw);
+            normalizer.apply(&mut log);
+            assert_eq!(log.level, canonical);
+        }
+
+        // Seviye sayılamayan bir değerse alan extra'da kalır
+        let log = to_entry("app", &Msgpack::Nil, record(&[("level", Msgpack::Array(vec![]))]));
+        assert_eq!(log.level, "info");
+        assert_eq!(log.extra["level"], serde_json::json!([]));
+    }
+}
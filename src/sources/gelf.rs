@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use axum::response::{IntoResponse, Response};
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use serde_json::{Map, Value};
+use tokio::net::UdpSocket;
+use tracing::{debug, error, info, warn};
+
+use super::from_peer;
+use super::syslog::severity_to_level;
+use crate::auth::Caller;
+use crate::config::Backpressure;
+use crate::ingest::{self, Outcome};
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// Parçalı (chunked) GELF mesajlarının sihirli baytları
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+// GELF spesifikasyonu en fazla 128 parçaya izin verir
+const MAX_CHUNKS: u8 = 128;
+// Tamamlanmayan parçalı mesajlar bu süre sonunda atılır
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(5);
+// Açılmış (decompressed) mesaj için üst sınır
+const MAX_MESSAGE: u64 = 8 * 1024 * 1024;
+
+// --- GELF (Graylog Extended Log Format) ---
+// GELF alanları LogEntry'ye şöyle eşlenir:
+//   short_message       -> message
+//   level (syslog 0-7)  -> level (varsayılan 1 = alert)
+//   timestamp (epoch)   -> extra.timestamp (RFC 3339)
+//   host, full_message  -> extra
+//   _alan               -> extra.alan (baştaki '_' atılır)
+pub fn parse(value: Value) -> Option<LogEntry> {
+    let Value::Object(fields) = value else {
+        return None;
+    };
+
+    let mut extra = Map::new();
+    extra.insert("source".to_string(), Value::String("gelf".to_string()));
+    let mut message = None;
+    let mut severity = 1u8;
+
+    for (key, value) in fields {
+        match key.as_str() {
+            "short_message" => message = value.as_str().map(str::to_string),
+            "level" => severity = value.as_u64().map(|l| l.min(7) as u8).unwrap_or(1),
+            "timestamp" => {
+                if let Some(ts) = value.as_f64().and_then(epoch_to_rfc3339) {
+                    extra.insert("timestamp".to_string(), Value::String(ts));
+                }
+            }
+            "version" => {}
+            // '_id' GELF'te yasaktır
+            "_id" => {}
+            _ => {
+                let name = key.strip_prefix('_').unwrap_or(&key).to_string();
+                extra.insert(name, value);
+            }
+        }
+    }
+
+    Some(LogEntry {
+        level: severity_to_level(severity).to_string(),
+        message: message?,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    })
+}
+
+// Epoch saniye (ondalıklı) -> RFC 3339
+fn epoch_to_rfc3339(secs: f64) -> Option<String> {
+    let nanos = (secs.fract() * 1e9).round() as u32;
+    chrono::DateTime::from_timestamp(secs.trunc() as i64, nanos.min(999_999_999)).map(|t| t.to_rfc3339())
+}
+
+// --- HTTP GELF Girişi ---
+// POST /gelf  (Graylog'un GELF HTTP input'u ile aynı: tek bir JSON nesnesi)
+pub async fn gelf_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Json(payload): Json<Value>,
+) -> Response {
+    match parse(payload) {
+        Some(mut log) => {
+            if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), 1) {
+                return rejection;
+            }
+            if let Some(rejection) = ingest::check_capacity(&state, 1) {
+                return rejection;
+            }
+            if let Some(Extension(caller)) = &caller {
+                caller.tag(&mut log);
+            }
+            if ingest::submit(&state, log).await == Outcome::Overloaded && state.backpressure == Backpressure::Reject {
+                return ingest::overloaded_response(&state);
+            }
+            StatusCode::ACCEPTED.into_response()
+        }
+        None => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+// --- GELF UDP Dinleyicisi ---
+// Datagramlar düz JSON, gzip veya zlib olabilir; büyük mesajlar parçalı gelir.
+pub async fn run_udp(bind: String, state: AppState) {
+    let socket = match UdpSocket::bind(&bind).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            error!("❌ GELF UDP {} dinlenemedi: {}", bind, e);
+            return;
+        }
+    };
+    info!("📡 GELF UDP dinleyicisi {} adresinde çalışıyor...", bind);
+
+    let mut buf = vec![0u8; 65_535];
+    let mut pending = Reassembler::default();
+    loop {
+        let (len, peer) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            Err(e) => {
+                error!("❌ GELF UDP okuma hatası: {}", e);
+                continue;
+            }
+        };
+
+        let datagram = &buf[..len];
+        let payload = if datagram.starts_with(&CHUNK_MAGIC) {
+            match pending.push(datagram) {
+                Some(complete) => complete,
+                None => continue,
+            }
+        } else {
+            datagram.to_vec()
+        };
+
+        let value = match decompress(&payload).and_then(|raw| serde_json::from_slice::<Value>(&raw).ok()) {
+            Some(value) => value,
+            None => {
+                warn!("⚠️ Geçersiz GELF datagramı atlandı");
+                continue;
+            }
+        };
+        if let Some(log) = parse(value) {
+            debug!("📥 GELF mesajı alındı");
+            ingest::submit(&state, from_peer(log, peer)).await;
+        }
+    }
+}
+
+// gzip (1f 8b), zlib (78 ..) veya sıkıştırılmamış JSON
+fn decompress(payload: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    match payload {
+        [0x1f, 0x8b, ..] => {
+            flate2::read::GzDecoder::new(payload).take(MAX_MESSAGE).read_to_end(&mut out).ok()?;
+        }
+        [0x78, ..] => {
+            flate2::read::ZlibDecoder::new(payload).take(MAX_MESSAGE).read_to_end(&mut out).ok()?;
+        }
+        _ => return Some(payload.to_vec()),
+    }
+    Some(out)
+}
+
+// Parçalı mesajları birleştirir.
+// Parça başlığı: magic (2) + mesaj id (8) + sıra no (1) + toplam parça (1)
+#[derive(Default)]
+struct Reassembler {
+    messages: HashMap<[u8; 8], Partial>,
+}
+
+struct Partial {
+    started: Instant,
+    chunks: Vec<Option<Vec<u8>>>,
+}
+
+impl Reassembler {
+    fn push(&mut self, datagram: &[u8]) -> Option<Vec<u8>> {
+        // Süresi dolan yarım mesajları temizle
+        self.messages.retain(|_, p| p.started.elapsed() < CHUNK_TIMEOUT);
+
+        if datagram.len() < 12 {
+            return None;
+        }
+        let id: [u8; 8] = datagram[2..10].try_into().ok()?;
+        let seq = datagram[10];
+        let count = datagram[11];
+        if count == 0 || count > MAX_CHUNKS || seq >= count {
+            return None;
+        }
+
+        let partial = self.messages.entry(id).or_insert_with(|| Partial {
+            started: Instant::now(),
+            chunks: vec![None; count as usize],
+        });
+        if partial.chunks.len() != count as usize {
+            return None;
+        }
+        partial.chunks[seq as usize] = Some(datagram[12..].to_vec());
+
+        if partial.chunks.iter().all(Option::is_some) {
+            let partial = self.messages.remove(&id)?;
+            return Some(partial.chunks.into_iter().flatten().flatten().collect());
+        }
+        None
+    }
+}
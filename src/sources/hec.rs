@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use chrono::DateTime;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+use crate::auth::{self, Caller};
+use crate::config::{Backpressure, HecConfig};
+use crate::ingest::{self, Outcome};
+use crate::pipeline::Ack;
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// --- Splunk HTTP Event Collector Uyumluluğu ---
+// Splunk evrensel iletici (HEC çıkışı), Splunk SDK'ları, Fluent Bit 'splunk' ve
+// Vector 'splunk_hec_logs' çıkışları bu sunucuya yazabilir:
+//   POST /services/collector[/event[/1.0]]   olay zarfları (art arda JSON nesneleri)
+//   POST /services/collector/ack             kanal onayları
+//   GET  /services/collector/health          sağlık
+// HEC token'ı API anahtarıdır: "Authorization: Splunk <anahtar>".
+//
+// Zarf alanları şöyle eşlenir:
+//   event (metin)                 -> message
+//   event (nesne)                 -> message alanı message, diğerleri extra
+//   time (saniye, kesirli)        -> extra.timestamp
+//   host / sourcetype / index     -> extra.host / extra.sourcetype / extra.index
+//   source                        -> extra.splunk_source
+//   fields                        -> extra
+//   level / severity              -> level (event nesnesinde veya fields'da; yoksa "info")
+pub async fn event_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HecQuery>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let events = match parse_events(&body) {
+        Ok(events) => events,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let channel = headers
+        .get("x-splunk-request-channel")
+        .and_then(|v| v.to_str().ok())
+        .or(query.channel.as_deref());
+    // Onaylar açıkken her isteğin bir kanalı olmalıdır (Splunk ile aynı)
+    let channel = match (state.hec.enabled(), channel) {
+        (false, _) => None,
+        (true, None) => return reply(StatusCode::BAD_REQUEST, 10, "Data channel is missing"),
+        (true, Some(channel)) if !is_guid(channel) => return reply(StatusCode::BAD_REQUEST, 11, "Invalid data channel"),
+        (true, Some(channel)) => Some(format!("{}/{}", auth::tenant_of(caller.as_deref()), channel.to_ascii_lowercase())),
+    };
+
+    debug!("📥 HEC isteği alındı: {} olay", events.len());
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), events.len()) {
+        return rejection;
+    }
+    if let Some(rejection) = ingest::check_capacity(&state, events.len()) {
+        return rejection;
+    }
+    let (ack_id, ack) = match &channel {
+        Some(channel) => match state.hec.register(channel) {
+            Some(id) => (Some(id), Some(Ack::group(events.len()))),
+            None => return reply(StatusCode::SERVICE_UNAVAILABLE, 9, "Server is busy"),
+        },
+        None => (None, None),
+    };
+
+    let mut overloaded = false;
+    for event in events {
+        let mut log = convert(event, &query);
+        if let Some(Extension(caller)) = &caller {
+            caller.tag(&mut log);
+        }
+        let ack = ack.as_ref().map(|(ack, _)| ack.clone());
+        overloaded |= ingest::submit_with_ack(&state, log, ack).await == Outcome::Overloaded;
+    }
+
+    match (ack_id, ack, channel) {
+        (Some(id), Some((ack, done)), Some(channel)) => {
+            drop(ack);
+            let acks = state.hec.clone();
+            tokio::spawn(async move {
+                acks.finish(&channel, id, done.await.unwrap_or(false));
+            });
+            (StatusCode::OK, Json(json!({ "text": "Success", "code": 0, "ackId": id }))).into_response()
+        }
+        _ if overloaded && state.backpressure == Backpressure::Reject => ingest::overloaded_response(&state),
+        _ => reply(StatusCode::OK, 0, "Success"),
+    }
+}
+
+// İstek adresiyle verilen varsayılanlar (zarfta olmayan alanlar için)
+#[derive(Debug, Default, Deserialize)]
+pub struct HecQuery {
+    channel: Option<String>,
+    host: Option<String>,
+    source: Option<String>,
+    sourcetype: Option<String>,
+    index: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    time: Option<Value>,
+    host: Option<String>,
+    source: Option<String>,
+    sourcetype: Option<String>,
+    index: Option<String>,
+    event: Option<Value>,
+    #[serde(default)]
+    fields: Map<String, Value>,
+}
+
+// Gövde art arda (ayraçsız veya satır satır) JSON nesneleridir. Hatalı olayın
+// sırası Splunk'taki gibi invalidEventNumber ile bildirilir.
+fn parse_events(body: &[u8]) -> Result<Vec<Event>, Rejection> {
+    let mut events = Vec::new();
+    for (number, event) in serde_json::Deserializer::from_slice(body).into_iter::<Event>().enumerate() {
+        let event = event.map_err(|_| Rejection { code: 6, text: "Invalid data format", event: Some(number) })?;
+        match &event.event {
+            None | Some(Value::Null) => {
+                return Err(Rejection { code: 12, text: "Event field is required", event: Some(number) });
+            }
+            Some(Value::String(text)) if text.trim().is_empty() => {
+                return Err(Rejection { code: 13, text: "Event field cannot be blank", event: Some(number) });
+            }
+            _ => {}
+        }
+        events.push(event);
+    }
+    if events.is_empty() {
+        return Err(Rejection { code: 5, text: "No data", event: None });
+    }
+    Ok(events)
+}
+
+// Gövdenin reddedilme nedeni (HEC hata kodu ve hatalı olayın sırası)
+struct Rejection {
+    code: u16,
+    text: &'static str,
+    event: Option<usize>,
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        let mut body = json!({ "text": self.text, "code": self.code });
+        if let Some(number) = self.event {
+            body["invalidEventNumber"] = json!(number);
+        }
+        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+    }
+}
+
+fn convert(event: Event, query: &HecQuery) -> LogEntry {
+    let mut extra = Map::new();
+    let mut level = None;
+    let message = match event.event.unwrap_or_default() {
+        Value::String(message) => message,
+        Value::Object(mut object) => {
+            level = take_level(&mut object);
+            let message = match object.remove("message") {
+                Some(Value::String(message)) => message,
+                Some(other) => other.to_string(),
+                None => Value::Object(object.clone()).to_string(),
+            };
+            extra.extend(object);
+            message
+        }
+        other => other.to_string(),
+    };
+    let mut fields = event.fields;
+    level = level.or_else(|| take_level(&mut fields));
+    for (key, value) in fields {
+        extra.entry(key).or_insert(value);
+    }
+
+    extra.entry("source").or_insert_with(|| Value::String("hec".to_string()));
+    if let Some(time) = event.time.as_ref().and_then(parse_time) {
+        extra.entry("timestamp").or_insert(Value::String(time));
+    }
+    let metadata = [
+        ("host", event.host.or_else(|| query.host.clone())),
+        ("splunk_source", event.source.or_else(|| query.source.clone())),
+        ("sourcetype", event.sourcetype.or_else(|| query.sourcetype.clone())),
+        ("index", event.index.or_else(|| query.index.clone())),
+    ];
+    for (key, value) in metadata {
+        if let Some(value) = value {
+            extra.entry(key).or_insert(Value::String(value));
+        }
+    }
+
+    LogEntry {
+        level: level.unwrap_or_else(|| "info".to_string()),
+        message,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+fn take_level(object: &mut Map<String, Value>) -> Option<String> {
+    ["level", "severity"].into_iter().find_map(|key| match object.remove(key)? {
+        Value::String(level) if !level.is_empty() => Some(level),
+        Value::Number(level) => Some(level.to_string()),
+        other => {
+            object.insert(key.to_string(), other);
+            None
+        }
+    })
+}
+
+// "1426279439.123" veya 1426279439.123 (Unix saniyesi)
+fn parse_time(time: &Value) -> Option<String> {
+    let seconds = match time {
+        Value::Number(seconds) => seconds.as_f64()?,
+        Value::String(seconds) => seconds.trim().parse::<f64>().ok()?,
+        _ => return None,
+    };
+    let time = DateTime::from_timestamp_micros((seconds * 1_000_000.0).round() as i64)?;
+    Some(time.to_rfc3339())
+}
+
+// Splunk kanal kimlikleri GUID'dir: 8-4-4-4-12 onaltılık
+fn is_guid(channel: &str) -> bool {
+    let parts: Vec<&str> = channel.split('-').collect();
+    parts.len() == 5
+        && parts.iter().zip([8, 4, 4, 4, 12]).all(|(part, len)| part.len() == len && part.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+fn reply(status: StatusCode, code: u16, text: &str) -> Response {
+    (status, Json(json!({ "text": text, "code": code }))).into_response()
+}
+
+// POST /services/collector/ack  {"acks": [0, 1, 2]} -> {"acks": {"0": true, "1": false, ...}}
+// true: isteğin tüm olayları veritabanına yazıldı. Sorgulanan true onaylar silinir;
+// bilinmeyen, bekleyen ve yazılamamış olanlar false döner (istemci zamanı gelince
+// isteği tekrar gönderir).
+pub async fn ack_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HecQuery>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !state.hec.enabled() {
+        return reply(StatusCode::BAD_REQUEST, 14, "ACK is disabled");
+    }
+    let channel = headers
+        .get("x-splunk-request-channel")
+        .and_then(|v| v.to_str().ok())
+        .or(query.channel.as_deref());
+    let channel = match channel {
+        None => return reply(StatusCode::BAD_REQUEST, 10, "Data channel is missing"),
+        Some(channel) if !is_guid(channel) => return reply(StatusCode::BAD_REQUEST, 11, "Invalid data channel"),
+        Some(channel) => format!("{}/{}", auth::tenant_of(caller.as_deref()), channel.to_ascii_lowercase()),
+    };
+    #[derive(Deserialize)]
+    struct AckRequest {
+        acks: Vec<u64>,
+    }
+    let request = match serde_json::from_slice::<AckRequest>(&body) {
+        Ok(request) => request,
+        Err(_) => return reply(StatusCode::BAD_REQUEST, 6, "Invalid data format"),
+    };
+    let statuses = state.hec.query(&channel, &request.acks);
+    let acks: Map<String, Value> = statuses.into_iter().map(|(id, done)| (id.to_string(), Value::Bool(done))).collect();
+    Json(json!({ "acks": acks })).into_response()
+}
+
+// GET /services/collector/health: kanal doluysa 503 (yük dengeleyiciler için)
+pub async fn health_handler(State(state): State<AppState>) -> Response {
+    if state.tx.free() == 0 {
+        return reply(StatusCode::SERVICE_UNAVAILABLE, 18, "HEC is unhealthy, queues are full");
+    }
+    reply(StatusCode::OK, 17, "HEC is healthy")
+}
+
+// --- Kanal Onayları ---
+// Her kanal (kiracı + istemcinin GUID'i) kendi onay numaralarını 0'dan sayar.
+// Bir istemcinin uzun süre kullanmadığı kanalın onayları silinir.
+pub struct HecAcks {
+    enabled: bool,
+    idle_timeout: Duration,
+    max_pending: usize,
+    channels: Mutex<HashMap<String, Channel>>,
+}
+
+struct Channel {
+    next_id: u64,
+    // Sonuçlanmamış onaylar None, sonuçlananlar yazıldıysa Some(true)
+    statuses: HashMap<u64, Option<bool>>,
+    last_used: Instant,
+}
+
+impl HecAcks {
+    pub fn from_config(config: &HecConfig) -> Self {
+        HecAcks {
+            enabled: config.ack,
+            idle_timeout: Duration::from_secs(config.ack_idle_timeout_secs),
+            max_pending: config.max_pending_acks.max(1),
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Yeni onay numarası; kanalda sorgulanmamış onay sayısı sınırdaysa None.
+    fn register(&self, channel: &str) -> Option<u64> {
+        let mut channels = self.channels.lock().unwrap();
+        let now = Instant::now();
+        channels.retain(|_, channel| now.duration_since(channel.last_used) < self.idle_timeout);
+        let channel = channels.entry(channel.to_string()).or_insert_with(|| Channel {
+            next_id: 0,
+            statuses: HashMap::new(),
+            last_used: now,
+        });
+        channel.last_used = now;
+        if channel.statuses.len() >= self.max_pending {
+            return None;
+        }
+        let id = channel.next_id;
+        channel.next_id += 1;
+        channel.statuses.insert(id, None);
+        Some(id)
+    }
+
+    fn finish(&self, channel: &str, id: u64, ok: bool) {
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(channel) {
+            if let Some(status) = channel.statuses.get_mut(&id) {
+                *status = Some(ok);
+            }
+        }
+    }
+
+    fn query(&self, channel: &str, ids: &[u64]) -> Vec<(u64, bool)> {
+        let mut channels = self.channels.lock().unwrap();
+        let Some(channel) = channels.get_mut(channel) else {
+            return ids.iter().map(|id| (*id, false)).collect();
+        };
+        channel.last_used = Instant::now();
+        ids.iter()
+            .map(|id| match channel.statuses.get(id) {
+                Some(Some(ok)) => {
+                    let ok = *ok;
+                    channel.statuses.remove(id);
+                    (*id, ok)
+                }
+                _ => (*id, false),
+            })
+            .collect()
+    }
+}
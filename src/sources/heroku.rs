@@ -0,0 +1,175 @@
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use chrono::Utc;
+use serde_json::{Map, Value};
+use tracing::debug;
+
+use super::syslog::{next_frames, parse_pri, severity_to_level};
+use crate::auth::Caller;
+use crate::config::Backpressure;
+use crate::ingest::{self, Outcome};
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// --- Heroku Log Drain (Logplex) ---
+// POST /logplex  (Content-Type: application/logplex-1)
+// Gövde octet counting ile çerçevelenmiş syslog satırlarıdır:
+//   83 <40>1 2012-11-30T06:45:29+00:00 host app web.3 - State changed from starting to up
+// Logplex bu biçimde yapılandırılmış veri (STRUCTURED-DATA) göndermez; mesaj
+// MSGID'den hemen sonra başlar. Alanlar şöyle eşlenir:
+//   PRI                 -> level (syslog ölçeği)
+//   APP-NAME            -> extra.app ("app" uygulama, "heroku" platform logları)
+//   PROCID              -> extra.dyno ("web.1", "router", ...)
+//   Logplex-Drain-Token -> extra.drain_token (hangi uygulamanın drain'i)
+// Platform loglarındaki logfmt alanları (at=error code=H12 ...) extra'ya açılır;
+// 'at' seviyeyi belirler.
+pub async fn logplex_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !content_type.starts_with("application/logplex-1") {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "sadece application/logplex-1 desteklenir").into_response();
+    }
+
+    let mut buffer = body.to_vec();
+    let frames = match next_frames(&mut buffer) {
+        Ok(frames) => frames,
+        Err(reason) => return (StatusCode::BAD_REQUEST, reason).into_response(),
+    };
+    if !buffer.is_empty() {
+        return (StatusCode::BAD_REQUEST, "yarım kalmış Logplex çerçevesi").into_response();
+    }
+    let expected = headers
+        .get("logplex-msg-count")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<usize>().ok());
+    if let Some(expected) = expected.filter(|expected| *expected != frames.len()) {
+        let message = format!("Logplex-Msg-Count {} ama gövdede {} mesaj var", expected, frames.len());
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+    let drain_token = headers.get("logplex-drain-token").and_then(|v| v.to_str().ok());
+    debug!(
+        "📥 Logplex isteği alındı: {} mesaj (çerçeve {})",
+        frames.len(),
+        headers.get("logplex-frame-id").and_then(|v| v.to_str().ok()).unwrap_or("-")
+    );
+
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), frames.len()) {
+        return rejection;
+    }
+    if let Some(rejection) = ingest::check_capacity(&state, frames.len()) {
+        return rejection;
+    }
+    let mut overloaded = false;
+    for frame in frames {
+        let mut log = parse(&frame);
+        if let (Some(token), Value::Object(map)) = (drain_token, &mut log.extra) {
+            map.insert("drain_token".to_string(), Value::String(token.to_string()));
+        }
+        if let Some(Extension(caller)) = &caller {
+            caller.tag(&mut log);
+        }
+        overloaded |= ingest::submit(&state, log).await == Outcome::Overloaded;
+    }
+
+    if overloaded && state.backpressure == Backpressure::Reject {
+        return ingest::overloaded_response(&state);
+    }
+    // Logplex 2xx dışındaki yanıtlarda aynı çerçeveyi tekrar gönderir
+    StatusCode::NO_CONTENT.into_response()
+}
+
+// "<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID MSG" -> LogEntry
+pub fn parse(frame: &str) -> LogEntry {
+    let mut extra = Map::new();
+    extra.insert("source".to_string(), Value::String("heroku".to_string()));
+
+    let Some((pri, rest)) = parse_pri(frame) else {
+        return LogEntry {
+            level: "info".to_string(),
+            message: frame.to_string(),
+            extra: Value::Object(extra),
+            tenant_id: crate::default_tenant(),
+            source: crate::Source::default(),
+        };
+    };
+    let mut level = severity_to_level(pri % 8);
+    extra.insert("severity".to_string(), Value::from(pri % 8));
+
+    let mut fields = rest.strip_prefix("1 ").unwrap_or(rest).splitn(6, ' ');
+    let timestamp = fields.next().unwrap_or("-");
+    let _hostname = fields.next();
+    let app = fields.next().unwrap_or("-");
+    let dyno = fields.next().unwrap_or("-");
+    let _msgid = fields.next();
+    let message = fields.next().unwrap_or("").trim_start_matches('\u{feff}');
+
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(timestamp) {
+        extra.insert("timestamp".to_string(), Value::String(time.with_timezone(&Utc).to_rfc3339()));
+    }
+    if app != "-" {
+        extra.insert("app".to_string(), Value::String(app.to_string()));
+    }
+    if dyno != "-" {
+        extra.insert("dyno".to_string(), Value::String(dyno.to_string()));
+    }
+    // Platform (router, dyno yöneticisi) logları logfmt'dir
+    if app == "heroku" {
+        for (key, value) in logfmt(message) {
+            if key == "at" {
+                level = match value.as_str() {
+                    "error" => "error",
+                    "warning" | "warn" => "warn",
+                    _ => level,
+                };
+            }
+            extra.entry(key).or_insert(Value::String(value));
+        }
+    }
+
+    LogEntry {
+        level: level.to_string(),
+        message: message.to_string(),
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// key=value key2="boşluklu değer" -> [(key, value), ...]; değersiz kelimeler atlanır
+fn logfmt(input: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let end = rest.find([' ', '=']).unwrap_or(rest.len());
+        let key = &rest[..end];
+        rest = &rest[end..];
+        let Some(after) = rest.strip_prefix('=') else {
+            rest = rest.trim_start();
+            continue;
+        };
+        let (value, tail) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.find('"') {
+                Some(close) => (&quoted[..close], &quoted[close + 1..]),
+                None => (quoted, ""),
+            },
+            None => {
+                let end = after.find(' ').unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        if !key.is_empty() {
+            pairs.push((key.to_string(), value.to_string()));
+        }
+        rest = tail.trim_start();
+    }
+    pairs
+}
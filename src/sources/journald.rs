@@ -0,0 +1,203 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tracing::{debug, error, info, warn};
+
+use super::syslog::severity_to_level;
+use crate::config::{FileStart, JournaldConfig};
+use crate::ingest;
+use crate::pipeline::Ack;
+use crate::{AppState, LogEntry};
+
+// journalctl kapandığında veya yazma başarısız olduğunda yeniden başlatmadan önce beklenen süre
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+// --- journald Girişi ---
+// journalctl --output=json --follow çıktısını okur; her kayıt yapılandırılmış
+// alanlarıyla birlikte boru hattına girer. Harici kütüphane (libsystemd) gerekmez.
+//
+// Batch'teki kayıtlar veritabanına yazıldıktan sonra son kaydın imleci
+// (__CURSOR) state_path dosyasına işlenir; yeniden başlayınca --after-cursor ile
+// kalınan yerden devam edilir (en az bir kez teslim). Yazma başarısız olursa
+// journalctl durdurulur ve kayıtlı imleçten yeniden başlatılır.
+pub async fn run(config: JournaldConfig, state: AppState, batch_size: usize, flush_interval: Duration) {
+    let mut cursor = std::fs::read_to_string(&config.state_path)
+        .ok()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    info!(
+        "📡 journald okunuyor (birimler: {:?}, önem: {}, {})",
+        config.units,
+        config.priority.as_deref().unwrap_or("hepsi"),
+        if cursor.is_some() { "kayıtlı imleçten" } else { "imleç yok" }
+    );
+
+    loop {
+        let mut command = Command::new(&config.journalctl);
+        command.args(["--output=json", "--follow", "--no-pager", "--all"]);
+        match &cursor {
+            Some(cursor) => {
+                command.arg(format!("--after-cursor={}", cursor));
+            }
+            None if config.start_at == FileStart::End => {
+                command.arg("--lines=0");
+            }
+            None => {
+                command.arg("--lines=all");
+            }
+        }
+        for unit in &config.units {
+            command.arg(format!("--unit={}", unit));
+        }
+        if let Some(priority) = &config.priority {
+            command.arg(format!("--priority={}", priority));
+        }
+        if let Some(directory) = &config.directory {
+            command.arg(format!("--directory={}", directory));
+        }
+        let mut child = match command.stdout(Stdio::piped()).stdin(Stdio::null()).kill_on_drop(true).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                error!("❌ {} başlatılamadı, journald girişi kapalı: {}", config.journalctl, e);
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        let mut lines = BufReader::new(stdout).lines();
+
+        loop {
+            // --- Batch topla (Kafka tüketicisi gibi) ---
+            let mut entries = Vec::new();
+            let mut last_cursor = None;
+            let mut closed = false;
+            // İlk satır süresiz beklenir; süre ondan sonra başlar
+            let mut deadline = None;
+            while entries.len() < batch_size.max(1) {
+                let next = match deadline {
+                    None => lines.next_line().await,
+                    Some(deadline) => match tokio::time::timeout_at(deadline, lines.next_line()).await {
+                        Ok(next) => next,
+                        Err(_) => break,
+                    },
+                };
+                deadline.get_or_insert_with(|| tokio::time::Instant::now() + flush_interval);
+                let line = match next {
+                    Ok(Some(line)) => line,
+                    Ok(None) => {
+                        closed = true;
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("⚠️ journalctl çıktısı okunamadı: {}", e);
+                        closed = true;
+                        break;
+                    }
+                };
+                let fields = match serde_json::from_str::<Map<String, Value>>(&line) {
+                    Ok(fields) => fields,
+                    Err(e) => {
+                        debug!("Geçersiz journalctl satırı atlandı: {}", e);
+                        continue;
+                    }
+                };
+                if let Some(Value::String(cursor)) = fields.get("__CURSOR") {
+                    last_cursor = Some(cursor.clone());
+                }
+                entries.push(parse(fields));
+            }
+
+            if !entries.is_empty() {
+                debug!("📥 journald batch'i alındı: {} kayıt", entries.len());
+                let (ack, done) = Ack::group(entries.len());
+                for log in entries {
+                    ingest::submit_with_ack(&state, log, Some(ack.clone())).await;
+                }
+                drop(ack);
+                if !done.await.unwrap_or(false) {
+                    error!("❌ journald kayıtları veritabanına yazılamadı, kayıtlı imleçten tekrar okunacak.");
+                    break;
+                }
+            }
+            if let Some(last) = last_cursor {
+                if let Err(e) = std::fs::write(&config.state_path, &last) {
+                    warn!("⚠️ journald imleci {} dosyasına yazılamadı: {}", config.state_path, e);
+                }
+                cursor = Some(last);
+            }
+            if closed {
+                warn!("⚠️ journalctl kapandı, yeniden başlatılacak.");
+                break;
+            }
+        }
+
+        let _ = child.kill().await;
+        tokio::time::sleep(RESTART_DELAY).await;
+    }
+}
+
+// --- Kayıt Ayrıştırıcı ---
+// MESSAGE -> message, PRIORITY -> level (syslog ölçeği), __REALTIME_TIMESTAMP ->
+// timestamp. Diğer alanlar baştaki '_' atılıp küçük harfe çevrilerek extra'ya
+// eklenir (_SYSTEMD_UNIT -> systemd_unit, _HOSTNAME -> hostname). '__' ile
+// başlayan iç alanlar (imleç, monotonik zaman) eklenmez.
+pub fn parse(fields: Map<String, Value>) -> LogEntry {
+    let mut extra = Map::new();
+    extra.insert("source".to_string(), Value::String("journald".to_string()));
+
+    let mut level = "info";
+    let mut message = String::new();
+    for (key, value) in fields {
+        match key.as_str() {
+            "MESSAGE" => message = text(value).unwrap_or_default(),
+            "PRIORITY" => {
+                if let Some(severity) = text(value).and_then(|p| p.parse::<u8>().ok()) {
+                    level = severity_to_level(severity);
+                    extra.insert("severity".to_string(), Value::from(severity));
+                }
+            }
+            "__REALTIME_TIMESTAMP" => {
+                let time = text(value)
+                    .and_then(|micros| micros.parse::<i64>().ok())
+                    .and_then(DateTime::from_timestamp_micros);
+                if let Some(time) = time {
+                    extra.insert("timestamp".to_string(), Value::String(time.to_rfc3339()));
+                }
+            }
+            _ if key.starts_with("__") => {}
+            _ => {
+                if let Some(value) = text(value) {
+                    extra.insert(key.trim_start_matches('_').to_ascii_lowercase(), Value::String(value));
+                }
+            }
+        }
+    }
+
+    LogEntry {
+        level: level.to_string(),
+        message,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// journalctl alan değerleri metindir; yazdırılamayan veya UTF-8 olmayan değerler
+// bayt dizisi olarak gelir. Aynı alan birden fazla kez geçtiyse değerler dizidir
+// (sonuncusu alınır).
+fn text(value: Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Array(items) if items.iter().all(Value::is_u64) => {
+            let bytes: Vec<u8> = items.iter().filter_map(Value::as_u64).map(|b| b as u8).collect();
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        Value::Array(items) => items.into_iter().rev().find_map(text),
+        _ => None,
+    }
+}
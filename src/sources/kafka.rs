@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::Message;
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::{debug, error, info, warn};
+
+use crate::config::KafkaConfig;
+use crate::ingest;
+use crate::pipeline::Ack;
+use crate::{AppState, LogEntry};
+
+// Yazma başarısız olduğunda aynı batch tekrar denenmeden önce beklenen süre
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// Kafka mesajı tek bir log nesnesi ya da log dizisi olabilir (/ingest gövdesiyle aynı)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Payload {
+    One(LogEntry),
+    Many(Vec<LogEntry>),
+}
+
+// --- Kafka Tüketicisi ---
+// Yapılandırılan topic'lerden JSON logları okur ve boru hattına sokar.
+// Offset'ler otomatik commit edilmez: bir batch'teki tüm loglar veritabanına
+// yazıldıktan sonra commit edilir (en az bir kez teslim). Yazma başarısız olursa
+// bölümler batch'in başına geri sarılır ve aynı mesajlar tekrar okunur.
+pub async fn run(config: KafkaConfig, state: AppState, batch_size: usize, flush_interval: Duration) {
+    let brokers = config.brokers.unwrap_or_default();
+    let consumer: StreamConsumer = match ClientConfig::new()
+        .set("bootstrap.servers", &brokers)
+        .set("group.id", &config.group_id)
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "earliest")
+        .create()
+    {
+        Ok(consumer) => consumer,
+        Err(e) => {
+            error!("❌ Kafka tüketicisi oluşturulamadı: {}", e);
+            return;
+        }
+    };
+    let topics: Vec<&str> = config.topics.iter().map(String::as_str).collect();
+    if let Err(e) = consumer.subscribe(&topics) {
+        error!("❌ Kafka topic'lerine abone olunamadı {:?}: {}", topics, e);
+        return;
+    }
+    info!("📡 Kafka tüketicisi çalışıyor: {} -> {:?} (grup: {})", brokers, topics, config.group_id);
+
+    let batch_size = batch_size.max(1);
+    loop {
+        // --- Batch topla ---
+        // İlk mesajı süresiz bekle, sonrakileri 'flush_interval' dolana kadar topla.
+        let mut entries = Vec::new();
+        // (topic, bölüm) -> (ilk offset, son offset)
+        let mut offsets: HashMap<(String, i32), (i64, i64)> = HashMap::new();
+        let deadline = tokio::time::Instant::now() + flush_interval;
+        let mut messages = 0;
+        while messages < batch_size {
+            let received = if messages == 0 {
+                consumer.recv().await
+            } else {
+                match tokio::time::timeout_at(deadline, consumer.recv()).await {
+                    Ok(received) => received,
+                    Err(_) => break,
+                }
+            };
+            let message = match received {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("⚠️ Kafka okuma hatası: {}", e);
+                    continue;
+                }
+            };
+            messages += 1;
+
+            offsets
+                .entry((message.topic().to_string(), message.partition()))
+                .and_modify(|(_, last)| *last = message.offset())
+                .or_insert((message.offset(), message.offset()));
+
+            match message.payload().map(serde_json::from_slice::<Payload>) {
+                Some(Ok(Payload::One(log))) => entries.push(tag(log, message.topic())),
+                Some(Ok(Payload::Many(logs))) => {
+                    entries.extend(logs.into_iter().map(|log| tag(log, message.topic())))
+                }
+                // Geçersiz mesajlar atlanır; offset'leri yine de commit edilir
+                Some(Err(e)) => warn!(
+                    "⚠️ Geçersiz Kafka mesajı atlandı ({}/{}@{}): {}",
+                    message.topic(),
+                    message.partition(),
+                    message.offset(),
+                    e
+                ),
+                None => {}
+            }
+        }
+        debug!("📥 Kafka batch'i alındı: {} mesaj, {} log", messages, entries.len());
+
+        // --- Boru hattına gönder ve yazılmasını bekle ---
+        let written = if entries.is_empty() {
+            true
+        } else {
+            let (ack, done) = Ack::group(entries.len());
+            for log in entries {
+                ingest::submit_with_ack(&state, log, Some(ack.clone())).await;
+            }
+            drop(ack);
+            done.await.unwrap_or(false)
+        };
+
+        if written {
+            let mut commit = TopicPartitionList::new();
+            for ((topic, partition), (_, last)) in &offsets {
+                if let Err(e) = commit.add_partition_offset(topic, *partition, Offset::Offset(last + 1)) {
+                    warn!("⚠️ Kafka offset'i eklenemedi: {}", e);
+                }
+            }
+            if let Err(e) = consumer.commit(&commit, CommitMode::Async) {
+                warn!("⚠️ Kafka offset commit başarısız: {}", e);
+            }
+        } else {
+            error!("❌ Kafka batch'i veritabanına yazılamadı, {} mesaj tekrar okunacak", messages);
+            for ((topic, partition), (first, _)) in &offsets {
+                if let Err(e) = consumer.seek(topic, *partition, Offset::Offset(*first), Duration::from_secs(5)) {
+                    warn!("⚠️ Kafka {}/{} geri sarılamadı: {}", topic, partition, e);
+                }
+            }
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+}
+
+// Diğer kaynaklarda olduğu gibi kaynağı ve topic'i ayrıntılara ekler
+fn tag(mut log: LogEntry, topic: &str) -> LogEntry {
+    if let Value::Object(ref mut map) = log.extra {
+        map.entry("source").or_insert_with(|| Value::String("kafka".to_string()));
+        map.insert("kafka_topic".to_string(), Value::String(topic.to_string()));
+    }
+    log
+}
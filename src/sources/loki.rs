@@ -0,0 +1,302 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use chrono::DateTime;
+use prost::Message;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tracing::{debug, warn};
+
+use crate::auth::Caller;
+use crate::config::Backpressure;
+use crate::ingest::{self, Outcome};
+use crate::limits;
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// Seviyenin okunduğu etiketler / yapılandırılmış üst veri alanları (sırayla)
+const LEVEL_LABELS: [&str; 4] = ["level", "detected_level", "severity", "lvl"];
+
+// --- Loki Push API Uyumluluğu ---
+// POST /loki/api/v1/push: promtail, Grafana Agent / Alloy ve Loki istemcileri
+// hiçbir değişiklik yapmadan bu sunucuya gönderebilir. İki biçim desteklenir:
+//   - application/json: {"streams":[{"stream":{...},"values":[["ns","satır",{...}]]}]}
+//     (gzip gövdeler RequestDecompressionLayer ile açılır)
+//   - application/x-protobuf: snappy ile sıkıştırılmış logproto.PushRequest
+// Eşleme:
+//   satır                     -> message (satır bir JSON log nesnesiyse olduğu gibi alınır)
+//   zaman (nanosaniye)        -> extra.timestamp
+//   akış etiketleri           -> extra (service_name, host, env ... kolonlara taşınır)
+//   yapılandırılmış üst veri  -> extra
+//   level / detected_level    -> level (yoksa "info")
+pub async fn push_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/x-protobuf");
+    let streams = if content_type.starts_with("application/json") {
+        serde_json::from_slice::<JsonPush>(&body)
+            .map(JsonPush::into_streams)
+            .map_err(|e| e.to_string())
+    } else if content_type.starts_with("application/x-protobuf") {
+        // Açılmış uzunluk snappy başlığından okunur; gzip gövdeler gibi açılmış hali
+        // server.max_body_bytes ile sınırlıdır ve aşan gövde için yer ayrılmaz
+        if snap::raw::decompress_len(&body).is_ok_and(|length| length > state.max_body_bytes) {
+            debug!("📦 Loki push gövdesi açılınca çok büyük: {} bayt", body.len());
+            return limits::payload_too_large(state.max_body_bytes);
+        }
+        decode_protobuf(&body)
+    } else {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "sadece application/json ve application/x-protobuf desteklenir")
+            .into_response();
+    };
+    let streams = match streams {
+        Ok(streams) => streams,
+        Err(e) => {
+            warn!("⚠️ Loki push isteği çözülemedi: {}", e);
+            return (StatusCode::BAD_REQUEST, format!("geçersiz push isteği: {}", e)).into_response();
+        }
+    };
+
+    let count = streams.iter().map(|s| s.entries.len()).sum();
+    debug!("📥 Loki push isteği alındı: {} akış, {} satır", streams.len(), count);
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), count) {
+        return rejection;
+    }
+    if let Some(rejection) = ingest::check_capacity(&state, count) {
+        return rejection;
+    }
+    let mut overloaded = false;
+    for stream in streams {
+        for entry in &stream.entries {
+            let mut log = convert(&stream.labels, entry);
+            if let Some(Extension(caller)) = &caller {
+                caller.tag(&mut log);
+            }
+            overloaded |= ingest::submit(&state, log).await == Outcome::Overloaded;
+        }
+    }
+
+    if overloaded && state.backpressure == Backpressure::Reject {
+        return ingest::overloaded_response(&state);
+    }
+    // Loki başarılı push'a 204 döner
+    StatusCode::NO_CONTENT.into_response()
+}
+
+// Biçimden bağımsız akış
+struct Stream {
+    labels: Vec<(String, String)>,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    nanos: i64,
+    line: String,
+    metadata: Vec<(String, String)>,
+}
+
+fn convert(labels: &[(String, String)], entry: &Entry) -> LogEntry {
+    let mut log = match serde_json::from_str::<LogEntry>(&entry.line) {
+        Ok(log) if entry.line.starts_with('{') => log,
+        _ => LogEntry {
+            level: String::new(),
+            message: entry.line.clone(),
+            extra: Value::Object(Map::new()),
+            tenant_id: crate::default_tenant(),
+            source: crate::Source::default(),
+        },
+    };
+
+    let mut level = None;
+    if let Value::Object(ref mut map) = log.extra {
+        map.entry("source").or_insert_with(|| Value::String("loki".to_string()));
+        let time = DateTime::from_timestamp_nanos(entry.nanos);
+        map.entry("timestamp").or_insert_with(|| Value::String(time.to_rfc3339()));
+        // Öncelik: satırdaki alanlar, satırın üst verisi, akış etiketleri
+        for (name, value) in entry.metadata.iter().chain(labels) {
+            if LEVEL_LABELS.contains(&name.as_str()) {
+                level.get_or_insert_with(|| value.to_ascii_lowercase());
+                continue;
+            }
+            map.entry(name.clone()).or_insert_with(|| Value::String(value.clone()));
+        }
+    }
+    if log.level.is_empty() {
+        log.level = level.unwrap_or_else(|| "info".to_string());
+    }
+    log
+}
+
+// --- JSON Biçimi ---
+#[derive(Deserialize)]
+struct JsonPush {
+    streams: Vec<JsonStream>,
+}
+
+#[derive(Deserialize)]
+struct JsonStream {
+    #[serde(default)]
+    stream: Map<String, Value>,
+    #[serde(default)]
+    values: Vec<Vec<Value>>,
+}
+
+impl JsonPush {
+    fn into_streams(self) -> Vec<Stream> {
+        self.streams
+            .into_iter()
+            .map(|stream| Stream {
+                labels: text_pairs(stream.stream),
+                entries: stream
+                    .values
+                    .into_iter()
+                    .filter_map(|value| {
+                        let mut value = value.into_iter();
+                        let nanos = match value.next()? {
+                            Value::String(nanos) => nanos.parse().ok()?,
+                            Value::Number(nanos) => nanos.as_i64()?,
+                            _ => return None,
+                        };
+                        let line = match value.next()? {
+                            Value::String(line) => line,
+                            _ => return None,
+                        };
+                        let metadata = match value.next() {
+                            Some(Value::Object(metadata)) => text_pairs(metadata),
+                            _ => Vec::new(),
+                        };
+                        Some(Entry { nanos, line, metadata })
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+fn text_pairs(map: Map<String, Value>) -> Vec<(String, String)> {
+    map.into_iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => (name, value),
+            other => (name, other.to_string()),
+        })
+        .collect()
+}
+
+// --- Protobuf Biçimi (logproto.PushRequest) ---
+#[derive(Clone, PartialEq, Message)]
+struct PushRequest {
+    #[prost(message, repeated, tag = "1")]
+    streams: Vec<StreamAdapter>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct StreamAdapter {
+    // Prometheus biçiminde etiketler: {job="api", level="error"}
+    #[prost(string, tag = "1")]
+    labels: String,
+    #[prost(message, repeated, tag = "2")]
+    entries: Vec<EntryAdapter>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct EntryAdapter {
+    #[prost(message, optional, tag = "1")]
+    timestamp: Option<Timestamp>,
+    #[prost(string, tag = "2")]
+    line: String,
+    #[prost(message, repeated, tag = "3")]
+    structured_metadata: Vec<LabelPair>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct LabelPair {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+// google.protobuf.Timestamp
+#[derive(Clone, PartialEq, Message)]
+struct Timestamp {
+    #[prost(int64, tag = "1")]
+    seconds: i64,
+    #[prost(int32, tag = "2")]
+    nanos: i32,
+}
+
+fn decode_protobuf(body: &[u8]) -> Result<Vec<Stream>, String> {
+    let raw = snap::raw::Decoder::new()
+        .decompress_vec(body)
+        .map_err(|e| format!("snappy: {}", e))?;
+    let request = PushRequest::decode(&raw[..]).map_err(|e| format!("protobuf: {}", e))?;
+    request
+        .streams
+        .into_iter()
+        .map(|stream| {
+            Ok(Stream {
+                labels: parse_labels(&stream.labels)?,
+                entries: stream
+                    .entries
+                    .into_iter()
+                    .map(|entry| Entry {
+                        nanos: entry
+                            .timestamp
+                            .map(|t| t.seconds.saturating_mul(1_000_000_000).saturating_add(t.nanos as i64))
+                            .unwrap_or_default(),
+                        line: entry.line,
+                        metadata: entry.structured_metadata.into_iter().map(|p| (p.name, p.value)).collect(),
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+// {job="api", path="/a \"b\""} -> [(job, api), (path, /a "b")]
+fn parse_labels(input: &str) -> Result<Vec<(String, String)>, String> {
+    let body = input
+        .trim()
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(|| format!("geçersiz etiket kümesi: {}", input))?;
+    let mut labels = Vec::new();
+    let mut rest = body.trim_start();
+    while !rest.is_empty() {
+        let eq = rest.find('=').ok_or_else(|| format!("geçersiz etiket kümesi: {}", input))?;
+        let name = rest[..eq].trim().to_string();
+        let quoted = rest[eq + 1..]
+            .trim_start()
+            .strip_prefix('"')
+            .ok_or_else(|| format!("geçersiz etiket kümesi: {}", input))?;
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        let mut end = None;
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, other)) => value.push(other),
+                    None => break,
+                },
+                '"' => {
+                    end = Some(i + 1);
+                    break;
+                }
+                c => value.push(c),
+            }
+        }
+        let end = end.ok_or_else(|| format!("kapanmamış etiket değeri: {}", input))?;
+        labels.push((name, value));
+        rest = quoted[end..].trim_start().trim_start_matches(',').trim_start();
+    }
+    Ok(labels)
+}
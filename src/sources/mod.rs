@@ -0,0 +1,31 @@
+// --- Ek Log Kaynakları ---
+// HTTP dışındaki girişler. Hepsi logları ingest::submit üzerinden aynı
+// boru hattına (filtre -> kanal -> yazıcı) sokar.
+pub mod datadog;
+#[cfg(unix)]
+pub mod docker;
+pub mod elasticsearch;
+pub mod file;
+pub mod fluentd;
+pub mod gelf;
+pub mod hec;
+pub mod heroku;
+#[cfg(target_os = "linux")]
+pub mod journald;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod loki;
+pub mod otlp;
+pub mod raw;
+pub mod stdin;
+pub mod syslog;
+
+use std::net::SocketAddr;
+
+use crate::LogEntry;
+
+// Ağ kaynaklarında gönderen bağlantının adresini loga işler (source.remote_addr).
+pub fn from_peer(mut log: LogEntry, peer: SocketAddr) -> LogEntry {
+    log.source.remote_addr = Some(peer.ip());
+    log
+}
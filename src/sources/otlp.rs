@@ -0,0 +1,338 @@
+use std::io::Read;
+use std::net::SocketAddr;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::{LogsService, LogsServiceServer};
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsPartialSuccess, ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+use opentelemetry_proto::tonic::logs::v1::LogRecord;
+use prost::Message;
+use serde_json::{Map, Value};
+use tracing::{debug, error, info, warn};
+
+use super::from_peer;
+use crate::auth::{self, Caller};
+use crate::config::{AttributeLayout, OtlpConfig};
+use crate::ingest::{self, Outcome};
+use crate::ratelimit;
+use crate::{AppState, LogEntry};
+
+// gzip ile açılmış OTLP/HTTP gövdesi için üst sınır
+const MAX_HTTP_BODY: u64 = 16 * 1024 * 1024;
+
+// --- OTLP Log Dönüşümü ---
+// OpenTelemetry LogRecord -> LogEntry:
+//   body (string)               -> message (string değilse JSON'a çevrilir ve extra.body'de de tutulur)
+//   severity_number / _text     -> level
+//   time_unix_nano              -> extra.timestamp (yoksa observed_time_unix_nano)
+//   attributes                  -> extra (üst seviye alanlar)
+//   trace_id / span_id          -> extra.trace_id / extra.span_id (hex)
+//   resource.attributes         -> extra.resource (veya [otlp] resource_attributes'a göre)
+//   scope (name/version/attrs)  -> extra.scope (veya [otlp] scope_attributes'a göre)
+pub fn convert(request: ExportLogsServiceRequest, config: &OtlpConfig) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = resource_logs
+            .resource
+            .map(|r| attributes_to_map(r.attributes))
+            .unwrap_or_default();
+
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_logs.scope.map(|scope| {
+                let mut map = Map::new();
+                if !scope.name.is_empty() {
+                    map.insert("name".to_string(), Value::String(scope.name));
+                }
+                if !scope.version.is_empty() {
+                    map.insert("version".to_string(), Value::String(scope.version));
+                }
+                if !scope.attributes.is_empty() {
+                    let attributes = attributes_to_map(scope.attributes);
+                    match config.scope_attributes {
+                        AttributeLayout::Nested => {
+                            map.insert("attributes".to_string(), Value::Object(attributes));
+                        }
+                        // Düzleştirilirken ad ve sürümle aynı seviyeye çıkar
+                        AttributeLayout::Flatten | AttributeLayout::Promote => {
+                            for (key, value) in attributes {
+                                map.entry(key).or_insert(value);
+                            }
+                        }
+                    }
+                }
+                map
+            });
+
+            for record in scope_logs.log_records {
+                entries.push(convert_record(record, &resource, scope.as_ref(), config));
+            }
+        }
+    }
+    entries
+}
+
+fn convert_record(
+    record: LogRecord,
+    resource: &Map<String, Value>,
+    scope: Option<&Map<String, Value>>,
+    config: &OtlpConfig,
+) -> LogEntry {
+    let mut extra = attributes_to_map(record.attributes);
+    extra.insert("source".to_string(), Value::String("otlp".to_string()));
+
+    let nanos = if record.time_unix_nano > 0 {
+        record.time_unix_nano
+    } else {
+        record.observed_time_unix_nano
+    };
+    if nanos > 0 {
+        let time = chrono::DateTime::from_timestamp_nanos(nanos as i64);
+        extra.insert("timestamp".to_string(), Value::String(time.to_rfc3339()));
+    }
+    if !record.trace_id.is_empty() {
+        extra.insert("trace_id".to_string(), Value::String(hex(&record.trace_id)));
+    }
+    if !record.span_id.is_empty() {
+        extra.insert("span_id".to_string(), Value::String(hex(&record.span_id)));
+    }
+    if !record.event_name.is_empty() {
+        extra.insert("event_name".to_string(), Value::String(record.event_name));
+    }
+    attach(&mut extra, "resource", resource, config.resource_attributes, &config.resource_prefix);
+    if let Some(scope) = scope {
+        attach(&mut extra, "scope", scope, config.scope_attributes, &config.scope_prefix);
+    }
+
+    let message = match record.body.map(any_value_to_json) {
+        Some(Value::String(text)) => text,
+        Some(Value::Null) | None => String::new(),
+        Some(other) => {
+            let text = other.to_string();
+            extra.insert("body".to_string(), other);
+            text
+        }
+    };
+
+    LogEntry {
+        level: severity_to_level(record.severity_number, &record.severity_text),
+        message,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// Kaynak / kapsam özniteliklerini extra'ya ekler. Düzleştirilen adlardaki noktalar
+// '_' olur (k8s.pod.name -> k8s_pod_name), böylece filtre ifadelerinde
+// extra.resource_k8s_pod_name gibi yazılabilir; aynı adlı kayıt öznitelikleri ezilmez.
+// service.name, host.name ve deployment.environment her biçimde kendi
+// sütunlarına taşınır (iç içe biçimde depolama bunları resource altından okur).
+fn attach(extra: &mut Map<String, Value>, key: &str, attributes: &Map<String, Value>, layout: AttributeLayout, prefix: &str) {
+    if attributes.is_empty() {
+        return;
+    }
+    if layout == AttributeLayout::Nested {
+        extra.insert(key.to_string(), Value::Object(attributes.clone()));
+        return;
+    }
+    for (name, value) in attributes {
+        let field = match (key, name.as_str(), layout) {
+            ("resource", "service.name", _) => "service".to_string(),
+            ("resource", "host.name", _) => "host".to_string(),
+            ("resource", "deployment.environment" | "deployment.environment.name", _) => "env".to_string(),
+            (_, _, AttributeLayout::Flatten) => format!("{}{}", prefix, name.replace('.', "_")),
+            // Öneksiz biçimde kapsamın adı ve sürümü tek başına anlamsız kalır
+            ("scope", "name" | "version", _) => format!("scope_{}", name),
+            _ => name.replace('.', "_"),
+        };
+        extra.entry(field).or_insert_with(|| value.clone());
+    }
+}
+
+// OTel SeverityNumber aralıkları: 1-4 trace, 5-8 debug, 9-12 info, 13-16 warn, 17-20 error, 21-24 fatal
+fn severity_to_level(number: i32, text: &str) -> String {
+    let level = match number {
+        1..=4 => "trace",
+        5..=8 => "debug",
+        9..=12 => "info",
+        13..=16 => "warn",
+        17..=20 => "error",
+        21..=24 => "fatal",
+        _ if !text.is_empty() => return text.to_ascii_lowercase(),
+        _ => "info",
+    };
+    level.to_string()
+}
+
+fn attributes_to_map(attributes: Vec<KeyValue>) -> Map<String, Value> {
+    attributes
+        .into_iter()
+        .map(|kv| (kv.key, kv.value.map(any_value_to_json).unwrap_or(Value::Null)))
+        .collect()
+}
+
+fn any_value_to_json(value: AnyValue) -> Value {
+    match value.value {
+        Some(any_value::Value::StringValue(s)) => Value::String(s),
+        Some(any_value::Value::BoolValue(b)) => Value::Bool(b),
+        Some(any_value::Value::IntValue(i)) => Value::from(i),
+        Some(any_value::Value::DoubleValue(d)) => Value::from(d),
+        Some(any_value::Value::ArrayValue(array)) => {
+            Value::Array(array.values.into_iter().map(any_value_to_json).collect())
+        }
+        Some(any_value::Value::KvlistValue(list)) => Value::Object(attributes_to_map(list.values)),
+        Some(any_value::Value::BytesValue(bytes)) => Value::String(hex(&bytes)),
+        // Sadece profil sinyalinde (sözlük indeksi) kullanılır, loglarda anlamı yok
+        Some(any_value::Value::StringValueStrindex(_)) | None => Value::Null,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Dönüştürülen logları boru hattına sokar; kanala giremeyenler OTLP
+// 'partial_success' olarak bildirilir. Seviye filtresine takılanlar bilinçli
+// olarak atıldığı için reddedilmiş sayılmaz (Collector'da gereksiz uyarı üretmesin).
+// 'caller' HTTP üzerinden gelen isteklerde verilir (kiracı, sertifika CN'i ve
+// kaynak bilgisi); gRPC'de sadece bağlantının adresi bilinir.
+pub async fn export(
+    state: &AppState,
+    request: ExportLogsServiceRequest,
+    caller: Option<&Caller>,
+    peer: Option<SocketAddr>,
+) -> ExportLogsServiceResponse {
+    let mut entries = convert(request, &state.otlp);
+    if let Some(peer) = peer {
+        entries = entries.into_iter().map(|log| from_peer(log, peer)).collect();
+    }
+    auth::tag_all(caller, &mut entries);
+    debug!("📥 OTLP isteği alındı: {} adet log", entries.len());
+
+    let mut rejected = 0i64;
+    for log in entries {
+        if matches!(ingest::submit(state, log).await, Outcome::Dropped | Outcome::Overloaded) {
+            rejected += 1;
+        }
+    }
+
+    let partial_success = (rejected > 0).then(|| ExportLogsPartialSuccess {
+        rejected_log_records: rejected,
+        error_message: "bazı kayıtlar kuyruğa alınamadı".to_string(),
+    });
+    ExportLogsServiceResponse { partial_success }
+}
+
+// --- OTLP/gRPC LogsService ---
+struct OtlpLogs {
+    state: AppState,
+}
+
+#[tonic::async_trait]
+impl LogsService for OtlpLogs {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportLogsServiceRequest>,
+    ) -> Result<tonic::Response<ExportLogsServiceResponse>, tonic::Status> {
+        let peer = request.remote_addr();
+        // UNAVAILABLE, Collector'ın yeniden deneyeceği durum kodudur
+        let records = request
+            .get_ref()
+            .resource_logs
+            .iter()
+            .flat_map(|r| &r.scope_logs)
+            .map(|s| s.log_records.len())
+            .sum();
+        if ingest::check_capacity(&self.state, records).is_some() {
+            return Err(tonic::Status::unavailable("kuyruk dolu, daha sonra tekrar deneyin"));
+        }
+        Ok(tonic::Response::new(export(&self.state, request.into_inner(), None, peer).await))
+    }
+}
+
+// gRPC sunucusunu ayrı bir portta (varsayılan OTLP portu 4317) çalıştırır.
+pub async fn run_grpc(bind: String, state: AppState) {
+    let addr = match bind.parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            error!("❌ Geçersiz OTLP gRPC adresi {}: {}", bind, e);
+            return;
+        }
+    };
+    info!("📡 OTLP/gRPC dinleyicisi {} adresinde çalışıyor...", bind);
+
+    let service = LogsServiceServer::new(OtlpLogs { state })
+        .accept_compressed(tonic::codec::CompressionEncoding::Gzip);
+    if let Err(e) = tonic::transport::Server::builder().add_service(service).serve(addr).await {
+        error!("❌ OTLP gRPC sunucusu durdu: {}", e);
+    }
+}
+
+// --- OTLP/HTTP (protobuf) ---
+// POST /v1/logs — Collector'ın 'otlphttp' exporter'ı ile uyumlu.
+// Gövde protobuf ExportLogsServiceRequest'tir; exporter varsayılan olarak gzip ile sıkıştırır.
+pub async fn http_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !content_type.starts_with("application/x-protobuf") {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "sadece application/x-protobuf desteklenir").into_response();
+    }
+
+    let gzip = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("gzip"));
+    let raw = if gzip {
+        let mut out = Vec::new();
+        if let Err(e) = flate2::read::GzDecoder::new(&body[..]).take(MAX_HTTP_BODY).read_to_end(&mut out) {
+            warn!("⚠️ OTLP/HTTP gzip gövdesi açılamadı: {}", e);
+            return (StatusCode::BAD_REQUEST, "geçersiz gzip gövdesi").into_response();
+        }
+        Bytes::from(out)
+    } else {
+        body
+    };
+
+    let request = match ExportLogsServiceRequest::decode(raw) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("⚠️ OTLP/HTTP protobuf çözülemedi: {}", e);
+            return (StatusCode::BAD_REQUEST, "geçersiz protobuf gövdesi").into_response();
+        }
+    };
+
+    // OTLP/HTTP istemcileri 429 + Retry-After'ı yeniden denenebilir sayar
+    let records = request
+        .resource_logs
+        .iter()
+        .flat_map(|r| &r.scope_logs)
+        .map(|s| s.log_records.len())
+        .sum();
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), records) {
+        return rejection;
+    }
+    // 503 + Retry-After da yeniden denenebilir
+    if let Some(rejection) = ingest::check_capacity(&state, records) {
+        return rejection;
+    }
+
+    let response = export(&state, request, caller.as_deref(), None).await;
+    (
+        [(header::CONTENT_TYPE, "application/x-protobuf")],
+        response.encode_to_vec(),
+    )
+        .into_response()
+}
@@ -0,0 +1,218 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tracing::debug;
+
+use crate::auth::Caller;
+use crate::config::{Backpressure, LineParserConfig, RawConfig};
+use crate::ingest::{self, Outcome};
+use crate::timestamp;
+use crate::ratelimit;
+use crate::sanitize;
+use crate::{AppState, LogEntry};
+
+// Hiç ayrıştırıcı tanımlanmadığında kullanılan desen. Şu satırları anlar:
+//   "2024-05-01T10:00:00Z ERROR disk full"
+//   "[2024-05-01 10:00:00] [warn] yavaş sorgu"
+//   "error: bağlantı koptu"
+//   "sadece mesaj"
+const DEFAULT_PATTERN: &str = r"(?i)^\s*(?:\[?(?P<timestamp>\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]?\s+)?(?:\[?(?P<level>trace|debug|info|warn|warning|error|fatal|critical)\]?:?\s+)?(?P<message>.*)$";
+
+// --- Düz Metin Ayrıştırıcıları ---
+// JSON üretemeyen uygulamalar için: gövdenin her satırı yapılandırılmış bir
+// regex ile LogEntry'ye çevrilir.
+//   (?P<level>...)      -> level (küçük harfe çevrilir)
+//   (?P<message>...)    -> message (grup yoksa satırın tamamı)
+//   (?P<timestamp>...)  -> extra.timestamp (RFC 3339'a çevrilir)
+//   diğer gruplar       -> extra
+// Desene uymayan satırlar 'default_level' seviyesinde ham olarak alınır.
+#[derive(Debug)]
+pub struct LineParser {
+    name: String,
+    regex: Regex,
+    timestamp_format: Option<String>,
+    default_level: String,
+}
+
+impl LineParser {
+    pub fn new(config: &LineParserConfig) -> Result<Self, String> {
+        let regex = Regex::new(&config.pattern)
+            .map_err(|e| format!("'{}' ayrıştırıcısının deseni geçersiz: {}", config.name, e))?;
+        Ok(LineParser {
+            name: config.name.clone(),
+            regex,
+            timestamp_format: config.timestamp_format.clone(),
+            default_level: config.default_level.to_ascii_lowercase(),
+        })
+    }
+
+    pub fn parse(&self, line: &str) -> LogEntry {
+        let mut extra = Map::new();
+        extra.insert("source".to_string(), Value::String("raw".to_string()));
+        extra.insert("parser".to_string(), Value::String(self.name.clone()));
+
+        let Some(captures) = self.regex.captures(line) else {
+            return LogEntry {
+                level: self.default_level.clone(),
+                message: line.to_string(),
+                extra: Value::Object(extra),
+                tenant_id: crate::default_tenant(),
+                source: crate::Source::default(),
+            };
+        };
+
+        let mut level = None;
+        let mut message = None;
+        for name in self.regex.capture_names().flatten() {
+            let Some(value) = captures.name(name).map(|m| m.as_str()) else {
+                continue;
+            };
+            match name {
+                "level" => level = Some(value.to_ascii_lowercase()).filter(|l| !l.is_empty()),
+                "message" => message = Some(value.to_string()),
+                "timestamp" => {
+                    let timestamp = self.parse_timestamp(value).unwrap_or_else(|| value.to_string());
+                    extra.insert("timestamp".to_string(), Value::String(timestamp));
+                }
+                _ => {
+                    extra.insert(name.to_string(), Value::String(value.to_string()));
+                }
+            }
+        }
+
+        LogEntry {
+            level: level.unwrap_or_else(|| self.default_level.clone()),
+            message: message.unwrap_or_else(|| line.to_string()),
+            extra: Value::Object(extra),
+            tenant_id: crate::default_tenant(),
+            source: crate::Source::default(),
+        }
+    }
+
+    // Yapılandırılmış biçim (saat dilimli ya da UTC kabul edilen), yoksa
+    // timestamp::parse'ın tanıdığı biçimler denenir. Olmazsa None.
+    fn parse_timestamp(&self, value: &str) -> Option<String> {
+        if let Some(format) = &self.timestamp_format {
+            if let Ok(time) = DateTime::parse_from_str(value, format) {
+                return Some(time.with_timezone(&Utc).to_rfc3339());
+            }
+            return NaiveDateTime::parse_from_str(value, format)
+                .ok()
+                .map(|naive| naive.and_utc().to_rfc3339());
+        }
+
+        timestamp::parse(value).map(|time| time.to_rfc3339())
+    }
+}
+
+// Yapılandırılmış ayrıştırıcılar ve ?parser= verilmezse kullanılacak olan.
+#[derive(Debug)]
+pub struct RawParsers {
+    parsers: Vec<LineParser>,
+    default: usize,
+}
+
+impl RawParsers {
+    pub fn from_config(config: &RawConfig) -> Result<Self, String> {
+        let mut parsers = config
+            .parsers
+            .iter()
+            .map(LineParser::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        if parsers.is_empty() {
+            parsers.push(LineParser::new(&LineParserConfig {
+                name: "default".to_string(),
+                pattern: DEFAULT_PATTERN.to_string(),
+                timestamp_format: None,
+                default_level: "info".to_string(),
+            })?);
+        }
+
+        let default = match &config.default_parser {
+            Some(name) => parsers
+                .iter()
+                .position(|p| &p.name == name)
+                .ok_or_else(|| format!("raw.default_parser '{}' tanımlı değil", name))?,
+            None => 0,
+        };
+        Ok(RawParsers { parsers, default })
+    }
+
+    // Ada göre ayrıştırıcı; ad verilmezse varsayılan.
+    pub fn get(&self, name: Option<&str>) -> Option<&LineParser> {
+        match name {
+            Some(name) => self.parsers.iter().find(|p| p.name == name),
+            None => self.parsers.get(self.default),
+        }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.parsers.iter().map(|p| p.name.as_str()).collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawQuery {
+    pub parser: Option<String>,
+}
+
+// --- Düz Metin Girişi ---
+// POST /ingest/raw?parser=nginx  (Content-Type: text/plain, her satır bir log)
+pub async fn raw_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RawQuery>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !content_type.starts_with("text/plain") {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "sadece text/plain desteklenir").into_response();
+    }
+
+    let Some(parser) = state.raw.get(params.parser.as_deref()) else {
+        let message = format!(
+            "bilinmeyen ayrıştırıcı '{}' (tanımlı: {})",
+            params.parser.unwrap_or_default(),
+            state.raw.names().join(", ")
+        );
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    };
+
+    // Geçersiz UTF-8 ve kontrol karakterleri satırı düşürmez, temizlenir
+    let body = sanitize::text(&body);
+    let lines: Vec<&str> = body
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    debug!("📥 Düz metin isteği alındı: {} satır ({})", lines.len(), parser.name);
+    if let Some(rejection) = ratelimit::limit_entries(&state, caller.as_deref(), lines.len()) {
+        return rejection;
+    }
+    if let Some(rejection) = ingest::check_capacity(&state, lines.len()) {
+        return rejection;
+    }
+    let mut overloaded = false;
+    for line in lines {
+        let mut log = parser.parse(line);
+        if let Some(Extension(caller)) = &caller {
+            caller.tag(&mut log);
+        }
+        overloaded |= ingest::submit(&state, log).await == Outcome::Overloaded;
+    }
+
+    if overloaded && state.backpressure == Backpressure::Reject {
+        return ingest::overloaded_response(&state);
+    }
+    StatusCode::ACCEPTED.into_response()
+}
@@ -0,0 +1,80 @@
+use std::io::Write;
+
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+use tracing::debug;
+
+use crate::ingest::{self, Outcome};
+use crate::{AppState, LogEntry};
+
+// --- Standart Girdi (log-ingestor pipe) ---
+// "myapp | log-ingestor pipe" ile uygulamanın çıktısı doğrudan veritabanına
+// yazılır. Her satır ayrı değerlendirilir:
+//   {"level": ..., "message": ...}  -> /ingest gövdesindeki gibi
+//   [{...}, {...}]                  -> birden fazla log
+//   diğer satırlar                  -> [raw] ayrıştırıcısı
+// Girdi kapanınca (EOF) döner; yazıcıların bitirmesi Ingestor::shutdown'dadır.
+
+// Okuma sonunda basılan özet
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PipeSummary {
+    pub lines: u64,
+    pub accepted: u64,
+    // Seviye filtresi, kurallar veya örnekleme yüzünden yazılmayanlar
+    pub filtered: u64,
+    pub dropped: u64,
+}
+
+pub async fn run(
+    input: impl AsyncBufRead + Unpin,
+    state: &AppState,
+    parser: Option<&str>,
+    tee: bool,
+) -> std::io::Result<PipeSummary> {
+    let parser = state
+        .raw
+        .get(parser)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("bilinmeyen ayrıştırıcı '{}'", parser.unwrap_or_default())))?;
+    let mut summary = PipeSummary::default();
+    let mut lines = input.lines();
+    let mut stdout = std::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if tee {
+            let _ = writeln!(stdout, "{}", line);
+        }
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        summary.lines += 1;
+
+        let logs = match parse_json(line) {
+            Some(logs) => logs,
+            None => {
+                let mut log = parser.parse(line);
+                if let Value::Object(ref mut map) = log.extra {
+                    map.insert("source".to_string(), Value::String("stdin".to_string()));
+                }
+                vec![log]
+            }
+        };
+        for log in logs {
+            match ingest::submit(state, log).await {
+                Outcome::Accepted | Outcome::Merged => summary.accepted += 1,
+                Outcome::Filtered | Outcome::Sampled => summary.filtered += 1,
+                Outcome::Dropped | Outcome::Overloaded | Outcome::Oversized => summary.dropped += 1,
+            }
+        }
+    }
+    debug!("📥 Standart girdi kapandı: {:?}", summary);
+    Ok(summary)
+}
+
+// Satır JSON log nesnesi veya dizisiyse loglar; değilse None (düz metin sayılır).
+fn parse_json(line: &str) -> Option<Vec<LogEntry>> {
+    match line.trim_start().as_bytes().first()? {
+        b'{' => serde_json::from_str::<LogEntry>(line).ok().map(|log| vec![log]),
+        b'[' => serde_json::from_str::<Vec<LogEntry>>(line).ok(),
+        _ => None,
+    }
+}
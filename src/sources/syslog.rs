@@ -0,0 +1,367 @@
+use std::net::SocketAddr;
+
+use chrono::Utc;
+use serde_json::{Map, Value};
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
+
+use super::from_peer;
+use crate::ingest;
+use crate::timestamp;
+use crate::{AppState, LogEntry};
+
+// Tek bir UDP datagramının alabileceği en büyük boyut
+const MAX_DATAGRAM: usize = 65_535;
+
+// TCP üzerinde tek bir çerçevenin (mesajın) izin verilen en büyük boyutu
+const MAX_TCP_FRAME: usize = 1024 * 1024;
+
+// --- Syslog UDP Dinleyicisi ---
+// Her datagram tek bir syslog mesajıdır (RFC 3164 veya RFC 5424).
+// Ayrıştırılan mesajlar HTTP ile gelenlerle aynı boru hattına girer.
+pub async fn run_udp(bind: String, state: AppState) {
+    let socket = match UdpSocket::bind(&bind).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            error!("❌ Syslog UDP {} dinlenemedi: {}", bind, e);
+            return;
+        }
+    };
+    info!("📡 Syslog UDP dinleyicisi {} adresinde çalışıyor...", bind);
+
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    loop {
+        let (len, peer) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            Err(e) => {
+                error!("❌ Syslog UDP okuma hatası: {}", e);
+                continue;
+            }
+        };
+        let text = String::from_utf8_lossy(&buf[..len]);
+        let line = text.trim_end_matches(['\n', '\r', '\0']);
+        if line.is_empty() {
+            continue;
+        }
+        debug!("📥 Syslog mesajı alındı ({})", peer);
+        ingest::submit(&state, from_peer(parse(line), peer)).await;
+    }
+}
+
+// --- Syslog TCP Dinleyicisi (RFC 6587) ---
+// İki çerçeveleme desteklenir ve her mesajda ayrı ayrı tespit edilir:
+//   - Octet counting:      "MSG-LEN SP SYSLOG-MSG" (rsyslog/syslog-ng varsayılanı)
+//   - Non-transparent:     mesajlar '\n' ile ayrılır
+// Birden fazla okumaya bölünmüş uzun mesajlar tamponda birleştirilir.
+pub async fn run_tcp(bind: String, state: AppState) {
+    let listener = match TcpListener::bind(&bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("❌ Syslog TCP {} dinlenemedi: {}", bind, e);
+            return;
+        }
+    };
+    info!("📡 Syslog TCP dinleyicisi {} adresinde çalışıyor...", bind);
+
+    // Bağlantı görevleri bu kümede tutulur; dinleyici durdurulunca hepsi iptal edilir.
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    debug!("🔌 Syslog TCP bağlantısı: {}", peer);
+                    connections.spawn(handle_tcp(stream, peer, state.clone()));
+                }
+                Err(e) => error!("❌ Syslog TCP bağlantı hatası: {}", e),
+            },
+            // Biten bağlantıları kümeden temizle
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+async fn handle_tcp(mut stream: TcpStream, peer: SocketAddr, state: AppState) {
+    let mut buffer: Vec<u8> = Vec::with_capacity(8 * 1024);
+    let mut chunk = vec![0u8; 16 * 1024];
+    loop {
+        let read = match stream.read(&mut chunk).await {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => {
+                debug!("Syslog TCP okuma hatası: {}", e);
+                break;
+            }
+        };
+        buffer.extend_from_slice(&chunk[..read]);
+
+        match next_frames(&mut buffer) {
+            Ok(frames) => {
+                for frame in frames {
+                    ingest::submit(&state, from_peer(parse(&frame), peer)).await;
+                }
+            }
+            Err(reason) => {
+                warn!("⚠️ Syslog TCP bağlantısı kapatılıyor: {}", reason);
+                return;
+            }
+        }
+    }
+
+    // Bağlantı kapandı: sonunda '\n' olmayan son mesajı da al
+    let tail = String::from_utf8_lossy(&buffer);
+    let tail = tail.trim();
+    if !tail.is_empty() {
+        ingest::submit(&state, from_peer(parse(tail), peer)).await;
+    }
+}
+
+// Tampondaki tamamlanmış çerçeveleri çıkarır; yarım kalan kısım tamponda bekler.
+pub(super) fn next_frames(buffer: &mut Vec<u8>) -> Result<Vec<String>, String> {
+    let mut frames = Vec::new();
+    let mut pos = 0;
+    loop {
+        // Çerçeveler arasındaki boş satırları atla
+        while pos < buffer.len() && matches!(buffer[pos], b'\n' | b'\r' | b'\0') {
+            pos += 1;
+        }
+        let rest = &buffer[pos..];
+        if rest.is_empty() {
+            break;
+        }
+
+        if rest[0].is_ascii_digit() {
+            // Octet counting: uzunluk, boşluk, mesaj
+            let Some(space) = rest.iter().position(|b| *b == b' ') else {
+                if rest.len() > 10 {
+                    return Err("geçersiz çerçeve uzunluğu".to_string());
+                }
+                break;
+            };
+            let len: usize = std::str::from_utf8(&rest[..space])
+                .ok()
+                .and_then(|digits| digits.parse().ok())
+                .ok_or_else(|| "geçersiz çerçeve uzunluğu".to_string())?;
+            if len > MAX_TCP_FRAME {
+                return Err(format!("çerçeve çok büyük ({} bayt)", len));
+            }
+            let start = space + 1;
+            if rest.len() < start + len {
+                break;
+            }
+            frames.push(String::from_utf8_lossy(&rest[start..start + len]).trim_end().to_string());
+            pos += start + len;
+        } else {
+            // Non-transparent: satır sonuna kadar
+            let Some(newline) = rest.iter().position(|b| *b == b'\n') else {
+                if rest.len() > MAX_TCP_FRAME {
+                    return Err("satır sonu olmadan çok büyük mesaj".to_string());
+                }
+                break;
+            };
+            frames.push(String::from_utf8_lossy(&rest[..newline]).trim_end().to_string());
+            pos += newline + 1;
+        }
+    }
+    buffer.drain(..pos);
+    Ok(frames)
+}
+
+// --- Syslog Ayrıştırıcı ---
+// Önce RFC 5424 ("<PRI>1 ..."), olmazsa RFC 3164 ("<PRI>Mmm dd hh:mm:ss ...") denenir.
+// Hiçbiri uymazsa mesajın tamamı 'info' seviyesinde ham olarak alınır.
+pub fn parse(line: &str) -> LogEntry {
+    let mut extra = Map::new();
+    extra.insert("source".to_string(), Value::String("syslog".to_string()));
+
+    let (pri, rest) = match parse_pri(line) {
+        Some(parsed) => parsed,
+        None => {
+            return LogEntry {
+                level: "info".to_string(),
+                message: line.to_string(),
+                extra: Value::Object(extra),
+                tenant_id: crate::default_tenant(),
+                source: crate::Source::default(),
+            };
+        }
+    };
+    let facility = pri / 8;
+    let severity = pri % 8;
+    extra.insert("facility".to_string(), Value::from(facility));
+    extra.insert("severity".to_string(), Value::from(severity));
+
+    let message = match rest.strip_prefix("1 ") {
+        Some(body) => parse_5424(body, &mut extra),
+        None => parse_3164(rest, &mut extra),
+    };
+
+    LogEntry {
+        level: severity_to_level(severity).to_string(),
+        message,
+        extra: Value::Object(extra),
+        tenant_id: crate::default_tenant(),
+        source: crate::Source::default(),
+    }
+}
+
+// "<34>..." -> (34, "...")
+pub(super) fn parse_pri(line: &str) -> Option<(u8, &str)> {
+    let rest = line.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    if end == 0 || end > 3 {
+        return None;
+    }
+    let pri: u8 = rest[..end].parse().ok()?;
+    if pri > 191 {
+        return None;
+    }
+    Some((pri, &rest[end + 1..]))
+}
+
+// Syslog önem derecesi (0-7) -> kanonik seviye adı (GELF de aynı ölçeği kullanır)
+pub fn severity_to_level(severity: u8) -> &'static str {
+    match severity {
+        0..=2 => "fatal", // emerg, alert, crit
+        3 => "error",
+        4 => "warn",
+        5 | 6 => "info", // notice, info
+        _ => "debug",
+    }
+}
+
+// RFC 5424: TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]
+fn parse_5424(body: &str, extra: &mut Map<String, Value>) -> String {
+    let mut rest = body;
+    for field in ["timestamp", "hostname", "app_name", "procid", "msgid"] {
+        let (value, tail) = next_token(rest);
+        rest = tail;
+        if value != "-" && !value.is_empty() {
+            let value = if field == "timestamp" {
+                chrono::DateTime::parse_from_rfc3339(value)
+                    .map(|t| t.with_timezone(&Utc).to_rfc3339())
+                    .unwrap_or_else(|_| value.to_string())
+            } else {
+                value.to_string()
+            };
+            extra.insert(field.to_string(), Value::String(value));
+        }
+    }
+
+    let rest = if let Some(tail) = rest.strip_prefix('-') {
+        tail
+    } else if rest.starts_with('[') {
+        let (sd, tail) = parse_structured_data(rest);
+        if !sd.is_empty() {
+            extra.insert("structured_data".to_string(), Value::Object(sd));
+        }
+        tail
+    } else {
+        rest
+    };
+
+    // MSG başındaki UTF-8 BOM'u at
+    rest.trim_start_matches(' ').trim_start_matches('\u{feff}').to_string()
+}
+
+// [id k="v" k2="v2"][id2 ...] -> {"id": {"k": "v", ...}, ...}
+fn parse_structured_data(input: &str) -> (Map<String, Value>, &str) {
+    let mut result = Map::new();
+    let mut rest = input;
+    while let Some(body) = rest.strip_prefix('[') {
+        let (id, mut tail) = next_token_until(body, &[' ', ']']);
+        let mut params = Map::new();
+        loop {
+            tail = tail.trim_start_matches(' ');
+            if let Some(after) = tail.strip_prefix(']') {
+                tail = after;
+                break;
+            }
+            let Some(eq) = tail.find('=') else {
+                tail = "";
+                break;
+            };
+            let name = tail[..eq].to_string();
+            let (value, after) = parse_quoted(&tail[eq + 1..]);
+            params.insert(name, Value::String(value));
+            tail = after;
+        }
+        result.insert(id.to_string(), Value::Object(params));
+        rest = tail;
+    }
+    (result, rest)
+}
+
+// "değer \" kaçışlı" -> (değer, kalan)
+fn parse_quoted(input: &str) -> (String, &str) {
+    let Some(body) = input.strip_prefix('"') else {
+        return (String::new(), input);
+    };
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    value.push(escaped);
+                }
+            }
+            '"' => return (value, &body[i + 1..]),
+            _ => value.push(c),
+        }
+    }
+    (value, "")
+}
+
+// RFC 3164: Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG
+fn parse_3164(body: &str, extra: &mut Map<String, Value>) -> String {
+    let mut rest = body;
+
+    // Zaman damgası 15 karakterdir ve yıl içermez; içinde bulunulan yıl varsayılır (UTC).
+    if let Some(stamp) = body.get(..15) {
+        if let Some(timestamp) = timestamp::parse_syslog(stamp) {
+            extra.insert("timestamp".to_string(), Value::String(timestamp.to_rfc3339()));
+            rest = body[15..].trim_start_matches(' ');
+
+            let (hostname, tail) = next_token(rest);
+            if !hostname.is_empty() {
+                extra.insert("hostname".to_string(), Value::String(hostname.to_string()));
+            }
+            rest = tail;
+        }
+    }
+
+    // TAG: en fazla 32 alfanümerik karakter, ardından '[pid]' ve/veya ':'
+    if let Some(colon) = rest.find(':') {
+        let tag = &rest[..colon];
+        if !tag.is_empty() && tag.len() <= 48 && !tag.contains(' ') {
+            let (app, pid) = match (tag.find('['), tag.strip_suffix(']')) {
+                (Some(open), Some(stripped)) => (&tag[..open], Some(&stripped[open + 1..])),
+                _ => (tag, None),
+            };
+            extra.insert("app_name".to_string(), Value::String(app.to_string()));
+            if let Some(pid) = pid {
+                extra.insert("procid".to_string(), Value::String(pid.to_string()));
+            }
+            rest = rest[colon + 1..].trim_start_matches(' ');
+        }
+    }
+
+    rest.to_string()
+}
+
+// Boşluğa kadar olan parçayı ve kalanını döner.
+fn next_token(input: &str) -> (&str, &str) {
+    next_token_until(input, &[' '])
+}
+
+fn next_token_until<'a>(input: &'a str, stops: &[char]) -> (&'a str, &'a str) {
+    match input.find(stops) {
+        Some(i) => {
+            let tail = &input[i..];
+            (&input[..i], tail.strip_prefix(' ').unwrap_or(tail))
+        }
+        None => (input, ""),
+    }
+}
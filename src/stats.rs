@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+use crate::auth::{self, Caller};
+use crate::query::LogQuery;
+use crate::storage::MessageGrouping;
+use crate::timestamp;
+use crate::AppState;
+
+// 'from' verilmezse bakılan süre
+const DEFAULT_WINDOW_MICROS: i64 = 24 * 3600 * 1_000_000;
+// Bir yanıttaki en fazla zaman dilimi (çok küçük 'bucket' ile çok geniş aralık)
+const MAX_BUCKETS: i64 = 10_000;
+// En sık mesajlarda dönen grup sayısı
+const DEFAULT_TOP: i64 = 10;
+const MAX_TOP: i64 = 100;
+
+// --- Seviye Histogramı ---
+// GET /stats/levels?from=...&to=...&bucket=5m: zaman dilimi başına seviye
+// sayıları (grafikler için; sayım veritabanında GROUP BY ile yapılır).
+// /logs'taki filtreler (service, host, q, ...) ve kiracı sınırı geçerlidir.
+// 'to' verilmezse şimdi, 'from' verilmezse 'to'dan 24 saat öncesi kullanılır.
+// Dilimler epoch'a (UTC) hizalıdır; log olmayan dilimler de sıfırla döner.
+#[derive(Debug, Deserialize)]
+pub struct BucketParams {
+    // "30s", "5m", "1h", "1d" veya saniye (varsayılan 5m)
+    bucket: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelHistogram {
+    bucket_secs: i64,
+    from: String,
+    to: String,
+    buckets: Vec<LevelBucket>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelBucket {
+    start: String,
+    total: i64,
+    levels: BTreeMap<String, i64>,
+}
+
+pub async fn levels_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(mut params): Query<LogQuery>,
+    Query(bucket): Query<BucketParams>,
+) -> Result<Json<LevelHistogram>, (StatusCode, String)> {
+    let bucket_secs = parse_bucket(bucket.bucket.as_deref().unwrap_or("5m")).map_err(bad_request)?;
+    let bucket = bucket_secs
+        .checked_mul(1_000_000)
+        .ok_or_else(|| bad_request("dilim çok büyük".to_string()))?;
+    let to = params.to.unwrap_or_else(|| chrono::Utc::now().timestamp_micros());
+    let from = params.from.unwrap_or(to - DEFAULT_WINDOW_MICROS);
+    if from > to {
+        return Err(bad_request("'from', 'to'dan sonra olamaz".to_string()));
+    }
+    let first = from - from.rem_euclid(bucket);
+    if (to - first) / bucket >= MAX_BUCKETS {
+        return Err(bad_request(format!("en fazla {} zaman dilimi istenebilir", MAX_BUCKETS)));
+    }
+
+    params.from = Some(from);
+    params.to = Some(to);
+    scope(&mut params, caller.as_deref());
+    debug!("📊 Seviye histogramı istendi: {:?} ({} sn)", params, bucket_secs);
+
+    let counts = state.store.level_counts(&params, bucket).await.map_err(|e| {
+        error!("❌ Seviye histogramı hesaplanamadı: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+    })?;
+
+    let mut buckets: BTreeMap<i64, BTreeMap<String, i64>> =
+        (0..=(to - first) / bucket).map(|n| (first + n * bucket, BTreeMap::new())).collect();
+    for count in counts {
+        *buckets.entry(count.bucket).or_default().entry(count.level).or_default() += count.count;
+    }
+
+    Ok(Json(LevelHistogram {
+        bucket_secs,
+        from: timestamp::format_micros(from),
+        to: timestamp::format_micros(to),
+        buckets: buckets
+            .into_iter()
+            .map(|(start, levels)| LevelBucket {
+                start: timestamp::format_micros(start),
+                total: levels.values().sum(),
+                levels,
+            })
+            .collect(),
+    }))
+}
+
+// --- En Sık Mesajlar ---
+// GET /stats/top-messages?from=...&to=...&limit=10&group_by=service: zaman
+// aralığında en çok tekrarlanan mesajlar (nöbetçinin hata bütçesini neyin
+// tükettiğini görmesi için). group_by: message (varsayılan), service veya
+// fingerprint (details.fingerprint; metni değişen aynı hata tek satır olur).
+// Seviye filtresi verilmezse sadece error ve üstü sayılır; diğer /logs
+// filtreleri ve kiracı sınırı geçerlidir. Aralık /stats/levels ile aynıdır.
+#[derive(Debug, Deserialize)]
+pub struct TopParams {
+    #[serde(default)]
+    group_by: MessageGrouping,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopMessages {
+    from: String,
+    to: String,
+    // Dönen grupların toplam log sayısı
+    total: i64,
+    messages: Vec<TopMessage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    service: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fingerprint: Option<String>,
+    // Gruptaki mesajlardan biri (parmak izine göre gruplamada metinler farklı olabilir)
+    message: String,
+    count: i64,
+    first_seen: String,
+    last_seen: String,
+}
+
+pub async fn top_messages_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(mut params): Query<LogQuery>,
+    Query(top): Query<TopParams>,
+) -> Result<Json<TopMessages>, (StatusCode, String)> {
+    let limit = params.limit.unwrap_or(DEFAULT_TOP).clamp(1, MAX_TOP);
+    let to = params.to.unwrap_or_else(|| chrono::Utc::now().timestamp_micros());
+    let from = params.from.unwrap_or(to - DEFAULT_WINDOW_MICROS);
+    if from > to {
+        return Err(bad_request("'from', 'to'dan sonra olamaz".to_string()));
+    }
+
+    params.from = Some(from);
+    params.to = Some(to);
+    if params.level.is_none() && params.min_level.is_none() {
+        params.min_level = crate::filter::severity("error");
+    }
+    scope(&mut params, caller.as_deref());
+    debug!("📊 En sık mesajlar istendi: {:?} ({:?})", params, top.group_by);
+
+    let counts = state.store.top_messages(&params, top.group_by, limit).await.map_err(|e| {
+        error!("❌ En sık mesajlar hesaplanamadı: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+    })?;
+
+    let messages: Vec<TopMessage> = counts
+        .into_iter()
+        .map(|count| {
+            let (service, fingerprint) = match top.group_by {
+                MessageGrouping::Message => (None, None),
+                MessageGrouping::Service => (count.group_key, None),
+                MessageGrouping::Fingerprint => (None, count.group_key),
+            };
+            TopMessage {
+                service,
+                fingerprint,
+                message: count.sample,
+                count: count.count,
+                first_seen: timestamp::format_micros(count.first_seen),
+                last_seen: timestamp::format_micros(count.last_seen),
+            }
+        })
+        .collect();
+    Ok(Json(TopMessages {
+        from: timestamp::format_micros(from),
+        to: timestamp::format_micros(to),
+        total: messages.iter().map(|m| m.count).sum(),
+        messages,
+    }))
+}
+
+// "5m" -> 300. Birim yoksa saniyedir; sıfır ve negatif kabul edilmez.
+fn parse_bucket(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    let (number, unit) = text.split_at(text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len()));
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("geçersiz dilim birimi: {} (s, m, h veya d)", text)),
+    };
+    match number.parse::<i64>() {
+        Ok(n) if n > 0 => n.checked_mul(multiplier).ok_or_else(|| format!("dilim çok büyük: {}", text)),
+        _ => Err(format!("geçersiz dilim süresi: {}", text)),
+    }
+}
+
+// /logs'taki gibi: istemcinin kiracısıyla sınırlanır, iz kimlikleri küçük harfe çevrilir.
+fn scope(params: &mut LogQuery, caller: Option<&Caller>) {
+    params.tenant = Some(auth::tenant_of(caller));
+    for id in [&mut params.trace_id, &mut params.span_id].into_iter().flatten() {
+        id.make_ascii_lowercase();
+    }
+}
+
+fn bad_request(message: String) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message)
+}
@@ -0,0 +1,214 @@
+use std::ffi::{c_char, c_int};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use libsqlite3_sys as ffi;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use zstd::bulk::Compressor;
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+use super::StorageResult;
+use crate::config::SqliteConfig;
+
+// --- details Sıkıştırması (SQLite) ---
+// storage.sqlite.compress_details açıkken details JSON'u zstd ile sıkıştırılıp BLOB
+// olarak yazılır. Küçük JSON nesneleri tek başına iyi sıkışmadığından sözlük
+// kullanılır: veritabanındaki son kayıtların details'inden eğitilir ve
+// details_dictionaries tablosunda saklanır. zstd çerçevesi sözlüğün kimliğini
+// taşır; eski kayıtlar yeni sözlük eğitildikten (veya sıkıştırma kapatıldıktan)
+// sonra da okunur.
+//
+// Okuma tarafında her bağlantıya eklenen details_json() SQL fonksiyonu BLOB'u JSON
+// metnine çevirir, metin değerleri olduğu gibi döner. Sorgular, details filtreleri
+// ve tam metin dizini tetikleyicileri bu fonksiyonu kullanır.
+
+// Eğitilen sözlüğün en büyük boyutu
+const DICTIONARY_BYTES: usize = 32 * 1024;
+
+// Sözlük kimliği -> çözücü sözlük. Süreç boyunca tutulur (sözlükler küçüktür ve
+// nadiren eklenir); açılan tüm SQLite veritabanları için ortaktır.
+static DECODERS: RwLock<Vec<(u32, &'static DecoderDictionary<'static>)>> = RwLock::new(Vec::new());
+
+pub(super) struct DetailsCodec {
+    level: i32,
+    // Sözlük eğitimi için gereken örnek sayısı (0 = sözlüksüz sıkıştırılır)
+    samples: usize,
+    // Yazarken kullanılan sözlük (en son eğitilen); None ise sözlüksüz.
+    // Sözlükler çözücüler gibi süreç boyunca yaşar.
+    dictionary: RwLock<Option<&'static EncoderDictionary<'static>>>,
+    // Sözlük yokken son eğitim denemesinden beri yazılan satır sayısı
+    pending: AtomicUsize,
+    training: AtomicBool,
+}
+
+// Veritabanındaki sözlükleri çözücülere ekler; en son eğitilenin verisini döner.
+pub(super) async fn load_dictionaries(pool: &SqlitePool) -> StorageResult<Option<Vec<u8>>> {
+    let rows: Vec<(i64, Vec<u8>)> = sqlx::query_as("SELECT id, dictionary FROM details_dictionaries ORDER BY created_at, rowid")
+        .fetch_all(pool)
+        .await?;
+    for (id, dictionary) in &rows {
+        register_decoder(*id as u32, dictionary);
+    }
+    Ok(rows.into_iter().last().map(|(_, dictionary)| dictionary))
+}
+
+fn register_decoder(id: u32, dictionary: &[u8]) {
+    let mut decoders = DECODERS.write().unwrap_or_else(|e| e.into_inner());
+    if !decoders.iter().any(|(known, _)| *known == id) {
+        decoders.push((id, Box::leak(Box::new(DecoderDictionary::copy(dictionary)))));
+    }
+}
+
+impl std::fmt::Debug for DetailsCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DetailsCodec")
+            .field("level", &self.level)
+            .field("samples", &self.samples)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DetailsCodec {
+    pub(super) fn new(config: &SqliteConfig, dictionary: Option<Vec<u8>>) -> Self {
+        DetailsCodec {
+            level: config.compression_level,
+            samples: config.dictionary_samples,
+            dictionary: RwLock::new(dictionary.map(|d| encoder(&d, config.compression_level))),
+            pending: AtomicUsize::new(0),
+            training: AtomicBool::new(false),
+        }
+    }
+
+    // Bir parti için sıkıştırıcı (güncel sözlükle)
+    pub(super) fn compressor(&self) -> Option<Compressor<'static>> {
+        let dictionary = *self.dictionary.read().unwrap_or_else(|e| e.into_inner());
+        match dictionary {
+            Some(dictionary) => Compressor::with_prepared_dictionary(dictionary).ok(),
+            None => Compressor::new(self.level).ok(),
+        }
+    }
+
+    // Yazılan satırları sayar; sözlük yoksa ve yeterli örnek biriktiyse eğitir.
+    pub(super) async fn after_insert(&self, pool: &SqlitePool, rows: usize) {
+        if self.samples == 0 || self.dictionary.read().unwrap_or_else(|e| e.into_inner()).is_some() {
+            return;
+        }
+        if self.pending.fetch_add(rows, Ordering::Relaxed) + rows < self.samples {
+            return;
+        }
+        if self.training.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.pending.store(0, Ordering::Relaxed);
+        match self.train(pool).await {
+            Ok(Some((id, size))) => tracing::info!("🗜️ details sıkıştırma sözlüğü eğitildi (kimlik {}, {} bayt).", id, size),
+            Ok(None) => tracing::debug!("🗜️ Sözlük eğitimi için örnekler yetersiz, daha sonra yeniden denenecek."),
+            Err(e) => tracing::warn!("⚠️ details sıkıştırma sözlüğü eğitilemedi: {}", e),
+        }
+        self.training.store(false, Ordering::Release);
+    }
+
+    async fn train(&self, pool: &SqlitePool) -> StorageResult<Option<(u32, usize)>> {
+        let samples: Vec<String> = sqlx::query_scalar("SELECT details_json(details) FROM logs WHERE details IS NOT NULL ORDER BY id DESC LIMIT ?")
+            .bind(self.samples as i64)
+            .fetch_all(pool)
+            .await?;
+        let trained = tokio::task::spawn_blocking(move || zstd::dict::from_samples(&samples, DICTIONARY_BYTES))
+            .await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+        // zstd, örnekler sözlük için yetersizse hata döner
+        let Ok(dictionary) = trained else {
+            return Ok(None);
+        };
+        let Some(id) = zstd::zstd_safe::get_dict_id_from_dict(&dictionary) else {
+            return Ok(None);
+        };
+        sqlx::query("INSERT OR IGNORE INTO details_dictionaries (id, dictionary, created_at) VALUES (?, ?, ?)")
+            .bind(id.get() as i64)
+            .bind(&dictionary)
+            .bind(chrono::Utc::now().timestamp_micros())
+            .execute(pool)
+            .await?;
+        register_decoder(id.get(), &dictionary);
+        *self.dictionary.write().unwrap_or_else(|e| e.into_inner()) = Some(encoder(&dictionary, self.level));
+        Ok(Some((id.get(), dictionary.len())))
+    }
+}
+
+fn encoder(dictionary: &[u8], level: i32) -> &'static EncoderDictionary<'static> {
+    Box::leak(Box::new(EncoderDictionary::copy(dictionary, level)))
+}
+
+// BLOB'u JSON metnine çözer.
+fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let size = zstd::zstd_safe::get_frame_content_size(data)
+        .ok()
+        .flatten()
+        .ok_or("geçersiz zstd çerçevesi")?;
+    let mut decompressor = zstd::bulk::Decompressor::new().map_err(|e| e.to_string())?;
+    if let Some(id) = zstd::zstd_safe::get_dict_id_from_frame(data) {
+        let decoders = DECODERS.read().unwrap_or_else(|e| e.into_inner());
+        let dictionary = decoders
+            .iter()
+            .find(|(known, _)| *known == id.get())
+            .map(|(_, dictionary)| *dictionary)
+            .ok_or_else(|| format!("{} kimlikli sıkıştırma sözlüğü bulunamadı", id))?;
+        decompressor.set_prepared_dictionary(dictionary).map_err(|e| e.to_string())?;
+    }
+    decompressor.decompress(data, size as usize).map_err(|e| e.to_string())
+}
+
+// Bağlantıya details_json() fonksiyonunu ekler (havuzların after_connect'i).
+pub(super) fn register_function(
+    conn: &mut SqliteConnection,
+) -> Pin<Box<dyn Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let mut handle = conn.lock_handle().await?;
+        let db = handle.as_raw_handle().as_ptr();
+        // SAFETY: tutamaç kilitliyken bağlantının iş parçacığı onu kullanmaz.
+        let rc = unsafe {
+            ffi::sqlite3_create_function_v2(
+                db,
+                c"details_json".as_ptr(),
+                1,
+                ffi::SQLITE_UTF8 | ffi::SQLITE_DETERMINISTIC,
+                std::ptr::null_mut(),
+                Some(details_json),
+                None,
+                None,
+                None,
+            )
+        };
+        if rc != ffi::SQLITE_OK {
+            return Err(sqlx::Error::Protocol(format!("details_json() eklenemedi (kod {})", rc)));
+        }
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn details_json(ctx: *mut ffi::sqlite3_context, _argc: c_int, argv: *mut *mut ffi::sqlite3_value) {
+    let value = *argv;
+    if ffi::sqlite3_value_type(value) != ffi::SQLITE_BLOB {
+        ffi::sqlite3_result_value(ctx, value);
+        return;
+    }
+    let len = ffi::sqlite3_value_bytes(value) as usize;
+    let data = match ffi::sqlite3_value_blob(value) as *const u8 {
+        pointer if pointer.is_null() || len == 0 => &[][..],
+        pointer => std::slice::from_raw_parts(pointer, len),
+    };
+    match decompress(data) {
+        Ok(text) => ffi::sqlite3_result_text(
+            ctx,
+            text.as_ptr() as *const c_char,
+            text.len() as c_int,
+            ffi::SQLITE_TRANSIENT(),
+        ),
+        Err(e) => {
+            let message = format!("details çözülemedi: {}", e);
+            ffi::sqlite3_result_error(ctx, message.as_ptr() as *const c_char, message.len() as c_int);
+        }
+    }
+}
@@ -0,0 +1,972 @@
+use std::borrow::Borrow;
+use std::fmt::Write;
+use std::sync::{Arc, OnceLock};
+
+use async_trait::async_trait;
+use sqlx::database::HasArguments;
+use sqlx::query::Query;
+use sqlx::QueryBuilder;
+
+use crate::config::{SqlitePartition, StorageConfig};
+use crate::expr::{Expr, Field, Op, Value};
+use crate::query::{LogQuery, StoredLog};
+use crate::timestamp;
+use crate::LogEntry;
+
+mod clickhouse;
+mod compression;
+mod partition;
+mod postgres;
+mod routing;
+mod sqlite;
+
+pub use clickhouse::ClickHouseStorage;
+pub use partition::PartitionedStorage;
+pub use postgres::PostgresStorage;
+pub use routing::{Route, Routes};
+pub use sqlite::{SqliteStorage, MEMORY};
+
+// Tek INSERT ifadesindeki maksimum satır (bind parametresi sınırının altında kalmak için)
+const ROWS_PER_STATEMENT: usize = 1000;
+
+// Tek sorguda dönebilecek maksimum satır sayısı
+const MAX_LIMIT: i64 = 1000;
+const DEFAULT_LIMIT: i64 = 100;
+
+const LOG_COLUMNS: &str = "id, tenant_id, level, message, timestamp, details, remote_addr, user_agent, api_key, received_at, severity, service, host, env, trace_id, span_id";
+const SELECT_LOGS: &str = "SELECT id, tenant_id, level, message, timestamp, details, remote_addr, user_agent, api_key, received_at, severity, service, host, env, trace_id, span_id FROM logs WHERE 1=1";
+
+// Kaynak bilgisi sütunları (sonradan eklendi; eski tablolara açılışta eklenir)
+const SOURCE_COLUMNS: [&str; 4] = ["remote_addr", "user_agent", "api_key", "received_at"];
+
+// Sık sorgulanan alanlar 'extra'dan kendi (dizinli) sütunlarına taşınır; details'te
+// tekrar saklanmaz. Her sütun için bakılan anahtarlar sırasıyla denenir, ilk dolu
+// metin (veya sayı) alınır. "resource" altındakiler OTLP kaynak öznitelikleridir;
+// "service.name", "host.name", "trace.id" ECS, "dd.trace_id" Datadog biçimidir.
+// trace_id / span_id bulunamazsa W3C 'traceparent' alanından okunur (bkz. trace_context).
+const PROMOTED_COLUMNS: [&str; 5] = ["service", "host", "env", "trace_id", "span_id"];
+const PROMOTED_KEYS: [&[&[&str]]; 5] = [
+    &[&["service"], &["service_name"], &["app"], &["app_name"], &["resource", "service.name"], &["service", "name"]],
+    &[&["host"], &["hostname"], &["resource", "host.name"], &["host", "name"]],
+    &[
+        &["env"],
+        &["environment"],
+        &["resource", "deployment.environment"],
+        &["resource", "deployment.environment.name"],
+        &["service", "environment"],
+    ],
+    &[&["trace_id"], &["traceId"], &["trace", "id"], &["dd", "trace_id"]],
+    &[&["span_id"], &["spanId"], &["span", "id"], &["dd", "span_id"]],
+];
+
+// severity sütunu olmadan yazılmış eski kayıtlar için seviyenin sayısal karşılığı
+// (filter::severity ile aynı eşleme; açılışta bir kez doldurulur)
+const SEVERITY_CASE: &str = "CASE LOWER(level) \
+    WHEN 'trace' THEN 0 WHEN 'debug' THEN 1 WHEN 'info' THEN 2 \
+    WHEN 'warn' THEN 3 WHEN 'warning' THEN 3 WHEN 'error' THEN 4 \
+    WHEN 'fatal' THEN 5 WHEN 'critical' THEN 5 END";
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("veritabanı hatası: {0}")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("depolama hatası: {0}")]
+    Backend(String),
+    // Ağ hatası veya sunucunun geçici olarak cevap verememesi (HTTP arka uçları)
+    #[error("depolama erişilemiyor: {0}")]
+    Unavailable(String),
+}
+
+impl StorageError {
+    // Aynı işlemin kısa bir süre sonra tekrar denenince başarılı olabileceği hatalar:
+    // kilitli SQLite dosyası, dolu bağlantı havuzu, kopan bağlantı, PostgreSQL
+    // serileştirme / kilitlenme çakışmaları, erişilemeyen ClickHouse.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            StorageError::Sqlx(sqlx::Error::PoolTimedOut | sqlx::Error::Io(_) | sqlx::Error::WorkerCrashed) => true,
+            StorageError::Sqlx(sqlx::Error::Database(e)) => match e.code() {
+                // SQLite genişletilmiş kodlarının alt baytı: 5 SQLITE_BUSY, 6 SQLITE_LOCKED
+                Some(code) if code.parse::<i32>().is_ok_and(|n| matches!(n & 0xff, 5 | 6)) => true,
+                // PostgreSQL: 40001 serialization_failure, 40P01 deadlock_detected,
+                // 08xxx bağlantı, 53xxx kaynak yetersizliği, 57P0x sunucu kapanıyor
+                Some(code) => {
+                    matches!(code.as_ref(), "40001" | "40P01")
+                        || code.starts_with("08")
+                        || code.starts_with("53")
+                        || code.starts_with("57P0")
+                }
+                None => false,
+            },
+            StorageError::Sqlx(_) | StorageError::Backend(_) => false,
+            StorageError::Unavailable(_) => true,
+        }
+    }
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+// --- Depolama Soyutlaması ---
+// Handler'lar ve yazıcı görevleri sadece bu trait'i bilir; yeni bir arka uç
+// eklemek için bu trait'i uygulayıp 'open' içinde seçilmesini sağlamak yeterlidir.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    // Arka ucun kısa adı (loglar ve metrikler için)
+    fn name(&self) -> &'static str;
+
+    // Veritabanı sorgu çalıştırabiliyor mu? (/readyz)
+    async fn ping(&self) -> StorageResult<()>;
+
+    // Logları tek seferde (mümkünse tek transaction içinde) yazar.
+    async fn insert_batch(&self, logs: &[LogEntry]) -> StorageResult<()>;
+
+    // Filtrelere uyan logları en yeniden eskiye doğru döner.
+    async fn query(&self, params: &LogQuery) -> StorageResult<Vec<StoredLog>>;
+
+    // Filtrelere uyan ve id'si 'after_id'den büyük ilk 'limit' logu id sırasıyla
+    // döner (dışa aktarma; 'limit'/'offset' parametreleri yok sayılır).
+    async fn export(&self, params: &LogQuery, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>>;
+
+    // Filtrelere uyan logları 'bucket' mikro saniyelik zaman dilimlerine ve
+    // seviyeye göre sayar (dilimler epoch'a hizalıdır; 'limit'/'offset' yok sayılır).
+    async fn level_counts(&self, params: &LogQuery, bucket: i64) -> StorageResult<Vec<LevelCount>>;
+
+    // Filtrelere uyan logları mesaja (ve isteğe göre servise / parmak izine)
+    // göre gruplayıp en sık görülen 'limit' grubu sayılarıyla döner.
+    async fn top_messages(&self, params: &LogQuery, group_by: MessageGrouping, limit: i64) -> StorageResult<Vec<MessageCount>>;
+
+    // Filtreye uyan eski kayıtları siler, silinen satır sayısını döner.
+    async fn purge(&self, filter: &PurgeFilter) -> StorageResult<u64>;
+
+    // 'purge'un sileceği kayıtlardan id'si 'after_id'den büyük ilk 'limit' tanesini
+    // id sırasıyla döner (silmeden önce arşivlemek için).
+    async fn select_purge(&self, filter: &PurgeFilter, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>>;
+
+    // Satır sayısı ve diskte kapladığı yer (boyut sınırı için).
+    async fn usage(&self) -> StorageResult<StorageUsage>;
+
+    // En eski (en küçük id'li) 'rows' kaydı siler, silinen satır sayısını döner.
+    async fn evict_oldest(&self, rows: u64) -> StorageResult<u64>;
+
+    // Silmelerden sonra boşalan alanı geri kazanır (SQLite: incremental vacuum).
+    async fn compact(&self) -> StorageResult<()>;
+
+    // Yazılanları kalıcı ana dosyaya aktarır; dosya kopyalanarak alınan yedek
+    // tutarlı olur (SQLite: WAL checkpoint). POST /admin/drain çağırır.
+    async fn checkpoint(&self) -> StorageResult<()>;
+
+    // Denetim kaydını 'audit' tablosuna ekler (bkz. audit).
+    async fn record_audit(&self, event: &AuditEvent) -> StorageResult<()>;
+
+    // Filtreye uyan denetim kayıtlarını en yeniden eskiye doğru döner.
+    async fn audit_log(&self, query: &AuditQuery) -> StorageResult<Vec<AuditRecord>>;
+}
+
+// Bir zaman dilimindeki bir seviyenin log sayısı (dilimin başı epoch mikro saniye)
+#[derive(Debug, sqlx::FromRow, serde::Deserialize)]
+pub struct LevelCount {
+    pub bucket: i64,
+    pub level: String,
+    pub count: i64,
+}
+
+// En sık mesajların gruplanması (GET /stats/top-messages?group_by=...)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageGrouping {
+    // Aynı mesaj metni bir gruptur
+    #[default]
+    Message,
+    // Servis ve mesaj metni
+    Service,
+    // details.fingerprint değeri (metni değişen aynı hata tek grup olur);
+    // parmak izi olmayan loglar mesaj metnine göre gruplanır
+    Fingerprint,
+}
+
+// Parmak izinin okunduğu details alanı (dedup.fingerprint_field varsayılanıyla aynı)
+const FINGERPRINT_KEY: &str = "fingerprint";
+
+// Bir mesaj grubunun sayısı. 'group_key' servis veya parmak izidir (mesaja göre
+// gruplamada boş); 'sample' gruptaki mesajlardan biri. Zamanlar epoch mikro saniye.
+#[derive(Debug, sqlx::FromRow, serde::Deserialize)]
+pub struct MessageCount {
+    pub group_key: Option<String>,
+    pub sample: String,
+    pub count: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StorageUsage {
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+// --- Denetim Kayıtları ---
+// Yönetim ve silme işlemleri loglardan ayrı 'audit' tablosunda tutulur; saklama
+// politikası ve silme talepleri bu tabloya dokunmaz.
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    // epoch mikro saniye
+    pub timestamp: i64,
+    // İşlemi yapan: istemci kimliği ("key:<etiket>", "jwt:<sub>", ...) veya
+    // sunucunun kendisi ("retention", "sighup", "cli")
+    pub actor: String,
+    pub remote_addr: Option<String>,
+    // "logs.delete", "retention.purge", "config.reload", "token.create", ...
+    pub action: String,
+    // İşlemin parametreleri ve sonucu (JSON nesnesi)
+    pub params: serde_json::Value,
+}
+
+// Okunan kayıt; 'params' JSON metnidir.
+#[derive(Debug, sqlx::FromRow, serde::Deserialize)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub timestamp: i64,
+    pub actor: String,
+    pub remote_addr: Option<String>,
+    pub action: String,
+    pub params: String,
+}
+
+// GET /admin/audit filtreleri; zamanlar epoch mikro saniye (dahil).
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub action: Option<String>,
+    pub actor: Option<String>,
+    // Sayfalama: sadece id'si bundan küçük olanlar
+    pub before_id: Option<i64>,
+    pub limit: i64,
+}
+
+const AUDIT_COLUMNS: &str = "id, timestamp, actor, remote_addr, action, params";
+
+// --- Silme Filtresi ---
+// Tüm alanlar silinecek kayıtları daraltır; boş filtre tüm tabloyu seçer.
+// Seviyeler büyük/küçük harf duyarsız karşılaştırılır ("ERROR" == "error").
+#[derive(Debug, Clone, Default)]
+pub struct PurgeFilter {
+    // Bu zamandan (UTC epoch mikro saniye) eski kayıtlar
+    pub before: Option<i64>,
+    // Sadece bu seviye
+    pub level: Option<String>,
+    // Sadece bu kiracı
+    pub tenant: Option<String>,
+    // Bu seviyeler dokunulmadan bırakılır (saklama politikasında kendi süresi olanlar)
+    pub except_levels: Vec<String>,
+    // Sadece id'si bu değere eşit veya küçük olanlar (arşivlenmiş parçayı silmek için)
+    pub max_id: Option<i64>,
+    // Bu zamandan itibaren (dahil) ve bu zamana kadar (dahil) olan kayıtlar
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    // Sadece bir alanı verilen değere eşit olanlar (DELETE /logs?field=user_id&value=42)
+    pub field: Option<FieldMatch>,
+}
+
+// Alan eşitliği: alan öne çıkarılmış bir sütun (service, host, env, trace_id,
+// span_id) ya da details içindeki bir anahtardır ("user.id" iç içe alan).
+// Sayılar metin olarak karşılaştırılır (value=42 hem 42'yi hem "42"'yi bulur).
+// Anahtar SQL'e yazıldığı için sadece harf, rakam ve '_' içerebilir.
+#[derive(Debug, Clone)]
+pub struct FieldMatch {
+    field: String,
+    value: String,
+}
+
+impl FieldMatch {
+    pub fn new(field: &str, value: &str) -> Result<Self, String> {
+        let valid = field
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        if !valid {
+            return Err(format!("geçersiz alan adı: {} (harf, rakam, '_' ve '.')", field));
+        }
+        Ok(FieldMatch { field: field.to_string(), value: value.to_string() })
+    }
+
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    // Alan öne çıkarılmış bir sütunsa adı; değilse details'e bakılır.
+    fn column(&self) -> Option<&'static str> {
+        PROMOTED_COLUMNS.into_iter().find(|column| *column == self.field)
+    }
+}
+
+// Havuzlar paylaşılır; yazıcılar ve okuma API'si aynı bağlantıları kullanır.
+pub type SharedStorage = Arc<dyn Storage>;
+
+// Bağlantı dizesine göre arka ucu seçer:
+//   - "postgres://..." veya "postgresql://..."       -> PostgreSQL
+//   - "clickhouse://..." veya "clickhouse+https://..." -> ClickHouse
+//   - ":memory:"                                     -> bellek içi SQLite (kalıcı değil)
+//   - diğer her şey                                  -> SQLite dosya yolu (varsayılan);
+//     storage.sqlite.partition = "day" ise günlük dosyalar
+// Havuz boyutu ve SQLite ayarları 'config'ten alınır ('db_path' değil 'target' açılır).
+pub async fn open(target: &str, config: &StorageConfig) -> StorageResult<SharedStorage> {
+    if target.starts_with("postgres://") || target.starts_with("postgresql://") {
+        return Ok(Arc::new(PostgresStorage::open(target, config).await?));
+    }
+    if target.starts_with("clickhouse://") || target.starts_with("clickhouse+https://") {
+        return Ok(Arc::new(ClickHouseStorage::open(target).await?));
+    }
+    if config.sqlite.partition == SqlitePartition::Day && target != MEMORY {
+        return Ok(Arc::new(PartitionedStorage::open(target, config).await?));
+    }
+    Ok(Arc::new(SqliteStorage::open(target, config).await?))
+}
+
+// Kaynak bilgisi sütunlarının değerleri (SOURCE_COLUMNS sırasıyla)
+fn source_columns(log: &LogEntry) -> [Option<String>; 4] {
+    [
+        log.source.remote_addr.map(|ip| ip.to_string()),
+        log.source.user_agent.clone(),
+        log.source.api_key.clone(),
+        log.source.received_at.clone(),
+    ]
+}
+
+// Seviyenin sayısal karşılığı (tanınmayan seviyelerde NULL)
+fn severity_column(log: &LogEntry) -> Option<i32> {
+    crate::filter::severity(&log.level).map(i32::from)
+}
+
+// Öne çıkarılan alanları 'extra'dan söküp PROMOTED_COLUMNS sırasıyla döner.
+fn take_promoted(extra: &mut serde_json::Value) -> [Option<String>; 5] {
+    let mut promoted = PROMOTED_KEYS.map(|candidates| candidates.iter().find_map(|path| take_text(extra, path)));
+    let [.., trace_id, span_id] = &mut promoted;
+    if trace_id.is_none() || span_id.is_none() {
+        if let Some((trace, span)) = extra.get("traceparent").and_then(|v| v.as_str()).and_then(trace_context) {
+            trace_id.get_or_insert(trace);
+            span_id.get_or_insert(span);
+        }
+    }
+    // Jaeger / Tempo kimlikleri küçük harfli onaltılık gösterir
+    for id in [trace_id, span_id].into_iter().flatten() {
+        id.make_ascii_lowercase();
+    }
+    promoted
+}
+
+// W3C traceparent: "00-<32 hex trace-id>-<16 hex parent-id>-<2 hex flags>".
+// Sıfırlardan oluşan (geçersiz) kimlikler kabul edilmez.
+fn trace_context(traceparent: &str) -> Option<(String, String)> {
+    let mut parts = traceparent.trim().split('-');
+    let (version, trace, span, flags) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let hex = |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_hexdigit());
+    if !hex(version, 2) || version.eq_ignore_ascii_case("ff") || !hex(trace, 32) || !hex(span, 16) || !hex(flags, 2) {
+        return None;
+    }
+    if trace.bytes().all(|b| b == b'0') || span.bytes().all(|b| b == b'0') {
+        return None;
+    }
+    Some((trace.to_ascii_lowercase(), span.to_ascii_lowercase()))
+}
+
+fn take_text(extra: &mut serde_json::Value, path: &[&str]) -> Option<String> {
+    let (key, parents) = path.split_last()?;
+    let parent = parents.iter().try_fold(&mut *extra, |value, key| value.get_mut(*key))?;
+    let text = match parent.get(*key)? {
+        serde_json::Value::String(text) if !text.is_empty() => text.clone(),
+        serde_json::Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+    parent.as_object_mut()?.remove(*key);
+    // Boşalan üst nesne ({"trace": {}}) details'te bırakılmaz
+    if let [outer] = parents {
+        if extra.get(*outer).and_then(|v| v.as_object()).is_some_and(|map| map.is_empty()) {
+            extra.as_object_mut()?.remove(*outer);
+        }
+    }
+    Some(text)
+}
+
+// Satırın 'timestamp' değeri (epoch mikro saniye): extra'daki zaman damgası (ingest
+// RFC 3339'a çevirmişti), yoksa alınma zamanı, o da yoksa şu an.
+fn row_timestamp(log: &LogEntry) -> i64 {
+    [log.extra.get("timestamp").and_then(|v| v.as_str()), log.source.received_at.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(|text| timestamp::parse_micros(text).ok())
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_micros())
+}
+
+// Logdan 'timestamp' (epoch mikro saniye), 'details' ve öne çıkarılan sütunların
+// değerlerini çıkarır.
+fn row_columns(log: &LogEntry) -> (i64, String, [Option<String>; 5]) {
+    let timestamp = row_timestamp(log);
+    // Öne çıkarılanlar dışındaki veriyi JSON string'e çevir (details sütunu için)
+    let mut extra = log.extra.clone();
+    let promoted = take_promoted(&mut extra);
+    let details = serde_json::to_string(&extra).unwrap_or_default();
+    (timestamp, details, promoted)
+}
+
+// --- INSERT ---
+// Parti sabit boyutlu parçalara bölünür: tam parçalar ROWS_PER_STATEMENT satırlık,
+// kalan ikinin kuvvetlerine ayrılır (737 -> 512 + 128 + 64 + 32 + 1). Böylece en
+// fazla INSERT_SHAPES kadar farklı SQL metni olur; metin her boyut için bir kez
+// kurulur, sqlx de her bağlantıda bir kez hazırlayıp (prepare) sonraki partilerde
+// önbellekten kullanır. Satır başına SQL ayrıştırılmaz.
+const INSERT_SHAPES: usize = 11;
+
+// Partiyi INSERT parçalarına böler.
+fn insert_chunks<T>(mut logs: &[T]) -> impl Iterator<Item = &[T]> {
+    std::iter::from_fn(move || {
+        if logs.is_empty() {
+            return None;
+        }
+        let rows = match logs.len() {
+            n if n >= ROWS_PER_STATEMENT => ROWS_PER_STATEMENT,
+            n => 1 << n.ilog2(),
+        };
+        let (chunk, rest) = logs.split_at(rows);
+        logs = rest;
+        Some(chunk)
+    })
+}
+
+// Yer tutucu biçimi
+#[derive(Debug, Clone, Copy)]
+enum Placeholder {
+    // SQLite: ?
+    Question,
+    // PostgreSQL: $1, $2, ...
+    Numbered,
+}
+
+// Arka uç başına bir kez kurulan INSERT metinleri (parça boyutuna göre)
+struct InsertStatements {
+    placeholder: Placeholder,
+    sql: [OnceLock<String>; INSERT_SHAPES],
+}
+
+impl InsertStatements {
+    const fn new(placeholder: Placeholder) -> Self {
+        InsertStatements {
+            placeholder,
+            sql: [const { OnceLock::new() }; INSERT_SHAPES],
+        }
+    }
+
+    // 'rows' satırlık INSERT (rows, insert_chunks'ın ürettiği boyutlardan biri)
+    fn sql(&self, rows: usize) -> &str {
+        let shape = if rows == ROWS_PER_STATEMENT { 0 } else { 1 + rows.ilog2() as usize };
+        self.sql[shape].get_or_init(|| {
+            let columns = 6 + SOURCE_COLUMNS.len() + PROMOTED_COLUMNS.len();
+            let mut sql = format!(
+                "INSERT INTO logs (tenant_id, level, message, timestamp, details, severity, {}, {}) VALUES ",
+                SOURCE_COLUMNS.join(", "),
+                PROMOTED_COLUMNS.join(", ")
+            );
+            for row in 0..rows {
+                sql.push_str(if row == 0 { "(" } else { ", (" });
+                for column in 0..columns {
+                    if column > 0 {
+                        sql.push_str(", ");
+                    }
+                    match self.placeholder {
+                        Placeholder::Question => sql.push('?'),
+                        Placeholder::Numbered => {
+                            let _ = write!(sql, "${}", row * columns + column + 1);
+                        }
+                    }
+                }
+                sql.push(')');
+            }
+            sql
+        })
+    }
+}
+
+// Parçanın değerlerini INSERT sütun sırasıyla bağlar.
+fn bind_insert<'q, DB, T: Borrow<LogEntry>>(
+    mut query: Query<'q, DB, <DB as HasArguments<'q>>::Arguments>,
+    logs: &[T],
+    mut compressor: Option<&mut zstd::bulk::Compressor<'_>>,
+) -> Query<'q, DB, <DB as HasArguments<'q>>::Arguments>
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    Option<String>: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    Option<i32>: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    Vec<u8>: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+{
+    for log in logs {
+        let log = log.borrow();
+        let (timestamp, details, promoted) = row_columns(log);
+        query = query
+            .bind(log.tenant_id.clone())
+            .bind(log.level.clone())
+            .bind(log.message.clone())
+            .bind(timestamp);
+        // Sıkıştırıcı verilmişse details BLOB olarak yazılır (yalnızca SQLite)
+        query = match compressor.as_mut().map(|c| c.compress(details.as_bytes())) {
+            Some(Ok(compressed)) => query.bind(compressed),
+            _ => query.bind(details),
+        };
+        query = query.bind(severity_column(log));
+        for value in source_columns(log).into_iter().chain(promoted) {
+            query = query.bind(value);
+        }
+    }
+    query
+}
+
+// --- SQL Üreticiler ---
+// Aynı SQL iki veritabanı için de geçerlidir; yer tutucuları ('?' / '$1')
+// QueryBuilder kendisi üretir.
+
+// 'q' parametresinin arka uca göre karşılığı
+#[derive(Debug, Clone, Copy)]
+enum TextSearch {
+    // message ILIKE '%q%' (PostgreSQL)
+    ILike,
+    // SQLite FTS5 dizini (logs_fts): message ve details içinde kelime / kelime başı eşleşmesi
+    Fts5,
+}
+
+// Arka uca göre değişen SQL parçaları (SQLite / PostgreSQL)
+#[derive(Debug, Clone, Copy)]
+struct Dialect {
+    search: TextSearch,
+    // details'teki bir anahtarı ("a.b") metin / sayı olarak okuyan ifadeler;
+    // sayı okuyan ifade değer sayı değilse NULL döner
+    details_text: fn(&str) -> String,
+    details_number: fn(&str) -> String,
+    // Büyük/küçük harf duyarsız LIKE
+    like: &'static str,
+}
+
+fn push_filters<'a, DB>(qb: &mut QueryBuilder<'a, DB>, params: &LogQuery, dialect: &Dialect)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i32: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    push_conditions(qb, params, dialect);
+
+    // İmleç kaydının (timestamp, id) değeri alt sorguyla okunur; kayıt silinmişse sayfa boştur.
+    if let Some(before_id) = params.before_id {
+        qb.push(" AND (timestamp, id) < (SELECT timestamp, id FROM logs WHERE id = ")
+            .push_bind(before_id)
+            .push(")");
+    }
+    if let Some(after_id) = params.after_id {
+        qb.push(" AND (timestamp, id) > (SELECT timestamp, id FROM logs WHERE id = ")
+            .push_bind(after_id)
+            .push(")");
+    }
+
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+    qb.push(if params.reads_forward() {
+        " ORDER BY timestamp, id LIMIT "
+    } else {
+        " ORDER BY timestamp DESC, id DESC LIMIT "
+    })
+    .push_bind(limit);
+    qb.push(" OFFSET ").push_bind(offset);
+}
+
+fn push_export<'a, DB>(qb: &mut QueryBuilder<'a, DB>, params: &LogQuery, dialect: &Dialect, after_id: i64, limit: i64)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i32: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    push_conditions(qb, params, dialect);
+    qb.push(" AND id > ").push_bind(after_id);
+    qb.push(" ORDER BY id LIMIT ").push_bind(limit);
+}
+
+fn push_level_counts<'a, DB>(qb: &mut QueryBuilder<'a, DB>, params: &LogQuery, dialect: &Dialect, bucket: i64)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i32: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    qb.push("SELECT (timestamp / ").push_bind(bucket).push(") * ").push_bind(bucket);
+    qb.push(" AS bucket, level, COUNT(*) AS count FROM logs WHERE 1=1");
+    push_conditions(qb, params, dialect);
+    qb.push(" GROUP BY bucket, level ORDER BY bucket");
+}
+
+// 'fingerprint' arka uca göre details'ten FINGERPRINT_KEY'i metin olarak okuyan ifadedir.
+fn push_top_messages<'a, DB>(
+    qb: &mut QueryBuilder<'a, DB>,
+    params: &LogQuery,
+    dialect: &Dialect,
+    group_by: MessageGrouping,
+    fingerprint: &str,
+    limit: i64,
+) where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i32: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    let (key, groups) = match group_by {
+        MessageGrouping::Message => ("CAST(NULL AS TEXT)".to_string(), "message".to_string()),
+        MessageGrouping::Service => ("service".to_string(), "service, message".to_string()),
+        MessageGrouping::Fingerprint => (
+            fingerprint.to_string(),
+            format!("{0}, CASE WHEN {0} IS NULL THEN message END", fingerprint),
+        ),
+    };
+    qb.push(format!(
+        "SELECT {} AS group_key, MIN(message) AS sample, COUNT(*) AS count, \
+         MIN(timestamp) AS first_seen, MAX(timestamp) AS last_seen FROM logs WHERE 1=1",
+        key
+    ));
+    push_conditions(qb, params, dialect);
+    qb.push(format!(" GROUP BY {} ORDER BY count DESC, sample LIMIT ", groups)).push_bind(limit);
+}
+
+fn push_conditions<'a, DB>(qb: &mut QueryBuilder<'a, DB>, params: &LogQuery, dialect: &Dialect)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i32: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    if let Some(tenant) = &params.tenant {
+        qb.push(" AND tenant_id = ").push_bind(tenant.clone());
+    }
+    if let Some(level) = &params.level {
+        qb.push(" AND level = ").push_bind(level.clone());
+    }
+    if let Some(min_level) = params.min_level {
+        qb.push(" AND severity >= ").push_bind(i32::from(min_level));
+    }
+    for (column, value) in PROMOTED_COLUMNS.iter().zip(params.promoted()) {
+        if let Some(value) = value {
+            qb.push(format!(" AND {} = ", column)).push_bind(value.clone());
+        }
+    }
+    if let Some(from) = params.from {
+        qb.push(" AND timestamp >= ").push_bind(from);
+    }
+    if let Some(to) = params.to {
+        qb.push(" AND timestamp <= ").push_bind(to);
+    }
+    if let Some(q) = &params.q {
+        match dialect.search {
+            TextSearch::ILike => {
+                qb.push(" AND message ILIKE ").push_bind(format!("%{}%", q));
+            }
+            TextSearch::Fts5 => {
+                if let Some(query) = fts5_query(q) {
+                    qb.push(" AND id IN (SELECT rowid FROM logs_fts WHERE logs_fts MATCH ")
+                        .push_bind(query)
+                        .push(")");
+                }
+            }
+        }
+    }
+    if let Some(expr) = &params.filter {
+        qb.push(" AND ");
+        push_expr(qb, expr, dialect);
+    }
+}
+
+// Filtre ifadesini koşula çevirir. Karşılaştırmalar COALESCE(..., FALSE) ile
+// sarılır: olmayan alan (NULL) yanlış sayılır, NOT ile doğru olur.
+fn push_expr<'a, DB>(qb: &mut QueryBuilder<'a, DB>, expr: &Expr, dialect: &Dialect)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    match expr {
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            qb.push("(");
+            push_expr(qb, left, dialect);
+            qb.push(if matches!(expr, Expr::And(..)) { " AND " } else { " OR " });
+            push_expr(qb, right, dialect);
+            qb.push(")");
+        }
+        Expr::Not(inner) => {
+            qb.push("NOT ");
+            push_expr(qb, inner, dialect);
+        }
+        Expr::Compare(comparison) => {
+            let target = match (&comparison.field, &comparison.value) {
+                (Field::Column(column), _) => column.to_string(),
+                (Field::Details(path), Value::Float(_)) => (dialect.details_number)(path),
+                (Field::Details(path), _) => (dialect.details_text)(path),
+            };
+            qb.push(format!("COALESCE({} ", target));
+            match (comparison.op, &comparison.value) {
+                (Op::Contains, value) => {
+                    let text = match value {
+                        Value::Text(text) => text.clone(),
+                        Value::Integer(n) => n.to_string(),
+                        Value::Float(n) => n.to_string(),
+                    };
+                    qb.push(dialect.like).push(" ").push_bind(like_pattern(&text)).push(" ESCAPE '\\'");
+                }
+                (op, Value::Text(text)) => {
+                    qb.push(op.sql()).push(" ").push_bind(text.clone());
+                }
+                (op, Value::Integer(n)) => {
+                    qb.push(op.sql()).push(" ").push_bind(*n);
+                }
+                (op, Value::Float(n)) => {
+                    qb.push(op.sql()).push(" ").push_bind(*n);
+                }
+            }
+            qb.push(", FALSE)");
+        }
+    }
+}
+
+// '~' için alt dize deseni: LIKE joker karakterleri (%, _) kaçışlanır.
+fn like_pattern(text: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+// Kullanıcı metnini güvenli bir FTS5 sorgusuna çevirir: her kelime tırnaklanır
+// (FTS5 sözdizimi/operatörleri yorumlanmaz) ve kelime başı olarak aranır;
+// kelimelerin hepsi geçmelidir. "disk tim" -> "disk"* "tim"*
+fn fts5_query(q: &str) -> Option<String> {
+    let terms: Vec<String> = q
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+// 'details_text' arka uca göre details'teki bir anahtarı ("a.b") metin olarak
+// okuyan ifadeyi üretir (FieldMatch için).
+fn push_purge<'a, DB>(qb: &mut QueryBuilder<'a, DB>, filter: &PurgeFilter, details_text: fn(&str) -> String)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    qb.push("DELETE FROM logs WHERE 1=1");
+    push_purge_filters(qb, filter, details_text);
+}
+
+fn push_select_purge<'a, DB>(
+    qb: &mut QueryBuilder<'a, DB>,
+    select: &str,
+    filter: &PurgeFilter,
+    details_text: fn(&str) -> String,
+    after_id: i64,
+    limit: i64,
+) where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    qb.push(select);
+    push_purge_filters(qb, filter, details_text);
+    qb.push(" AND id > ").push_bind(after_id);
+    qb.push(" ORDER BY id LIMIT ").push_bind(limit);
+}
+
+fn push_purge_filters<'a, DB>(qb: &mut QueryBuilder<'a, DB>, filter: &PurgeFilter, details_text: fn(&str) -> String)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    if let Some(before) = filter.before {
+        qb.push(" AND timestamp < ").push_bind(before);
+    }
+    if let Some(level) = &filter.level {
+        qb.push(" AND LOWER(level) = ").push_bind(level.to_lowercase());
+    }
+    if let Some(tenant) = &filter.tenant {
+        qb.push(" AND tenant_id = ").push_bind(tenant.clone());
+    }
+    if !filter.except_levels.is_empty() {
+        qb.push(" AND LOWER(level) NOT IN (");
+        let mut levels = qb.separated(", ");
+        for level in &filter.except_levels {
+            levels.push_bind(level.to_lowercase());
+        }
+        qb.push(")");
+    }
+    if let Some(max_id) = filter.max_id {
+        qb.push(" AND id <= ").push_bind(max_id);
+    }
+    if let Some(from) = filter.from {
+        qb.push(" AND timestamp >= ").push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        qb.push(" AND timestamp <= ").push_bind(to);
+    }
+    if let Some(field) = &filter.field {
+        let target = field.column().map(str::to_string).unwrap_or_else(|| details_text(&field.field));
+        qb.push(format!(" AND {} = ", target)).push_bind(field.value.clone());
+    }
+}
+
+fn push_audit_log<'a, DB>(qb: &mut QueryBuilder<'a, DB>, query: &AuditQuery)
+where
+    DB: sqlx::Database,
+    String: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    qb.push(format!("SELECT {} FROM audit WHERE 1=1", AUDIT_COLUMNS));
+    if let Some(from) = query.from {
+        qb.push(" AND timestamp >= ").push_bind(from);
+    }
+    if let Some(to) = query.to {
+        qb.push(" AND timestamp <= ").push_bind(to);
+    }
+    if let Some(action) = &query.action {
+        qb.push(" AND action = ").push_bind(action.clone());
+    }
+    if let Some(actor) = &query.actor {
+        qb.push(" AND actor = ").push_bind(actor.clone());
+    }
+    if let Some(before_id) = query.before_id {
+        qb.push(" AND id < ").push_bind(before_id);
+    }
+    qb.push(" ORDER BY id DESC LIMIT ").push_bind(query.limit.clamp(1, MAX_LIMIT));
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Connection, Execute, Row, Sqlite, SqliteConnection};
+
+    use super::*;
+    use crate::expr;
+
+    const TEST_DIALECT: Dialect = Dialect {
+        search: TextSearch::Fts5,
+        details_text: |path| format!("text({})", path),
+        details_number: |path| format!("number({})", path),
+        like: "LIKE",
+    };
+
+    // İfadenin SQL'i ve bağlanan değerleri. Değerler, aynı argümanlarla
+    // "SELECT ?, ?, ..." çalıştırılarak SQLite'tan geri okunur.
+    async fn render(filter: &str) -> (String, Vec<serde_json::Value>) {
+        let mut qb = QueryBuilder::<Sqlite>::new("");
+        push_expr(&mut qb, &expr::parse(filter).unwrap(), &TEST_DIALECT);
+        let sql = qb.sql().to_string();
+        let count = sql.matches('?').count();
+        let mut query = qb.build();
+        let arguments = query.take_arguments().unwrap();
+
+        let select = format!("SELECT {}", vec!["?"; count].join(", "));
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        let row = sqlx::query_with(&select, arguments).fetch_one(&mut conn).await.unwrap();
+        let binds = (0..count)
+            .map(|i| {
+                row.try_get::<i64, _>(i)
+                    .map(serde_json::Value::from)
+                    .or_else(|_| row.try_get::<f64, _>(i).map(serde_json::Value::from))
+                    .or_else(|_| row.try_get::<String, _>(i).map(serde_json::Value::from))
+                    .unwrap()
+            })
+            .collect();
+        (sql, binds)
+    }
+
+    #[tokio::test]
+    async fn comparisons_are_wrapped_and_bound() {
+        let (sql, binds) = render(r#"level=error AND extra.user.id=123"#).await;
+        assert_eq!(sql, "(COALESCE(level = ?, FALSE) AND COALESCE(text(user.id) = ?, FALSE))");
+        assert_eq!(binds, [serde_json::json!("error"), serde_json::json!("123")]);
+
+        let (sql, binds) = render("level>=warn OR extra.ms>250.5").await;
+        assert_eq!(sql, "(COALESCE(severity >= ?, FALSE) OR COALESCE(number(ms) > ?, FALSE))");
+        assert_eq!(binds, [serde_json::json!(3), serde_json::json!(250.5)]);
+    }
+
+    #[tokio::test]
+    async fn not_equal_wraps_the_whole_comparison() {
+        // NOT COALESCE(..., FALSE): alanı olmayan loglar da döner
+        let (sql, binds) = render("extra.user_id!=5").await;
+        assert_eq!(sql, "NOT COALESCE(text(user_id) = ?, FALSE)");
+        assert_eq!(binds, [serde_json::json!("5")]);
+
+        let (sql, _) = render("NOT (a=1 OR b=2) AND c=3").await;
+        assert_eq!(
+            sql,
+            "(NOT (COALESCE(text(a) = ?, FALSE) OR COALESCE(text(b) = ?, FALSE)) AND COALESCE(text(c) = ?, FALSE))"
+        );
+    }
+
+    #[tokio::test]
+    async fn contains_escapes_like_wildcards() {
+        let (sql, binds) = render(r#"message~"50%_off\\x" OR extra.path~"a'b""#).await;
+        assert_eq!(
+            sql,
+            "(COALESCE(message LIKE ? ESCAPE '\\', FALSE) OR COALESCE(text(path) LIKE ? ESCAPE '\\', FALSE))"
+        );
+        assert_eq!(binds, [serde_json::json!(r"%50\%\_off\\x%"), serde_json::json!("%a'b%")]);
+    }
+
+    #[tokio::test]
+    async fn not_equal_matches_entries_without_the_field() {
+        let store = SqliteStorage::open(MEMORY, &StorageConfig::default()).await.unwrap();
+        let logs: Vec<LogEntry> = [
+            serde_json::json!({"level": "error", "message": "beş", "user_id": 5}),
+            serde_json::json!({"level": "error", "message": "altı", "user_id": "6"}),
+            serde_json::json!({"level": "error", "message": "yok"}),
+        ]
+        .into_iter()
+        .map(|raw| serde_json::from_value(raw).unwrap())
+        .collect();
+        store.insert_batch(&logs).await.unwrap();
+
+        let messages = |filter: &str| {
+            let params = LogQuery {
+                filter: Some(expr::parse(filter).unwrap()),
+                ..LogQuery::default()
+            };
+            let store = store.clone();
+            async move {
+                let mut found: Vec<String> =
+                    store.query(&params).await.unwrap().into_iter().map(|log| log.message).collect();
+                found.sort();
+                found
+            }
+        };
+        assert_eq!(messages("extra.user_id=5").await, ["beş"]);
+        assert_eq!(messages("extra.user_id!=5").await, ["altı", "yok"]);
+        assert_eq!(messages("extra.user_id>5").await, Vec::<String>::new());
+        assert_eq!(messages("extra.user_id>\"5\"").await, ["altı"]);
+    }
+
+    #[tokio::test]
+    async fn values_never_reach_the_sql_text() {
+        let (sql, binds) = render(r#"message="x'); DROP TABLE logs; --""#).await;
+        assert_eq!(sql, "COALESCE(message = ?, FALSE)");
+        assert_eq!(binds, [serde_json::json!("x'); DROP TABLE logs; --")]);
+    }
+}
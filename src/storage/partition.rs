@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use tokio::sync::OnceCell;
+
+use super::{
+    row_timestamp, AuditEvent, AuditQuery, AuditRecord, LevelCount, MessageCount, MessageGrouping, PurgeFilter, SqliteStorage, Storage, StorageError,
+    StorageResult, StorageUsage, DEFAULT_LIMIT, MAX_LIMIT,
+};
+use crate::config::StorageConfig;
+use crate::query::{LogQuery, StoredLog};
+use crate::LogEntry;
+
+// --- Günlük Bölümler (SQLite) ---
+// storage.sqlite.partition = "day" iken loglar zaman damgalarının UTC gününe göre
+// ayrı dosyalara yazılır: db_path "logs.db" ise "logs-2024-06-01.db",
+// "logs-2024-06-02.db", ... Her dosya kendi şemasıyla tam bir SQLite veritabanıdır.
+// Saklama politikası süresi dolan günlerin dosyalarını bütünüyle siler; büyük
+// tablolarda DELETE + VACUUM gerekmez.
+//
+// Okumalar ilgili günleri sırayla gezer. Günler zaman damgasına göre ayrık
+// olduğundan sonuçlar sıralı birleştirme gerektirmeden uç uca eklenir. id'ler her
+// günün kendi aralığından verilir (gün << 32 + sıra): dosyalar arasında tekildir,
+// imleçler ve dışa aktarma olduğu gibi çalışır, id'den günü bulunur.
+//
+// Denetim kayıtları günlere bölünmez; gün dosyalarıyla silinmesinler diye ayrı
+// bir dosyada ("logs-audit.db") tutulur.
+pub struct PartitionedStorage {
+    // "data/logs.db" -> "data", "logs", ".db"
+    dir: PathBuf,
+    stem: String,
+    extension: String,
+    config: StorageConfig,
+    // Dosyası olan günler (epoch'tan beri gün) -> ilk kullanımda açılan depo
+    days: Mutex<BTreeMap<i64, Arc<OnceCell<SqliteStorage>>>>,
+    // İlk denetim kaydında açılır
+    audit: OnceCell<SqliteStorage>,
+}
+
+const DAY_MICROS: i64 = 86_400_000_000;
+const ID_SHIFT: u32 = 32;
+
+// Günlerin "en sık mesajlar" listeleri birleştirilirken her günden istenen grup
+// sayısı (limit'in katı); nadir grupların toplamı yaklaşık olabilir.
+const TOP_MESSAGES_FACTOR: i64 = 10;
+
+// Gün aralığı (iki uç dahil)
+type DayRange = (i64, i64);
+
+impl PartitionedStorage {
+    // Dizindeki mevcut gün dosyalarını bulur; dosyalar ilk kullanımda açılır.
+    pub async fn open(path: &str, config: &StorageConfig) -> StorageResult<Self> {
+        let path = Path::new(path);
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+
+        let mut days = BTreeMap::new();
+        match std::fs::read_dir(&dir) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    let date = name
+                        .strip_prefix(&stem)
+                        .and_then(|rest| rest.strip_prefix('-'))
+                        .and_then(|rest| rest.strip_suffix(extension.as_str()))
+                        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+                    if let Some(date) = date {
+                        days.insert(day_of_date(date), Arc::new(OnceCell::new()));
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(StorageError::Backend(format!("{} okunamadı: {}", dir.display(), e))),
+        }
+        tracing::info!("📅 Günlük bölümler: {} dosya ({}/{}-YYYY-MM-DD{})", days.len(), dir.display(), stem, extension);
+
+        Ok(PartitionedStorage {
+            dir,
+            stem,
+            extension,
+            config: config.clone(),
+            days: Mutex::new(days),
+            audit: OnceCell::new(),
+        })
+    }
+
+    fn file(&self, day: i64) -> PathBuf {
+        let date = NaiveDate::default() + chrono::Duration::days(day);
+        self.dir.join(format!("{}-{}{}", self.stem, date.format("%Y-%m-%d"), self.extension))
+    }
+
+    // Denetim kayıtlarının deposu; dosya yoksa oluşturulur.
+    async fn audit(&self) -> StorageResult<&SqliteStorage> {
+        self.audit
+            .get_or_try_init(|| async {
+                let path = self.dir.join(format!("{}-audit{}", self.stem, self.extension));
+                SqliteStorage::open(&path.to_string_lossy(), &self.config).await
+            })
+            .await
+    }
+
+    // Günün deposu; dosya yoksa oluşturulur.
+    async fn partition(&self, day: i64) -> StorageResult<SqliteStorage> {
+        let cell = self.days.lock().unwrap_or_else(|e| e.into_inner()).entry(day).or_default().clone();
+        let store = cell
+            .get_or_try_init(|| async {
+                let path = self.file(day);
+                let store = SqliteStorage::open(&path.to_string_lossy(), &self.config).await?;
+                store.reserve_ids((day << ID_SHIFT) + 1).await?;
+                Ok::<_, StorageError>(store)
+            })
+            .await?;
+        Ok(store.clone())
+    }
+
+    // Aralıktaki mevcut günler (artan sırada)
+    fn existing(&self, (first, last): DayRange) -> Vec<i64> {
+        if first > last {
+            return Vec::new();
+        }
+        self.days.lock().unwrap_or_else(|e| e.into_inner()).range(first..=last).map(|(day, _)| *day).collect()
+    }
+
+    // Günün dosyasını siler; silinen satır sayısını döner.
+    async fn drop_day(&self, day: i64, store: SqliteStorage) -> StorageResult<u64> {
+        let rows = store.usage().await?.rows;
+        self.days.lock().unwrap_or_else(|e| e.into_inner()).remove(&day);
+        store.close().await;
+        let path = self.file(day);
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            match std::fs::remove_file(&file) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(StorageError::Backend(format!("{} silinemedi: {}", path.display(), e))),
+            }
+        }
+        tracing::info!("📅 {} silindi ({} kayıt).", path.display(), rows);
+        Ok(rows)
+    }
+}
+
+fn day_of_date(date: NaiveDate) -> i64 {
+    (date - NaiveDate::default()).num_days()
+}
+
+// Zaman damgasının (epoch mikro saniye) günü; 1970 öncesi ilk güne düşer.
+fn day_of(micros: i64) -> i64 {
+    micros.div_euclid(DAY_MICROS).max(0)
+}
+
+fn day_of_id(id: i64) -> i64 {
+    (id >> ID_SHIFT).max(0)
+}
+
+// Sorgunun dokunabileceği günler
+fn query_days(params: &LogQuery) -> DayRange {
+    let first = [params.from.map(day_of), params.after_id.map(day_of_id)].into_iter().flatten().max();
+    let last = [params.to.map(day_of), params.before_id.map(day_of_id)].into_iter().flatten().min();
+    (first.unwrap_or(i64::MIN), last.unwrap_or(i64::MAX))
+}
+
+// Silme filtresinin dokunabileceği günler ('before' hariç tutulur)
+fn purge_days(filter: &PurgeFilter) -> DayRange {
+    let first = filter.from.map(day_of);
+    let last = [filter.before.map(|before| day_of(before - 1)), filter.to.map(day_of), filter.max_id.map(day_of_id)]
+        .into_iter()
+        .flatten()
+        .min();
+    (first.unwrap_or(i64::MIN), last.unwrap_or(i64::MAX))
+}
+
+// Filtre günün tüm kayıtlarını seçiyor mu? (yalnızca zaman sınırları ve gün tamamen içinde)
+fn covers_day(filter: &PurgeFilter, day: i64) -> bool {
+    let start = day * DAY_MICROS;
+    let end = start + DAY_MICROS;
+    filter.level.is_none()
+        && filter.tenant.is_none()
+        && filter.except_levels.is_empty()
+        && filter.max_id.is_none()
+        && filter.field.is_none()
+        && filter.from.is_none_or(|from| from <= start)
+        && filter.to.is_none_or(|to| to >= end - 1)
+        && filter.before.is_none_or(|before| before >= end)
+}
+
+#[async_trait]
+impl Storage for PartitionedStorage {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn ping(&self) -> StorageResult<()> {
+        let latest = self.days.lock().unwrap_or_else(|e| e.into_inner()).keys().next_back().copied();
+        match latest {
+            Some(day) => self.partition(day).await?.ping().await,
+            None => Ok(()),
+        }
+    }
+
+    async fn insert_batch(&self, logs: &[LogEntry]) -> StorageResult<()> {
+        let mut by_day: BTreeMap<i64, Vec<&LogEntry>> = BTreeMap::new();
+        for log in logs {
+            by_day.entry(day_of(row_timestamp(log))).or_default().push(log);
+        }
+        for (day, logs) in by_day {
+            self.partition(day).await?.insert_entries(&logs).await?;
+        }
+        Ok(())
+    }
+
+    async fn query(&self, params: &LogQuery) -> StorageResult<Vec<StoredLog>> {
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+        let offset = params.offset.unwrap_or(0).max(0) as usize;
+        let wanted = offset + limit;
+        // İleri okuma (yalnızca after_id) imleçten sonraki en eski kayıtları ister:
+        // günler eskiden yeniye gezilir. Diğer durumlarda yeniden eskiye.
+        let forward = params.reads_forward();
+        let mut days = self.existing(query_days(params));
+        if !forward {
+            days.reverse();
+        }
+
+        let mut rows: Vec<StoredLog> = Vec::new();
+        'days: for day in days {
+            let store = self.partition(day).await?;
+            // İmleçler yalnızca kendi günlerinde anlamlıdır; diğer günler aralığın tamamen içindedir.
+            let mut page = LogQuery {
+                before_id: params.before_id.filter(|id| day_of_id(*id) == day),
+                after_id: params.after_id.filter(|id| day_of_id(*id) == day),
+                oldest_first: forward,
+                offset: Some(0),
+                ..params.clone()
+            };
+            // Gün içinde imleçle sayfalanır ('offset' günler arasında uygulanır)
+            loop {
+                let requested = (wanted - rows.len()).min(MAX_LIMIT as usize);
+                page.limit = Some(requested as i64);
+                let batch = store.query(&page).await?;
+                let fetched = batch.len();
+                if forward {
+                    // Sayfa yeniden eskiye döner; birikim eskiden yeniye tutulur
+                    if let Some(newest) = batch.first() {
+                        page.after_id = Some(newest.id);
+                    }
+                    rows.extend(batch.into_iter().rev());
+                } else {
+                    if let Some(oldest) = batch.last() {
+                        page.before_id = Some(oldest.id);
+                    }
+                    rows.extend(batch);
+                }
+                if rows.len() >= wanted {
+                    break 'days;
+                }
+                if fetched < requested {
+                    break;
+                }
+            }
+        }
+
+        let mut rows: Vec<StoredLog> = rows.into_iter().skip(offset).take(limit).collect();
+        if forward {
+            rows.reverse();
+        }
+        Ok(rows)
+    }
+
+    async fn export(&self, params: &LogQuery, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>> {
+        let (first, last) = query_days(params);
+        let mut rows = Vec::new();
+        for day in self.existing((first.max(day_of_id(after_id)), last)) {
+            let remaining = limit - rows.len() as i64;
+            if remaining <= 0 {
+                break;
+            }
+            rows.extend(self.partition(day).await?.export(params, after_id, remaining).await?);
+        }
+        Ok(rows)
+    }
+
+    async fn level_counts(&self, params: &LogQuery, bucket: i64) -> StorageResult<Vec<LevelCount>> {
+        // Bir günden uzun dilimler birden fazla dosyaya yayılır: sayılar toplanır
+        let mut counts: BTreeMap<(i64, String), i64> = BTreeMap::new();
+        for day in self.existing(query_days(params)) {
+            for count in self.partition(day).await?.level_counts(params, bucket).await? {
+                *counts.entry((count.bucket, count.level)).or_default() += count.count;
+            }
+        }
+        Ok(counts
+            .into_iter()
+            .map(|((bucket, level), count)| LevelCount { bucket, level, count })
+            .collect())
+    }
+
+    async fn top_messages(&self, params: &LogQuery, group_by: MessageGrouping, limit: i64) -> StorageResult<Vec<MessageCount>> {
+        let mut groups: HashMap<(Option<String>, Option<String>), MessageCount> = HashMap::new();
+        for day in self.existing(query_days(params)) {
+            let store = self.partition(day).await?;
+            for count in store.top_messages(params, group_by, limit.saturating_mul(TOP_MESSAGES_FACTOR)).await? {
+                // Parmak izi olan gruplar yalnızca parmak iziyle, diğerleri mesajla da ayrılır
+                let message = match (group_by, &count.group_key) {
+                    (MessageGrouping::Fingerprint, Some(_)) => None,
+                    _ => Some(count.sample.clone()),
+                };
+                groups
+                    .entry((count.group_key.clone(), message))
+                    .and_modify(|group| {
+                        group.count += count.count;
+                        group.first_seen = group.first_seen.min(count.first_seen);
+                        group.last_seen = group.last_seen.max(count.last_seen);
+                        if count.sample < group.sample {
+                            group.sample = count.sample.clone();
+                        }
+                    })
+                    .or_insert(count);
+            }
+        }
+        let mut groups: Vec<MessageCount> = groups.into_values().collect();
+        groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.sample.cmp(&b.sample)));
+        groups.truncate(limit.max(0) as usize);
+        Ok(groups)
+    }
+
+    async fn purge(&self, filter: &PurgeFilter) -> StorageResult<u64> {
+        let mut deleted = 0;
+        for day in self.existing(purge_days(filter)) {
+            let store = self.partition(day).await?;
+            deleted += if covers_day(filter, day) {
+                self.drop_day(day, store).await?
+            } else {
+                store.purge(filter).await?
+            };
+        }
+        Ok(deleted)
+    }
+
+    async fn select_purge(&self, filter: &PurgeFilter, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>> {
+        let (first, last) = purge_days(filter);
+        let mut rows = Vec::new();
+        for day in self.existing((first.max(day_of_id(after_id)), last)) {
+            let remaining = limit - rows.len() as i64;
+            if remaining <= 0 {
+                break;
+            }
+            rows.extend(self.partition(day).await?.select_purge(filter, after_id, remaining).await?);
+        }
+        Ok(rows)
+    }
+
+    async fn usage(&self) -> StorageResult<StorageUsage> {
+        let mut total = StorageUsage::default();
+        for day in self.existing((i64::MIN, i64::MAX)) {
+            let usage = self.partition(day).await?.usage().await?;
+            total.rows += usage.rows;
+            total.bytes += usage.bytes;
+        }
+        Ok(total)
+    }
+
+    async fn evict_oldest(&self, rows: u64) -> StorageResult<u64> {
+        // Tamamı silinecek eski günlerin dosyaları kaldırılır; son gün hiç kaldırılmaz
+        let days = self.existing((i64::MIN, i64::MAX));
+        let mut evicted = 0;
+        for (i, day) in days.iter().enumerate() {
+            let remaining = rows - evicted;
+            if remaining == 0 {
+                break;
+            }
+            let store = self.partition(*day).await?;
+            let count = store.usage().await?.rows;
+            evicted += if count <= remaining && i + 1 < days.len() {
+                self.drop_day(*day, store).await?
+            } else {
+                store.evict_oldest(remaining).await?
+            };
+        }
+        Ok(evicted)
+    }
+
+    async fn compact(&self) -> StorageResult<()> {
+        // Yalnızca bu süreçte açılmış (yazılmış veya silinmiş) günler
+        let opened: Vec<SqliteStorage> = self
+            .days
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter_map(|cell| cell.get().cloned())
+            .collect();
+        for store in opened {
+            store.compact().await?;
+        }
+        Ok(())
+    }
+
+    async fn checkpoint(&self) -> StorageResult<()> {
+        let opened: Vec<SqliteStorage> = self
+            .days
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter_map(|cell| cell.get().cloned())
+            .collect();
+        for store in opened.iter().chain(self.audit.get()) {
+            store.checkpoint().await?;
+        }
+        Ok(())
+    }
+
+    async fn record_audit(&self, event: &AuditEvent) -> StorageResult<()> {
+        self.audit().await?.record_audit(event).await
+    }
+
+    async fn audit_log(&self, query: &AuditQuery) -> StorageResult<Vec<AuditRecord>> {
+        self.audit().await?.audit_log(query).await
+    }
+}
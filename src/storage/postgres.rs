@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use sqlx::postgres::{PgPool, PgPoolOptions, Postgres};
+use sqlx::migrate::Migrator;
+use sqlx::QueryBuilder;
+
+use super::{bind_insert, push_audit_log, AuditEvent, AuditQuery, AuditRecord, insert_chunks, push_export, push_filters, push_level_counts, push_purge, push_select_purge, push_top_messages, LevelCount, MessageCount, MessageGrouping, PurgeFilter, Storage, StorageResult, StorageUsage, Dialect, TextSearch, InsertStatements, Placeholder, FINGERPRINT_KEY, PROMOTED_COLUMNS, SELECT_LOGS, SEVERITY_CASE, SOURCE_COLUMNS};
+use crate::config::StorageConfig;
+use crate::query::{LogQuery, LogRow, StoredLog};
+use crate::LogEntry;
+
+// --- PostgreSQL ---
+// SQLite'ı aşan kurulumlar için; aynı şema ve aynı HTTP API.
+#[derive(Clone, Debug)]
+pub struct PostgresStorage {
+    pool: PgPool,
+}
+
+static INSERTS: InsertStatements = InsertStatements::new(Placeholder::Numbered);
+
+const DIALECT: Dialect = Dialect {
+    search: TextSearch::ILike,
+    details_text,
+    details_number,
+    like: "ILIKE",
+};
+
+impl PostgresStorage {
+    pub async fn open(url: &str, config: &StorageConfig) -> StorageResult<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(config.max_connections.max(1))
+            .connect(url)
+            .await?;
+        // Sürüm takibinden (_sqlx_migrations) önce oluşturulmuş veritabanları önce
+        // ilk migration'ın şemasına getirilir; o migration IF NOT EXISTS olduğu için
+        // ardından yalnızca uygulanmış olarak kaydedilir.
+        let (exists, tracked): (bool, bool) = sqlx::query_as(
+            "SELECT to_regclass('logs') IS NOT NULL, to_regclass('_sqlx_migrations') IS NOT NULL",
+        )
+        .fetch_one(&pool)
+        .await?;
+        if exists && !tracked {
+            upgrade_legacy(&pool).await?;
+        }
+        MIGRATOR.run(&pool).await.map_err(sqlx::Error::from)?;
+        Ok(PostgresStorage { pool })
+    }
+}
+
+// Şema sürümleri (migrations/postgres); derlemeye gömülür.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+// Migration'lardan önceki sürümlerin bıraktığı tabloyu ilk migration'ın şemasına
+// getirir: eksik sütunlar eklenir, metin 'timestamp' dönüştürülür.
+async fn upgrade_legacy(pool: &PgPool) -> StorageResult<()> {
+    tracing::info!("🗂️ Sürümsüz veritabanı bulundu, şema sürüm takibine alınıyor...");
+
+    // Kiracı sütunu olmadan oluşturulmuş eski veritabanları
+    sqlx::query("ALTER TABLE logs ADD COLUMN IF NOT EXISTS tenant_id TEXT NOT NULL DEFAULT 'default'")
+        .execute(pool)
+        .await?;
+    // Kaynak bilgisi ve öne çıkarılan sütunlar olmadan oluşturulmuş eski veritabanları
+    for column in SOURCE_COLUMNS.into_iter().chain(PROMOTED_COLUMNS) {
+        sqlx::query(&format!("ALTER TABLE logs ADD COLUMN IF NOT EXISTS {} TEXT", column))
+            .execute(pool)
+            .await?;
+    }
+    // severity sütunu olmadan oluşturulmuş eski veritabanları: sütun eklenir ve
+    // mevcut kayıtlar bir kez doldurulur
+    let has_severity: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM information_schema.columns \
+         WHERE table_schema = current_schema() AND table_name = 'logs' AND column_name = 'severity')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if !has_severity {
+        let mut tx = pool.begin().await?;
+        sqlx::query("ALTER TABLE logs ADD COLUMN severity INTEGER").execute(&mut *tx).await?;
+        sqlx::query(&format!("UPDATE logs SET severity = {}", SEVERITY_CASE))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+    // 'timestamp' eskiden RFC 3339 metniydi; epoch mikro saniyeye (BIGINT) çevrilir.
+    // Çözülemeyen eski değerler received_at'e, o da yoksa 0'a düşer.
+    let timestamp_type: String = sqlx::query_scalar(
+        "SELECT data_type FROM information_schema.columns \
+         WHERE table_schema = current_schema() AND table_name = 'logs' AND column_name = 'timestamp'",
+    )
+    .fetch_one(pool)
+    .await?;
+    if timestamp_type != "bigint" {
+        tracing::info!("🕒 'timestamp' sütunu epoch mikro saniyeye dönüştürülüyor (tek seferlik)...");
+        sqlx::query(&format!(
+            "ALTER TABLE logs ALTER COLUMN timestamp TYPE BIGINT USING COALESCE({}, {}, 0)",
+            epoch_micros("timestamp"),
+            epoch_micros("received_at"),
+        ))
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+// RFC 3339 metin sütununu epoch mikro saniyeye çeviren SQL (tarih gibi görünmüyorsa NULL)
+fn epoch_micros(column: &str) -> String {
+    format!(
+        "CASE WHEN {0} ~ '^\\d{{4}}-\\d{{2}}-\\d{{2}}' THEN (EXTRACT(EPOCH FROM {0}::timestamptz) * 1000000)::BIGINT END",
+        column
+    )
+}
+
+#[async_trait]
+impl Storage for PostgresStorage {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn ping(&self) -> StorageResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn insert_batch(&self, logs: &[LogEntry]) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for chunk in insert_chunks(logs) {
+            bind_insert(sqlx::query(INSERTS.sql(chunk.len())), chunk, None)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn query(&self, params: &LogQuery) -> StorageResult<Vec<StoredLog>> {
+        // FTS5 PostgreSQL'de yok; 'q' büyük/küçük harf duyarsız alt dize olarak aranır
+        let mut qb = QueryBuilder::<Postgres>::new(SELECT_LOGS);
+        push_filters(&mut qb, params, &DIALECT);
+        let mut rows = qb.build_query_as::<LogRow>().fetch_all(&self.pool).await?;
+        if params.reads_forward() {
+            rows.reverse();
+        }
+        Ok(rows.into_iter().map(StoredLog::from).collect())
+    }
+
+    async fn export(&self, params: &LogQuery, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>> {
+        let mut qb = QueryBuilder::<Postgres>::new(SELECT_LOGS);
+        push_export(&mut qb, params, &DIALECT, after_id, limit);
+        let rows = qb.build_query_as::<LogRow>().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(StoredLog::from).collect())
+    }
+
+    async fn level_counts(&self, params: &LogQuery, bucket: i64) -> StorageResult<Vec<LevelCount>> {
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        push_level_counts(&mut qb, params, &DIALECT, bucket);
+        Ok(qb.build_query_as::<LevelCount>().fetch_all(&self.pool).await?)
+    }
+
+    async fn top_messages(&self, params: &LogQuery, group_by: MessageGrouping, limit: i64) -> StorageResult<Vec<MessageCount>> {
+        let fingerprint = details_text(FINGERPRINT_KEY);
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        push_top_messages(&mut qb, params, &DIALECT, group_by, &fingerprint, limit);
+        Ok(qb.build_query_as::<MessageCount>().fetch_all(&self.pool).await?)
+    }
+
+    async fn purge(&self, filter: &PurgeFilter) -> StorageResult<u64> {
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        push_purge(&mut qb, filter, details_text);
+        Ok(qb.build().execute(&self.pool).await?.rows_affected())
+    }
+
+    async fn select_purge(&self, filter: &PurgeFilter, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>> {
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        push_select_purge(&mut qb, SELECT_LOGS, filter, details_text, after_id, limit);
+        let rows = qb.build_query_as::<LogRow>().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(StoredLog::from).collect())
+    }
+
+    async fn usage(&self) -> StorageResult<StorageUsage> {
+        let (rows, bytes): (i64, i64) =
+            sqlx::query_as("SELECT (SELECT COUNT(*) FROM logs), pg_total_relation_size('logs')")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(StorageUsage {
+            rows: rows.max(0) as u64,
+            bytes: bytes.max(0) as u64,
+        })
+    }
+
+    async fn evict_oldest(&self, rows: u64) -> StorageResult<u64> {
+        let result = sqlx::query("DELETE FROM logs WHERE id IN (SELECT id FROM logs ORDER BY id LIMIT $1)")
+            .bind(rows.min(i64::MAX as u64) as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn compact(&self) -> StorageResult<()> {
+        // Alan dosya sistemine geri verilmez ama yeni satırlar için yeniden kullanılır;
+        // böylece tablo boyutu sınırın etrafında sabitlenir.
+        sqlx::query("VACUUM logs").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn checkpoint(&self) -> StorageResult<()> {
+        // Commit edilen her şey sunucudadır; CHECKPOINT süper kullanıcı ister ve
+        // pg_dump / pg_basebackup zaten tutarlı yedek alır.
+        Ok(())
+    }
+
+    async fn record_audit(&self, event: &AuditEvent) -> StorageResult<()> {
+        sqlx::query("INSERT INTO audit (timestamp, actor, remote_addr, action, params) VALUES ($1, $2, $3, $4, $5)")
+            .bind(event.timestamp)
+            .bind(&event.actor)
+            .bind(&event.remote_addr)
+            .bind(&event.action)
+            .bind(event.params.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn audit_log(&self, query: &AuditQuery) -> StorageResult<Vec<AuditRecord>> {
+        let mut qb = QueryBuilder::<Postgres>::new("");
+        push_audit_log(&mut qb, query);
+        Ok(qb.build_query_as::<AuditRecord>().fetch_all(&self.pool).await?)
+    }
+}
+
+// details'teki bir anahtarın ("a.b") metin değeri; sayılar da metne çevrilir.
+fn details_text(path: &str) -> String {
+    format!("(details::jsonb #>> '{{{}}}')", path.replace('.', ","))
+}
+
+// Sayı olmayan değerler (metin "42" dahil) NULL döner.
+fn details_number(path: &str) -> String {
+    let path = path.replace('.', ",");
+    format!(
+        "(CASE WHEN jsonb_typeof(details::jsonb #> '{{{0}}}') = 'number' THEN (details::jsonb #>> '{{{0}}}')::float8 END)",
+        path
+    )
+}
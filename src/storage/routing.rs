@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use regex::Regex;
+
+use super::{open, SharedStorage, StorageResult};
+use crate::config::StorageConfig;
+use crate::filter::field_value;
+use crate::LogEntry;
+
+// --- Yönlendirme Kuralları ---
+// Eşleşen loglar ana veritabanı yerine kuralın kendi deposuna (ayrı SQLite
+// dosyası, PostgreSQL şeması, ClickHouse veritabanı) yazılır; gürültülü
+// kaynaklar panoları besleyen ana tabloyu şişirmez. Kurallar yazıldıkları
+// sırayla denenir, ilk eşleşen kazanır; hiçbiri eşleşmezse log ana veritabanına
+// gider. Okuma API'si (GET /logs, istatistikler, dışa aktarma) yalnızca ana
+// veritabanını okur.
+pub struct Routes {
+    primary: SharedStorage,
+    routes: Vec<Route>,
+}
+
+pub struct Route {
+    pub name: String,
+    // "message", "level" ya da extra içindeki yol
+    field: Vec<String>,
+    regex: Regex,
+    pub store: SharedStorage,
+}
+
+impl Routes {
+    // Kuralların depolarını açar. Aynı 'db_path'i kullanan kurallar (ve ana
+    // veritabanını gösteren kurallar) aynı bağlantı havuzunu paylaşır.
+    pub async fn open(primary: SharedStorage, config: &StorageConfig) -> Result<Self, String> {
+        let mut routes: Vec<Route> = Vec::with_capacity(config.routes.len());
+        for (i, route) in config.routes.iter().enumerate() {
+            let regex = Regex::new(&route.pattern)
+                .map_err(|e| format!("'{}' yönlendirme kuralının deseni geçersiz: {}", route.name, e))?;
+            let shared = config.routes[..i]
+                .iter()
+                .zip(&routes)
+                .find(|(earlier, _)| earlier.db_path == route.db_path)
+                .map(|(_, earlier)| earlier.store.clone());
+            let store = match shared {
+                Some(store) => store,
+                None if route.db_path == config.db_path => primary.clone(),
+                None => open(&route.db_path, config)
+                    .await
+                    .map_err(|e| format!("'{}' yönlendirme kuralının deposu açılamadı: {}", route.name, e))?,
+            };
+            routes.push(Route {
+                name: route.name.clone(),
+                field: route.field.split('.').map(str::to_string).collect(),
+                regex,
+                store,
+            });
+        }
+        Ok(Routes { primary, routes })
+    }
+
+    pub fn primary(&self) -> &SharedStorage {
+        &self.primary
+    }
+
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    // Kuralların ayrı depoları, her biri bir kez ve onu ilk kullanan kuralın adıyla
+    // (ana veritabanını gösteren kurallar hariç). Saklama ve silme talepleri bunlara
+    // da uygulanır.
+    pub fn targets(&self) -> Vec<(&str, &SharedStorage)> {
+        let mut targets: Vec<(&str, &SharedStorage)> = Vec::new();
+        for route in &self.routes {
+            let seen = Arc::ptr_eq(&route.store, &self.primary)
+                || targets.iter().any(|(_, store)| Arc::ptr_eq(store, &route.store));
+            if !seen {
+                targets.push((&route.name, &route.store));
+            }
+        }
+        targets
+    }
+
+    // Logun yazılacağı kuralın sırası; None ise ana veritabanı.
+    fn position(&self, log: &LogEntry) -> Option<usize> {
+        self.routes
+            .iter()
+            .position(|route| field_value(&route.field, log).is_some_and(|value| route.regex.is_match(&value)))
+    }
+
+    // Kayıtları hedeflerine göre gruplar (sıra korunur). Ana veritabanının grubu,
+    // varsa, ilk sıradadır.
+    pub fn split<T>(&self, items: Vec<T>, log: impl Fn(&T) -> &LogEntry) -> Vec<(Option<&Route>, Vec<T>)> {
+        if self.routes.is_empty() {
+            return vec![(None, items)];
+        }
+        let mut groups: Vec<Vec<T>> = (0..=self.routes.len()).map(|_| Vec::new()).collect();
+        for item in items {
+            let target = self.position(log(&item)).map_or(0, |i| i + 1);
+            groups[target].push(item);
+        }
+        groups
+            .into_iter()
+            .enumerate()
+            .filter(|(_, items)| !items.is_empty())
+            .map(|(target, items)| (target.checked_sub(1).map(|i| &self.routes[i]), items))
+            .collect()
+    }
+
+    // Logları kurallara göre dağıtarak yazar (ölü mektup dosyasından yeniden yazma).
+    pub async fn insert_batch(&self, logs: Vec<LogEntry>) -> StorageResult<()> {
+        for (route, group) in self.split(logs, |log| log) {
+            let store = route.map_or(&self.primary, |route| &route.store);
+            store.insert_batch(&group).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,413 @@
+use async_trait::async_trait;
+use std::borrow::Borrow;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::sqlite::{SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use sqlx::migrate::Migrator;
+use sqlx::{QueryBuilder, Sqlite};
+
+use super::compression::{self, DetailsCodec};
+use super::{bind_insert, push_audit_log, AuditEvent, AuditQuery, AuditRecord, insert_chunks, push_export, push_filters, push_level_counts, push_purge, push_select_purge, push_top_messages, LevelCount, MessageCount, MessageGrouping, PurgeFilter, Storage, StorageResult, StorageUsage, Dialect, TextSearch, InsertStatements, Placeholder, FINGERPRINT_KEY, LOG_COLUMNS, PROMOTED_COLUMNS, SEVERITY_CASE, SOURCE_COLUMNS};
+use crate::config::{SqliteConfig, SqliteSynchronous, StorageConfig};
+use crate::query::{LogQuery, LogRow, StoredLog};
+use crate::LogEntry;
+
+// --- SQLite (varsayılan) ---
+// Yol ":memory:" ise veritabanı bellekte tutulur (testler, demolar); süreç
+// kapanınca loglar kaybolur.
+pub const MEMORY: &str = ":memory:";
+
+static INSERTS: InsertStatements = InsertStatements::new(Placeholder::Question);
+
+// details sıkıştırılmış (BLOB) olabilir; okunurken details_json() ile çözülür.
+const SELECT_LOGS: &str = "SELECT id, tenant_id, level, message, timestamp, details_json(details) AS details, remote_addr, user_agent, api_key, received_at, severity, service, host, env, trace_id, span_id FROM logs WHERE 1=1";
+
+const DIALECT: Dialect = Dialect {
+    search: TextSearch::Fts5,
+    details_text,
+    details_number,
+    like: "LIKE",
+};
+
+// Yazmalar (ve silme / bakım) 'pool' üzerinden, okuma API'sinin sorguları ayrı
+// salt okunur 'reader' havuzundan yapılır: WAL modunda okuyucular yazıcıyı
+// beklemez, ağır analitik sorgular da yazıcının bağlantılarını tutmaz.
+#[derive(Clone, Debug)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+    reader: SqlitePool,
+    // storage.sqlite.compress_details açıksa
+    codec: Option<Arc<DetailsCodec>>,
+}
+
+impl SqliteStorage {
+    // Dosyayı açar (yoksa oluşturur) ve tabloyu hazırlar.
+    pub async fn open(path: &str, config: &StorageConfig) -> StorageResult<Self> {
+        let pool = if path == MEMORY {
+            // Her bağlantının kendi bellek veritabanı olur: tek bağlantı açılır ve hiç kapatılmaz
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .after_connect(|conn, _| compression::register_function(conn))
+                .connect_with(tune(SqliteConnectOptions::from_str("sqlite::memory:")?, &config.sqlite))
+                .await?
+        } else {
+            // WAL Modu (Write-Ahead Logging) performansı artırır.
+            // Incremental auto_vacuum: silinen sayfalar 'compact' ile dosyadan geri verilir.
+            let db_options = SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal)
+                .auto_vacuum(SqliteAutoVacuum::Incremental);
+            SqlitePoolOptions::new()
+                .max_connections(config.max_connections.max(1))
+                .after_connect(|conn, _| compression::register_function(conn))
+                .connect_with(tune(db_options, &config.sqlite))
+                .await?
+        };
+
+        // auto_vacuum ancak VACUUM ile değişir (WAL pragması dosyayı önce oluşturduğu için
+        // yeni dosyalarda da). Tablo henüz yoksa dosya boştur ve bu işlem anlıktır;
+        // eski dosyalar ilk 'compact' sırasında dönüştürülür.
+        let fresh: bool = sqlx::query_scalar("SELECT COUNT(*) = 0 FROM sqlite_master WHERE name = 'logs'")
+            .fetch_one(&pool)
+            .await?;
+        if fresh {
+            sqlx::query("VACUUM").execute(&pool).await?;
+        }
+
+        // Sürüm takibinden (_sqlx_migrations) önce oluşturulmuş veritabanları önce
+        // ilk migration'ın şemasına getirilir; o migration IF NOT EXISTS olduğu için
+        // ardından yalnızca uygulanmış olarak kaydedilir.
+        let tracked: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = '_sqlx_migrations'")
+            .fetch_one(&pool)
+            .await?;
+        let mut rebuild_fts = false;
+        if !fresh && !tracked {
+            rebuild_fts = upgrade_legacy(&pool).await?;
+        }
+
+        MIGRATOR.run(&pool).await.map_err(sqlx::Error::from)?;
+
+        // Dizinden önce yazılmış kayıtlar bir kez dizine eklenir
+        if rebuild_fts {
+            tracing::info!("🔎 Mevcut kayıtlar tam metin dizinine ekleniyor (tek seferlik)...");
+            sqlx::query("INSERT INTO logs_fts(logs_fts) VALUES ('rebuild')")
+                .execute(&pool)
+                .await?;
+        }
+
+        // Okuma havuzu şema hazır olduktan sonra açılır (salt okunur bağlantı dosyayı
+        // oluşturamaz); bağlantılar ilk sorguda açılır.
+        let reader = if path == MEMORY || config.sqlite.read_connections == 0 {
+            pool.clone()
+        } else {
+            let read_options = SqliteConnectOptions::new().filename(path).read_only(true);
+            SqlitePoolOptions::new()
+                .max_connections(config.sqlite.read_connections)
+                .after_connect(|conn, _| compression::register_function(conn))
+                .connect_lazy_with(tune(read_options, &config.sqlite))
+        };
+
+        // Sıkıştırma kapalıyken de sözlükler yüklenir: önceden sıkıştırılmış kayıtlar okunabilmeli
+        let dictionary = compression::load_dictionaries(&pool).await?;
+        let codec = config
+            .sqlite
+            .compress_details
+            .then(|| Arc::new(DetailsCodec::new(&config.sqlite, dictionary)));
+
+        Ok(SqliteStorage { pool, reader, codec })
+    }
+
+    // insert_batch; günlük bölümler partinin yalnızca kendi gününe düşen kayıtlarını verir.
+    pub(super) async fn insert_entries<T: Borrow<LogEntry> + Sync>(&self, logs: &[T]) -> StorageResult<()> {
+        let mut compressor = self.codec.as_ref().and_then(|codec| codec.compressor());
+        let mut tx = self.pool.begin().await?;
+        for chunk in insert_chunks(logs) {
+            bind_insert(sqlx::query(INSERTS.sql(chunk.len())), chunk, compressor.as_mut())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        if let Some(codec) = &self.codec {
+            codec.after_insert(&self.pool, logs.len()).await;
+        }
+        Ok(())
+    }
+
+    // Sonraki id'lerin en az 'first' olmasını sağlar (günlük bölümlerin id aralıkları
+    // çakışmasın diye). AUTOINCREMENT sayacı yalnızca ileri alınır.
+    pub(super) async fn reserve_ids(&self, first: i64) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        let current: Option<i64> = sqlx::query_scalar("SELECT seq FROM sqlite_sequence WHERE name = 'logs'")
+            .fetch_optional(&mut *tx)
+            .await?;
+        match current {
+            Some(seq) if seq >= first - 1 => {}
+            Some(_) => {
+                sqlx::query("UPDATE sqlite_sequence SET seq = ? WHERE name = 'logs'")
+                    .bind(first - 1)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            None => {
+                sqlx::query("INSERT INTO sqlite_sequence (name, seq) VALUES ('logs', ?)")
+                    .bind(first - 1)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Havuzları kapatır (dosya silinmeden önce).
+    pub(super) async fn close(&self) {
+        self.reader.close().await;
+        self.pool.close().await;
+    }
+}
+
+// [storage.sqlite] PRAGMA'larını bağlantı ayarlarına ekler (her yeni bağlantıda uygulanır).
+fn tune(options: SqliteConnectOptions, config: &SqliteConfig) -> SqliteConnectOptions {
+    let synchronous = match config.synchronous {
+        SqliteSynchronous::Off => sqlx::sqlite::SqliteSynchronous::Off,
+        SqliteSynchronous::Normal => sqlx::sqlite::SqliteSynchronous::Normal,
+        SqliteSynchronous::Full => sqlx::sqlite::SqliteSynchronous::Full,
+        SqliteSynchronous::Extra => sqlx::sqlite::SqliteSynchronous::Extra,
+    };
+    options
+        .synchronous(synchronous)
+        .busy_timeout(Duration::from_millis(config.busy_timeout_ms))
+        .pragma("cache_size", config.cache_size.to_string())
+        .pragma("mmap_size", config.mmap_size.to_string())
+}
+
+// Şema sürümleri (migrations/sqlite); derlemeye gömülür.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+// Migration'lardan önceki sürümlerin bıraktığı tabloyu ilk migration'ın şemasına
+// getirir: eksik sütunlar eklenir, metin 'timestamp' dönüştürülür. Tam metin dizini
+// yoksa true döner (dizin migration ile oluşturulduktan sonra doldurulmalı).
+async fn upgrade_legacy(pool: &SqlitePool) -> StorageResult<bool> {
+    tracing::info!("🗂️ Sürümsüz veritabanı bulundu, şema sürüm takibine alınıyor...");
+
+    // Sonradan eklenen sütunlar olmadan oluşturulmuş eski veritabanları
+    let added = [("tenant_id", "TEXT NOT NULL DEFAULT 'default'")]
+        .into_iter()
+        .chain(SOURCE_COLUMNS.map(|column| (column, "TEXT")))
+        .chain([("severity", "INTEGER")])
+        .chain(PROMOTED_COLUMNS.map(|column| (column, "TEXT")));
+    for (column, definition) in added {
+        let exists: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM pragma_table_info('logs') WHERE name = ?")
+            .bind(column)
+            .fetch_one(pool)
+            .await?;
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE logs ADD COLUMN {} {}", column, definition))
+                .execute(pool)
+                .await?;
+            if column == "severity" {
+                sqlx::query(&format!("UPDATE logs SET severity = {}", SEVERITY_CASE))
+                    .execute(pool)
+                    .await?;
+            }
+        }
+    }
+
+    // 'timestamp' eskiden RFC 3339 metniydi; tablo epoch mikro saniye sütunuyla
+    // yeniden kurulur (SQLite sütun tipi değiştiremez). id'ler korunduğu için
+    // tam metin dizini geçerli kalır; tetikleyiciler migration ile yeniden oluşturulur.
+    let timestamp_type: String = sqlx::query_scalar("SELECT type FROM pragma_table_info('logs') WHERE name = 'timestamp'")
+        .fetch_one(pool)
+        .await?;
+    if !timestamp_type.eq_ignore_ascii_case("INTEGER") {
+        tracing::info!("🕒 'timestamp' sütunu epoch mikro saniyeye dönüştürülüyor (tek seferlik)...");
+        let timestamp = format!("COALESCE({}, {}, 0)", epoch_micros("timestamp"), epoch_micros("received_at"));
+        let mut tx = pool.begin().await?;
+        sqlx::query(&create_table("logs_migrated")).execute(&mut *tx).await?;
+        sqlx::query(&format!(
+            "INSERT INTO logs_migrated ({}) SELECT {} FROM logs",
+            LOG_COLUMNS,
+            LOG_COLUMNS.replace("timestamp", &timestamp),
+        ))
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DROP TABLE logs").execute(&mut *tx).await?;
+        sqlx::query("ALTER TABLE logs_migrated RENAME TO logs").execute(&mut *tx).await?;
+        tx.commit().await?;
+    }
+
+    let has_fts: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'logs_fts'")
+        .fetch_one(pool)
+        .await?;
+    Ok(!has_fts)
+}
+
+// Eski 'timestamp' dönüşümünde kullanılan tablo; migrations/sqlite/0001_initial.sql ile aynı
+fn create_table(name: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id TEXT NOT NULL DEFAULT 'default',
+            level TEXT NOT NULL,
+            message TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            details TEXT,
+            remote_addr TEXT,
+            user_agent TEXT,
+            api_key TEXT,
+            received_at TEXT,
+            severity INTEGER,
+            service TEXT,
+            host TEXT,
+            env TEXT,
+            trace_id TEXT,
+            span_id TEXT
+        )",
+        name
+    )
+}
+
+// RFC 3339 metin sütununu epoch mikro saniyeye çeviren SQL (tarih gibi görünmüyorsa
+// veya çözülemezse NULL). julianday milisaniye hassasiyetindedir; eski kayıtlar için yeterli.
+fn epoch_micros(column: &str) -> String {
+    format!(
+        "CASE WHEN {0} GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]*' \
+         THEN CAST(ROUND((julianday({0}) - 2440587.5) * 86400000.0) AS INTEGER) * 1000 END",
+        column
+    )
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn ping(&self) -> StorageResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn insert_batch(&self, logs: &[LogEntry]) -> StorageResult<()> {
+        self.insert_entries(logs).await
+    }
+
+    async fn query(&self, params: &LogQuery) -> StorageResult<Vec<StoredLog>> {
+        let mut qb = QueryBuilder::<Sqlite>::new(SELECT_LOGS);
+        push_filters(&mut qb, params, &DIALECT);
+        let mut rows = qb.build_query_as::<LogRow>().fetch_all(&self.reader).await?;
+        if params.reads_forward() {
+            rows.reverse();
+        }
+        Ok(rows.into_iter().map(StoredLog::from).collect())
+    }
+
+    async fn export(&self, params: &LogQuery, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>> {
+        let mut qb = QueryBuilder::<Sqlite>::new(SELECT_LOGS);
+        push_export(&mut qb, params, &DIALECT, after_id, limit);
+        let rows = qb.build_query_as::<LogRow>().fetch_all(&self.reader).await?;
+        Ok(rows.into_iter().map(StoredLog::from).collect())
+    }
+
+    async fn level_counts(&self, params: &LogQuery, bucket: i64) -> StorageResult<Vec<LevelCount>> {
+        let mut qb = QueryBuilder::<Sqlite>::new("");
+        push_level_counts(&mut qb, params, &DIALECT, bucket);
+        Ok(qb.build_query_as::<LevelCount>().fetch_all(&self.reader).await?)
+    }
+
+    async fn top_messages(&self, params: &LogQuery, group_by: MessageGrouping, limit: i64) -> StorageResult<Vec<MessageCount>> {
+        let fingerprint = details_text(FINGERPRINT_KEY);
+        let mut qb = QueryBuilder::<Sqlite>::new("");
+        push_top_messages(&mut qb, params, &DIALECT, group_by, &fingerprint, limit);
+        Ok(qb.build_query_as::<MessageCount>().fetch_all(&self.reader).await?)
+    }
+
+    async fn purge(&self, filter: &PurgeFilter) -> StorageResult<u64> {
+        let mut qb = QueryBuilder::<Sqlite>::new("");
+        push_purge(&mut qb, filter, details_text);
+        Ok(qb.build().execute(&self.pool).await?.rows_affected())
+    }
+
+    async fn select_purge(&self, filter: &PurgeFilter, after_id: i64, limit: i64) -> StorageResult<Vec<StoredLog>> {
+        let mut qb = QueryBuilder::<Sqlite>::new("");
+        push_select_purge(&mut qb, SELECT_LOGS, filter, details_text, after_id, limit);
+        let rows = qb.build_query_as::<LogRow>().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(StoredLog::from).collect())
+    }
+
+    async fn usage(&self) -> StorageResult<StorageUsage> {
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM logs").fetch_one(&self.pool).await?;
+        let bytes: i64 = sqlx::query_scalar(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(StorageUsage {
+            rows: rows.max(0) as u64,
+            bytes: bytes.max(0) as u64,
+        })
+    }
+
+    async fn evict_oldest(&self, rows: u64) -> StorageResult<u64> {
+        let result = sqlx::query("DELETE FROM logs WHERE id IN (SELECT id FROM logs ORDER BY id LIMIT ?)")
+            .bind(rows.min(i64::MAX as u64) as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn compact(&self) -> StorageResult<()> {
+        // auto_vacuum olmadan oluşturulmuş eski dosyalar bir kez VACUUM ile dönüştürülür
+        // (bağlantı ayarı INCREMENTAL istediği için VACUUM modu da değiştirir).
+        let mode: i64 = sqlx::query_scalar("PRAGMA auto_vacuum").fetch_one(&self.pool).await?;
+        if mode != 2 {
+            tracing::info!("🗜️ SQLite dosyası incremental auto_vacuum'a dönüştürülüyor (tek seferlik VACUUM)...");
+            sqlx::query("VACUUM").execute(&self.pool).await?;
+        }
+        sqlx::query("PRAGMA incremental_vacuum").execute(&self.pool).await?;
+        // Boşalan sayfaların dosyadan gerçekten düşmesi için WAL ana dosyaya aktarılır
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn checkpoint(&self) -> StorageResult<()> {
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn record_audit(&self, event: &AuditEvent) -> StorageResult<()> {
+        sqlx::query("INSERT INTO audit (timestamp, actor, remote_addr, action, params) VALUES (?, ?, ?, ?, ?)")
+            .bind(event.timestamp)
+            .bind(&event.actor)
+            .bind(&event.remote_addr)
+            .bind(&event.action)
+            .bind(event.params.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn audit_log(&self, query: &AuditQuery) -> StorageResult<Vec<AuditRecord>> {
+        let mut qb = QueryBuilder::<Sqlite>::new("");
+        push_audit_log(&mut qb, query);
+        Ok(qb.build_query_as::<AuditRecord>().fetch_all(&self.reader).await?)
+    }
+}
+
+// details'teki bir anahtarın ("a.b") metin değeri; sayılar da metne çevrilir.
+fn details_text(path: &str) -> String {
+    format!("CAST(json_extract(details_json(details), '$.{}') AS TEXT)", path)
+}
+
+// Sayı olmayan değerler (metin "42" dahil) NULL döner.
+fn details_number(path: &str) -> String {
+    format!(
+        "(CASE WHEN json_type(details_json(details), '$.{0}') IN ('integer', 'real') THEN json_extract(details_json(details), '$.{0}') END)",
+        path
+    )
+}
@@ -0,0 +1,63 @@
+use std::os::fd::FromRawFd;
+use std::os::unix::net::UnixDatagram;
+
+use tracing::{debug, warn};
+
+// --- systemd Entegrasyonu ---
+// Harici kütüphane olmadan iki özellik:
+//   - sd_notify: NOTIFY_SOCKET tanımlıysa durum bildirimleri gönderilir
+//     (READY=1 sunucu istek kabul etmeye başlayınca, STOPPING=1 kapanış
+//     başlayınca). Birim dosyasında Type=notify kullanılabilir.
+//   - Soket aktivasyonu: LISTEN_PID bu süreçse ve LISTEN_FDS >= 1 ise HTTP
+//     sunucusu kendi portunu açmak yerine systemd'nin verdiği ilk soketi
+//     (fd 3) dinler. Yeniden başlatma sırasında soket systemd'de açık kalır;
+//     gelen bağlantılar kaybolmaz, yeni süreç başlayınca kabul edilir.
+// Değişkenler tanımlı değilse hiçbir şey yapılmaz.
+
+// systemd'nin aktarılan soketlere verdiği ilk dosya tanımlayıcısı (SD_LISTEN_FDS_START)
+const LISTEN_FDS_START: i32 = 3;
+
+// Durum bildirimi gönderir (örn. "READY=1", "STATUS=..."). Hata olursa sadece uyarır.
+pub fn notify(state: &str) {
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    let result = UnixDatagram::unbound().and_then(|socket| {
+        let path = path.to_string_lossy();
+        // '@' ile başlayan yol Linux soyut ad alanındadır
+        #[cfg(target_os = "linux")]
+        if let Some(name) = path.strip_prefix('@') {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            return socket.send_to_addr(state.as_bytes(), &addr);
+        }
+        socket.send_to(state.as_bytes(), path.as_ref())
+    });
+    match result {
+        Ok(_) => debug!("📣 systemd bildirimi: {}", state),
+        Err(e) => warn!("⚠️ systemd bildirimi gönderilemedi ({}): {}", state, e),
+    }
+}
+
+// systemd'nin soket aktivasyonuyla verdiği dinleme soketini döner (yoksa None).
+// Değişkenler okunduktan sonra silinir; alt süreçlere geçmez.
+pub fn listener() -> Option<std::net::TcpListener> {
+    let pid = std::env::var("LISTEN_PID").ok()?;
+    let fds = std::env::var("LISTEN_FDS").ok()?;
+    std::env::remove_var("LISTEN_PID");
+    std::env::remove_var("LISTEN_FDS");
+    std::env::remove_var("LISTEN_FDNAMES");
+    if pid.parse::<u32>().ok()? != std::process::id() {
+        return None;
+    }
+    match fds.parse::<i32>().ok()? {
+        0 => None,
+        count => {
+            if count > 1 {
+                warn!("⚠️ systemd {} soket verdi; sadece ilki dinlenecek.", count);
+            }
+            // Güvenli: systemd bu tanımlayıcıyı bu sürece özel olarak açık bıraktı
+            Some(unsafe { std::net::TcpListener::from_raw_fd(LISTEN_FDS_START) })
+        }
+    }
+}
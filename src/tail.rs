@@ -0,0 +1,248 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use axum::Extension;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tracing::{debug, info};
+
+use crate::auth::{self, Caller};
+use crate::metrics::Metrics;
+use crate::{AppState, LogEntry};
+
+// --- Canlı Akış (Live Tail) ---
+// Boru hattına kabul edilen her log, veritabanına yazılmayı beklemeden
+// bağlı izleyicilere dağıtılır. Dinleyen yoksa hiçbir iş yapılmaz.
+// Kanal sınırlıdır: yetişemeyen izleyici kayıt kaçırır, sunucu beklemez.
+// İzleyici görevleri AppState tutmaz (yazıcı kanalları açık kalırdı); kapanışta
+// 'close' ile sonlandırılır, aksi halde düzenli kapanış bağlantıları bekler.
+#[derive(Clone)]
+pub struct Tail {
+    tx: broadcast::Sender<Arc<TailEvent>>,
+    // Kapatma sinyali geldiğinde true olur
+    closing: Arc<watch::Sender<bool>>,
+    // SSE bağlantısı başına bekleyen en fazla olay
+    sse_buffer: usize,
+}
+
+// Dağıtılan kayıt. JSON bir kez üretilir, tüm izleyiciler paylaşır.
+#[derive(Debug)]
+pub struct TailEvent {
+    pub tenant_id: String,
+    pub level: String,
+    pub message: String,
+    pub json: String,
+}
+
+// İzleyiciye giden biçim: GET /logs ile aynı (id henüz yok)
+#[derive(Serialize)]
+struct TailEntry<'a> {
+    tenant_id: &'a str,
+    level: &'a str,
+    message: &'a str,
+    timestamp: &'a str,
+    details: &'a serde_json::Value,
+}
+
+impl Tail {
+    pub fn new(buffer: usize, sse_buffer: usize) -> Self {
+        let (tx, _) = broadcast::channel(buffer.max(1));
+        Tail {
+            tx,
+            closing: Arc::new(watch::Sender::new(false)),
+            sse_buffer: sse_buffer.max(1),
+        }
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<TailEvent>> {
+        self.tx.subscribe()
+    }
+
+    // Bağlı tüm izleyicilerin akışını bitirir (sunucu kapanırken).
+    pub fn close(&self) {
+        self.closing.send_replace(true);
+    }
+
+    // 'close' çağrılana kadar bekler.
+    async fn closed(&self) {
+        let _ = self.closing.subscribe().wait_for(|closing| *closing).await;
+    }
+
+    // İzleyicilere gidecek olayı hazırlar (dinleyen yoksa None). Log kanala
+    // girerken taşındığı için olay önceden üretilir, kanala girince 'publish' edilir.
+    pub fn prepare(&self, log: &LogEntry) -> Option<Arc<TailEvent>> {
+        if !self.has_subscribers() {
+            return None;
+        }
+        let entry = TailEntry {
+            tenant_id: &log.tenant_id,
+            level: &log.level,
+            message: &log.message,
+            timestamp: log.extra.get("timestamp").and_then(|v| v.as_str()).unwrap_or(""),
+            details: &log.extra,
+        };
+        let json = serde_json::to_string(&entry).ok()?;
+        Some(Arc::new(TailEvent {
+            tenant_id: log.tenant_id.clone(),
+            level: log.level.clone(),
+            message: log.message.clone(),
+            json,
+        }))
+    }
+
+    // Kanala giren logun olayını izleyicilere gönderir.
+    pub fn publish(&self, event: Arc<TailEvent>) {
+        let _ = self.tx.send(event);
+    }
+}
+
+// --- İzleyici Filtresi ---
+// GET /tail?level=error&q=timeout  (WebSocket)
+// GET /tail/sse?level=error&q=timeout  (Server-Sent Events)
+//   level  seviye (tam eşleşme, /logs gibi)
+//   q      mesajda geçen metin (büyük/küçük harf duyarsız)
+// Kiracı istemciden okunmaz; istemcinin kiracısı kullanılır.
+#[derive(Debug, Default, Deserialize)]
+pub struct TailQuery {
+    pub level: Option<String>,
+    pub q: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct TailFilter {
+    tenant: String,
+    level: Option<String>,
+    q: Option<String>,
+}
+
+impl TailFilter {
+    pub fn new(tenant: String, query: TailQuery) -> Self {
+        TailFilter {
+            tenant,
+            level: query.level.filter(|l| !l.is_empty()),
+            q: query.q.map(|q| q.to_lowercase()).filter(|q| !q.is_empty()),
+        }
+    }
+
+    pub fn matches(&self, event: &TailEvent) -> bool {
+        event.tenant_id == self.tenant
+            && self.level.as_ref().is_none_or(|level| &event.level == level)
+            && self.q.as_ref().is_none_or(|q| event.message.to_lowercase().contains(q))
+    }
+}
+
+// --- WebSocket Ucu ---
+// GET /tail (WebSocket). Filtreye uyan her log ayrı bir metin mesajı (JSON)
+// olarak gönderilir. İzleyici geride kalıp kayıt kaçırırsa
+// {"skipped": <sayı>} mesajı gelir ve akış kaldığı yerden sürer.
+pub async fn ws_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(query): Query<TailQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let filter = TailFilter::new(auth::tenant_of(caller.as_deref()), query);
+    let (tail, metrics) = (state.tail.clone(), state.metrics.clone());
+    upgrade.on_upgrade(move |socket| watch(tail, metrics, socket, filter))
+}
+
+async fn watch(tail: Tail, metrics: Arc<Metrics>, mut socket: WebSocket, filter: TailFilter) {
+    let mut rx = tail.subscribe();
+    metrics.tail_clients.fetch_add(1, Ordering::Relaxed);
+    debug!("📡 Canlı akış izleyicisi bağlandı: {:?}", filter);
+
+    loop {
+        tokio::select! {
+            // Sunucu kapanıyor: istemciye kapanış çerçevesi gönderilir
+            _ = tail.closed() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            event = rx.recv() => {
+                let text = match event {
+                    Ok(event) if filter.matches(&event) => event.json.clone(),
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("📡 Canlı akış izleyicisi geride kaldı, {} kayıt atlandı.", skipped);
+                        serde_json::json!({ "skipped": skipped }).to_string()
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            // İstemciden gelenler yok sayılır; kapanınca akış biter
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    metrics.tail_clients.fetch_sub(1, Ordering::Relaxed);
+    debug!("📡 Canlı akış izleyicisi ayrıldı.");
+}
+
+// --- Server-Sent Events Ucu ---
+// GET /tail/sse: /tail ile aynı akış ve filtreler; tarayıcıdan (EventSource)
+// veya 'curl -N' ile izlenebilir. Her log bir 'data:' olayıdır.
+// Bağlantı başına en fazla 'tail.sse_buffer' olay bekletilir. İstemci
+// yetişemezse (tampon dolar ya da ortak kanalda geride kalırsa) bağlantı
+// kapatılır; yavaş bir istemci için sunucuda bellek birikmez.
+pub async fn sse_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(query): Query<TailQuery>,
+) -> Sse<impl tokio_stream::Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let filter = TailFilter::new(auth::tenant_of(caller.as_deref()), query);
+    let (tx, rx) = mpsc::channel(state.tail.sse_buffer);
+    tokio::spawn(forward(state.tail.clone(), state.metrics.clone(), filter, tx));
+    let stream = ReceiverStream::new(rx).map(|json: String| Ok(Event::default().data(json)));
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+// Ortak kanaldan bağlantının tamponuna aktarır. Tampon dolarsa, istemci
+// ayrılırsa veya sunucu kapanırsa biter; gönderici düşünce SSE yanıtı da
+// kapanır (keep-alive akışı açık tutmaz).
+async fn forward(tail: Tail, metrics: Arc<Metrics>, filter: TailFilter, out: mpsc::Sender<String>) {
+    let mut rx = tail.subscribe();
+    metrics.tail_clients.fetch_add(1, Ordering::Relaxed);
+    debug!("📡 SSE izleyicisi bağlandı: {:?}", filter);
+
+    let slow = loop {
+        let event = tokio::select! {
+            event = rx.recv() => event,
+            _ = out.closed() => break false,
+            _ = tail.closed() => break false,
+        };
+        match event {
+            Ok(event) if filter.matches(&event) => match out.try_send(event.json.clone()) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => break true,
+                Err(mpsc::error::TrySendError::Closed(_)) => break false,
+            },
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(_)) => break true,
+            Err(broadcast::error::RecvError::Closed) => break false,
+        }
+    };
+
+    metrics.tail_clients.fetch_sub(1, Ordering::Relaxed);
+    if slow {
+        metrics.tail_dropped.fetch_add(1, Ordering::Relaxed);
+        info!("📡 SSE izleyicisi yetişemediği için kapatıldı: {:?}", filter);
+    } else {
+        debug!("📡 SSE izleyicisi ayrıldı.");
+    }
+}
@@ -0,0 +1,66 @@
+use std::net::SocketAddr;
+
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::config::Config;
+use crate::storage::MEMORY;
+use crate::Ingestor;
+
+// --- Test Desteği ---
+// Entegrasyon testleri için süreç içinde çalışan sunucu: bellek içi
+// veritabanıyla 127.0.0.1 üzerinde boş bir portta başlar, çalışma dizinine
+// dosya yazmaz (logs.db, dead-letter.ndjson, erasure-audit.ndjson). Örnek:
+//   let server = TestServer::start().await;
+//   client.post(server.url("/ingest")).json(&logs).send().await?;
+//   server.stop().await;
+// stop çağrılmadan düşürülürse sunucu arka planda kapanır.
+pub struct TestServer {
+    addr: SocketAddr,
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<std::io::Result<()>>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        Self::with_config(Config::default()).await
+    }
+
+    // Verilen ayarlarla başlatır; veritabanı, ölü mektup ve silme denetim dosyaları yine de kapalıdır.
+    pub async fn with_config(mut config: Config) -> Self {
+        config.storage.db_path = MEMORY.to_string();
+        config.dead_letter.path = String::new();
+        config.erasure.audit_path = String::new();
+        let ingestor = Ingestor::builder()
+            .config(config)
+            .build()
+            .await
+            .expect("Test sunucusu kurulamadı");
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Test portu açılamadı");
+        let addr = listener.local_addr().expect("Test portu alınamadı");
+        let (stop, stopped) = oneshot::channel();
+        let task = tokio::spawn(ingestor.serve_with_shutdown(listener, async {
+            let _ = stopped.await;
+        }));
+        TestServer { addr, stop, task }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Örn. url("/logs?level=error") -> "http://127.0.0.1:41234/logs?level=error"
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    // Sunucuyu durdurur ve kanalda kalan loglar yazılana kadar bekler.
+    pub async fn stop(self) {
+        let _ = self.stop.send(());
+        self.task
+            .await
+            .expect("Test sunucusu görevi çöktü")
+            .expect("Test sunucusu hata ile kapandı");
+    }
+}
@@ -0,0 +1,240 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+// --- Zaman Damgası Normalleştirme ---
+// İstemcilerin gönderdiği 'timestamp' değeri ne biçimde olursa olsun UTC
+// RFC 3339'a çevrilir; böylece sıralama ve zaman aralığı sorguları tüm
+// kayıtlarda aynı şekilde çalışır. Kabul edilen biçimler:
+//   - epoch sayı veya sayı metni: saniye, milisaniye, mikrosaniye, nanosaniye
+//     (büyüklüğüne göre ayırt edilir; ondalıklı olabilir)
+//   - RFC 3339 / ISO 8601: "2024-05-01T12:00:00+03:00", "2024-05-01 12:00:00,123Z",
+//     "+0300" biçimli ofset; ofset yoksa UTC kabul edilir; sadece tarih ("2024-05-01") günün başıdır
+//   - RFC 2822: "Wed, 01 May 2024 12:00:00 +0000"
+//   - Apache / nginx (CLF): "01/May/2024:12:00:00 +0000"
+//   - syslog (RFC 3164): "May  1 12:00:00" (yıl yoksa içinde bulunulan yıl) ve "May 1 2024 12:00:00"
+// Çözülemeyen değer için None döner.
+pub fn normalize(value: &Value) -> Option<String> {
+    let time = match value {
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => from_epoch_int(i)?,
+            None => n.as_f64().and_then(from_epoch)?,
+        },
+        Value::String(text) => parse(text)?,
+        _ => return None,
+    };
+    Some(time.to_rfc3339())
+}
+
+// Metin zaman damgasını çözer (bkz. normalize).
+pub fn parse(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(i) = text.parse::<i64>() {
+        return from_epoch_int(i);
+    }
+    // Sadece tarih: günün başı (UTC)
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|naive| naive.and_utc());
+    }
+    if text.bytes().all(|b| b.is_ascii_digit() || b == b'.' || b == b'-') {
+        return text.parse::<f64>().ok().and_then(from_epoch);
+    }
+
+    // Tarih ile saat arasındaki boşluk 'T'ye, virgüllü kesir noktaya çevrilir
+    let iso = match text.as_bytes().get(10) {
+        Some(b' ') => format!("{}T{}", &text[..10], &text[11..]).replace(',', "."),
+        _ => text.replace(',', "."),
+    };
+    if let Ok(time) = DateTime::parse_from_rfc3339(&iso) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = DateTime::parse_from_str(&iso, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(time.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y/%m/%dT%H:%M:%S%.f"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(&iso, format) {
+            return Some(naive.and_utc());
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_rfc2822(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = DateTime::parse_from_str(text, "%d/%b/%Y:%H:%M:%S %z") {
+        return Some(time.with_timezone(&Utc));
+    }
+    parse_syslog(text)
+}
+
+// RFC 3164 zaman damgası ("Mmm dd hh:mm:ss", gün tek haneliyse çift boşluk).
+// Yıl verilmemişse içinde bulunulan yıl varsayılır; sonuç bir günden fazla
+// ilerideyse (Aralık loglarının Ocak'ta gelmesi) bir önceki yıl alınır.
+pub fn parse_syslog(text: &str) -> Option<DateTime<Utc>> {
+    syslog_at(text, Utc::now())
+}
+
+// Yılı 'now'a göre tamamlar (testler sabit bir an verir).
+fn syslog_at(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Ok(naive) = NaiveDateTime::parse_from_str(&text, "%b %d %Y %H:%M:%S") {
+        return Some(naive.and_utc());
+    }
+    let naive = NaiveDateTime::parse_from_str(&format!("{} {}", now.year(), text), "%Y %b %d %H:%M:%S").ok()?;
+    let time = Utc.from_utc_datetime(&naive);
+    if time > now + Duration::days(1) {
+        return naive.with_year(now.year() - 1).map(|naive| naive.and_utc());
+    }
+    Some(time)
+}
+
+// Tam sayı epoch: büyüklüğüne göre saniye / ms / µs / ns (hassasiyet kaybı olmadan)
+fn from_epoch_int(value: i64) -> Option<DateTime<Utc>> {
+    let nanos = match value.unsigned_abs() {
+        v if v < 100_000_000_000 => value.checked_mul(1_000_000_000)?,
+        v if v < 100_000_000_000_000 => value.checked_mul(1_000_000)?,
+        v if v < 100_000_000_000_000_000 => value.checked_mul(1_000)?,
+        _ => value,
+    };
+    Some(DateTime::from_timestamp_nanos(nanos))
+}
+
+// Ondalıklı epoch değeri (from_epoch_int ile aynı eşikler). Tam kısım ve kesir
+// ayrı ölçeklenir: 1714564800.25 * 1e9 f64'te .249999872'ye yuvarlanırdı.
+fn from_epoch(value: f64) -> Option<DateTime<Utc>> {
+    if !value.is_finite() || value.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    let scale: i64 = match value.abs() {
+        v if v < 1e11 => 1_000_000_000,
+        v if v < 1e14 => 1_000_000,
+        v if v < 1e17 => 1_000,
+        _ => 1,
+    };
+    let whole = value.trunc();
+    let fraction = ((value - whole) * scale as f64).round() as i64;
+    let nanos = (whole as i64).checked_mul(scale)?.checked_add(fraction)?;
+    Some(DateTime::from_timestamp_nanos(nanos))
+}
+
+// --- Saklama Biçimi ---
+// Veritabanında 'timestamp' UTC epoch mikro saniye (tam sayı) olarak tutulur;
+// sıralanabilir ve dizinlenebilir. API'de yine RFC 3339 olarak görünür.
+
+// Metin zaman damgasını (parse ile aynı biçimler) epoch mikro saniyeye çevirir.
+pub fn parse_micros(text: &str) -> Result<i64, String> {
+    parse(text)
+        .map(|time| time.timestamp_micros())
+        .ok_or_else(|| format!("zaman damgası çözülemedi: {}", text))
+}
+
+// Epoch mikro saniyeyi RFC 3339'a çevirir (normalize ile aynı biçim).
+pub fn format_micros(micros: i64) -> String {
+    DateTime::from_timestamp_micros(micros)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+// Sorgu parametreleri için: "from=2024-05-01T00:00:00Z" -> epoch mikro saniye.
+// Çözülemeyen değer isteği 400 ile reddeder.
+pub fn deserialize_micros<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) => parse_micros(&text).map(Some).map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn normalize_accepts_known_formats() {
+        let cases = [
+            // epoch: saniye / ms / µs / ns büyüklüğe göre
+            (json!(1714564800), "2024-05-01T12:00:00+00:00"),
+            (json!(1714564800123i64), "2024-05-01T12:00:00.123+00:00"),
+            (json!(1714564800123456i64), "2024-05-01T12:00:00.123456+00:00"),
+            (json!(1714564800123456789i64), "2024-05-01T12:00:00.123456789+00:00"),
+            (json!(1714564800.5), "2024-05-01T12:00:00.500+00:00"),
+            (json!(1714564800123.5), "2024-05-01T12:00:00.123500+00:00"),
+            (json!(-1.5), "1969-12-31T23:59:58.500+00:00"),
+            (json!("1714564800"), "2024-05-01T12:00:00+00:00"),
+            (json!("1714564800.25"), "2024-05-01T12:00:00.250+00:00"),
+            (json!(-1), "1969-12-31T23:59:59+00:00"),
+            (json!(0), "1970-01-01T00:00:00+00:00"),
+            // RFC 3339 / ISO 8601
+            (json!("2024-05-01T15:00:00+03:00"), "2024-05-01T12:00:00+00:00"),
+            (json!("2024-05-01T12:00:00Z"), "2024-05-01T12:00:00+00:00"),
+            (json!("2024-05-01 12:00:00,123Z"), "2024-05-01T12:00:00.123+00:00"),
+            (json!("2024-05-01T15:00:00.5+0300"), "2024-05-01T12:00:00.500+00:00"),
+            (json!("2024-05-01T12:00:00"), "2024-05-01T12:00:00+00:00"),
+            (json!("2024-05-01 12:00:00.000001"), "2024-05-01T12:00:00.000001+00:00"),
+            (json!("2024/05/01 12:00:00"), "2024-05-01T12:00:00+00:00"),
+            (json!("2024-05-01"), "2024-05-01T00:00:00+00:00"),
+            (json!("  2024-05-01T12:00:00Z  "), "2024-05-01T12:00:00+00:00"),
+            // RFC 2822
+            (json!("Wed, 01 May 2024 15:00:00 +0300"), "2024-05-01T12:00:00+00:00"),
+            (json!("Wed, 1 May 2024 12:00:00 GMT"), "2024-05-01T12:00:00+00:00"),
+            // Apache / nginx (CLF)
+            (json!("01/May/2024:12:00:00 +0000"), "2024-05-01T12:00:00+00:00"),
+            (json!("01/May/2024:12:00:00 +0200"), "2024-05-01T10:00:00+00:00"),
+            // syslog, yıllı
+            (json!("May  1 2024 12:00:00"), "2024-05-01T12:00:00+00:00"),
+            (json!("May 01 2024 12:00:00"), "2024-05-01T12:00:00+00:00"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize(&input).as_deref(), Some(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn normalize_rejects_unknown_values() {
+        for input in [
+            json!(""),
+            json!("   "),
+            json!("dün"),
+            json!("2024-13-01"),
+            json!("2024-05-01T25:00:00Z"),
+            json!("32/May/2024:12:00:00 +0000"),
+            json!("Foo  1 12:00:00"),
+            json!(true),
+            json!(null),
+            json!({"seconds": 1}),
+            json!([1714564800]),
+        ] {
+            assert_eq!(normalize(&input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn syslog_year_is_inferred() {
+        let now = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 8, 0, 0).unwrap();
+        let cases = [
+            // Yıl içinde: içinde bulunulan yıl
+            (now(2024, 6, 10), "May  1 12:00:00", "2024-05-01T12:00:00+00:00"),
+            (now(2024, 6, 10), "Jun 10 07:59:59", "2024-06-10T07:59:59+00:00"),
+            // Aralık logu Ocak'ta gelir: bir önceki yıl
+            (now(2025, 1, 2), "Dec 31 23:59:59", "2024-12-31T23:59:59+00:00"),
+            // Bir güne kadar ilerideki zaman saat kayması sayılır
+            (now(2025, 1, 2), "Jan  2 23:00:00", "2025-01-02T23:00:00+00:00"),
+            (now(2025, 1, 2), "Jan  4 00:00:00", "2024-01-04T00:00:00+00:00"),
+            // Yıl verilmişse olduğu gibi alınır
+            (now(2025, 1, 2), "Dec 31 2025 23:59:59", "2025-12-31T23:59:59+00:00"),
+        ];
+        for (now, text, expected) in cases {
+            assert_eq!(syslog_at(text, now).map(|t| t.to_rfc3339()).as_deref(), Some(expected), "{} @ {}", text, now);
+        }
+        assert_eq!(syslog_at("May 32 12:00:00", now(2024, 6, 10)), None);
+    }
+
+    #[test]
+    fn micros_round_trip() {
+        assert_eq!(parse_micros("2024-05-01T12:00:00.123456Z"), Ok(1_714_564_800_123_456));
+        assert_eq!(format_micros(1_714_564_800_123_456), "2024-05-01T12:00:00.123456+00:00");
+        assert!(parse_micros("dün").unwrap_err().contains("dün"));
+    }
+}
@@ -0,0 +1,231 @@
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use axum::extract::ConnectInfo;
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{debug, error, info, warn};
+
+use crate::config::TlsConfig;
+
+// TLS el sıkışması bu sürede bitmezse bağlantı kapatılır
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// --- İstemci Kimliği (mTLS) ---
+// Doğrulanmış istemci sertifikasının sahibi. HTTPS bağlantısındaki her isteğe
+// eklenti (extension) olarak eklenir; API anahtarı doğrulaması sertifikayı
+// yeterli sayar ve CN'i istemciye (auth::Caller) aktarır.
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    // Sertifika konusunun CN'i (yoksa konunun tamamı)
+    pub common_name: String,
+}
+
+impl ClientIdentity {
+    fn from_der(der: &[u8]) -> Option<Self> {
+        let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+        let subject = cert.subject();
+        let common_name = subject
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(str::to_string)
+            .unwrap_or_else(|| subject.to_string());
+        Some(ClientIdentity { common_name })
+    }
+}
+
+// --- Sertifika Deposu ---
+// Geçerli rustls yapılandırmasını tutar. Yeniden yükleme açıksa sertifika ve
+// anahtar dosyalarının değişiklik zamanı düzenli aralıklarla kontrol edilir;
+// değişince yeni bağlantılar yeni sertifikayla kabul edilir. Yükleme başarısız
+// olursa eski sertifika kullanılmaya devam eder.
+struct CertStore {
+    config: TlsConfig,
+    current: RwLock<Arc<ServerConfig>>,
+    modified: RwLock<Option<(SystemTime, SystemTime)>>,
+}
+
+impl CertStore {
+    fn load(config: TlsConfig) -> Result<Self, String> {
+        let server_config = build_server_config(&config)?;
+        let store = CertStore {
+            modified: RwLock::new(modified_times(&config)),
+            current: RwLock::new(Arc::new(server_config)),
+            config,
+        };
+        Ok(store)
+    }
+
+    fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.read().unwrap().clone())
+    }
+
+    // Dosyalar değiştiyse yeniden yükler.
+    fn reload_if_changed(&self) {
+        let modified = modified_times(&self.config);
+        if modified.is_none() || modified == *self.modified.read().unwrap() {
+            return;
+        }
+        match build_server_config(&self.config) {
+            Ok(server_config) => {
+                *self.current.write().unwrap() = Arc::new(server_config);
+                *self.modified.write().unwrap() = modified;
+                info!("🔁 TLS sertifikası yeniden yüklendi: {}", self.config.cert_path);
+            }
+            Err(e) => warn!("⚠️ TLS sertifikası yeniden yüklenemedi, eskisi kullanılıyor: {}", e),
+        }
+    }
+}
+
+fn modified_times(config: &TlsConfig) -> Option<(SystemTime, SystemTime)> {
+    let cert = std::fs::metadata(&config.cert_path).and_then(|m| m.modified()).ok()?;
+    let key = std::fs::metadata(&config.key_path).and_then(|m| m.modified()).ok()?;
+    Some((cert, key))
+}
+
+// PEM sertifika zinciri ve özel anahtardan rustls sunucu yapılandırması üretir.
+fn build_server_config(config: &TlsConfig) -> Result<ServerConfig, String> {
+    let certs = CertificateDer::pem_file_iter(&config.cert_path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("{} okunamadı: {}", config.cert_path, e))?;
+    if certs.is_empty() {
+        return Err(format!("{} içinde sertifika yok", config.cert_path));
+    }
+    let key = PrivateKeyDer::from_pem_file(&config.key_path)
+        .map_err(|e| format!("{} okunamadı: {}", config.key_path, e))?;
+
+    // Süreç genelindeki varsayılan sağlayıcıya güvenmek yerine ring açıkça seçilir.
+    let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS yapılandırılamadı: {}", e))?;
+
+    // İstemci sertifikası: CA verilmişse doğrulanır; 'require_client_cert' ile zorunlu olur.
+    let builder = match &config.client_ca_path {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in CertificateDer::pem_file_iter(ca_path).map_err(|e| format!("{} okunamadı: {}", ca_path, e))? {
+                let cert = cert.map_err(|e| format!("{} okunamadı: {}", ca_path, e))?;
+                roots.add(cert).map_err(|e| format!("{} içinde geçersiz CA: {}", ca_path, e))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = if config.require_client_cert {
+                verifier
+            } else {
+                verifier.allow_unauthenticated()
+            };
+            let verifier = verifier
+                .build()
+                .map_err(|e| format!("istemci doğrulayıcısı kurulamadı: {}", e))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("geçersiz sertifika/anahtar: {}", e))?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(server_config)
+}
+
+// --- HTTPS Sunucusu ---
+// axum::serve'ün TLS'li karşılığı: her bağlantı önce rustls ile açılır, sonra
+// HTTP/1.1 veya HTTP/2 (ALPN) olarak sunulur. Kapatma sinyali gelince yeni
+// bağlantı kabul edilmez ve açık bağlantıların bitmesi beklenir.
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    config: TlsConfig,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), String> {
+    let reload_interval = config.reload_interval_secs;
+    let store = Arc::new(CertStore::load(config)?);
+
+    let reloader = (reload_interval > 0).then(|| {
+        let store = store.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(reload_interval));
+            loop {
+                ticker.tick().await;
+                store.reload_if_changed();
+            }
+        })
+    });
+
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!("❌ HTTPS bağlantı hatası: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let acceptor = store.acceptor();
+        let app = app.clone();
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    debug!("TLS el sıkışması başarısız ({}): {}", peer, e);
+                    return;
+                }
+                Err(_) => {
+                    debug!("TLS el sıkışması zaman aşımına uğradı ({})", peer);
+                    return;
+                }
+            };
+
+            // Doğrulanmış istemci sertifikası varsa bağlantıdaki her isteğe eklenir
+            let identity = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certs| certs.first())
+                .and_then(|cert| ClientIdentity::from_der(cert));
+            if let Some(identity) = &identity {
+                debug!("🔏 İstemci sertifikası: {} ({})", identity.common_name, peer);
+            }
+            let app = app.map_request(move |mut req: axum::http::Request<_>| {
+                if let Some(identity) = &identity {
+                    req.extensions_mut().insert(identity.clone());
+                }
+                req.extensions_mut().insert(ConnectInfo(peer));
+                req
+            });
+            let service = TowerToHyperService::new(app);
+
+            let builder = Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            if let Err(e) = watcher.watch(connection.into_owned()).await {
+                debug!("HTTPS bağlantısı hata ile kapandı ({}): {}", peer, e);
+            }
+        });
+    }
+
+    drop(listener);
+    if let Some(reloader) = reloader {
+        reloader.abort();
+    }
+    graceful.shutdown().await;
+    Ok(())
+}
@@ -0,0 +1,313 @@
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::Utc;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{error, info};
+
+use crate::audit;
+use crate::auth::{self, Caller, KeyInfo, Roles, DEFAULT_TENANT};
+use crate::config::Role;
+use crate::AppState;
+
+// Oluşturulan anahtarların öneki (sızıntı taramalarında tanınsın diye)
+const KEY_PREFIX: &str = "lit_";
+// Listede anahtarı tanımak için gösterilen baş kısım ("lit_3f9a2c")
+const SHOWN_PREFIX_LEN: usize = 10;
+
+// --- Anahtar Yönetimi ---
+// GET /admin/tokens, POST /admin/tokens, DELETE /admin/tokens/{ad}: yeniden
+// başlatmadan rollü anahtar oluşturma ve iptal etme. Anahtar sadece oluşturulurken
+// bir kez döner; dosyada SHA-256 özeti tutulur, dosya sızsa da anahtarlar sızmaz.
+// [[auth.tokens]] anahtarları listede görünür ama buradan silinemez.
+pub struct TokenStore {
+    path: PathBuf,
+    tokens: RwLock<Vec<StoredToken>>,
+    // Dosyaya yazmalar sırayla yapılır
+    write: tokio::sync::Mutex<()>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredToken {
+    name: String,
+    role: Role,
+    tenant: String,
+    // Anahtarın SHA-256 özeti (hex)
+    sha256: String,
+    prefix: String,
+    created_at: String,
+    created_by: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokenFile {
+    tokens: Vec<StoredToken>,
+}
+
+impl TokenStore {
+    // Dosya yoksa boş başlar; ilk anahtar oluşturulunca yazılır.
+    pub fn open(path: &str) -> Result<Option<Self>, String> {
+        if path.trim().is_empty() {
+            return Ok(None);
+        }
+        let file: TokenFile = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| format!("anahtar dosyası geçersiz ({}): {}", path, e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TokenFile::default(),
+            Err(e) => return Err(format!("anahtar dosyası okunamadı ({}): {}", path, e)),
+        };
+        Ok(Some(TokenStore {
+            path: PathBuf::from(path),
+            tokens: RwLock::new(file.tokens),
+            write: tokio::sync::Mutex::new(()),
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    // Özetler sabit sürede karşılaştırılır; her anahtar denenir.
+    pub fn lookup(&self, candidate: &str) -> Option<KeyInfo> {
+        let digest = hex::encode(Sha256::digest(candidate.as_bytes()));
+        let tokens = self.tokens.read().unwrap();
+        tokens
+            .iter()
+            .fold(None, |found, token| {
+                if auth::constant_time_eq(token.sha256.as_bytes(), digest.as_bytes()) {
+                    Some(token)
+                } else {
+                    found
+                }
+            })
+            .map(|token| KeyInfo {
+                tenant: token.tenant.clone(),
+                label: format!("token:{}", token.name),
+                roles: Roles::of(token.role),
+            })
+    }
+
+    // Listeyi değiştirip dosyaya yazar; yazılamazsa bellekteki liste de değişmez.
+    async fn update<T>(&self, change: impl FnOnce(&mut Vec<StoredToken>) -> Result<T, TokenError>) -> Result<T, TokenError> {
+        let _write = self.write.lock().await;
+        let mut tokens = self.tokens.read().unwrap().clone();
+        let result = change(&mut tokens)?;
+        let text = serde_json::to_string_pretty(&TokenFile { tokens: tokens.clone() }).map_err(|e| TokenError::Io(e.to_string()))?;
+        let temp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&temp, text).await.map_err(|e| TokenError::Io(e.to_string()))?;
+        tokio::fs::rename(&temp, &self.path).await.map_err(|e| TokenError::Io(e.to_string()))?;
+        *self.tokens.write().unwrap() = tokens;
+        Ok(result)
+    }
+}
+
+enum TokenError {
+    Exists,
+    NotFound,
+    Io(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenList {
+    tokens: Vec<TokenEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenEntry {
+    name: String,
+    role: Role,
+    tenant: String,
+    // "config" ([[auth.tokens]]) veya "api" (/admin/tokens)
+    source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_by: Option<String>,
+}
+
+// GET /admin/tokens
+pub async fn list_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<TokenList>, StatusCode> {
+    auth::require_default_tenant(caller.as_deref())?;
+    let api_keys = state.api_keys.load();
+    let configured = api_keys.tokens().iter().map(|token| TokenEntry {
+        name: token.name.clone(),
+        role: token.role,
+        tenant: token.tenant.clone(),
+        source: "config",
+        prefix: None,
+        created_at: None,
+        created_by: None,
+    });
+    let managed: Vec<TokenEntry> = state
+        .tokens
+        .as_ref()
+        .map(|store| store.tokens.read().unwrap().clone())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|token| TokenEntry {
+            name: token.name,
+            role: token.role,
+            tenant: token.tenant,
+            source: "api",
+            prefix: Some(token.prefix),
+            created_at: Some(token.created_at),
+            created_by: Some(token.created_by),
+        })
+        .collect();
+    Ok(Json(TokenList {
+        tokens: configured.chain(managed).collect(),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateToken {
+    name: String,
+    role: Role,
+    // Verilmezse varsayılan kiracı
+    tenant: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedToken {
+    name: String,
+    role: Role,
+    tenant: String,
+    // Sadece bu yanıtta görünür
+    key: String,
+    created_at: String,
+}
+
+// POST /admin/tokens {"name": "ci-agent", "role": "ingest", "tenant": "payments"} -> 201
+pub async fn create_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Json(request): Json<CreateToken>,
+) -> Result<(StatusCode, Json<CreatedToken>), (StatusCode, String)> {
+    auth::require_default_tenant(caller.as_deref()).map_err(|status| (status, String::new()))?;
+    let store = store(&state)?;
+    let name = request.name.trim().to_string();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+        return Err((StatusCode::BAD_REQUEST, "ad boş olamaz; harf, rakam, '-', '_' ve '.' içerebilir".to_string()));
+    }
+    if state.api_keys.load().tokens().iter().any(|token| token.name == name) {
+        return Err((StatusCode::CONFLICT, format!("'{}' adı yapılandırmada kullanılıyor", name)));
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let key = format!("{}{}", KEY_PREFIX, hex::encode(bytes));
+    let created_at = Utc::now().to_rfc3339();
+    let token = StoredToken {
+        name: name.clone(),
+        role: request.role,
+        tenant: request.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string()),
+        sha256: hex::encode(Sha256::digest(key.as_bytes())),
+        prefix: key[..SHOWN_PREFIX_LEN].to_string(),
+        created_at: created_at.clone(),
+        created_by: caller.as_deref().map_or("anonymous", |c| &c.id).to_string(),
+    };
+    let (role, tenant) = (token.role, token.tenant.clone());
+    store
+        .update(move |tokens| {
+            if tokens.iter().any(|t| t.name == token.name) {
+                return Err(TokenError::Exists);
+            }
+            tokens.push(token);
+            Ok(())
+        })
+        .await
+        .map_err(|e| failure(&name, e))?;
+    info!("🔑 Anahtar oluşturuldu: {} ({}, kiracı: {})", name, role.as_str(), tenant);
+    audit::record_caller(
+        &state.store,
+        caller.as_deref(),
+        "token.create",
+        serde_json::json!({ "name": name, "role": role, "tenant": tenant }),
+    )
+    .await;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedToken {
+            name,
+            role,
+            tenant,
+            key,
+            created_at,
+        }),
+    ))
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevokedToken {
+    name: String,
+    revoked: bool,
+}
+
+// DELETE /admin/tokens/{ad}: anahtar hemen geçersiz olur
+pub async fn revoke_handler(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(name): Path<String>,
+) -> Result<Json<RevokedToken>, (StatusCode, String)> {
+    auth::require_default_tenant(caller.as_deref()).map_err(|status| (status, String::new()))?;
+    let store = store(&state)?;
+    if state.api_keys.load().tokens().iter().any(|token| token.name == name) {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("'{}' yapılandırmadan geliyor; [[auth.tokens]]'tan silinip yeniden yüklenmeli", name),
+        ));
+    }
+    let target = name.clone();
+    store
+        .update(move |tokens| {
+            let before = tokens.len();
+            tokens.retain(|t| t.name != target);
+            if tokens.len() == before {
+                return Err(TokenError::NotFound);
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| failure(&name, e))?;
+    info!("🔑 Anahtar iptal edildi: {}", name);
+    audit::record_caller(&state.store, caller.as_deref(), "token.revoke", serde_json::json!({ "name": name })).await;
+    Ok(Json(RevokedToken { name, revoked: true }))
+}
+
+fn store(state: &AppState) -> Result<&TokenStore, (StatusCode, String)> {
+    state.tokens.as_deref().ok_or_else(|| {
+        (
+            StatusCode::CONFLICT,
+            "auth.token_file boş; anahtarlar sadece yapılandırmadan yönetilebilir".to_string(),
+        )
+    })
+}
+
+fn failure(name: &str, e: TokenError) -> (StatusCode, String) {
+    match e {
+        TokenError::Exists => (StatusCode::CONFLICT, format!("'{}' adlı anahtar zaten var", name)),
+        TokenError::NotFound => (StatusCode::NOT_FOUND, format!("'{}' adlı anahtar yok", name)),
+        TokenError::Io(e) => {
+            error!("❌ Anahtar dosyası yazılamadı: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use serde_json::{Map, Value};
+
+use crate::config::{CoerceType, TransformStepConfig};
+use crate::filter::add_tag;
+use crate::LogEntry;
+
+// --- Dönüştürme Adımları ---
+// Farklı biçimlerde gelen loglar kod değişikliği olmadan ortak bir şekle
+// getirilir. Adımlar her loga yazıldıkları sırayla, seviye eşlemesi ve filtre
+// kurallarından önce uygulanır; böylece örn. "severity" alanı "level"a
+// taşınıp kanonik seviyeye çevrilebilir:
+//   rename      alanı taşır ("message" / "level" hedefi de olabilir)
+//   parse_json  JSON metnini çözer (yerinde, başka alana veya köke)
+//   add_tags    extra.tags listesine etiket ekler
+//   add_fields  sabit alanlar ekler
+//   coerce      alanın tipini değiştirir (integer / float / boolean / string)
+// Uygulanamayan adım (geçersiz JSON, çevrilemeyen değer) logu değiştirmez;
+// sonraki adımlarla devam edilir.
+#[derive(Debug, Default)]
+pub struct Transforms {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug)]
+enum Step {
+    Rename { from: Vec<String>, to: Target },
+    ParseJson { field: Source, output: Output },
+    AddTags(Vec<String>),
+    AddFields { fields: Map<String, Value>, overwrite: bool },
+    Coerce { field: Vec<String>, to: CoerceType },
+}
+
+// rename hedefi
+#[derive(Debug)]
+enum Target {
+    Message,
+    Level,
+    Extra(Vec<String>),
+}
+
+// parse_json kaynağı
+#[derive(Debug)]
+enum Source {
+    Message,
+    Extra(Vec<String>),
+}
+
+// parse_json sonucunun yazılacağı yer
+#[derive(Debug)]
+enum Output {
+    // Nesnenin anahtarları extra'nın köküne eklenir (var olanlar ezilmez)
+    Merge,
+    Field(Vec<String>),
+}
+
+impl Transforms {
+    pub fn from_config(steps: &[TransformStepConfig]) -> Result<Self, String> {
+        let steps = steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| Step::new(step).map_err(|e| format!("{}. dönüştürme adımı geçersiz: {}", i + 1, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Transforms { steps })
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    // Adımları logun üzerinde uygular; uygulanamayan adım sayısını döner.
+    pub fn apply(&self, log: &mut LogEntry) -> usize {
+        self.steps.iter().filter(|step| !step.apply(log)).count()
+    }
+}
+
+impl Step {
+    fn new(config: &TransformStepConfig) -> Result<Self, String> {
+        Ok(match config {
+            TransformStepConfig::Rename { from, to } => Step::Rename {
+                from: extra_path(from)?,
+                to: match to.as_str() {
+                    "message" => Target::Message,
+                    "level" => Target::Level,
+                    _ => Target::Extra(extra_path(to)?),
+                },
+            },
+            TransformStepConfig::ParseJson { field, target, merge } => {
+                let source = match field.as_str() {
+                    "message" => Source::Message,
+                    _ => Source::Extra(extra_path(field)?),
+                };
+                let output = match (target, *merge, &source) {
+                    (Some(_), true, _) => return Err("'target' ve 'merge' birlikte kullanılamaz".to_string()),
+                    (Some(target), false, _) => Output::Field(extra_path(target)?),
+                    (None, true, _) => Output::Merge,
+                    (None, false, Source::Extra(path)) => Output::Field(path.clone()),
+                    (None, false, Source::Message) => {
+                        return Err("'message' çözülürken 'target' veya merge = true gerekli".to_string())
+                    }
+                };
+                Step::ParseJson { field: source, output }
+            }
+            TransformStepConfig::AddTags { tags } => {
+                if tags.iter().any(|tag| tag.is_empty()) {
+                    return Err("boş etiket".to_string());
+                }
+                Step::AddTags(tags.clone())
+            }
+            TransformStepConfig::AddFields { fields, overwrite } => {
+                if let Some(key) = fields.keys().find(|key| matches!(key.as_str(), "" | "message" | "level")) {
+                    return Err(format!("'{}' alanı eklenemez", key));
+                }
+                Step::AddFields {
+                    fields: fields.clone(),
+                    overwrite: *overwrite,
+                }
+            }
+            TransformStepConfig::Coerce { field, to } => Step::Coerce {
+                field: extra_path(field)?,
+                to: *to,
+            },
+        })
+    }
+
+    // Adım uygulanabildiyse (veya uygulanacak bir şey yoksa) true döner.
+    fn apply(&self, log: &mut LogEntry) -> bool {
+        match self {
+            Step::Rename { from, to } => {
+                let Some(value) = take(&mut log.extra, from) else {
+                    return true;
+                };
+                match to {
+                    Target::Message => log.message = into_text(value),
+                    Target::Level => log.level = into_text(value),
+                    Target::Extra(path) => return set(&mut log.extra, path, value),
+                }
+                true
+            }
+            Step::ParseJson { field, output } => {
+                let parsed = match field {
+                    Source::Message => serde_json::from_str::<Value>(&log.message),
+                    Source::Extra(path) => match get(&log.extra, path) {
+                        Some(Value::String(text)) => serde_json::from_str(text),
+                        // Zaten çözülmüş (nesne) veya hiç olmayan alan
+                        _ => return true,
+                    },
+                };
+                let Ok(parsed) = parsed else {
+                    return false;
+                };
+                match output {
+                    Output::Field(path) => set(&mut log.extra, path, parsed),
+                    Output::Merge => {
+                        let Value::Object(parsed) = parsed else {
+                            return false;
+                        };
+                        if let Source::Extra(path) = field {
+                            take(&mut log.extra, path);
+                        }
+                        let Value::Object(extra) = &mut log.extra else {
+                            return false;
+                        };
+                        for (key, value) in parsed {
+                            if key != "message" && key != "level" {
+                                extra.entry(key).or_insert(value);
+                            }
+                        }
+                        true
+                    }
+                }
+            }
+            Step::AddTags(tags) => {
+                for tag in tags {
+                    add_tag(&mut log.extra, tag);
+                }
+                true
+            }
+            Step::AddFields { fields, overwrite } => {
+                let Value::Object(extra) = &mut log.extra else {
+                    return false;
+                };
+                for (key, value) in fields {
+                    if *overwrite || !extra.contains_key(key) {
+                        extra.insert(key.clone(), value.clone());
+                    }
+                }
+                true
+            }
+            Step::Coerce { field, to } => {
+                let Some(value) = get_mut(&mut log.extra, field) else {
+                    return true;
+                };
+                match coerce(value, *to) {
+                    Some(coerced) => {
+                        *value = coerced;
+                        true
+                    }
+                    None => value.is_null(),
+                }
+            }
+        }
+    }
+}
+
+// "http.status" -> ["http", "status"]; "message" ve "level" extra'da değildir.
+fn extra_path(field: &str) -> Result<Vec<String>, String> {
+    let path: Vec<String> = field.split('.').map(str::to_string).collect();
+    if path.iter().any(String::is_empty) {
+        return Err(format!("'{}' geçerli bir alan yolu değil", field));
+    }
+    if matches!(field, "message" | "level") {
+        return Err(format!("'{}' burada kullanılamaz", field));
+    }
+    Ok(path)
+}
+
+fn get<'a>(extra: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(extra, |value, key| value.get(key))
+}
+
+fn get_mut<'a>(extra: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    path.iter().try_fold(extra, |value, key| value.get_mut(key))
+}
+
+// Alanı çıkarıp döner; boşalan üst nesneler bırakılır.
+fn take(extra: &mut Value, path: &[String]) -> Option<Value> {
+    let (key, parents) = path.split_last()?;
+    get_mut(extra, parents)?.as_object_mut()?.remove(key)
+}
+
+// Alanı yazar; eksik üst nesneler oluşturulur. Yol nesne olmayan bir değerden
+// geçiyorsa yazılmaz (false).
+fn set(extra: &mut Value, path: &[String], value: Value) -> bool {
+    let Some((key, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut target = extra;
+    for parent in parents {
+        let Value::Object(map) = target else {
+            return false;
+        };
+        target = map.entry(parent.clone()).or_insert_with(|| Value::Object(Map::new()));
+    }
+    match target {
+        Value::Object(map) => {
+            map.insert(key.clone(), value);
+            true
+        }
+        _ => false,
+    }
+}
+
+// message / level'a taşınan değerin metin hali (metin olmayanlar JSON olarak)
+fn into_text(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+// Değeri istenen tipe çevirir; çevrilemezse None. null olduğu gibi kalır.
+fn coerce(value: &Value, to: CoerceType) -> Option<Value> {
+    match (to, value) {
+        (_, Value::Null) => None,
+        (CoerceType::Integer, Value::Number(n)) if n.is_i64() || n.is_u64() => Some(value.clone()),
+        (CoerceType::Integer, Value::Number(n)) => {
+            let f = n.as_f64()?;
+            (f.fract() == 0.0 && f.abs() < i64::MAX as f64).then(|| Value::from(f as i64))
+        }
+        (CoerceType::Integer, Value::String(text)) => text.trim().parse::<i64>().ok().map(Value::from),
+        (CoerceType::Integer, Value::Bool(b)) => Some(Value::from(*b as i64)),
+        (CoerceType::Float, Value::Number(n)) => n.as_f64().map(Value::from),
+        (CoerceType::Float, Value::String(text)) => text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .map(Value::from),
+        (CoerceType::Boolean, Value::Bool(_)) => Some(value.clone()),
+        (CoerceType::Boolean, Value::Number(n)) => match n.as_i64()? {
+            0 => Some(Value::Bool(false)),
+            1 => Some(Value::Bool(true)),
+            _ => None,
+        },
+        (CoerceType::Boolean, Value::String(text)) => match text.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "off" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (CoerceType::String, Value::String(_)) => Some(value.clone()),
+        (CoerceType::String, other) => Some(Value::String(other.to_string())),
+        _ => None,
+    }
+}
@@ -0,0 +1,67 @@
+use std::future::Future;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::Path;
+
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use tokio::net::UnixListener;
+use tracing::{debug, error};
+
+// --- Unix Domain Soketi ---
+// server.unix_socket verilirse HTTP sunucusu TCP portu yerine bu yoldaki
+// sokette dinler (yan konteyner / sidecar kurulumları: ağ portu açılmaz).
+// Önceki çalışmadan kalan soket dosyası silinir; dosya izinleri
+// 'unix_socket_mode' ile ayarlanır (istemcilerin yazabilmesi için).
+// İsteklerde uzak adres yoktur; remote_addr sütunu boş kalır.
+pub fn bind(path: &str, mode: u32) -> Result<UnixListener, String> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => {
+            std::fs::remove_file(path).map_err(|e| format!("eski soket silinemedi: {}", e))?;
+        }
+        Ok(_) => return Err(format!("{} zaten var ve soket değil", path)),
+        Err(_) => {}
+    }
+    let listener = UnixListener::bind(path).map_err(|e| e.to_string())?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .map_err(|e| format!("izinler ayarlanamadı: {}", e))?;
+    Ok(listener)
+}
+
+// axum::serve'ün unix soketli karşılığı (bkz. tls::serve). Kapatma sinyali
+// gelince yeni bağlantı kabul edilmez, açıkların bitmesi beklenir ve soket
+// dosyası silinir.
+pub async fn serve(listener: UnixListener, path: &str, app: Router, shutdown: impl Future<Output = ()>) {
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    error!("❌ Unix soketi bağlantı hatası: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let service = TowerToHyperService::new(app.clone());
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let builder = Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            if let Err(e) = watcher.watch(connection.into_owned()).await {
+                debug!("Unix soketi bağlantısı hata ile kapandı: {}", e);
+            }
+        });
+    }
+
+    drop(listener);
+    graceful.shutdown().await;
+    if Path::new(path).exists() {
+        let _ = std::fs::remove_file(path);
+    }
+}
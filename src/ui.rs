@@ -0,0 +1,46 @@
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use rust_embed::RustEmbed;
+
+// --- Web Arayüzü ---
+// GET /ui/: Grafana'sı olmayan ekipler için arama ve canlı akış sayfası. Sayfa
+// sadece mevcut okuma uçlarını (GET /logs, GET /tail/sse) kullanır; anahtar
+// tarayıcıda girilir ve her istekte gönderilir, sayfanın kendisi korumasızdır.
+// Dosyalar (ui/) sürüm derlemesinde ikiliye gömülür; ayrıca dağıtılacak dosya
+// yoktur. Hata ayıklama derlemesinde diskten okunur (sayfa derlemesiz değişir).
+#[derive(RustEmbed)]
+#[folder = "ui/"]
+struct Assets;
+
+// Dosya uzantısına göre Content-Type
+fn content_type(name: &str) -> &'static str {
+    match name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+// GET /ui -> /ui/ (sayfadaki göreli adresler dizine göre çözülür). Yönlendirme
+// de göreli olduğu için sunucu bir ön ek altında yayınlansa da çalışır.
+pub async fn redirect_handler() -> Redirect {
+    Redirect::permanent("ui/")
+}
+
+// GET /ui/ ve GET /ui/{dosya}
+pub async fn asset_handler(file: Option<Path<String>>) -> Response {
+    let name = file.as_ref().map_or("index.html", |Path(file)| file.as_str());
+    match Assets::get(name) {
+        Some(asset) => (
+            [(header::CONTENT_TYPE, content_type(name)), (header::CACHE_CONTROL, "no-cache")],
+            asset.data,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
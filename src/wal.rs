@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tracing::{debug, info, warn};
+
+use crate::config::WalConfig;
+use crate::deadletter::SavedLog;
+use crate::pipeline::Dispatcher;
+use crate::LogEntry;
+
+// --- Diskte Kuyruk (WAL) ---
+// Açıksa kanala giren her log önce diskteki bir parça (segment) dosyasına
+// eklenir; böylece 202 ile kabul edilip henüz yazılmamış loglar süreç
+// çökse bile kaybolmaz. Yazıcı bir partiyi veritabanına (veya ölü mektup
+// dosyasına) yazınca logların kaydını düşer; bir parçanın tüm logları
+// düşülünce ve parça artık yazılmıyorsa dosyası silinir.
+//
+// Açılışta kalan parçalar (önceki çalışmadan yazılmamış loglar) yeniden
+// kanala gönderilir, sonra silinir. Dosyalar NDJSON'dur: <dizin>/<numara>.wal
+//
+// Yazma onayı bekleyen kaynakların (Kafka) logları WAL'a yazılmaz; onları
+// kaynak zaten yeniden gönderir. Kayıtlar en az bir kez teslim edilir: çökme
+// anında parçası henüz silinmemiş ama yazılmış loglar açılışta tekrar yazılabilir.
+pub struct Wal {
+    dir: PathBuf,
+    segment_bytes: u64,
+    fsync: bool,
+    state: Mutex<WalState>,
+}
+
+struct WalState {
+    file: File,
+    // Yazılan parçanın numarası ve boyutu
+    current: u64,
+    size: u64,
+    // Parça numarası -> henüz veritabanına yazılmamış log sayısı
+    pending: HashMap<u64, usize>,
+}
+
+// Kanaldaki bir logun WAL kaydı; yazıcı log yazılınca 'commit' çağırır.
+// Commit edilmeden düşürülürse kayıt diskte kalır (açılışta yeniden yüklenir).
+pub struct WalTicket {
+    wal: Arc<Wal>,
+    segment: u64,
+}
+
+impl WalTicket {
+    pub fn commit(self) {
+        self.wal.commit(self.segment);
+    }
+}
+
+impl Wal {
+    // Dizini açar ve önceki çalışmadan kalan parçaların yollarını döner
+    // (yeni parça bunlardan sonra numaralanır; bkz. replay).
+    pub fn open(dir: &str, config: &WalConfig) -> Result<(Arc<Self>, Vec<PathBuf>), String> {
+        let dir = PathBuf::from(dir);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{} oluşturulamadı: {}", dir.display(), e))?;
+        let mut leftovers: Vec<(u64, PathBuf)> = std::fs::read_dir(&dir)
+            .map_err(|e| format!("{} okunamadı: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter_map(|path| segment_number(&path).map(|n| (n, path)))
+            .collect();
+        leftovers.sort();
+
+        let current = leftovers.last().map_or(1, |(n, _)| n + 1);
+        let file = create_segment(&dir, current)?;
+        let wal = Wal {
+            dir,
+            segment_bytes: config.segment_bytes.max(1),
+            fsync: config.fsync,
+            state: Mutex::new(WalState {
+                file,
+                current,
+                size: 0,
+                pending: HashMap::new(),
+            }),
+        };
+        Ok((Arc::new(wal), leftovers.into_iter().map(|(_, path)| path).collect()))
+    }
+
+    // Logu sıradaki parçaya ekler; parça doluysa önce yenisine geçer.
+    pub fn append(self: &Arc<Self>, log: &LogEntry) -> Result<WalTicket, String> {
+        let mut line = serde_json::to_vec(&SavedLog::from(log)).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+
+        let mut state = self.state.lock().unwrap();
+        if state.size >= self.segment_bytes {
+            let next = state.current + 1;
+            state.file = create_segment(&self.dir, next)?;
+            let previous = std::mem::replace(&mut state.current, next);
+            state.size = 0;
+            if !state.pending.contains_key(&previous) {
+                self.remove(previous);
+            }
+        }
+        state.file.write_all(&line).map_err(|e| e.to_string())?;
+        if self.fsync {
+            state.file.sync_data().map_err(|e| e.to_string())?;
+        }
+        state.size += line.len() as u64;
+        let segment = state.current;
+        *state.pending.entry(segment).or_default() += 1;
+        Ok(WalTicket {
+            wal: self.clone(),
+            segment,
+        })
+    }
+
+    fn commit(&self, segment: u64) {
+        let mut state = self.state.lock().unwrap();
+        let Some(count) = state.pending.get_mut(&segment) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            state.pending.remove(&segment);
+            if segment != state.current {
+                self.remove(segment);
+            } else if let Err(e) = state.file.set_len(0) {
+                // Yazılan parçanın tüm logları yazıldı: boşaltılır ki çökmede tekrar yüklenmesin
+                warn!("⚠️ WAL parçası boşaltılamadı: {}", e);
+            } else {
+                state.size = 0;
+            }
+        }
+    }
+
+    // Yazılan parçayı diske zorlar (POST /admin/drain). Kuyruk boşaldıysa parça
+    // zaten boşaltılmıştır; bekleyen loglar varsa onlar diskte kalır.
+    pub fn checkpoint(&self) -> Result<(), String> {
+        self.state.lock().unwrap().file.sync_all().map_err(|e| e.to_string())
+    }
+
+    // Kanalda olup henüz yazılmamış loglar
+    pub fn pending(&self) -> usize {
+        self.state.lock().unwrap().pending.values().sum()
+    }
+
+    fn remove(&self, segment: u64) {
+        let path = segment_path(&self.dir, segment);
+        match std::fs::remove_file(&path) {
+            Ok(()) => debug!("🗑️ WAL parçası silindi: {}", path.display()),
+            Err(e) => warn!("⚠️ WAL parçası silinemedi ({}): {}", path.display(), e),
+        }
+    }
+}
+
+// Önceki çalışmadan kalan parçalardaki logları kanala gönderir (her biri
+// yeniden WAL'a yazılır) ve parçaları siler. Yazıcılar çalışıyor olmalı;
+// kanal dolarsa yer açılmasını bekler. Gönderilen log sayısını döner.
+pub async fn replay(leftovers: Vec<PathBuf>, tx: &Dispatcher) -> Result<u64, String> {
+    let mut replayed = 0;
+    for path in leftovers {
+        let file = File::open(&path).map_err(|e| format!("{} açılamadı: {}", path.display(), e))?;
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("{} okunamadı: {}", path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Çökme anında yarım kalmış son satır okunamaz; atlanır
+            let log = match serde_json::from_str::<SavedLog>(&line) {
+                Ok(saved) => LogEntry::from(&saved),
+                Err(e) => {
+                    warn!("⚠️ {}:{} okunamadı, atlandı: {}", path.display(), number + 1, e);
+                    continue;
+                }
+            };
+            tx.send(log, None).await.map_err(|_| "kanal kapalı".to_string())?;
+            replayed += 1;
+        }
+        std::fs::remove_file(&path).map_err(|e| format!("{} silinemedi: {}", path.display(), e))?;
+        info!("♻️ WAL parçası yeniden yüklendi: {}", path.display());
+    }
+    Ok(replayed)
+}
+
+fn segment_path(dir: &Path, segment: u64) -> PathBuf {
+    dir.join(format!("{:020}.wal", segment))
+}
+
+fn segment_number(path: &Path) -> Option<u64> {
+    if path.extension()? != "wal" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+fn create_segment(dir: &Path, segment: u64) -> Result<File, String> {
+    let path = segment_path(dir, segment);
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("{} açılamadı: {}", path.display(), e))
+}
@@ -0,0 +1,284 @@
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+use tracing::debug;
+use wasmtime::{Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+use crate::config::WasmHookConfig;
+use crate::hook::{Hook, Verdict};
+use crate::metrics::Metrics;
+use crate::LogEntry;
+
+// process'in döndürebileceği en büyük çıktı (log JSON'u)
+const MAX_OUTPUT: usize = 16 * 1024 * 1024;
+
+// --- WASM Kancaları ---
+// [[wasm]] ile verilen modüller ('wasm' özelliğiyle derlenmiş ikilide) her logu
+// JSON olarak alır; değiştirebilir veya atabilir. Böylece crate'i çatallamadan
+// özel zenginleştirme ve filtreleme yazılabilir. Modüle hiçbir içe aktarma
+// (import) verilmez, sadece kendi belleğini görür. Dışa aktarması gerekenler:
+//   memory                               doğrusal bellek
+//   alloc(len: i32) -> i32               girdinin yazılacağı yer
+//   process(ptr: i32, len: i32) -> i64   -1: logu at, 0: değiştirmeden geç,
+//                                        diğer: (ptr << 32) | len, logun yeni JSON hali
+//   dealloc(ptr: i32, len: i32)          isteğe bağlı; girdi ve çıktı okununca çağrılır
+// Girdi ve çıktı {"level": ..., "message": ..., <diğer alanlar>} nesnesidir.
+// Her çağrı 'fuel' komutla ve 'max_memory_mb' bellekle sınırlıdır. Hata (tuzak,
+// yakıtın bitmesi, geçersiz çıktı) logu düşürmez: log değişmeden geçer, hata
+// sayılır ve o örnek atılır (sonraki çağrı yenisini açar).
+// Store eşzamanlı kullanılamaz; eşzamanlı istekler birbirini beklemesin diye
+// her çağrı havuzdan boştaki bir örneği alır, yoksa yenisini açar. Bitince
+// geri konur; en fazla işlemci sayısı kadar boş örnek tutulur.
+pub struct WasmHook {
+    name: String,
+    fuel: u64,
+    max_memory_bytes: usize,
+    engine: Engine,
+    module: Module,
+    // Boştaki örnekler
+    idle: Mutex<Vec<Runtime>>,
+    pool: usize,
+    metrics: Arc<Metrics>,
+}
+
+struct Runtime {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    process: TypedFunc<(i32, i32), i64>,
+    dealloc: Option<TypedFunc<(i32, i32), ()>>,
+}
+
+impl WasmHook {
+    pub fn load(config: &WasmHookConfig, metrics: Arc<Metrics>) -> Result<Self, String> {
+        let mut engine_config = wasmtime::Config::new();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config).map_err(|e| e.to_string())?;
+        // .wasm veya metin biçimi (.wat)
+        let module = Module::from_file(&engine, &config.path).map_err(|e| format!("{}: {:#}", config.path, e))?;
+        let name = Path::new(&config.path)
+            .file_stem()
+            .map_or_else(|| config.path.clone(), |stem| stem.to_string_lossy().into_owned());
+        let max_memory_bytes = config.max_memory_mb.saturating_mul(1024 * 1024);
+        let runtime = Runtime::new(&engine, &module, max_memory_bytes).map_err(|e| format!("{}: {}", config.path, e))?;
+        Ok(WasmHook {
+            name,
+            fuel: config.fuel,
+            max_memory_bytes,
+            engine,
+            module,
+            idle: Mutex::new(vec![runtime]),
+            pool: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            metrics,
+        })
+    }
+
+    // Boştaki bir örneği alır; hepsi kullanımdaysa yenisini açar.
+    fn checkout(&self) -> Result<Runtime, String> {
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        match idle {
+            Some(runtime) => Ok(runtime),
+            None => Runtime::new(&self.engine, &self.module, self.max_memory_bytes),
+        }
+    }
+
+    fn checkin(&self, runtime: Runtime) {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < self.pool {
+            idle.push(runtime);
+        }
+    }
+}
+
+impl Runtime {
+    fn new(engine: &Engine, module: &Module, max_memory_bytes: usize) -> Result<Self, String> {
+        let limits = StoreLimitsBuilder::new().memory_size(max_memory_bytes).instances(1).build();
+        let mut store = Store::new(engine, limits);
+        store.limiter(|limits| limits);
+        // Başlatma (start) fonksiyonu da yakıt harcar
+        store.set_fuel(u64::MAX).map_err(|e| e.to_string())?;
+        let instance: Instance = Linker::new(engine)
+            .instantiate(&mut store, module)
+            .map_err(|e| format!("modül örneklenemedi: {:#}", e))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| "modül 'memory' dışa aktarmıyor".to_string())?;
+        let alloc = instance
+            .get_typed_func(&mut store, "alloc")
+            .map_err(|e| format!("'alloc(i32) -> i32' bulunamadı: {}", e))?;
+        let process = instance
+            .get_typed_func(&mut store, "process")
+            .map_err(|e| format!("'process(i32, i32) -> i64' bulunamadı: {}", e))?;
+        let dealloc = instance.get_typed_func(&mut store, "dealloc").ok();
+        Ok(Runtime { store, memory, alloc, process, dealloc })
+    }
+
+    fn call(&mut self, input: &[u8], fuel: u64) -> Result<Option<Vec<u8>>, String> {
+        let len = i32::try_from(input.len()).map_err(|_| "log çok büyük".to_string())?;
+        self.store.set_fuel(fuel).map_err(|e| e.to_string())?;
+        let ptr = self.alloc.call(&mut self.store, len).map_err(|e| format!("alloc: {:#}", e))?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, input)
+            .map_err(|_| "alloc geçersiz adres döndü".to_string())?;
+        let result = self.process.call(&mut self.store, (ptr, len)).map_err(|e| format!("process: {:#}", e))?;
+        self.free(ptr, len)?;
+
+        match result {
+            -1 => Ok(None),
+            0 => Ok(Some(Vec::new())),
+            packed => {
+                let (out_ptr, out_len) = ((packed >> 32) as u32, packed as u32);
+                if out_len as usize > MAX_OUTPUT {
+                    return Err(format!("process {} bayttan uzun çıktı döndü", MAX_OUTPUT));
+                }
+                // Uzunluk modülden gelir: bellek sınırları içinde olduğu görülmeden yer ayrılmaz
+                let start = out_ptr as usize;
+                let output = start
+                    .checked_add(out_len as usize)
+                    .filter(|end| *end <= self.memory.data_size(&self.store))
+                    .map(|end| self.memory.data(&self.store)[start..end].to_vec())
+                    .ok_or_else(|| "process geçersiz adres döndü".to_string())?;
+                self.free(out_ptr as i32, out_len as i32)?;
+                Ok(Some(output))
+            }
+        }
+    }
+
+    fn free(&mut self, ptr: i32, len: i32) -> Result<(), String> {
+        match &self.dealloc {
+            Some(dealloc) => dealloc.call(&mut self.store, (ptr, len)).map_err(|e| format!("dealloc: {:#}", e)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Hook for WasmHook {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn apply(&self, log: &mut LogEntry) -> Verdict {
+        let input = match serde_json::to_vec(&log) {
+            Ok(input) => input,
+            Err(_) => return Verdict::Keep,
+        };
+        let result = self.checkout().and_then(|mut runtime| {
+            let output = runtime.call(&input, self.fuel)?;
+            self.checkin(runtime);
+            Ok(output)
+        });
+        // Hata durumunda örnek havuza geri konmaz: tuzaktan sonra belleği tutarsız olabilir
+        let output = match result {
+            Ok(None) => return Verdict::Drop,
+            Ok(Some(output)) if output.is_empty() => return Verdict::Keep,
+            Ok(Some(output)) => output,
+            Err(e) => {
+                debug!("ℹ️ WASM kancası '{}' başarısız, log değişmeden geçiyor: {}", self.name, e);
+                self.metrics.hook_errors.fetch_add(1, Ordering::Relaxed);
+                return Verdict::Keep;
+            }
+        };
+        match serde_json::from_slice::<LogEntry>(&output) {
+            Ok(changed) => {
+                log.level = changed.level;
+                log.message = changed.message;
+                log.extra = changed.extra;
+            }
+            Err(e) => {
+                debug!("ℹ️ WASM kancası '{}' geçersiz log döndü: {}", self.name, e);
+                self.metrics.hook_errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Verdict::Keep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Tek sayfa bellek ve basit bir artan ayırıcı; 'process' gövdesi teste göre değişir.
+    // 1024 adresindeki veri bölümü değiştirilmiş logun JSON halidir.
+    fn module(process: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 4096))
+                (data (i32.const 1024) "{{\22level\22:\22warn\22,\22message\22:\22changed\22,\22by\22:\22wasm\22}}")
+                (func (export "alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "process") (param $ptr i32) (param $len i32) (result i64)
+                    {process}))"#
+        )
+    }
+
+    fn hook(name: &str, process: &str) -> (WasmHook, Arc<Metrics>) {
+        let path = std::env::temp_dir().join(format!("log-ingestor-{}-{}.wat", name, std::process::id()));
+        std::fs::write(&path, module(process)).unwrap();
+        let config = WasmHookConfig { path: path.to_string_lossy().into_owned(), fuel: 1_000_000, max_memory_mb: 16 };
+        let metrics = Arc::new(Metrics::default());
+        let hook = WasmHook::load(&config, metrics.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        (hook, metrics)
+    }
+
+    fn entry() -> LogEntry {
+        serde_json::from_value(json!({"level": "info", "message": "original", "user": 7})).unwrap()
+    }
+
+    #[test]
+    fn output_replaces_level_message_and_fields() {
+        let json_len = r#"{"level":"warn","message":"changed","by":"wasm"}"#.len();
+        let (hook, metrics) = hook("mutate", &format!("(i64.or (i64.shl (i64.const 1024) (i64.const 32)) (i64.const {}))", json_len));
+        let mut log = entry();
+        assert_eq!(hook.apply(&mut log), Verdict::Keep);
+        assert_eq!(log.level, "warn");
+        assert_eq!(log.message, "changed");
+        assert_eq!(log.extra, json!({"by": "wasm"}));
+        assert_eq!(metrics.hook_errors.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn zero_keeps_the_entry_unchanged() {
+        let (hook, _) = hook("keep", "(i64.const 0)");
+        let mut log = entry();
+        assert_eq!(hook.apply(&mut log), Verdict::Keep);
+        assert_eq!(log.message, "original");
+        assert_eq!(log.extra, json!({"user": 7}));
+    }
+
+    #[test]
+    fn minus_one_drops_the_entry() {
+        let (hook, metrics) = hook("drop", "(i64.const -1)");
+        assert_eq!(hook.apply(&mut entry()), Verdict::Drop);
+        assert_eq!(metrics.hook_errors.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn out_of_bounds_output_is_an_error_and_keeps_the_entry() {
+        // Bellek 64 KiB; çıktı 65000'den başlayıp 1000 bayt sürüyor
+        let (hook, metrics) = hook("oob", "(i64.or (i64.shl (i64.const 65000) (i64.const 32)) (i64.const 1000))");
+        let mut log = entry();
+        assert_eq!(hook.apply(&mut log), Verdict::Keep);
+        assert_eq!(log.message, "original");
+        assert_eq!(metrics.hook_errors.load(Ordering::Relaxed), 1);
+        // Hatalı örnek havuza dönmez, sonraki çağrı yenisini açar
+        assert!(hook.idle.lock().unwrap().is_empty());
+        assert_eq!(hook.apply(&mut log), Verdict::Keep);
+        assert_eq!(metrics.hook_errors.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn trap_keeps_the_entry() {
+        let (hook, metrics) = hook("trap", "(unreachable)");
+        let mut log = entry();
+        assert_eq!(hook.apply(&mut log), Verdict::Keep);
+        assert_eq!(log.message, "original");
+        assert_eq!(metrics.hook_errors.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,204 @@
+use std::ffi::c_void;
+use std::sync::mpsc;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread::JoinHandle;
+
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+// --- Windows Hizmeti ---
+// 'log-ingestor serve --service' ile Hizmet Denetim Yöneticisi'ne (SCM)
+// bağlanılır; hizmet durdurulunca (veya Windows kapanırken) sunucu
+// CTRL+C ile aynı düzenli kapanışı yapar ve yazıcılar bitince SCM'e
+// "durdu" bildirilir. Kurulum örneği:
+//   sc.exe create log-ingestor binPath= "C:\log-ingestor\log-ingestor.exe serve --service --config C:\log-ingestor\config.toml" start= auto
+// Hizmetin çalışma dizini System32'dir; yollar mutlak verilmelidir.
+
+const SERVICE_NAME: &str = "log-ingestor";
+
+type ServiceStatusHandle = isize;
+type HandlerEx = unsafe extern "system" fn(u32, u32, *mut c_void, *mut c_void) -> u32;
+type ServiceMain = unsafe extern "system" fn(u32, *mut *mut u16);
+
+#[repr(C)]
+struct ServiceTableEntryW {
+    service_name: *mut u16,
+    service_proc: Option<ServiceMain>,
+}
+
+#[repr(C)]
+struct ServiceStatus {
+    service_type: u32,
+    current_state: u32,
+    controls_accepted: u32,
+    win32_exit_code: u32,
+    service_specific_exit_code: u32,
+    check_point: u32,
+    wait_hint: u32,
+}
+
+#[link(name = "advapi32")]
+extern "system" {
+    fn StartServiceCtrlDispatcherW(table: *const ServiceTableEntryW) -> i32;
+    fn RegisterServiceCtrlHandlerExW(name: *const u16, handler: HandlerEx, context: *mut c_void) -> ServiceStatusHandle;
+    fn SetServiceStatus(handle: ServiceStatusHandle, status: *const ServiceStatus) -> i32;
+}
+
+const SERVICE_WIN32_OWN_PROCESS: u32 = 0x10;
+const SERVICE_STOPPED: u32 = 1;
+const SERVICE_STOP_PENDING: u32 = 3;
+const SERVICE_RUNNING: u32 = 4;
+const SERVICE_ACCEPT_STOP: u32 = 0x1;
+const SERVICE_ACCEPT_SHUTDOWN: u32 = 0x4;
+const SERVICE_CONTROL_STOP: u32 = 1;
+const SERVICE_CONTROL_INTERROGATE: u32 = 4;
+const SERVICE_CONTROL_SHUTDOWN: u32 = 5;
+const NO_ERROR: u32 = 0;
+const ERROR_CALL_NOT_IMPLEMENTED: u32 = 120;
+
+struct Service {
+    handle: Mutex<ServiceStatusHandle>,
+    // Durdurma isteğinde kapanışın en fazla ne kadar süreceği (SCM'e bildirilir)
+    stop_wait_ms: u32,
+    // SCM durdurma istedi
+    stop: Notify,
+    // Kapanış bitti; hizmet ana iş parçacığı "durdu" bildirip çıkar
+    done: (Mutex<bool>, Condvar),
+    // service_main'in SCM'e bağlanma sonucu (start bekler)
+    ready: Mutex<Option<mpsc::Sender<Result<(), String>>>>,
+    dispatcher: Mutex<Option<JoinHandle<()>>>,
+}
+
+static SERVICE: OnceLock<Service> = OnceLock::new();
+
+// SCM'e bağlanır ve hizmeti "çalışıyor" olarak bildirir. Süreç SCM tarafından
+// başlatılmamışsa (örn. konsoldan) hata döner.
+pub fn start(stop_wait_secs: u64) -> Result<(), String> {
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let service = Service {
+        handle: Mutex::new(0),
+        stop_wait_ms: (stop_wait_secs.saturating_add(10)).saturating_mul(1000).min(u32::MAX as u64) as u32,
+        stop: Notify::new(),
+        done: (Mutex::new(false), Condvar::new()),
+        ready: Mutex::new(Some(ready_tx.clone())),
+        dispatcher: Mutex::new(None),
+    };
+    if SERVICE.set(service).is_err() {
+        return Err("hizmet zaten başlatıldı".to_string());
+    }
+
+    // StartServiceCtrlDispatcherW hizmet durana kadar dönmez; ayrı iş parçacığında çalışır
+    let dispatcher = std::thread::spawn(move || {
+        let mut name = wide(SERVICE_NAME);
+        let table = [
+            ServiceTableEntryW {
+                service_name: name.as_mut_ptr(),
+                service_proc: Some(service_main),
+            },
+            ServiceTableEntryW {
+                service_name: std::ptr::null_mut(),
+                service_proc: None,
+            },
+        ];
+        if unsafe { StartServiceCtrlDispatcherW(table.as_ptr()) } == 0 {
+            let _ = ready_tx.send(Err(format!(
+                "hizmet denetim yöneticisine bağlanılamadı: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+    });
+    *SERVICE.get().unwrap().dispatcher.lock().unwrap() = Some(dispatcher);
+
+    ready_rx
+        .recv()
+        .map_err(|_| "hizmet başlatılamadı".to_string())?
+}
+
+// SCM durdurma isteyene kadar bekler (hizmet olarak çalışılmıyorsa hiç dönmez).
+pub async fn stop_requested() {
+    match SERVICE.get() {
+        Some(service) => service.stop.notified().await,
+        None => std::future::pending().await,
+    }
+}
+
+// Kapanış bittiğinde çağrılır: SCM'e "durdu" bildirilir.
+pub fn stopped() {
+    let Some(service) = SERVICE.get() else {
+        return;
+    };
+    let (done, signal) = &service.done;
+    *done.lock().unwrap() = true;
+    signal.notify_all();
+    if let Some(dispatcher) = service.dispatcher.lock().unwrap().take() {
+        let _ = dispatcher.join();
+    }
+}
+
+unsafe extern "system" fn service_main(_argc: u32, _argv: *mut *mut u16) {
+    let service = SERVICE.get().expect("hizmet durumu yok");
+    let name = wide(SERVICE_NAME);
+    let handle = RegisterServiceCtrlHandlerExW(name.as_ptr(), control_handler, std::ptr::null_mut());
+    let ready = service.ready.lock().unwrap().take();
+    if handle == 0 {
+        if let Some(ready) = ready {
+            let _ = ready.send(Err(format!(
+                "hizmet denetim işleyicisi kaydedilemedi: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+        return;
+    }
+    *service.handle.lock().unwrap() = handle;
+    report(service, SERVICE_RUNNING, 0);
+    info!("🪟 Windows hizmeti olarak çalışıyor.");
+    if let Some(ready) = ready {
+        let _ = ready.send(Ok(()));
+    }
+
+    // Sunucu kapanışını bitirene kadar bu iş parçacığı bekler
+    let (done, signal) = &service.done;
+    let mut finished = done.lock().unwrap();
+    while !*finished {
+        finished = signal.wait(finished).unwrap();
+    }
+    report(service, SERVICE_STOPPED, 0);
+}
+
+unsafe extern "system" fn control_handler(control: u32, _event: u32, _data: *mut c_void, _context: *mut c_void) -> u32 {
+    let Some(service) = SERVICE.get() else {
+        return ERROR_CALL_NOT_IMPLEMENTED;
+    };
+    match control {
+        SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+            report(service, SERVICE_STOP_PENDING, service.stop_wait_ms);
+            service.stop.notify_one();
+            NO_ERROR
+        }
+        SERVICE_CONTROL_INTERROGATE => NO_ERROR,
+        _ => ERROR_CALL_NOT_IMPLEMENTED,
+    }
+}
+
+fn report(service: &Service, state: u32, wait_hint: u32) {
+    let status = ServiceStatus {
+        service_type: SERVICE_WIN32_OWN_PROCESS,
+        current_state: state,
+        controls_accepted: match state {
+            SERVICE_RUNNING => SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN,
+            _ => 0,
+        },
+        win32_exit_code: NO_ERROR,
+        service_specific_exit_code: 0,
+        check_point: 0,
+        wait_hint,
+    };
+    let handle = *service.handle.lock().unwrap();
+    if unsafe { SetServiceStatus(handle, &status) } == 0 {
+        warn!("⚠️ Hizmet durumu bildirilemedi: {}", std::io::Error::last_os_error());
+    }
+}
+
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
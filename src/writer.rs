@@ -0,0 +1,232 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tracing::{debug, error, info_span, warn, Instrument};
+
+use crate::config::PipelineConfig;
+use crate::deadletter::DeadLetter;
+use crate::metrics::{Metrics, WriteFailure};
+use crate::pipeline::Envelope;
+use crate::sinks::Sinks;
+use crate::wal::WalTicket;
+use crate::storage::{Routes, SharedStorage};
+use crate::LogEntry;
+
+// --- Arka Plan Veritabanı Yazıcısı (Consumer) ---
+// Kanaldan gelen logları tamponda biriktirir ve iki koşuldan biri gerçekleşince
+// tek bir transaction içinde toplu olarak yazar:
+//   - tampon 'batch_size' kadar dolduğunda
+//   - tampona ilk log girdikten 'flush_interval' süre geçtiğinde
+// Kanal kapanınca (sunucu durunca) kalan tampon da yazılır. Geçici hatalarda
+// parti beklemeli olarak yeniden denenir; yine yazılamazsa (açıksa) ölü mektup
+// dosyasına eklenir. Hiçbir yere yazılamayan loglar WAL'da kalır (açılışta yeniden yüklenir).
+// Yazılan partiler (varsa) yönlendiricilere de bırakılır (bkz. sinks).
+// Yönlendirme kuralları varsa tampon hedef depolara bölünür; her grup ayrı
+// yazılır, yeniden denenir ve gerekirse ölü mektup dosyasına eklenir.
+#[derive(Clone)]
+pub struct Writer {
+    pub routes: Arc<Routes>,
+    pub metrics: Arc<Metrics>,
+    pub dead_letter: Option<Arc<DeadLetter>>,
+    pub sinks: Sinks,
+    pub retry: RetryPolicy,
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    // Kanala girip henüz sonuçlanmamış loglar (Dispatcher::in_flight); her partiden sonra düşülür
+    pub in_flight: Arc<AtomicUsize>,
+}
+
+impl Writer {
+    pub async fn run(self, mut rx: mpsc::Receiver<Envelope>) {
+        let batch_size = self.batch_size.max(1);
+        let mut buffer: Vec<Envelope> = Vec::with_capacity(batch_size);
+        // Tampondaki en eski logun yazılması gereken zaman
+        let mut deadline: Option<tokio::time::Instant> = None;
+
+        loop {
+            let timer = async {
+                match deadline {
+                    Some(at) => tokio::time::sleep_until(at).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            let room = batch_size - buffer.len();
+            tokio::select! {
+                received = rx.recv_many(&mut buffer, room) => {
+                    // 0 dönerse kanal kapanmış ve boşalmıştır
+                    if received == 0 {
+                        break;
+                    }
+                    if deadline.is_none() {
+                        deadline = Some(tokio::time::Instant::now() + self.flush_interval);
+                    }
+                    if buffer.len() >= batch_size {
+                        self.flush(&mut buffer).await;
+                        deadline = None;
+                    }
+                }
+                _ = timer => {
+                    self.flush(&mut buffer).await;
+                    deadline = None;
+                }
+            }
+        }
+
+        self.flush(&mut buffer).await;
+        // Veritabanı bağlantı havuzu (pool) otomatik kapanır.
+    }
+
+    // Tamponu veritabanına yazar, bekleyen onayları sonuçlandırır ve tamponu boşaltır.
+    async fn flush(&self, buffer: &mut Vec<Envelope>) {
+        let count = buffer.len();
+        self.write(buffer).await;
+        self.in_flight.fetch_sub(count, Ordering::Relaxed);
+    }
+
+    async fn write(&self, buffer: &mut Vec<Envelope>) {
+        if buffer.is_empty() {
+            return;
+        }
+        let groups = self.routes.split(std::mem::take(buffer), |envelope| &envelope.log);
+        for (route, envelopes) in groups {
+            match route {
+                Some(route) => {
+                    debug!("💾 '{}' deposuna {} log yazılıyor", route.name, envelopes.len());
+                    let count = envelopes.len() as u64;
+                    if self.write_to(&route.store, envelopes).await {
+                        self.metrics.routed.fetch_add(count, Ordering::Relaxed);
+                    }
+                }
+                None => {
+                    debug!("💾 DB'ye {} log yazılıyor", envelopes.len());
+                    self.write_to(self.routes.primary(), envelopes).await;
+                }
+            }
+        }
+    }
+
+    // Bir grubu tek depoya yazar; yazıldıysa true döner.
+    async fn write_to(&self, store: &SharedStorage, envelopes: Vec<Envelope>) -> bool {
+        let (metrics, dead_letter, retry) = (&*self.metrics, self.dead_letter.as_deref(), self.retry);
+        let mut logs: Vec<LogEntry> = Vec::with_capacity(envelopes.len());
+        let mut acks = Vec::with_capacity(envelopes.len());
+        let mut tickets = Vec::new();
+        // En eski logun kanala girişinden bu yana geçen süre: kanal + tampon beklemesi
+        let lag = envelopes.iter().map(|envelope| envelope.queued_at.elapsed()).max().unwrap_or_default();
+        metrics.write_lag.observe(lag.as_secs_f64());
+        metrics.write_lag_last_ms.store(lag.as_millis() as u64, Ordering::Relaxed);
+        for envelope in envelopes {
+            logs.push(envelope.log);
+            acks.push(envelope.ack);
+            tickets.extend(envelope.wal);
+        }
+        metrics.batch_size.observe(logs.len() as f64);
+        let mut attempt = 0;
+        let result = loop {
+            let started = Instant::now();
+            let span = info_span!("write_batch", rows = logs.len(), attempt, lag_ms = lag.as_millis() as u64);
+            let result = store.insert_batch(&logs).instrument(span).await;
+            metrics.write_latency.observe(started.elapsed().as_secs_f64());
+            match result {
+                Err(e) if e.is_transient() && attempt < retry.attempts => {
+                    let delay = retry.delay(attempt);
+                    attempt += 1;
+                    warn!(
+                        "⏳ {} log yazılamadı, {} ms sonra yeniden denenecek ({}/{}): {}",
+                        logs.len(),
+                        delay.as_millis(),
+                        attempt,
+                        retry.attempts,
+                        e
+                    );
+                    metrics.write_retries.fetch_add(1, Ordering::Relaxed);
+                    tokio::time::sleep(delay).await;
+                }
+                result => break result,
+            }
+        };
+
+        // Veritabanına ya da ölü mektup dosyasına yazılan loglar WAL'dan düşülür
+        let persisted = match &result {
+            Ok(()) => {
+                metrics.written.fetch_add(logs.len() as u64, Ordering::Relaxed);
+                if attempt > 0 {
+                    debug!("✅ {} log {}. yeniden denemede yazıldı", logs.len(), attempt);
+                    metrics.write_recovered.fetch_add(1, Ordering::Relaxed);
+                }
+                true
+            }
+            Err(e) => {
+                error!("❌ {} log yazılamadı: {}", logs.len(), e);
+                metrics.write_errors.fetch_add(1, Ordering::Relaxed);
+                *metrics.last_write_error.lock().unwrap() = Some(WriteFailure {
+                    at: chrono::Utc::now().to_rfc3339(),
+                    rows: logs.len(),
+                    error: e.to_string(),
+                });
+                match dead_letter {
+                    Some(dead_letter) => match dead_letter.push(&logs, &e.to_string()).await {
+                        Ok(id) => {
+                            warn!("🪦 {} log ölü mektup dosyasına eklendi (parti #{}).", logs.len(), id);
+                            metrics.dead_lettered.fetch_add(logs.len() as u64, Ordering::Relaxed);
+                            true
+                        }
+                        Err(e) => {
+                            error!("❌ {} log ölü mektup dosyasına da eklenemedi: {}", logs.len(), e);
+                            false
+                        }
+                    },
+                    None => false,
+                }
+            }
+        };
+        if persisted {
+            tickets.into_iter().for_each(WalTicket::commit);
+        }
+        for ack in acks.into_iter().flatten() {
+            ack.complete(result.is_ok());
+        }
+        if result.is_ok() {
+            self.sinks.offer(logs);
+        }
+        result.is_ok()
+    }
+}
+
+// --- Yeniden Deneme ---
+// Bekleme süresi her denemede ikiye katlanır (üst sınır 'max'); eşzamanlı
+// yazıcıların aynı anda tekrar denemesini önlemek için sürenin yarısı rastgeledir.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    attempts: u32,
+    base: Duration,
+    max: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &PipelineConfig) -> Self {
+        Self::new(config.write_retries, config.retry_backoff_ms, config.retry_backoff_max_ms)
+    }
+
+    pub fn new(attempts: u32, backoff_ms: u64, backoff_max_ms: u64) -> Self {
+        RetryPolicy {
+            attempts,
+            base: Duration::from_millis(backoff_ms),
+            max: Duration::from_millis(backoff_max_ms.max(backoff_ms)),
+        }
+    }
+
+    // Yeniden deneme sayısı (yönlendiricilerde 0 = sınırsız)
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    // 'attempt' numaralı (0'dan başlayan) yeniden denemeden önceki bekleme
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let full = self.base.saturating_mul(2u32.saturating_pow(attempt)).min(self.max);
+        full / 2 + full.mul_f64(rand::random::<f64>() / 2.0)
+    }
+}
@@ -0,0 +1,341 @@
+// HTTP uçlarının uçtan uca testleri: her test kendi TestServer'ını başlatır.
+use std::time::Duration;
+
+use log_ingestor::config::Backpressure;
+use log_ingestor::testing::TestServer;
+use log_ingestor::Config;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+// Yazıcılar partileri arka planda yazar; kayıtlar görünene kadar sorgulanır.
+async fn wait_for_logs(client: &Client, url: &str, count: usize) -> Vec<Value> {
+    for _ in 0..50 {
+        let logs: Vec<Value> = client.get(url).send().await.unwrap().json().await.unwrap();
+        if logs.len() >= count {
+            return logs;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("{} log beklenirken süre doldu: {}", count, url);
+}
+
+#[tokio::test]
+async fn ingest_then_query_round_trip() {
+    let mut config = Config::default();
+    config.filter.min_level = Some("info".to_string());
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+
+    let response = client
+        .post(server.url("/ingest"))
+        .json(&json!([
+            {"level": "error", "message": "ödeme başarısız", "order_id": 42},
+            {"level": "info", "message": "kullanıcı girdi"}
+        ]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let logs = wait_for_logs(&client, &server.url("/logs?level=error"), 1).await;
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["message"], "ödeme başarısız");
+    assert_eq!(logs[0]["tenant_id"], "default");
+    assert_eq!(logs[0]["details"]["order_id"], 42);
+
+    let logs = wait_for_logs(&client, &server.url("/logs"), 2).await;
+    assert_eq!(logs.len(), 2);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn escaped_nul_is_stored_as_visible_text() {
+    let server = TestServer::start().await;
+    let client = Client::new();
+
+    let response = client
+        .post(server.url("/ingest"))
+        .header("Content-Type", "application/json")
+        .body(r#"[{"level":"error","message":"a\u0000b","ctx":{"k\u0000":"v\u0000"}}]"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let logs = wait_for_logs(&client, &server.url("/logs"), 1).await;
+    assert_eq!(logs[0]["message"], "a\\u0000b");
+    assert_eq!(logs[0]["details"]["ctx"], json!({"k\\u0000": "v\\u0000"}));
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn unparsable_timestamp_is_kept_as_timestamp_raw() {
+    let server = TestServer::start().await;
+    let client = Client::new();
+
+    let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+    let response = client
+        .post(server.url("/ingest"))
+        .json(&json!([
+            {"level": "error", "message": "epoch", "timestamp": 1714564800123i64},
+            {"level": "error", "message": "metin", "timestamp": "geçen salı"},
+            {"level": "error", "message": "nesne", "timestamp": {"seconds": 1}}
+        ]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let logs = wait_for_logs(&client, &server.url("/logs"), 3).await;
+    let by_message = |message: &str| logs.iter().find(|log| log["message"] == message).unwrap().clone();
+    let epoch = by_message("epoch");
+    assert_eq!(epoch["timestamp"], "2024-05-01T12:00:00.123+00:00");
+    assert_eq!(epoch["details"].get("timestamp_raw"), None);
+
+    // Çözülemeyen değer details.timestamp_raw'a taşınır, zaman alınma anıdır
+    for (message, raw) in [("metin", json!("geçen salı")), ("nesne", json!({"seconds": 1}))] {
+        let log = by_message(message);
+        assert_eq!(log["details"]["timestamp_raw"], raw);
+        let timestamp = chrono::DateTime::parse_from_rfc3339(log["timestamp"].as_str().unwrap()).unwrap();
+        assert!(timestamp >= before, "{}", log);
+    }
+
+    let metrics = client.get(server.url("/metrics")).send().await.unwrap().text().await.unwrap();
+    assert!(metrics.lines().any(|line| line == "log_ingestor_invalid_timestamps_total 2"), "{}", metrics);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn roles_decide_between_401_and_403() {
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [auth]
+        api_keys = ["{ADMIN_KEY}"]
+
+        [[auth.tokens]]
+        name = "writer"
+        key = "writer-key"
+        role = "ingest"
+
+        [[auth.tokens]]
+        name = "reader"
+        key = "reader-key"
+        role = "read"
+
+        [[auth.tokens]]
+        name = "ops"
+        key = "ops-key"
+        role = "admin"
+        "#
+    ))
+    .unwrap();
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+
+    let cases = [
+        // (yöntem, yol, anahtar, beklenen durum)
+        ("POST", "/ingest", None, StatusCode::UNAUTHORIZED),
+        ("POST", "/ingest", Some("bilinmeyen"), StatusCode::UNAUTHORIZED),
+        ("POST", "/ingest", Some("writer-key"), StatusCode::ACCEPTED),
+        ("POST", "/ingest", Some("reader-key"), StatusCode::FORBIDDEN),
+        ("POST", "/ingest", Some("ops-key"), StatusCode::ACCEPTED),
+        ("POST", "/ingest", Some(ADMIN_KEY), StatusCode::ACCEPTED),
+        ("GET", "/logs", None, StatusCode::UNAUTHORIZED),
+        ("GET", "/logs", Some("writer-key"), StatusCode::FORBIDDEN),
+        ("GET", "/logs", Some("reader-key"), StatusCode::OK),
+        ("GET", "/logs", Some("ops-key"), StatusCode::OK),
+        ("GET", "/admin/tokens", None, StatusCode::UNAUTHORIZED),
+        ("GET", "/admin/tokens", Some("writer-key"), StatusCode::FORBIDDEN),
+        ("GET", "/admin/tokens", Some("reader-key"), StatusCode::FORBIDDEN),
+        ("GET", "/admin/tokens", Some("ops-key"), StatusCode::OK),
+        ("GET", "/admin/tokens", Some(ADMIN_KEY), StatusCode::OK),
+    ];
+    for (method, path, key, expected) in cases {
+        let request = match method {
+            "POST" => client.post(server.url(path)).json(&json!([{"level": "info", "message": "rol"}])),
+            _ => client.get(server.url(path)),
+        };
+        let request = match key {
+            Some(key) => request.header("X-API-Key", key),
+            None => request,
+        };
+        let status = request.send().await.unwrap().status();
+        assert_eq!(status, expected, "{} {} anahtar={:?}", method, path, key);
+    }
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn oversized_body_gets_structured_413() {
+    let mut config = Config::default();
+    config.server.max_body_bytes = 1024;
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+
+    let message = "x".repeat(4096);
+    let response = client
+        .post(server.url("/ingest"))
+        .json(&json!([{"level": "info", "message": message}]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "payload_too_large");
+    assert_eq!(body["limit_bytes"], 1024);
+
+    // Sınırın altındaki gövde etkilenmez
+    let response = client
+        .post(server.url("/ingest"))
+        .json(&json!([{"level": "info", "message": "kısa"}]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn verbose_ingest_reports_each_entry() {
+    let mut config = Config::default();
+    config.filter.min_level = Some("warn".to_string());
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+
+    let response = client
+        .post(server.url("/ingest?verbose=true"))
+        .json(&json!([
+            {"level": "error", "message": "tamam"},
+            {"level": "debug", "message": "filtrelenir"},
+            {"message": "seviyesiz"},
+            {"level": "warn", "message": "bu da tamam"}
+        ]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(report["accepted"], 2);
+    assert_eq!(report["filtered"], 1);
+    assert_eq!(report["rejected"], 1);
+    assert_eq!(report["errors"][0]["index"], 2);
+    assert!(report["errors"][0]["reason"].as_str().unwrap().contains("level"), "{}", report);
+
+    // verbose olmadan tek hatalı kayıt tüm isteği reddeder
+    let response = client
+        .post(server.url("/ingest"))
+        .json(&json!([{"level": "error", "message": "tamam"}, {"message": "seviyesiz"}]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn paused_intake_answers_503_until_resume() {
+    let mut config = Config::default();
+    config.auth.api_keys = vec![ADMIN_KEY.to_string()];
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+    let ingest = || {
+        client
+            .post(server.url("/ingest"))
+            .header("X-API-Key", ADMIN_KEY)
+            .json(&json!([{"level": "info", "message": "bakım"}]))
+            .send()
+    };
+
+    let paused: Value = client
+        .post(server.url("/admin/pause"))
+        .header("X-API-Key", ADMIN_KEY)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(paused, json!({"paused": true, "changed": true}));
+
+    let response = ingest().await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+
+    let response = client.post(server.url("/admin/resume")).header("X-API-Key", ADMIN_KEY).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(ingest().await.unwrap().status(), StatusCode::ACCEPTED);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn batch_larger_than_free_capacity_is_rejected_whole() {
+    let mut config = Config::default();
+    config.pipeline.channel_capacity = 2;
+    config.pipeline.backpressure = Backpressure::Reject;
+    config.pipeline.retry_after_secs = 7;
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+
+    let batch: Vec<Value> = (0..5).map(|i| json!({"level": "error", "message": format!("log {}", i)})).collect();
+    let response = client.post(server.url("/ingest")).json(&batch).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["retry-after"], "7");
+
+    // Reddedilen partiden hiçbir kayıt kuyruğa girmez
+    let response = client.post(server.url("/ingest")).json(&batch[..1]).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let logs = wait_for_logs(&client, &server.url("/logs"), 1).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let logs_after: Vec<Value> = client.get(server.url("/logs")).send().await.unwrap().json().await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs_after.len(), 1);
+    assert_eq!(logs_after[0]["message"], "log 0");
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn snappy_push_over_limit_when_decompressed_gets_413() {
+    let mut config = Config::default();
+    config.server.max_body_bytes = 1024;
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+    let push = |body: Vec<u8>| {
+        client
+            .post(server.url("/loki/api/v1/push"))
+            .header("Content-Type", "application/x-protobuf")
+            .body(body)
+            .send()
+    };
+
+    // Sıkıştırılmış hali sınırın çok altında, başlıkta bildirilen açılmış uzunluğu üstünde
+    let bomb = snap::raw::Encoder::new().compress_vec(&[0u8; 8 * 1024]).unwrap();
+    assert!(bomb.len() < 1024);
+    let response = push(bomb).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "payload_too_large");
+    assert_eq!(body["limit_bytes"], 1024);
+
+    // PushRequest{streams: [{labels: "{level=\"error\"}", entries: [{line: "merhaba"}]}]}
+    let labels = br#"{level="error"}"#;
+    let entry = [&[0x12, 7][..], b"merhaba"].concat();
+    let stream = [&[0x0a, labels.len() as u8][..], labels, &[0x12, entry.len() as u8], &entry].concat();
+    let request = [&[0x0a, stream.len() as u8][..], &stream].concat();
+    let response = push(snap::raw::Encoder::new().compress_vec(&request).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let logs = wait_for_logs(&client, &server.url("/logs"), 1).await;
+    assert_eq!(logs[0]["message"], "merhaba");
+
+    server.stop().await;
+}
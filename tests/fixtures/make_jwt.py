@@ -0,0 +1,80 @@
+#!/usr/bin/env python3
+# jwt.rs testlerinin kullandığı sabit token'ları ve JWKS'i üretir:
+#   python3 tests/fixtures/make_jwt.py
+# Her çalıştırmada yeni bir RSA anahtarı oluşturulur; özel anahtar saklanmaz.
+# Token'lar 2100 yılına kadar geçerlidir (exp = 4102444800).
+import base64
+import hashlib
+import hmac
+import json
+import os
+
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import padding, rsa
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+
+def b64(data):
+    return base64.urlsafe_b64encode(data).rstrip(b"=").decode()
+
+
+def part(value):
+    return b64(json.dumps(value, separators=(",", ":")).encode())
+
+
+def token(header, claims, sign):
+    signed = f"{part(header)}.{part(claims)}"
+    return f"{signed}.{b64(sign(signed.encode()))}"
+
+
+key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+numbers = key.public_key().public_numbers()
+jwk = {
+    "kty": "RSA",
+    "kid": "k1",
+    "use": "sig",
+    "alg": "RS256",
+    "n": b64(numbers.n.to_bytes(256, "big")),
+    "e": b64(numbers.e.to_bytes(3, "big")),
+}
+pem = key.public_key().public_bytes(serialization.Encoding.PEM, serialization.PublicFormat.SubjectPublicKeyInfo)
+
+
+def rs256(data):
+    return key.sign(data, padding.PKCS1v15(), hashes.SHA256())
+
+
+claims = {
+    "sub": "svc-1",
+    "tenant": "acme",
+    "scope": "read write",
+    "iss": "https://sso.example",
+    "aud": ["ingestor", "billing"],
+    "exp": 4102444800,
+}
+rs = {"alg": "RS256", "kid": "k1", "typ": "JWT"}
+without_exp = {k: v for k, v in claims.items() if k != "exp"}
+
+fixtures = {
+    "jwks": {"keys": [jwk]},
+    "public_key_pem": pem.decode(),
+    "rs256": token(rs, claims, rs256),
+    "rs256_no_kid": token({"alg": "RS256", "typ": "JWT"}, claims, rs256),
+    "rs256_unknown_kid": token(dict(rs, kid="k2"), claims, rs256),
+    "rs256_no_exp": token(rs, without_exp, rs256),
+    "rs256_wrong_iss": token(rs, dict(claims, iss="https://evil.example"), rs256),
+    "rs256_aud_string": token(rs, dict(claims, aud="ingestor"), rs256),
+    "rs256_other_aud": token(rs, dict(claims, aud=["billing"]), rs256),
+    # Algoritma karışıklığı: açık anahtar (PEM) HMAC anahtarı olarak kullanılmış
+    "hs256_signed_with_public_key": token(
+        {"alg": "HS256", "kid": "k1", "typ": "JWT"},
+        claims,
+        lambda data: hmac.new(pem, data, hashlib.sha256).digest(),
+    ),
+    "alg_none": token({"alg": "none", "typ": "JWT"}, claims, lambda data: b""),
+}
+
+with open(os.path.join(HERE, "jwt.json"), "w") as out:
+    json.dump(fixtures, out, indent=2)
+    out.write("\n")
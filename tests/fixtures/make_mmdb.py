@@ -0,0 +1,148 @@
+#!/usr/bin/env python3
+# geoip.rs testlerinin kullandığı küçük MaxMind veritabanlarını üretir:
+#   python3 tests/fixtures/make_mmdb.py
+# Biçim: https://maxmind.github.io/MaxMind-DB/
+import ipaddress
+import os
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+
+def ctrl(kind, size):
+    if size < 29:
+        head, tail = size, b""
+    elif size < 285:
+        head, tail = 29, bytes([size - 29])
+    elif size < 65821:
+        head, tail = 30, struct.pack(">H", size - 285)
+    else:
+        head, tail = 31, struct.pack(">I", size - 65821)[1:]
+    if kind <= 7:
+        return bytes([(kind << 5) | head]) + tail
+    return bytes([head, kind - 7]) + tail
+
+
+def uint(kind, n):
+    raw = n.to_bytes((n.bit_length() + 7) // 8, "big")
+    return ctrl(kind, len(raw)) + raw
+
+
+def encode(value):
+    if isinstance(value, Pointer):
+        return bytes([0x20 | (value.target >> 8), value.target & 0xFF])
+    if isinstance(value, str):
+        raw = value.encode()
+        return ctrl(2, len(raw)) + raw
+    if isinstance(value, float):
+        return ctrl(3, 8) + struct.pack(">d", value)
+    if isinstance(value, bool):
+        return ctrl(14, int(value))
+    if isinstance(value, int):
+        return uint(6 if value < 2**32 else 9, value)
+    if isinstance(value, list):
+        return ctrl(11, len(value)) + b"".join(encode(v) for v in value)
+    if isinstance(value, dict):
+        return ctrl(7, len(value)) + b"".join(encode(k) + encode(v) for k, v in value.items())
+    raise TypeError(value)
+
+
+class Pointer:
+    # Veri bölümünün başına göre 11 bitlik işaretçi
+    def __init__(self, target):
+        self.target = target
+
+
+def build(path, ip_version, record_size, database_type, shared, networks):
+    # Ortak değerler veri bölümünün başına yazılır; kayıtlar onlara işaretçiyle bağlanır
+    data = bytearray()
+    pointers = {}
+    for name, value in shared.items():
+        pointers[name] = Pointer(len(data))
+        data += encode(value)
+    offsets = []
+    for _, record in networks:
+        offsets.append(len(data))
+        data += encode(record(pointers))
+
+    bits = 128 if ip_version == 6 else 32
+    nodes = [[None, None]]
+    for (network, _), offset in zip(networks, offsets):
+        net = ipaddress.ip_network(network)
+        address, prefix = int(net.network_address), net.prefixlen
+        if ip_version == 6 and net.version == 4:
+            prefix += 96
+        node = 0
+        for i in range(prefix):
+            # IPv6 ağacında IPv4 ağları ::/96 altındadır; tam sayı değeri aynıdır
+            bit = (address >> (bits - 1 - i)) & 1
+            if i == prefix - 1:
+                nodes[node][bit] = ("data", offset)
+            else:
+                if nodes[node][bit] is None:
+                    nodes.append([None, None])
+                    nodes[node][bit] = ("node", len(nodes) - 1)
+                node = nodes[node][bit][1]
+
+    count = len(nodes)
+
+    def value(record):
+        if record is None:
+            return count
+        kind, n = record
+        return n if kind == "node" else count + 16 + n
+
+    tree = bytearray()
+    for left, right in nodes:
+        left, right = value(left), value(right)
+        if record_size == 24:
+            tree += left.to_bytes(3, "big") + right.to_bytes(3, "big")
+        elif record_size == 28:
+            tree += left.to_bytes(4, "big")[1:] + bytes([((left >> 24) << 4) | (right >> 24)]) + right.to_bytes(4, "big")[1:]
+        else:
+            tree += left.to_bytes(4, "big") + right.to_bytes(4, "big")
+
+    metadata = {
+        "binary_format_major_version": 2,
+        "binary_format_minor_version": 0,
+        "build_epoch": 1_700_000_000,
+        "database_type": database_type,
+        "description": {"en": "log-ingestor test fixture"},
+        "ip_version": ip_version,
+        "languages": ["en"],
+        "node_count": count,
+        "record_size": record_size,
+    }
+    with open(os.path.join(HERE, path), "wb") as out:
+        out.write(tree + bytes(16) + data + b"\xab\xcd\xefMaxMind.com" + encode(metadata))
+
+
+build(
+    "GeoIP2-City-Test.mmdb",
+    6,
+    24,
+    "GeoIP2-City",
+    {"GB": {"iso_code": "GB", "names": {"en": "United Kingdom"}}},
+    [
+        ("81.2.69.0/24", lambda p: {
+            "city": {"names": {"en": "London"}},
+            "country": p["GB"],
+            "location": {"latitude": 51.5142, "longitude": -0.0931},
+        }),
+        ("2001:db8::/32", lambda p: {"country": p["GB"]}),
+    ],
+)
+
+build(
+    "GeoLite2-ASN-Test.mmdb",
+    4,
+    28,
+    "GeoLite2-ASN",
+    {},
+    [
+        ("81.2.69.0/24", lambda p: {
+            "autonomous_system_number": 20712,
+            "autonomous_system_organization": "Andrews & Arnold Ltd",
+        }),
+    ],
+)
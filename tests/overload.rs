@@ -0,0 +1,129 @@
+// Kuyruk doluyken atılan kayıtlar ne canlı akışa ne de uyarı kurallarına gider.
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::routing::post;
+use axum::{Json, Router};
+use log_ingestor::testing::TestServer;
+use log_ingestor::Config;
+use reqwest::{Client, Response, StatusCode};
+use serde_json::{json, Value};
+
+type Received = Arc<Mutex<Vec<Value>>>;
+
+// Uyarı webhook'una gelen gövdeleri biriktiren yerel sunucu.
+async fn webhook() -> (String, Received) {
+    let received = Received::default();
+    let store = received.clone();
+    let app = Router::new().route(
+        "/hook",
+        post(move |Json(body): Json<Value>| async move {
+            store.lock().unwrap().push(body);
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (url, received)
+}
+
+// SSE akışından 'wait' süresi boyunca gelen olayları toplar.
+async fn read_events(stream: &mut Response, wait: Duration) -> Vec<Value> {
+    let mut text = String::new();
+    let _ = tokio::time::timeout(wait, async {
+        while let Ok(Some(chunk)) = stream.chunk().await {
+            text.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await;
+    text.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| serde_json::from_str(data.trim()).unwrap())
+        .collect()
+}
+
+async fn wait_for_tail_client(client: &Client, server: &TestServer) {
+    for _ in 0..50 {
+        let metrics = client.get(server.url("/metrics")).send().await.unwrap().text().await.unwrap();
+        if metrics.lines().any(|line| line == "log_ingestor_tail_clients 1") {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("SSE izleyicisi bağlanmadı");
+}
+
+#[tokio::test]
+async fn entries_dropped_under_overload_reach_neither_tail_nor_alerts() {
+    let (hook_url, received) = webhook().await;
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [pipeline]
+        channel_capacity = 2
+        backpressure = "drop"
+        writers = 1
+
+        [[alerts.rules]]
+        name = "yük"
+        level = "error"
+        threshold = 2
+        window_secs = 60
+        webhook = "{hook_url}"
+        "#
+    ))
+    .unwrap();
+    let server = TestServer::with_config(config).await;
+    let client = Client::new();
+
+    let mut stream = client.get(server.url("/tail/sse")).send().await.unwrap();
+    assert_eq!(stream.status(), StatusCode::OK);
+    wait_for_tail_client(&client, &server).await;
+
+    // İstek işlenirken yazıcı çalışmaz; kanal iki kayıtta dolar, kalan üçü atılır
+    let batch: Vec<Value> = (0..5).map(|i| json!({"level": "error", "message": format!("log {}", i)})).collect();
+    let report: Value = client
+        .post(server.url("/ingest?verbose=true"))
+        .json(&batch)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["accepted"], 2, "{}", report);
+    assert_eq!(report["rejected"], 3, "{}", report);
+
+    let events = read_events(&mut stream, Duration::from_millis(500)).await;
+    let messages: Vec<&str> = events.iter().map(|event| event["message"].as_str().unwrap()).collect();
+    assert_eq!(messages, ["log 0", "log 1"]);
+
+    // Penceredeki sayı 2; atılanlar sayılsaydı eşik (2) aşılırdı
+    assert!(received.lock().unwrap().is_empty(), "{:?}", received.lock().unwrap());
+
+    // Bir kayıt daha eşiği aşar; özet sadece kanala girenleri sayar
+    let response = client
+        .post(server.url("/ingest"))
+        .json(&json!([{"level": "error", "message": "log 5"}]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    for _ in 0..50 {
+        if !received.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let alerts = received.lock().unwrap().clone();
+    assert_eq!(alerts.len(), 1, "{:?}", alerts);
+    assert_eq!(alerts[0]["count"], 3);
+    let samples: Vec<&str> = alerts[0]["samples"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|sample| sample["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(samples, ["log 0", "log 1", "log 5"]);
+
+    server.stop().await;
+}
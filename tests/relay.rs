@@ -0,0 +1,137 @@
+// /ingest/relay: uç sunuculardan gelen partiler kiracı ve kaynak bilgisiyle yazılır.
+use std::time::Duration;
+
+use log_ingestor::testing::TestServer;
+use log_ingestor::Config;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+
+async fn hub() -> TestServer {
+    let config: Config = toml::from_str(
+        r#"
+        [auth]
+        api_keys = ["hub-key"]
+        relay_keys = ["edge-key"]
+
+        [auth.tenants]
+        berlin = ["berlin-key"]
+
+        [[auth.tokens]]
+        name = "writer"
+        key = "writer-key"
+        role = "ingest"
+
+        [[auth.tokens]]
+        name = "edge-token"
+        key = "edge-token-key"
+        role = "relay"
+        "#,
+    )
+    .unwrap();
+    TestServer::with_config(config).await
+}
+
+fn relayed(message: &str) -> Value {
+    json!([{
+        "tenant_id": "berlin",
+        "level": "error",
+        "message": message,
+        "extra": {"order_id": 42},
+        "remote_addr": "10.0.0.5",
+        "user_agent": "edge-agent/1.0",
+        "api_key": "berlin#1",
+        "received_at": "2026-01-02T03:04:05+00:00"
+    }])
+}
+
+#[tokio::test]
+async fn only_relay_credentials_may_relay() {
+    let server = hub().await;
+    let client = Client::new();
+
+    let cases = [
+        (None, StatusCode::UNAUTHORIZED),
+        (Some("writer-key"), StatusCode::FORBIDDEN),
+        (Some("hub-key"), StatusCode::FORBIDDEN),
+        (Some("berlin-key"), StatusCode::FORBIDDEN),
+        (Some("edge-key"), StatusCode::ACCEPTED),
+        (Some("edge-token-key"), StatusCode::ACCEPTED),
+    ];
+    for (key, expected) in cases {
+        let request = client.post(server.url("/ingest/relay")).json(&relayed("yetki"));
+        let request = match key {
+            Some(key) => request.header("X-API-Key", key),
+            None => request,
+        };
+        let status = request.send().await.unwrap().status();
+        assert_eq!(status, expected, "anahtar={:?}", key);
+    }
+
+    // relay rolü ingest'i de kapsar
+    let response = client
+        .post(server.url("/ingest"))
+        .header("X-API-Key", "edge-key")
+        .json(&json!([{"level": "error", "message": "yerel"}]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn relayed_entries_keep_tenant_and_source() {
+    let server = hub().await;
+    let client = Client::new();
+
+    let response = client
+        .post(server.url("/ingest/relay"))
+        .header("X-API-Key", "edge-key")
+        .json(&relayed("uçtan geldi"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let mut logs = Vec::new();
+    for _ in 0..50 {
+        logs = client
+            .get(server.url("/logs"))
+            .header("X-API-Key", "berlin-key")
+            .send()
+            .await
+            .unwrap()
+            .json::<Vec<Value>>()
+            .await
+            .unwrap();
+        if !logs.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(logs.len(), 1, "berlin kiracısında log yok");
+    let log = &logs[0];
+    assert_eq!(log["tenant_id"], "berlin");
+    assert_eq!(log["message"], "uçtan geldi");
+    assert_eq!(log["details"]["order_id"], 42);
+    // Kaynak sütunları uç sunucunun yazdığı gibidir, hub'ın bağlantısı değil
+    assert_eq!(log["remote_addr"], "10.0.0.5");
+    assert_eq!(log["user_agent"], "edge-agent/1.0");
+    assert_eq!(log["api_key"], "berlin#1");
+    assert_eq!(log["received_at"], "2026-01-02T03:04:05+00:00");
+
+    // Varsayılan kiracı bu logu görmez
+    let default_logs: Vec<Value> = client
+        .get(server.url("/logs"))
+        .header("X-API-Key", "hub-key")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(default_logs.is_empty(), "{:?}", default_logs);
+
+    server.stop().await;
+}
@@ -0,0 +1,198 @@
+// --- log-ingestor Web Arayüzü ---
+// Mevcut okuma uçlarını kullanır: GET /logs (arama) ve GET /tail/sse (canlı akış).
+// Adresler göreli tutulur ('../logs'); sunucu bir ön ek altında yayınlansa da çalışır.
+// API anahtarı tarayıcıda (localStorage) saklanır ve X-API-Key başlığıyla gönderilir.
+"use strict";
+
+const LEVELS = ["trace", "debug", "info", "warn", "error", "fatal"];
+const PAGE = 100;
+// Canlı akışta tabloda tutulan en fazla satır
+const TAIL_MAX = 500;
+const RANGES = { "15m": 15 * 60, "1h": 3600, "6h": 6 * 3600, "24h": 86400, "7d": 7 * 86400 };
+
+const $ = (id) => document.getElementById(id);
+const rows = document.querySelector("#logs tbody");
+let oldestId = null;
+let tail = null;
+
+$("key").value = localStorage.getItem("log-ingestor.key") || "";
+$("key").addEventListener("change", () => localStorage.setItem("log-ingestor.key", $("key").value.trim()));
+$("range").addEventListener("change", () => { $("custom").hidden = $("range").value !== "custom"; });
+
+function headers() {
+  const key = $("key").value.trim();
+  return key ? { "X-API-Key": key } : {};
+}
+
+function status(text, error) {
+  $("status").textContent = text;
+  $("status").className = error ? "error" : "";
+}
+
+// Formdaki filtreler -> /logs parametreleri
+function params() {
+  const p = new URLSearchParams();
+  const set = (name, value) => { if (value) p.set(name, value); };
+  set("q", $("q").value.trim());
+  set("min_level", $("level").value);
+  set("service", $("service").value.trim());
+  set("host", $("host").value.trim());
+  const range = $("range").value;
+  if (RANGES[range]) {
+    p.set("from", new Date(Date.now() - RANGES[range] * 1000).toISOString());
+  } else if (range === "custom") {
+    if ($("from").value) p.set("from", new Date($("from").value).toISOString());
+    if ($("to").value) p.set("to", new Date($("to").value).toISOString());
+  }
+  p.set("limit", PAGE);
+  return p;
+}
+
+async function failure(response) {
+  if (response.status === 401) return "API key missing or invalid.";
+  if (response.status === 403) return "This key may not read these logs.";
+  const text = await response.text();
+  return `${response.status}: ${text || response.statusText}`;
+}
+
+async function search(older) {
+  stopTail();
+  const p = params();
+  if (older && oldestId !== null) p.set("before_id", oldestId);
+  status("Searching…");
+  try {
+    const response = await fetch(`../logs?${p}`, { headers: headers() });
+    if (!response.ok) return status(await failure(response), true);
+    const logs = await response.json();
+    if (!older) rows.replaceChildren();
+    logs.forEach((log) => rows.append(...render(log)));
+    if (logs.length) oldestId = logs[logs.length - 1].id;
+    $("more").hidden = logs.length < PAGE;
+    const shown = rows.querySelectorAll("tr.entry").length;
+    status(shown ? `${shown} entries` : "No entries match.");
+  } catch (e) {
+    status(`Request failed: ${e.message}`, true);
+  }
+}
+
+// /logs öne çıkarılan sütunları ayrı döner; canlı akışta alanlar details içindedir
+const field = (log, name) => log[name] || (log.details && log.details[name]) || "";
+
+// Bir log -> özet satırı + (tıklanınca açılan) ayrıntı satırı
+function render(log, fresh) {
+  const row = document.createElement("tr");
+  row.className = fresh ? "entry new" : "entry";
+  const level = String(log.level || "").toLowerCase();
+  const cells = [
+    formatTime(log.timestamp),
+    level,
+    field(log, "service"),
+    field(log, "host"),
+    log.message || "",
+  ];
+  cells.forEach((text, i) => {
+    const cell = document.createElement("td");
+    if (i === 1) {
+      const badge = document.createElement("span");
+      badge.className = `lv lv-${LEVELS.includes(level) ? level : "info"}`;
+      badge.textContent = text;
+      cell.append(badge);
+    } else {
+      cell.textContent = text;
+      cell.title = text;
+    }
+    row.append(cell);
+  });
+
+  const detail = document.createElement("tr");
+  detail.hidden = true;
+  const cell = document.createElement("td");
+  cell.colSpan = 5;
+  cell.className = "detail";
+  detail.append(cell);
+  row.addEventListener("click", () => {
+    if (!cell.textContent) cell.textContent = JSON.stringify(log, null, 2);
+    detail.hidden = !detail.hidden;
+  });
+  return [row, detail];
+}
+
+function formatTime(value) {
+  const time = new Date(value);
+  if (isNaN(time)) return value || "";
+  const pad = (n, w = 2) => String(n).padStart(w, "0");
+  return `${time.getFullYear()}-${pad(time.getMonth() + 1)}-${pad(time.getDate())} ` +
+    `${pad(time.getHours())}:${pad(time.getMinutes())}:${pad(time.getSeconds())}.${pad(time.getMilliseconds(), 3)}`;
+}
+
+// --- Canlı Akış ---
+// EventSource başlık gönderemediği için /tail/sse fetch ile okunur ve olaylar
+// elle ayrılır. Sunucu seviyeyi tam eşleşmeyle süzer; "warn +" gibi alt sınırlar
+// ve service / host burada süzülür.
+async function startTail() {
+  const p = new URLSearchParams();
+  if ($("q").value.trim()) p.set("q", $("q").value.trim());
+  const controller = new AbortController();
+  tail = controller;
+  $("tail").textContent = "■ Stop";
+  $("tail").classList.add("on");
+  $("more").hidden = true;
+  rows.replaceChildren();
+  status("Connecting…");
+
+  const minimum = LEVELS.indexOf($("level").value);
+  const service = $("service").value.trim();
+  const host = $("host").value.trim();
+  const wanted = (log) =>
+    (minimum < 0 || LEVELS.indexOf(String(log.level).toLowerCase()) >= minimum) &&
+    (!service || field(log, "service") === service) &&
+    (!host || field(log, "host") === host);
+
+  try {
+    const response = await fetch(`../tail/sse?${p}`, { headers: headers(), signal: controller.signal });
+    if (!response.ok) {
+      status(await failure(response), true);
+      return stopTail();
+    }
+    status("Live — waiting for entries…");
+    const reader = response.body.pipeThrough(new TextDecoderStream()).getReader();
+    let buffer = "";
+    let count = 0;
+    for (;;) {
+      const { value, done } = await reader.read();
+      if (done) break;
+      buffer += value;
+      let end;
+      while ((end = buffer.indexOf("\n\n")) >= 0) {
+        const block = buffer.slice(0, end);
+        buffer = buffer.slice(end + 2);
+        const data = block.split("\n").filter((l) => l.startsWith("data:")).map((l) => l.slice(5).trimStart()).join("\n");
+        if (!data) continue;
+        const log = JSON.parse(data);
+        if (!wanted(log)) continue;
+        rows.prepend(...render(log, true));
+        while (rows.children.length > TAIL_MAX * 2) rows.lastElementChild.remove();
+        count += 1;
+        status(`Live — ${count} entries received`);
+      }
+    }
+    if (tail === controller) status("Live tail closed by the server (client fell behind or server stopped).", true);
+  } catch (e) {
+    if (e.name !== "AbortError") status(`Live tail failed: ${e.message}`, true);
+  }
+  if (tail === controller) stopTail();
+}
+
+function stopTail() {
+  if (!tail) return;
+  tail.abort();
+  tail = null;
+  $("tail").textContent = "▶ Live tail";
+  $("tail").classList.remove("on");
+}
+
+$("search").addEventListener("submit", (e) => { e.preventDefault(); search(false); });
+$("more").addEventListener("click", () => search(true));
+$("tail").addEventListener("click", () => (tail ? (stopTail(), status("Live tail stopped.")) : startTail()));
+
+search(false);